@@ -0,0 +1,39 @@
+//! Storage::count vs get_all().len() benchmark
+//!
+//! cargo run --release --example count_bench [rows]
+
+use memory_brain::storage::Storage;
+use memory_brain::MemoryItem;
+use std::time::Instant;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let rows: usize = std::env::args()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(50_000);
+
+    let dir = tempfile::tempdir()?;
+    let storage = Storage::new(dir.path().to_str().unwrap(), "count_bench")?;
+
+    println!("📝 Inserting {} rows (256d embeddings)...", rows);
+    let start = Instant::now();
+    for i in 0..rows {
+        let mut item = MemoryItem::new(&format!("Benchmark memory {} with some content", i), None);
+        item.embedding = Some((0..256).map(|j| ((i + j) % 97) as f32 / 97.0).collect());
+        storage.save(&item)?;
+    }
+    println!("   done in {:?}\n", start.elapsed());
+
+    let start = Instant::now();
+    let full = storage.get_all()?.len();
+    let full_elapsed = start.elapsed();
+    println!("📦 get_all().len(): {} in {:?}", full, full_elapsed);
+
+    let start = Instant::now();
+    let counted = storage.count()?;
+    let count_elapsed = start.elapsed();
+    println!("⚡ count():          {} in {:?}", counted, count_elapsed);
+
+    println!("\n🚀 Speedup: {:.1}x", full_elapsed.as_secs_f64() / count_elapsed.as_secs_f64());
+    Ok(())
+}
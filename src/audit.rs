@@ -3,11 +3,16 @@
 //! Tracks all store/recall operations for monitoring and debugging.
 //! Now with beautiful TUI visualization! 🎨
 
+use std::collections::BTreeMap;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::PathBuf;
-use chrono::{Local, NaiveDate};
+use chrono::{Local, NaiveDate, NaiveDateTime};
 use colored::*;
+use serde::{Deserialize, Serialize};
+
+/// Timestamp format at the start of every log line
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
 /// Get the audit log path
 fn audit_log_path() -> PathBuf {
@@ -20,8 +25,19 @@ fn audit_log_path() -> PathBuf {
 /// Log an operation to the audit log
 pub fn log_operation(op: &str, content: &str, tags: Option<&[String]>, result: Option<&str>) {
     let path = audit_log_path();
-    let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
-    
+    let log_line = format_line(Local::now().naive_local(), op, content, tags, result);
+
+    if let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+    {
+        let _ = file.write_all(log_line.as_bytes());
+    }
+}
+
+/// One log line: `[timestamp] OP: "content" tags=[..] → result`
+fn format_line(at: NaiveDateTime, op: &str, content: &str, tags: Option<&[String]>, result: Option<&str>) -> String {
     let tags_str = tags
         .map(|t| format!(" tags=[{}]", t.join(", ")))
         .unwrap_or_default();
@@ -38,20 +54,93 @@ pub fn log_operation(op: &str, content: &str, tags: Option<&[String]>, result: O
         content_preview
     };
     
-    let log_line = format!(
+    format!(
         "[{}] {}: \"{}\"{}{}\n",
-        timestamp, op, content_display, tags_str, result_str
-    );
-    
-    if let Ok(mut file) = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&path)
-    {
-        let _ = file.write_all(log_line.as_bytes());
+        at.format(TIMESTAMP_FORMAT), op, content_display, tags_str, result_str
+    )
+}
+
+/// Date and command of a log line, or `None` if the line is malformed
+fn parse_line(line: &str) -> Option<(NaiveDate, &str)> {
+    let rest = line.strip_prefix('[')?;
+    let (timestamp, rest) = rest.split_once("] ")?;
+    let at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+    let (op, _) = rest.split_once(": ")?;
+    let valid = !op.is_empty() && op.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    valid.then_some((at.date(), op))
+}
+
+/// Aggregated audit counts over a date range
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditStats {
+    /// First day of the range (`YYYY-MM-DD`, inclusive)
+    pub from: String,
+    /// Last day of the range (`YYYY-MM-DD`, inclusive)
+    pub to: String,
+    /// Operations in the range
+    pub total: usize,
+    /// Per-day counts by command (`STORE`, `RECALL`, ...); days without
+    /// activity are omitted
+    pub by_day: BTreeMap<String, BTreeMap<String, usize>>,
+    /// Counts by command over the whole range
+    pub by_command: BTreeMap<String, usize>,
+    /// Malformed lines that were skipped (whole log, not just the range)
+    pub skipped: usize,
+}
+
+impl AuditStats {
+    /// Count for `command` over the whole range
+    pub fn count(&self, command: &str) -> usize {
+        self.by_command.get(command).copied().unwrap_or(0)
+    }
+
+    /// Count for `command` on `date`
+    pub fn count_on(&self, date: NaiveDate, command: &str) -> usize {
+        self.by_day
+            .get(&date.format("%Y-%m-%d").to_string())
+            .and_then(|day| day.get(command))
+            .copied()
+            .unwrap_or(0)
     }
 }
 
+/// Aggregate log lines between `start` and `end` (inclusive)
+fn aggregate(log: &str, start: NaiveDate, end: NaiveDate) -> AuditStats {
+    let mut stats = AuditStats {
+        from: start.format("%Y-%m-%d").to_string(),
+        to: end.format("%Y-%m-%d").to_string(),
+        ..Default::default()
+    };
+
+    for line in log.lines().filter(|l| !l.trim().is_empty()) {
+        let Some((date, op)) = parse_line(line) else {
+            stats.skipped += 1;
+            continue;
+        };
+        if date < start || date > end {
+            continue;
+        }
+        *stats
+            .by_day
+            .entry(date.format("%Y-%m-%d").to_string())
+            .or_default()
+            .entry(op.to_string())
+            .or_default() += 1;
+        *stats.by_command.entry(op.to_string()).or_default() += 1;
+        stats.total += 1;
+    }
+
+    stats
+}
+
+/// Per-day and per-command counts between `start` and `end` (inclusive)
+///
+/// Malformed log lines are skipped. A missing log yields empty stats.
+pub fn get_stats_range(start: NaiveDate, end: NaiveDate) -> AuditStats {
+    let log = fs::read_to_string(audit_log_path()).unwrap_or_default();
+    aggregate(&log, start, end)
+}
+
 /// Log a STORE operation
 pub fn log_store(content: &str, tags: &[String]) {
     log_operation("STORE", content, Some(tags), None);
@@ -69,53 +158,16 @@ pub fn log_search(query: &str, result_count: usize) {
 
 /// Get daily stats from audit log
 pub fn get_daily_stats() -> (usize, usize, usize) {
-    let path = audit_log_path();
-    let today = Local::now().format("%Y-%m-%d").to_string();
-    
-    let mut stores = 0;
-    let mut recalls = 0;
-    let mut searches = 0;
-    
-    if let Ok(content) = fs::read_to_string(&path) {
-        for line in content.lines() {
-            if line.starts_with(&format!("[{}", today)) {
-                if line.contains("] STORE:") {
-                    stores += 1;
-                } else if line.contains("] RECALL:") {
-                    recalls += 1;
-                } else if line.contains("] SEARCH:") {
-                    searches += 1;
-                }
-            }
-        }
-    }
-    
-    (stores, recalls, searches)
+    get_stats_for_date(&Local::now().format("%Y-%m-%d").to_string())
 }
 
-/// Get stats for a specific date
+/// Get stats for a specific date (`YYYY-MM-DD`)
 pub fn get_stats_for_date(date: &str) -> (usize, usize, usize) {
-    let path = audit_log_path();
-    
-    let mut stores = 0;
-    let mut recalls = 0;
-    let mut searches = 0;
-    
-    if let Ok(content) = fs::read_to_string(&path) {
-        for line in content.lines() {
-            if line.starts_with(&format!("[{}", date)) {
-                if line.contains("] STORE:") {
-                    stores += 1;
-                } else if line.contains("] RECALL:") {
-                    recalls += 1;
-                } else if line.contains("] SEARCH:") {
-                    searches += 1;
-                }
-            }
-        }
-    }
-    
-    (stores, recalls, searches)
+    let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+        return (0, 0, 0);
+    };
+    let stats = get_stats_range(date, date);
+    (stats.count("STORE"), stats.count("RECALL"), stats.count("SEARCH"))
 }
 
 /// Get weekly stats (last 7 days)
@@ -327,4 +379,37 @@ mod tests {
         let stats = get_weekly_stats();
         assert_eq!(stats.len(), 7);
     }
+
+    #[test]
+    fn test_stats_range_across_two_days() {
+        let day1 = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let at = |d: NaiveDate, h| d.and_hms_opt(h, 0, 0).unwrap();
+        let tags = ["rust".to_string()];
+
+        let mut log = String::new();
+        log += &format_line(at(day1, 9), "STORE", "Rust ownership", Some(&tags), None);
+        log += &format_line(at(day1, 10), "RECALL", "rust", None, Some("found 1 results"));
+        log += &format_line(at(day1, 23), "STORE", "Borrow checker", None, None);
+        log += "garbage line\n[not a date] STORE: \"x\"\n\n";
+        log += &format_line(at(day2, 8), "SEARCH", "memory: safety", None, Some("found 0 results"));
+        log += &format_line(at(day2, 9), "STORE", "Lifetimes", Some(&tags), None);
+        log += &format_line(at(day2 + chrono::Duration::days(1), 9), "STORE", "out of range", None, None);
+
+        let stats = aggregate(&log, day1, day2);
+        assert_eq!(stats.total, 5);
+        assert_eq!(stats.skipped, 2);
+        assert_eq!(stats.count("STORE"), 3);
+        assert_eq!(stats.count("RECALL"), 1);
+        assert_eq!(stats.count("SEARCH"), 1);
+        assert_eq!(stats.count_on(day1, "STORE"), 2);
+        assert_eq!(stats.count_on(day2, "STORE"), 1);
+        assert_eq!(stats.count_on(day2, "RECALL"), 0);
+        assert_eq!(stats.by_day.len(), 2);
+
+        // A single-day range only sees that day
+        let only_day2 = aggregate(&log, day2, day2);
+        assert_eq!(only_day2.total, 2);
+        assert_eq!(only_day2.from, "2024-03-02");
+    }
 }
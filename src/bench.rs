@@ -7,6 +7,7 @@
 //! - Search performance
 
 use std::time::{Duration, Instant};
+use crate::hnsw_index::HnswIndex;
 use crate::simd_ops::cosine_similarity_simd;
 
 /// Benchmark result for a single operation
@@ -209,6 +210,20 @@ pub fn run_benchmarks(verbose: bool) -> Vec<BenchResult> {
         let _ = set.contains(&5000);
     });
 
+    // HNSW vs brute force on random vectors
+    let index = random_hnsw_index(1000, 128);
+    let query: Vec<f32> = dim_128.clone();
+
+    bench.bench("hnsw_search_1000x128d", 1_000, || {
+        black_box(index.search(black_box(&query), 10).len());
+    });
+
+    bench.bench("brute_force_1000x128d", 1_000, || {
+        black_box(index.brute_force(black_box(&query), 10).len());
+    });
+
+    let index_stats = index.stats_with_recall(100);
+
     // Print results
     if verbose {
         bench.print_results();
@@ -229,11 +244,114 @@ pub fn run_benchmarks(verbose: bool) -> Vec<BenchResult> {
                 );
             }
         }
+
+        println!("\n🧭 {}", index_stats);
     }
 
     bench.results().to_vec()
 }
 
+/// HNSW index over `n` seeded random vectors of `dim` dimensions
+pub fn random_hnsw_index(n: usize, dim: usize) -> HnswIndex {
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = rand_pcg::Pcg64::seed_from_u64(1);
+    let index = HnswIndex::new(dim);
+    for _ in 0..n {
+        let v: Vec<f32> = (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        let _ = index.add(uuid::Uuid::new_v4(), v);
+    }
+    index
+}
+
+/// Largest SIMD vs scalar difference `compare_cosine` accepts
+pub const COSINE_TOLERANCE: f32 = 1e-5;
+
+/// Scalar vs SIMD cosine similarity on one embedding dimension
+#[derive(Debug, Clone)]
+pub struct CosineComparison {
+    pub dim: usize,
+    /// Random vectors compared (each against the next, wrapping around)
+    pub vectors: usize,
+    pub scalar: BenchResult,
+    pub simd: BenchResult,
+    /// Scalar time / SIMD time
+    pub speedup: f64,
+    /// Largest |scalar - simd| over all compared pairs
+    pub max_abs_error: f32,
+}
+
+impl CosineComparison {
+    /// SIMD results agree with scalar within [`COSINE_TOLERANCE`]
+    pub fn is_accurate(&self) -> bool {
+        self.max_abs_error < COSINE_TOLERANCE
+    }
+}
+
+impl std::fmt::Display for CosineComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "📐 Cosine similarity, {}d × {} vectors\n", self.dim, self.vectors)?;
+        writeln!(f, "{}", self.scalar)?;
+        writeln!(f, "{}", self.simd)?;
+        writeln!(f, "\n⚡ SIMD speedup: {:.2}x", self.speedup)?;
+        write!(
+            f,
+            "{} Max abs error: {:.2e} (limit {:.0e})",
+            if self.is_accurate() { "✅" } else { "❌" },
+            self.max_abs_error,
+            COSINE_TOLERANCE
+        )
+    }
+}
+
+/// Time scalar vs SIMD cosine similarity over `n` seeded random vectors of
+/// `dim` dimensions and check the two agree
+pub fn compare_cosine(dim: usize, n: usize) -> CosineComparison {
+    use rand::{Rng, SeedableRng};
+    use std::hint::black_box;
+
+    let dim = dim.max(1);
+    let n = n.max(2);
+    let mut rng = rand_pcg::Pcg64::seed_from_u64(7);
+    let vectors: Vec<Vec<f32>> = (0..n)
+        .map(|_| (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect())
+        .collect();
+    let pairs: Vec<(&[f32], &[f32])> = (0..n)
+        .map(|i| (vectors[i].as_slice(), vectors[(i + 1) % n].as_slice()))
+        .collect();
+
+    let max_abs_error = pairs
+        .iter()
+        .map(|(a, b)| (cosine_similarity_scalar(a, b) - cosine_similarity_simd(a, b)).abs())
+        .fold(0.0f32, f32::max);
+
+    // Enough passes for ~50M multiply-adds per variant
+    let rounds = (50_000_000 / (n * dim)).clamp(1, 10_000);
+    let time = |name: String, f: fn(&[f32], &[f32]) -> f32| {
+        let start = Instant::now();
+        for _ in 0..rounds {
+            for (a, b) in &pairs {
+                black_box(f(black_box(*a), black_box(*b)));
+            }
+        }
+        let elapsed = start.elapsed().max(Duration::from_nanos(1));
+        let iterations = rounds * pairs.len();
+        BenchResult {
+            name,
+            iterations,
+            total_time: elapsed,
+            ops_per_sec: iterations as f64 / elapsed.as_secs_f64(),
+            avg_time_us: elapsed.as_secs_f64() * 1e6 / iterations as f64,
+        }
+    };
+
+    let scalar = time(format!("scalar_cosine_{}d", dim), cosine_similarity_scalar);
+    let simd = time(format!("simd_cosine_{}d", dim), cosine_similarity_simd);
+    let speedup = scalar.total_time.as_secs_f64() / simd.total_time.as_secs_f64();
+
+    CosineComparison { dim, vectors: n, scalar, simd, speedup, max_abs_error }
+}
+
 /// Quick SIMD test
 pub fn test_simd_correctness() -> bool {
     let a: Vec<f32> = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
@@ -266,4 +384,17 @@ mod tests {
         assert_eq!(bench.results().len(), 1);
         assert!(bench.results()[0].ops_per_sec > 0.0);
     }
+
+    #[test]
+    fn test_compare_cosine_agrees_and_times() {
+        for dim in [7, 384] {
+            let cmp = compare_cosine(dim, 50);
+            assert!(cmp.is_accurate(), "{}d error {}", dim, cmp.max_abs_error);
+            assert_eq!(cmp.scalar.iterations, cmp.simd.iterations);
+            assert!(cmp.scalar.total_time > Duration::ZERO);
+            assert!(cmp.simd.total_time > Duration::ZERO);
+            assert!(cmp.scalar.ops_per_sec > 0.0 && cmp.simd.ops_per_sec > 0.0);
+            assert!(cmp.speedup > 0.0);
+        }
+    }
 }
@@ -149,6 +149,28 @@ impl BloomFilter {
         *count = 0;
     }
 
+    /// Bits packed 8 per byte (for persistence, see `from_bytes`)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let bits = self.bits.read().unwrap();
+        bits.chunks(8)
+            .map(|chunk| chunk.iter().enumerate().fold(0u8, |byte, (i, &b)| byte | ((b as u8) << i)))
+            .collect()
+    }
+
+    /// Rebuild a filter from `to_bytes` output
+    pub fn from_bytes(size: usize, num_hashes: usize, items_added: usize, bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() != (size + 7) / 8 {
+            return Err("Bloom filter byte length doesn't match size");
+        }
+        let bits = (0..size).map(|i| bytes[i / 8] & (1 << (i % 8)) != 0).collect();
+        Ok(Self {
+            bits: RwLock::new(bits),
+            size,
+            num_hashes,
+            count: RwLock::new(items_added),
+        })
+    }
+
     /// Merge another bloom filter into this one (OR operation)
     pub fn merge(&self, other: &BloomFilter) -> Result<(), &'static str> {
         if self.size != other.size || self.num_hashes != other.num_hashes {
@@ -206,6 +228,8 @@ pub struct CountingBloomFilter {
     size: usize,
     /// Number of hash functions
     num_hashes: usize,
+    /// Number of items currently in the filter
+    count: RwLock<usize>,
 }
 
 impl CountingBloomFilter {
@@ -221,6 +245,7 @@ impl CountingBloomFilter {
             counters: RwLock::new(vec![0u8; size]),
             size,
             num_hashes,
+            count: RwLock::new(0),
         }
     }
 
@@ -251,15 +276,32 @@ impl CountingBloomFilter {
         for hash in hashes {
             counters[hash] = counters[hash].saturating_add(1);
         }
+
+        let mut count = self.count.write().unwrap();
+        *count += 1;
     }
 
+    /// Remove one occurrence of an item
+    ///
+    /// Items that are definitely absent are ignored, so removing something
+    /// that was never added can't knock out counters other items share.
+    /// Counters stuck at `u8::MAX` have lost their true count and stay
+    /// there, so a removal can't clear an item still in the filter.
     pub fn remove<T: Hash>(&self, item: &T) {
         let hashes = self.hashes(item);
         let mut counters = self.counters.write().unwrap();
-        
+        if !hashes.iter().all(|&hash| counters[hash] > 0) {
+            return;
+        }
+
         for hash in hashes {
-            counters[hash] = counters[hash].saturating_sub(1);
+            if counters[hash] < u8::MAX {
+                counters[hash] -= 1;
+            }
         }
+
+        let mut count = self.count.write().unwrap();
+        *count = count.saturating_sub(1);
     }
 
     pub fn contains<T: Hash>(&self, item: &T) -> bool {
@@ -280,6 +322,67 @@ impl CountingBloomFilter {
     pub fn contains_str(&self, s: &str) -> bool {
         self.contains(&s.to_lowercase())
     }
+
+    /// Get the approximate false positive rate for the items currently held
+    pub fn false_positive_rate(&self) -> f64 {
+        let count = *self.count.read().unwrap();
+        if count == 0 {
+            return 0.0;
+        }
+
+        let k = self.num_hashes as f64;
+        let n = count as f64;
+        let m = self.size as f64;
+
+        (1.0 - (-k * n / m).exp()).powf(k)
+    }
+
+    /// Get statistics (`bits_set` counts non-zero counters)
+    pub fn stats(&self) -> BloomStats {
+        let counters = self.counters.read().unwrap();
+        let set = counters.iter().filter(|&&c| c > 0).count();
+
+        BloomStats {
+            size: self.size,
+            num_hashes: self.num_hashes,
+            items_added: *self.count.read().unwrap(),
+            bits_set: set,
+            fill_ratio: set as f64 / self.size as f64,
+            estimated_fpr: self.false_positive_rate(),
+        }
+    }
+
+    /// Clear the filter
+    pub fn clear(&self) {
+        let mut counters = self.counters.write().unwrap();
+        counters.fill(0);
+        let mut count = self.count.write().unwrap();
+        *count = 0;
+    }
+
+    /// Raw counters, one byte each (for persistence, see `from_bytes`)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.counters.read().unwrap().clone()
+    }
+
+    /// Rebuild a filter from `to_bytes` output
+    pub fn from_bytes(size: usize, num_hashes: usize, items: usize, bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() != size {
+            return Err("Counting bloom filter byte length doesn't match size");
+        }
+        Ok(Self {
+            counters: RwLock::new(bytes.to_vec()),
+            size,
+            num_hashes,
+            count: RwLock::new(items),
+        })
+    }
+}
+
+impl Default for CountingBloomFilter {
+    fn default() -> Self {
+        Self::new(10000, 0.01)
+    }
 }
 
 #[cfg(test)]
@@ -340,6 +443,44 @@ mod tests {
         assert!(!bloom.contains_str("test"));
     }
 
+    #[test]
+    fn test_counting_bloom_saturated_counters_are_sticky() {
+        let bloom = CountingBloomFilter::new(100, 0.01);
+        for _ in 0..300 {
+            bloom.add_str("rust");
+        }
+        bloom.add_str("rust");
+
+        // More removals than the counter can remember must not clear it
+        for _ in 0..300 {
+            bloom.remove_str("rust");
+        }
+        assert!(bloom.contains_str("rust"));
+    }
+
+    #[test]
+    fn test_counting_bloom_shared_terms_and_roundtrip() {
+        let bloom = CountingBloomFilter::new(100, 0.01);
+        bloom.add_str("rust");
+        bloom.add_str("rust");
+        bloom.add_str("memory");
+
+        // Removing a term that was never added is a no-op
+        bloom.remove_str("python");
+        assert_eq!(bloom.stats().items_added, 3);
+
+        bloom.remove_str("rust");
+        assert!(bloom.contains_str("rust"));
+
+        let restored = CountingBloomFilter::from_bytes(
+            bloom.stats().size, bloom.stats().num_hashes, 2, &bloom.to_bytes(),
+        ).unwrap();
+        restored.remove_str("rust");
+        assert!(!restored.contains_str("rust"));
+        assert!(restored.contains_str("memory"));
+        assert!(CountingBloomFilter::from_bytes(10, 3, 0, &[0u8; 2]).is_err());
+    }
+
     #[test]
     fn test_bloom_merge() {
         let bloom1 = BloomFilter::with_size(1000, 3);
@@ -353,4 +494,16 @@ mod tests {
         assert!(bloom1.contains_str("hello"));
         assert!(bloom1.contains_str("world"));
     }
+
+    #[test]
+    fn test_bloom_bytes_roundtrip() {
+        let bloom = BloomFilter::with_size(1001, 3);
+        bloom.add_str("hello");
+        bloom.add_str("rust");
+
+        let restored = BloomFilter::from_bytes(1001, 3, 2, &bloom.to_bytes()).unwrap();
+        assert!(restored.contains_str("hello"));
+        assert!(restored.contains_str("rust"));
+        assert_eq!(restored.stats().bits_set, bloom.stats().bits_set);
+    }
 }
@@ -11,13 +11,18 @@
 use lru::LruCache;
 use std::num::NonZeroUsize;
 use std::sync::{Arc, RwLock};
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
 use std::path::Path;
 use std::io::{BufReader, BufWriter};
 
 use crate::embedding::Embedder;
 
+/// Magic bytes opening a persisted cache file
+const CACHE_MAGIC: &[u8; 4] = b"MBEC";
+
+/// Bumped whenever the file layout or cache keys change (2: FNV-1a keys;
+/// v1 keys came from `DefaultHasher`, which Rust doesn't keep stable)
+pub const CACHE_FORMAT_VERSION: u32 = 2;
+
 /// Cached embedder wrapper with LRU cache
 pub struct CachedEmbedder<E: Embedder> {
     inner: E,
@@ -113,7 +118,10 @@ impl<E: Embedder> CachedEmbedder<E> {
     }
 
     /// Save cache to disk for persistence (binary format)
-    pub fn save_to_disk<P: AsRef<Path>>(&self, path: P) -> std::io::Result<usize> {
+    ///
+    /// The header records the format version and the inner embedder's
+    /// [`Embedder::id`] and dimension; see [`CachedEmbedder::load_from_disk`].
+    pub fn persist_to_disk<P: AsRef<Path>>(&self, path: P) -> std::io::Result<usize> {
         use std::io::Write;
         
         let cache = self.cache.read().unwrap();
@@ -121,8 +129,14 @@ impl<E: Embedder> CachedEmbedder<E> {
         let mut writer = BufWriter::new(file);
         
         let count = cache.len();
+        let id = self.inner.id();
         
-        // Write header: entry count
+        // Write header: magic, version, embedder id, dimension, entry count
+        writer.write_all(CACHE_MAGIC)?;
+        writer.write_all(&CACHE_FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&(id.len() as u32).to_le_bytes())?;
+        writer.write_all(id.as_bytes())?;
+        writer.write_all(&(self.inner.dimension() as u32).to_le_bytes())?;
         writer.write_all(&(count as u64).to_le_bytes())?;
         
         // Write each entry: key (u64) + embedding_len (u32) + embedding data
@@ -133,25 +147,65 @@ impl<E: Embedder> CachedEmbedder<E> {
                 writer.write_all(&val.to_le_bytes())?;
             }
         }
+        writer.flush()?;
         
         Ok(count)
     }
 
+    /// Old name of [`CachedEmbedder::persist_to_disk`]
+    #[deprecated(note = "renamed to `persist_to_disk`")]
+    pub fn save_to_disk<P: AsRef<Path>>(&self, path: P) -> std::io::Result<usize> {
+        self.persist_to_disk(path)
+    }
+
     /// Load cache from disk
+    ///
+    /// Fails with `InvalidData`, leaving the cache untouched, if the file
+    /// was written by another format version, embedder or dimension.
     pub fn load_from_disk<P: AsRef<Path>>(&self, path: P) -> std::io::Result<usize> {
-        use std::io::Read;
+        use std::io::{Error, ErrorKind, Read};
         
         let file = std::fs::File::open(path)?;
         let mut reader = BufReader::new(file);
         
         // Read header
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != CACHE_MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not an embedding cache file"));
+        }
+        let mut u32_bytes = [0u8; 4];
+        reader.read_exact(&mut u32_bytes)?;
+        let version = u32::from_le_bytes(u32_bytes);
+        if version != CACHE_FORMAT_VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("cache format v{}, expected v{}", version, CACHE_FORMAT_VERSION),
+            ));
+        }
+        reader.read_exact(&mut u32_bytes)?;
+        let mut id = vec![0u8; u32::from_le_bytes(u32_bytes) as usize];
+        reader.read_exact(&mut id)?;
+        let id = String::from_utf8_lossy(&id);
+        reader.read_exact(&mut u32_bytes)?;
+        let dimension = u32::from_le_bytes(u32_bytes) as usize;
+        if id != self.inner.id() || dimension != self.inner.dimension() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "cache written by {} ({}d), current embedder is {} ({}d)",
+                    id, dimension, self.inner.id(), self.inner.dimension()
+                ),
+            ));
+        }
+
         let mut count_bytes = [0u8; 8];
         reader.read_exact(&mut count_bytes)?;
         let count = u64::from_le_bytes(count_bytes) as usize;
         
-        let mut cache = self.cache.write().unwrap();
-        
-        // Read entries
+        // Read every entry before touching the cache, so a truncated file
+        // loads nothing
+        let mut entries = Vec::new();
         for _ in 0..count {
             let mut key_bytes = [0u8; 8];
             reader.read_exact(&mut key_bytes)?;
@@ -168,6 +222,11 @@ impl<E: Embedder> CachedEmbedder<E> {
                 embedding.push(f32::from_le_bytes(val_bytes));
             }
             
+            entries.push((key, embedding));
+        }
+
+        let mut cache = self.cache.write().unwrap();
+        for (key, embedding) in entries {
             cache.put(key, embedding);
         }
         
@@ -176,92 +235,148 @@ impl<E: Embedder> CachedEmbedder<E> {
 
     /// Hash text for cache key
     fn hash_text(text: &str) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        text.hash(&mut hasher);
-        hasher.finish()
-    }
-
-    /// Batch embed multiple texts efficiently
-    pub fn embed_batch(&self, texts: &[&str]) -> Vec<Vec<f32>> {
-        let mut results = Vec::with_capacity(texts.len());
-        let mut to_compute: Vec<(usize, &str)> = Vec::new();
-
-        // Check cache first
-        {
-            let cache = self.cache.read().unwrap();
-            for (i, text) in texts.iter().enumerate() {
-                let key = Self::hash_text(text);
-                if let Some(embedding) = cache.peek(&key) {
-                    results.push((i, embedding.clone()));
-                } else {
-                    to_compute.push((i, *text));
-                }
-            }
-        }
-
-        // Update hit/miss counts
-        {
-            *self.hits.write().unwrap() += results.len() as u64;
-            *self.misses.write().unwrap() += to_compute.len() as u64;
-        }
-
-        // Compute missing embeddings
-        let mut computed: Vec<(usize, Vec<f32>)> = Vec::new();
-        for (i, text) in &to_compute {
-            let embedding = self.inner.embed(text);
-            computed.push((*i, embedding));
-        }
-
-        // Update cache with new embeddings
-        {
-            let mut cache = self.cache.write().unwrap();
-            for ((_, text), (_, ref embedding)) in to_compute.iter().zip(computed.iter()) {
-                let key = Self::hash_text(text);
-                cache.put(key, embedding.clone());
-            }
-        }
-
-        // Merge results
-        results.extend(computed);
-        results.sort_by_key(|(i, _)| *i);
-        results.into_iter().map(|(_, v)| v).collect()
+        cache_key(&[text])
     }
 
     /// Preload cache with texts (useful for warmup)
     pub fn preload(&self, texts: &[&str]) {
         let _ = self.embed_batch(texts);
     }
+
+    /// [`Embedder::embed_batch`], callable without the trait in scope
+    pub fn embed_batch(&self, texts: &[&str]) -> Vec<Vec<f32>> {
+        Embedder::embed_batch(self, texts)
+    }
 }
 
-impl<E: Embedder> Embedder for CachedEmbedder<E> {
-    fn embed(&self, text: &str) -> Vec<f32> {
-        let key = Self::hash_text(text);
+/// FNV-1a over `parts`, each followed by 0xff (never part of UTF-8)
+///
+/// Keys are persisted by `persist_to_disk`, so they must not change
+/// between Rust releases (unlike `DefaultHasher`).
+fn cache_key(parts: &[&str]) -> u64 {
+    parts
+        .iter()
+        .flat_map(|part| part.bytes().chain(std::iter::once(0xff)))
+        .fold(0xcbf2_9ce4_8422_2325, |hash, b| (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3))
+}
 
+impl<E: Embedder> CachedEmbedder<E> {
+    /// Cache key for one side of an asymmetric embedder
+    fn hash_role(role: &str, text: &str) -> u64 {
+        cache_key(&[role, text])
+    }
+
+    /// Cached embedding for `key`, computed and stored on a miss
+    ///
+    /// Errors are passed through without touching the cache.
+    fn cached(&self, key: u64, compute: impl FnOnce() -> Result<Vec<f32>, String>) -> Result<Vec<f32>, String> {
         // Try to get from cache first
         {
             let mut cache = self.cache.write().unwrap();
             if let Some(embedding) = cache.get(&key) {
                 *self.hits.write().unwrap() += 1;
-                return embedding.clone();
+                return Ok(embedding.clone());
             }
         }
 
         // Cache miss - compute embedding
         *self.misses.write().unwrap() += 1;
-        let embedding = self.inner.embed(text);
+        let embedding = compute()?;
 
         // Store in cache
-        {
+        if !is_fallback(&embedding) {
             let mut cache = self.cache.write().unwrap();
             cache.put(key, embedding.clone());
         }
 
-        embedding
+        Ok(embedding)
+    }
+}
+
+/// Zero (or empty) vectors are what failing remote embedders return from
+/// the infallible methods; they are never cached.
+fn is_fallback(embedding: &[f32]) -> bool {
+    embedding.iter().all(|&x| x == 0.0)
+}
+
+impl<E: Embedder> Embedder for CachedEmbedder<E> {
+    fn embed(&self, text: &str) -> Vec<f32> {
+        self.cached(Self::hash_text(text), || Ok(self.inner.embed(text))).unwrap_or_default()
+    }
+
+    /// Cached apart from `embed`, so asymmetric inner embedders keep both sides
+    fn embed_query(&self, text: &str) -> Vec<f32> {
+        self.cached(Self::hash_role("query", text), || Ok(self.inner.embed_query(text))).unwrap_or_default()
+    }
+
+    fn embed_document(&self, text: &str) -> Vec<f32> {
+        self.try_embed_document(text).unwrap_or_else(|_| vec![0.0; self.dimension()])
+    }
+
+    fn try_embed_document(&self, text: &str) -> Result<Vec<f32>, String> {
+        self.cached(Self::hash_role("document", text), || self.inner.try_embed_document(text))
     }
 
     fn dimension(&self) -> usize {
         self.inner.dimension()
     }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        Some(self.stats())
+    }
+
+    /// Check the cache per text and embed only the distinct misses, in
+    /// one `embed_batch` call on the inner embedder
+    fn embed_batch(&self, texts: &[&str]) -> Vec<Vec<f32>> {
+        let keys: Vec<u64> = texts.iter().map(|text| Self::hash_text(text)).collect();
+        let mut results: Vec<Option<Vec<f32>>> = vec![None; texts.len()];
+        let mut misses: Vec<(u64, &str)> = Vec::new();
+        let mut missed = std::collections::HashSet::new();
+
+        // Check cache first
+        {
+            let mut cache = self.cache.write().unwrap();
+            for (i, (&key, text)) in keys.iter().zip(texts).enumerate() {
+                if let Some(embedding) = cache.get(&key) {
+                    results[i] = Some(embedding.clone());
+                } else if missed.insert(key) {
+                    misses.push((key, *text));
+                }
+            }
+        }
+
+        // Compute each distinct miss once
+        let miss_texts: Vec<&str> = misses.iter().map(|(_, text)| *text).collect();
+        let computed = if miss_texts.is_empty() {
+            Vec::new()
+        } else {
+            self.inner.embed_batch(&miss_texts)
+        };
+
+        // Update cache and hit/miss counts (repeats of a miss count as hits)
+        {
+            let mut cache = self.cache.write().unwrap();
+            for ((key, _), embedding) in misses.iter().zip(&computed) {
+                if !is_fallback(embedding) {
+                    cache.put(*key, embedding.clone());
+                }
+            }
+        }
+        *self.misses.write().unwrap() += misses.len() as u64;
+        *self.hits.write().unwrap() += (texts.len() - misses.len()) as u64;
+
+        // Merge results
+        let computed: std::collections::HashMap<u64, Vec<f32>> = misses
+            .iter()
+            .map(|(key, _)| *key)
+            .zip(computed)
+            .collect();
+        results
+            .into_iter()
+            .zip(&keys)
+            .map(|(cached, key)| cached.unwrap_or_else(|| computed.get(key).cloned().unwrap_or_default()))
+            .collect()
+    }
 }
 
 /// Cache statistics
@@ -333,6 +448,16 @@ mod tests {
     use super::*;
     use crate::embedding::HashEmbedder;
 
+    #[test]
+    fn test_cache_keys_are_stable() {
+        // Persisted caches depend on these exact values
+        assert_eq!(CachedEmbedder::<HashEmbedder>::hash_text("hello"), 0xa9bd_73cc_a220_c59c);
+        assert_ne!(
+            CachedEmbedder::<HashEmbedder>::hash_role("query", "hello"),
+            CachedEmbedder::<HashEmbedder>::hash_role("queryh", "ello")
+        );
+    }
+
     #[test]
     fn test_cached_embedder() {
         let inner = HashEmbedder::new(128);
@@ -369,6 +494,72 @@ mod tests {
         assert_eq!(stats.size, 3); // 3 unique texts cached
     }
 
+    /// Counts inner `embed` calls
+    struct CountingEmbedder {
+        inner: HashEmbedder,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl Embedder for CountingEmbedder {
+        fn embed(&self, text: &str) -> Vec<f32> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.inner.embed(text)
+        }
+
+        fn dimension(&self) -> usize {
+            self.inner.dimension()
+        }
+
+        fn id(&self) -> String {
+            self.inner.id()
+        }
+    }
+
+    #[test]
+    fn test_persisted_cache_serves_hits_and_rejects_other_embedders() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("embedding_cache.bin");
+
+        let cached = CachedEmbedder::new(HashEmbedder::new(64), 100);
+        let original = cached.embed("persisted text");
+        assert_eq!(cached.persist_to_disk(&path).unwrap(), 1);
+
+        // A fresh process: loaded entries are hits, the inner embedder is skipped
+        let counting = CountingEmbedder {
+            inner: HashEmbedder::new(64),
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        };
+        let restarted = CachedEmbedder::new(counting, 100);
+        assert_eq!(restarted.load_from_disk(&path).unwrap(), 1);
+        assert_eq!(restarted.embed("persisted text"), original);
+        assert_eq!(restarted.stats().hits, 1);
+        assert_eq!(restarted.inner.calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        // Another dimension (or embedder) must not reuse the vectors
+        let other = CachedEmbedder::new(HashEmbedder::new(128), 100);
+        let err = other.load_from_disk(&path).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(other.stats().size, 0);
+    }
+
+    #[test]
+    fn test_batch_processor_embeds_repeats_once() {
+        let counting = CountingEmbedder {
+            inner: HashEmbedder::new(64),
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        };
+        let processor = BatchProcessor::new(counting, 1000, 100);
+
+        let lines: Vec<String> = (0..1000).map(|i| format!("line {}", i % 10)).collect();
+        let texts: Vec<&str> = lines.iter().map(|s| s.as_str()).collect();
+        let embeddings = processor.process_batch(&texts, |_, embedding| embedding);
+
+        assert_eq!(embeddings.len(), 1000);
+        assert_eq!(embeddings[3], embeddings[13]);
+        assert_eq!(processor.embedder.inner.calls.load(std::sync::atomic::Ordering::SeqCst), 10);
+        assert!(processor.stats().hit_rate > 0.98);
+    }
+
     #[test]
     fn test_cache_stats() {
         let inner = HashEmbedder::new(128);
@@ -416,7 +607,7 @@ mod tests {
         
         // Save to temp file
         let path = PathBuf::from("/tmp/test_cache.bin");
-        let saved = cached.save_to_disk(&path).unwrap();
+        let saved = cached.persist_to_disk(&path).unwrap();
         assert_eq!(saved, 10);
         
         // Create new cache and load
@@ -0,0 +1,139 @@
+//! JSON Output Schema
+//!
+//! Stable structs printed by the CLI in `--json` mode (recall, search,
+//! list, stats). Kept in the library so the server can reuse the schema.
+
+use crate::types::{MemoryItem, MemoryType};
+use serde::{Deserialize, Serialize};
+
+/// One memory in JSON output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonMemory {
+    pub id: String,
+    pub content: String,
+    /// `working` | `episodic` | `semantic` | `procedural`
+    #[serde(rename = "type")]
+    pub memory_type: String,
+    pub strength: f32,
+    pub tags: Vec<String>,
+    /// Query similarity (semantic search only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f32>,
+    /// RFC 3339 timestamp
+    pub created_at: String,
+}
+
+impl JsonMemory {
+    pub fn new(item: &MemoryItem) -> Self {
+        Self {
+            id: item.id.to_string(),
+            content: item.content.clone(),
+            memory_type: type_name(&item.memory_type).to_string(),
+            strength: item.strength,
+            tags: item.tags.clone(),
+            similarity: None,
+            created_at: item.created_at.to_rfc3339(),
+        }
+    }
+
+    pub fn with_similarity(mut self, similarity: f32) -> Self {
+        self.similarity = Some(similarity);
+        self
+    }
+}
+
+impl From<&MemoryItem> for JsonMemory {
+    fn from(item: &MemoryItem) -> Self {
+        Self::new(item)
+    }
+}
+
+/// Brain statistics in JSON output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonStats {
+    pub vecdb_vectors: usize,
+    pub working: usize,
+    pub working_capacity: usize,
+    pub semantic: usize,
+    pub episodic: usize,
+    pub procedural: usize,
+    pub embedding_dim: usize,
+    /// Database size on disk, if it could be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_size_bytes: Option<u64>,
+}
+
+/// Lowercase type name used in JSON output
+pub fn type_name(memory_type: &MemoryType) -> &'static str {
+    match memory_type {
+        MemoryType::Working => "working",
+        MemoryType::Episodic => "episodic",
+        MemoryType::Semantic => "semantic",
+        MemoryType::Procedural => "procedural",
+    }
+}
+
+/// Serialize memories (recall / list output)
+pub fn memories_to_json(items: &[MemoryItem]) -> Result<String, serde_json::Error> {
+    let out: Vec<JsonMemory> = items.iter().map(JsonMemory::new).collect();
+    serde_json::to_string_pretty(&out)
+}
+
+/// Serialize `(memory, similarity)` pairs (semantic search output)
+pub fn search_results_to_json(results: &[(MemoryItem, f32)]) -> Result<String, serde_json::Error> {
+    let out: Vec<JsonMemory> = results
+        .iter()
+        .map(|(item, similarity)| JsonMemory::new(item).with_similarity(*similarity))
+        .collect();
+    serde_json::to_string_pretty(&out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> MemoryItem {
+        let mut item = MemoryItem::new("Rust is a systems language", None).with_type(MemoryType::Semantic);
+        item.tags = vec!["rust".to_string()];
+        item
+    }
+
+    #[test]
+    fn test_recall_and_list_json_roundtrip() {
+        let item = sample();
+        let json = memories_to_json(&[item.clone()]).unwrap();
+        assert!(!json.contains("similarity"));
+
+        let parsed: Vec<JsonMemory> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, vec![JsonMemory::new(&item)]);
+        assert_eq!(parsed[0].memory_type, "semantic");
+        assert_eq!(parsed[0].id, item.id.to_string());
+    }
+
+    #[test]
+    fn test_search_json_includes_similarity() {
+        let item = sample();
+        let json = search_results_to_json(&[(item.clone(), 0.75)]).unwrap();
+
+        let parsed: Vec<JsonMemory> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0].similarity, Some(0.75));
+        assert_eq!(parsed[0].content, item.content);
+    }
+
+    #[test]
+    fn test_stats_json_roundtrip() {
+        let stats = JsonStats {
+            vecdb_vectors: 0,
+            working: 2,
+            working_capacity: 7,
+            semantic: 10,
+            episodic: 4,
+            procedural: 1,
+            embedding_dim: 50,
+            db_size_bytes: None,
+        };
+        let json = serde_json::to_string(&stats).unwrap();
+        let parsed: JsonStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, stats);
+    }
+}
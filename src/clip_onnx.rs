@@ -4,7 +4,12 @@
 //! Models can be downloaded from Hugging Face or exported from PyTorch.
 
 use crate::visual::{ClipError, ClipProvider};
+use lru::LruCache;
+use std::num::NonZeroUsize;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[cfg(feature = "clip")]
 use ort::{Environment, Session, SessionBuilder, Value};
@@ -196,19 +201,223 @@ impl ClipProvider for ClipOnnx {
     }
 }
 
+// ============ Native ONNX CLIP (ViT-B/32) ============
+
+/// Vision tower file in an ONNX CLIP model directory
+pub const ONNX_VISION_MODEL: &str = "vision_model.onnx";
+/// Text tower file in an ONNX CLIP model directory
+pub const ONNX_TEXT_MODEL: &str = "text_model.onnx";
+/// Hugging Face `tokenizer.json` (CLIP BPE vocabulary and merges)
+pub const ONNX_TOKENIZER: &str = "tokenizer.json";
+
+/// CLIP input resolution
+pub const CLIP_IMAGE_SIZE: u32 = 224;
+/// CLIP text context length
+pub const CLIP_CONTEXT_LENGTH: usize = 77;
+
+const CLIP_MEAN: [f32; 3] = [0.48145466, 0.4578275, 0.40821073];
+const CLIP_STD: [f32; 3] = [0.26862954, 0.26130258, 0.27577711];
+const CLIP_START_TOKEN: i64 = 49406;
+const CLIP_END_TOKEN: i64 = 49407;
+
+/// Local CLIP ViT-B/32 inference via ONNX Runtime (no Python server)
+///
+/// Expects a model directory laid out like the Hugging Face
+/// `Xenova/clip-vit-base-patch32` export:
+/// `vision_model.onnx`, `text_model.onnx` and `tokenizer.json`.
+pub struct OnnxClipProvider {
+    #[cfg(feature = "clip-onnx")]
+    vision: Mutex<ort::session::Session>,
+    #[cfg(feature = "clip-onnx")]
+    text: Mutex<ort::session::Session>,
+    #[cfg(feature = "clip-onnx")]
+    tokenizer: tokenizers::Tokenizer,
+    embedding_dim: usize,
+}
+
+impl OnnxClipProvider {
+    /// Whether `model_dir` holds every file the provider needs
+    pub fn is_available(model_dir: &Path) -> bool {
+        [ONNX_VISION_MODEL, ONNX_TEXT_MODEL, ONNX_TOKENIZER]
+            .iter()
+            .all(|f| model_dir.join(f).is_file())
+    }
+}
+
+#[cfg(feature = "clip-onnx")]
+impl OnnxClipProvider {
+    /// Load the vision and text towers from `model_dir`
+    pub fn new(model_dir: &Path) -> Result<Self, ClipError> {
+        for file in [ONNX_VISION_MODEL, ONNX_TEXT_MODEL, ONNX_TOKENIZER] {
+            if !model_dir.join(file).is_file() {
+                return Err(ClipError::ModelError(format!(
+                    "{} not found in {:?}", file, model_dir
+                )));
+            }
+        }
+        
+        let vision = load_session(&model_dir.join(ONNX_VISION_MODEL))?;
+        let text = load_session(&model_dir.join(ONNX_TEXT_MODEL))?;
+        let tokenizer = tokenizers::Tokenizer::from_file(model_dir.join(ONNX_TOKENIZER))
+            .map_err(|e| ClipError::ModelError(format!("Failed to load tokenizer: {}", e)))?;
+        
+        Ok(Self {
+            vision: Mutex::new(vision),
+            text: Mutex::new(text),
+            tokenizer,
+            embedding_dim: 512, // CLIP ViT-B/32 projection size
+        })
+    }
+    
+    /// Token ids padded/truncated to the 77-token CLIP context
+    fn tokenize(&self, text: &str) -> Result<Vec<i64>, ClipError> {
+        let encoding = self.tokenizer
+            .encode(text, false)
+            .map_err(|e| ClipError::EncodingError(e.to_string()))?;
+        
+        let mut ids = Vec::with_capacity(CLIP_CONTEXT_LENGTH);
+        ids.push(CLIP_START_TOKEN);
+        ids.extend(encoding.get_ids().iter().take(CLIP_CONTEXT_LENGTH - 2).map(|&id| id as i64));
+        // The text tower pools at the first end token; padding with it is harmless
+        ids.resize(CLIP_CONTEXT_LENGTH, CLIP_END_TOKEN);
+        Ok(ids)
+    }
+    
+    /// Run one tower and return its L2-normalized projected embedding
+    fn run(
+        &self,
+        session: &Mutex<ort::session::Session>,
+        inputs: Vec<(&'static str, ort::value::DynValue)>,
+        output_name: &str,
+    ) -> Result<Vec<f32>, ClipError> {
+        let mut session = session.lock().unwrap_or_else(|e| e.into_inner());
+        let outputs = session
+            .run(inputs)
+            .map_err(|e| ClipError::ModelError(format!("Inference failed: {}", e)))?;
+        
+        let output = outputs.get(output_name).unwrap_or(&outputs[0]);
+        let (_, data) = output
+            .try_extract_tensor::<f32>()
+            .map_err(|e| ClipError::EncodingError(e.to_string()))?;
+        
+        if data.len() != self.embedding_dim {
+            return Err(ClipError::EncodingError(format!(
+                "Expected a {}-d {}, got {} values", self.embedding_dim, output_name, data.len()
+            )));
+        }
+        Ok(l2_normalize(data))
+    }
+}
+
+#[cfg(feature = "clip-onnx")]
+fn load_session(path: &Path) -> Result<ort::session::Session, ClipError> {
+    ort::session::Session::builder()
+        .and_then(|b| b.commit_from_file(path))
+        .map_err(|e| ClipError::ModelError(format!("Failed to load {:?}: {}", path, e)))
+}
+
+/// CLIP image preprocessing: shortest side to 224, center crop, normalize (CHW)
+#[cfg(feature = "clip-onnx")]
+pub fn clip_preprocess(img: &image::DynamicImage) -> Vec<f32> {
+    let size = CLIP_IMAGE_SIZE;
+    let rgb = img
+        .resize_to_fill(size, size, image::imageops::FilterType::CatmullRom)
+        .to_rgb8();
+    
+    let plane = (size * size) as usize;
+    let mut pixels = vec![0.0f32; 3 * plane];
+    for (x, y, pixel) in rgb.enumerate_pixels() {
+        let offset = (y * size + x) as usize;
+        for c in 0..3 {
+            pixels[c * plane + offset] = (pixel[c] as f32 / 255.0 - CLIP_MEAN[c]) / CLIP_STD[c];
+        }
+    }
+    pixels
+}
+
+#[cfg(feature = "clip-onnx")]
+fn l2_normalize(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return v.to_vec();
+    }
+    v.iter().map(|x| x / norm).collect()
+}
+
+#[cfg(feature = "clip-onnx")]
+impl ClipProvider for OnnxClipProvider {
+    fn embed_image(&self, image_path: &Path) -> Result<Vec<f32>, ClipError> {
+        let img = image::open(image_path)
+            .map_err(|e| ClipError::ImageError(format!("Failed to open image: {}", e)))?;
+        let size = CLIP_IMAGE_SIZE as usize;
+        
+        let pixels = ort::value::Tensor::from_array(([1usize, 3, size, size], clip_preprocess(&img)))
+            .map_err(|e| ClipError::EncodingError(e.to_string()))?;
+        
+        self.run(&self.vision, vec![("pixel_values", pixels.into_dyn())], "image_embeds")
+    }
+    
+    fn embed_text(&self, text: &str) -> Result<Vec<f32>, ClipError> {
+        let ids = self.tokenize(text)?;
+        let input_ids = ort::value::Tensor::from_array(([1usize, CLIP_CONTEXT_LENGTH], ids))
+            .map_err(|e| ClipError::EncodingError(e.to_string()))?;
+        
+        self.run(&self.text, vec![("input_ids", input_ids.into_dyn())], "text_embeds")
+    }
+    
+    fn embedding_dim(&self) -> usize {
+        self.embedding_dim
+    }
+}
+
+#[cfg(not(feature = "clip-onnx"))]
+impl OnnxClipProvider {
+    pub fn new(_model_dir: &Path) -> Result<Self, ClipError> {
+        Err(ClipError::ModelError("ONNX CLIP not enabled. Rebuild with --features clip-onnx".to_string()))
+    }
+}
+
+#[cfg(not(feature = "clip-onnx"))]
+impl ClipProvider for OnnxClipProvider {
+    fn embed_image(&self, _image_path: &Path) -> Result<Vec<f32>, ClipError> {
+        Err(ClipError::ModelError("ONNX CLIP not enabled".to_string()))
+    }
+    
+    fn embed_text(&self, _text: &str) -> Result<Vec<f32>, ClipError> {
+        Err(ClipError::ModelError("ONNX CLIP not enabled".to_string()))
+    }
+    
+    fn embedding_dim(&self) -> usize {
+        self.embedding_dim
+    }
+}
+
 /// Mock CLIP provider for testing (generates random embeddings)
 pub struct MockClipProvider {
     dim: usize,
+    text_calls: AtomicU64,
+    image_calls: AtomicU64,
 }
 
 impl MockClipProvider {
     pub fn new(dim: usize) -> Self {
-        Self { dim }
+        Self { dim, text_calls: AtomicU64::new(0), image_calls: AtomicU64::new(0) }
+    }
+
+    /// Number of `embed_text` calls so far
+    pub fn text_calls(&self) -> u64 {
+        self.text_calls.load(Ordering::Relaxed)
+    }
+
+    /// Number of `embed_image` calls so far
+    pub fn image_calls(&self) -> u64 {
+        self.image_calls.load(Ordering::Relaxed)
     }
 }
 
 impl ClipProvider for MockClipProvider {
     fn embed_image(&self, image_path: &Path) -> Result<Vec<f32>, ClipError> {
+        self.image_calls.fetch_add(1, Ordering::Relaxed);
         // Generate deterministic "embedding" based on file path
         let hash = image_path.to_string_lossy().bytes()
             .fold(0u64, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u64));
@@ -228,6 +437,7 @@ impl ClipProvider for MockClipProvider {
     }
     
     fn embed_text(&self, text: &str) -> Result<Vec<f32>, ClipError> {
+        self.text_calls.fetch_add(1, Ordering::Relaxed);
         // Generate deterministic "embedding" based on text
         let hash = text.bytes()
             .fold(0u64, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u64));
@@ -367,9 +577,149 @@ impl ClipProvider for ClipServerProvider {
     }
 }
 
+/// CLIP provider that survives an intermittently available primary
+///
+/// Retries the primary with exponential backoff and only uses the secondary
+/// (usually `MockClipProvider`) once every attempt has failed. Successful
+/// primary embeddings are cached; fallback embeddings are not, so the real
+/// embedding is picked up once the primary recovers.
+pub struct FallbackClipProvider {
+    primary: Arc<dyn ClipProvider>,
+    secondary: Arc<dyn ClipProvider>,
+    max_attempts: u32,
+    backoff: Duration,
+    cache: Mutex<LruCache<String, Vec<f32>>>,
+    fallbacks: AtomicU64,
+}
+
+impl FallbackClipProvider {
+    /// Wrap a primary provider with a secondary fallback
+    /// (3 attempts, 200ms initial backoff, 1000 cached embeddings)
+    pub fn new(primary: Arc<dyn ClipProvider>, secondary: Arc<dyn ClipProvider>) -> Self {
+        Self {
+            primary,
+            secondary,
+            max_attempts: 3,
+            backoff: Duration::from_millis(200),
+            cache: Mutex::new(LruCache::new(NonZeroUsize::new(1000).unwrap())),
+            fallbacks: AtomicU64::new(0),
+        }
+    }
+
+    /// Set how many times the primary is tried before falling back
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Set the initial backoff (doubled after each failed attempt)
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Set the embedding cache size
+    pub fn with_cache_size(mut self, size: usize) -> Self {
+        let size = NonZeroUsize::new(size).unwrap_or(NonZeroUsize::new(1000).unwrap());
+        self.cache = Mutex::new(LruCache::new(size));
+        self
+    }
+
+    /// Number of requests served by the secondary provider
+    pub fn fallback_count(&self) -> u64 {
+        self.fallbacks.load(Ordering::Relaxed)
+    }
+
+    /// Embed through the primary with retries, caching under `key` (no
+    /// caching when `None`)
+    fn embed_with<F>(&self, key: Option<String>, embed: F) -> Result<Vec<f32>, ClipError>
+    where
+        F: Fn(&dyn ClipProvider) -> Result<Vec<f32>, ClipError>,
+    {
+        if let Some(embedding) = key.as_ref().and_then(|key| self.cache.lock().unwrap().get(key).cloned()) {
+            return Ok(embedding);
+        }
+
+        let mut delay = self.backoff;
+        for attempt in 1..=self.max_attempts {
+            match embed(self.primary.as_ref()) {
+                Ok(embedding) => {
+                    if let Some(key) = key {
+                        self.cache.lock().unwrap().put(key, embedding.clone());
+                    }
+                    return Ok(embedding);
+                }
+                Err(_) if attempt < self.max_attempts => {
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                }
+                Err(_) => {}
+            }
+        }
+
+        self.fallbacks.fetch_add(1, Ordering::Relaxed);
+        embed(self.secondary.as_ref())
+    }
+}
+
+impl ClipProvider for FallbackClipProvider {
+    fn embed_image(&self, image_path: &Path) -> Result<Vec<f32>, ClipError> {
+        // Keyed by mtime too, so an edited image isn't served its old
+        // embedding; unreadable files are not cached
+        let mtime = std::fs::metadata(image_path).and_then(|m| m.modified()).ok();
+        let key = mtime.map(|mtime| format!("image:{}@{:?}", image_path.display(), mtime));
+        self.embed_with(key, |p| p.embed_image(image_path))
+    }
+
+    fn embed_text(&self, text: &str) -> Result<Vec<f32>, ClipError> {
+        let key = format!("text:{}", text);
+        self.embed_with(Some(key), |p| p.embed_text(text))
+    }
+
+    fn embedding_dim(&self) -> usize {
+        self.primary.embedding_dim()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    /// Provider that fails a fixed number of times before succeeding
+    struct FlakyProvider {
+        failures_left: AtomicU32,
+        calls: AtomicU32,
+    }
+
+    impl FlakyProvider {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures_left: AtomicU32::new(failures),
+                calls: AtomicU32::new(0),
+            }
+        }
+    }
+
+    impl ClipProvider for FlakyProvider {
+        fn embed_image(&self, _image_path: &Path) -> Result<Vec<f32>, ClipError> {
+            self.embed_text("")
+        }
+
+        fn embed_text(&self, _text: &str) -> Result<Vec<f32>, ClipError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let left = self.failures_left.load(Ordering::SeqCst);
+            if left > 0 {
+                self.failures_left.store(left - 1, Ordering::SeqCst);
+                return Err(ClipError::ModelError("server down".to_string()));
+            }
+            Ok(vec![1.0, 0.0, 0.0, 0.0])
+        }
+
+        fn embedding_dim(&self) -> usize {
+            4
+        }
+    }
     
     #[test]
     fn test_mock_provider() {
@@ -383,4 +733,63 @@ mod tests {
         assert_eq!(emb1, emb2); // Same path = same embedding
         assert_ne!(emb1, emb3); // Different path = different embedding
     }
+    
+    #[test]
+    fn test_fallback_retries_primary_before_falling_back() {
+        let primary = Arc::new(FlakyProvider::new(2));
+        let provider = FallbackClipProvider::new(primary.clone(), Arc::new(MockClipProvider::new(4)))
+            .with_max_attempts(3)
+            .with_backoff(Duration::from_millis(1));
+        
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("a.jpg");
+        std::fs::write(&image, b"not really a jpeg").unwrap();
+        
+        // Two failures, then recovery on the third attempt
+        let emb = provider.embed_image(&image).unwrap();
+        assert_eq!(emb, vec![1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(provider.fallback_count(), 0);
+        
+        // Cached: primary is not called again
+        provider.embed_image(&image).unwrap();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 3);
+    }
+    
+    #[test]
+    fn test_fallback_image_cache_follows_mtime() {
+        let primary = Arc::new(FlakyProvider::new(0));
+        let provider = FallbackClipProvider::new(primary.clone(), Arc::new(MockClipProvider::new(4)));
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("a.jpg");
+        std::fs::write(&image, b"not really a jpeg").unwrap();
+        
+        provider.embed_image(&image).unwrap();
+        provider.embed_image(&image).unwrap();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+        
+        // Edited file: embedded again
+        let later = std::time::SystemTime::now() + Duration::from_secs(60);
+        std::fs::File::options().write(true).open(&image).unwrap().set_modified(later).unwrap();
+        provider.embed_image(&image).unwrap();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 2);
+    }
+    
+    #[test]
+    fn test_fallback_uses_secondary_after_max_attempts() {
+        let primary = Arc::new(FlakyProvider::new(u32::MAX));
+        let secondary = MockClipProvider::new(4);
+        let provider = FallbackClipProvider::new(primary.clone(), Arc::new(MockClipProvider::new(4)))
+            .with_max_attempts(3)
+            .with_backoff(Duration::from_millis(1));
+        
+        let emb = provider.embed_text("coffee").unwrap();
+        assert_eq!(emb, secondary.embed_text("coffee").unwrap());
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(provider.fallback_count(), 1);
+        
+        // Fallback results are not cached, so the primary is retried
+        provider.embed_text("coffee").unwrap();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 6);
+    }
 }
@@ -248,6 +248,8 @@ pub struct CompressionStats {
     pub original_bytes: usize,
     pub compressed_bytes: usize,
     pub items_compressed: usize,
+    /// Mean absolute error per value after a round trip
+    pub mean_error: f64,
 }
 
 impl CompressionStats {
@@ -270,12 +272,13 @@ impl std::fmt::Display for CompressionStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Compression: {} items, {:.1} KB → {:.1} KB ({:.1}x, {:.1}% saved)",
+            "Compression: {} items, {:.1} KB → {:.1} KB ({:.1}x, {:.1}% saved, mean error {:.5})",
             self.items_compressed,
             self.original_bytes as f64 / 1024.0,
             self.compressed_bytes as f64 / 1024.0,
             self.ratio(),
-            self.savings_percent()
+            self.savings_percent(),
+            self.mean_error
         )
     }
 }
@@ -284,6 +287,7 @@ impl std::fmt::Display for CompressionStats {
 pub fn compress_embeddings(embeddings: &[Vec<f32>]) -> (Vec<CompressedF32>, CompressionStats) {
     let mut compressed = Vec::with_capacity(embeddings.len());
     let mut stats = CompressionStats::default();
+    let mut errors = ErrorSum::default();
 
     for embedding in embeddings {
         let original_size = embedding.len() * 4;
@@ -292,13 +296,53 @@ pub fn compress_embeddings(embeddings: &[Vec<f32>]) -> (Vec<CompressedF32>, Comp
         stats.original_bytes += original_size;
         stats.compressed_bytes += comp.size_bytes();
         stats.items_compressed += 1;
+        errors.add(embedding, &DeltaEncoder::decode_f32(&comp));
         
         compressed.push(comp);
     }
 
+    stats.mean_error = errors.mean();
     (compressed, stats)
 }
 
+/// Savings and error of storing each embedding as a [`QuantizedEmbedding`]
+pub fn quantization_stats(embeddings: &[Vec<f32>]) -> CompressionStats {
+    let mut stats = CompressionStats::default();
+    let mut errors = ErrorSum::default();
+
+    for embedding in embeddings {
+        let quantized = QuantizedEmbedding::from_f32(embedding);
+        stats.original_bytes += quantized.original_size_bytes();
+        stats.compressed_bytes += quantized.size_bytes();
+        stats.items_compressed += 1;
+        errors.add(embedding, &quantized.to_f32());
+    }
+
+    stats.mean_error = errors.mean();
+    stats
+}
+
+/// Running sum of absolute round-trip errors
+#[derive(Default)]
+struct ErrorSum {
+    total: f64,
+    values: usize,
+}
+
+impl ErrorSum {
+    fn add(&mut self, original: &[f32], restored: &[f32]) {
+        self.total += original.iter().zip(restored).map(|(a, b)| (a - b).abs() as f64).sum::<f64>();
+        self.values += original.len();
+    }
+
+    fn mean(&self) -> f64 {
+        if self.values == 0 {
+            return 0.0;
+        }
+        self.total / self.values as f64
+    }
+}
+
 /// Decompress a batch of embeddings
 pub fn decompress_embeddings(compressed: &[CompressedF32]) -> Vec<Vec<f32>> {
     compressed.iter().map(|c| DeltaEncoder::decode_f32(c)).collect()
@@ -407,4 +451,18 @@ mod tests {
         
         println!("{}", stats);
     }
+
+    #[test]
+    fn test_quantization_stats() {
+        let embeddings: Vec<Vec<f32>> = (0..10)
+            .map(|j| (0..128).map(|i| ((i + j) as f32 / 64.0) - 1.0).collect())
+            .collect();
+
+        let stats = quantization_stats(&embeddings);
+        assert_eq!(stats.items_compressed, 10);
+        assert_eq!(stats.original_bytes, 10 * 128 * 4);
+        assert!(stats.savings_percent() > 70.0, "{}", stats);
+        // Half a quantization step (range / 255 / 2) at most
+        assert!(stats.mean_error > 0.0 && stats.mean_error < 2.0 / 255.0, "{}", stats);
+    }
 }
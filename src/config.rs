@@ -0,0 +1,466 @@
+//! Configuration - `~/.memory-brain/config.toml`
+//!
+//! Settings are resolved with the following precedence (highest first):
+//! 1. CLI flags (e.g. `serve --port`, `recall --limit`)
+//! 2. Environment variables
+//! 3. Config file (`$MEMORY_BRAIN_CONFIG` or `~/.memory-brain/config.toml`)
+//! 4. Built-in defaults
+//!
+//! ```toml
+//! db_path = "/data/memory-brain/coredb"
+//! vecdb_url = "http://localhost:3100"
+//!
+//! [embedding]
+//! provider = "auto"          # auto | http | ollama | openai | glove | tfidf | test
+//! server_url = "http://localhost:3200"
+//! model = "nomic-embed-text"  # ollama/openai embedding model
+//! api_url = "http://localhost:11434"  # ollama/openai base URL (provider default if unset)
+//! glove_path = "/data/glove.6B.100d.txt"
+//! tfidf_path = "/data/tfidf.json"
+//! cache_path = "/data/embedding_cache.bin"  # ollama/openai embedding cache
+//! quantize = false           # store embeddings as int8 in CoreDB
+//!
+//! [llm]
+//! provider = "ollama"        # auto | ollama | mlx | openai | echo
+//! model = "llama3.2"
+//!
+//! [server]
+//! host = "0.0.0.0"
+//! port = 3030
+//!
+//! [visual]
+//! clip_server_url = "http://localhost:5050"
+//! clip_model_dir = "/data/models/clip-vit-b32"   # native ONNX CLIP (--features clip-onnx)
+//! db_path = "/data/memory-brain/visual.db"
+//!
+//! [thresholds]
+//! link = 0.4
+//! search = 0.05
+//! merge = 0.85
+//! recall_limit = 5
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Top-level configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// CoreDB directory for text memories
+    pub db_path: PathBuf,
+    /// CoreVecDB URL
+    pub vecdb_url: String,
+    pub embedding: EmbeddingConfig,
+    pub llm: LlmConfig,
+    pub server: ServerConfig,
+    pub visual: VisualConfig,
+    pub thresholds: Thresholds,
+}
+
+/// Embedder selection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbeddingConfig {
+    /// auto | http | ollama | openai | glove | tfidf | test
+    pub provider: String,
+    /// BGE-M3 embedding server URL
+    pub server_url: String,
+    /// Embedding model for the ollama/openai providers (provider default if unset)
+    pub model: Option<String>,
+    /// Base URL for the ollama/openai providers (provider default if unset)
+    pub api_url: Option<String>,
+    /// GloVe vectors file
+    pub glove_path: PathBuf,
+    /// Fitted TF-IDF vocabulary (written by `reindex --embedder tfidf`)
+    pub tfidf_path: PathBuf,
+    /// Persisted ollama/openai embedding cache (loaded on start, saved on exit)
+    pub cache_path: PathBuf,
+    /// Store embeddings as base64 int8 instead of JSON floats
+    pub quantize: bool,
+}
+
+/// LLM backend selection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LlmConfig {
+    /// auto | ollama | mlx | openai | echo
+    pub provider: String,
+    /// Model name (provider default if unset)
+    pub model: Option<String>,
+    /// Server URL for ollama / OpenAI-compatible APIs (provider default if unset)
+    pub base_url: Option<String>,
+}
+
+/// HTTP server settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+/// Visual memory settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VisualConfig {
+    pub clip_server_url: String,
+    /// ONNX CLIP model directory, preferred over the server when present
+    pub clip_model_dir: PathBuf,
+    pub db_path: PathBuf,
+}
+
+/// Similarity thresholds and limits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    /// Minimum similarity for auto-linking on store
+    pub link: f32,
+    /// Minimum similarity for `search`
+    pub search: f32,
+    /// Minimum similarity for `merge`
+    pub merge: f32,
+    /// Default result count for `recall`
+    pub recall_limit: usize,
+}
+
+fn data_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("memory-brain")
+}
+
+fn home_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from("."))
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            db_path: data_dir().join("coredb"),
+            vecdb_url: "http://localhost:3100".to_string(),
+            embedding: EmbeddingConfig::default(),
+            llm: LlmConfig::default(),
+            server: ServerConfig::default(),
+            visual: VisualConfig::default(),
+            thresholds: Thresholds::default(),
+        }
+    }
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            provider: "auto".to_string(),
+            server_url: "http://localhost:3200".to_string(),
+            model: None,
+            api_url: None,
+            glove_path: data_dir().join("glove.6B.100d.txt"),
+            tfidf_path: data_dir().join("tfidf.json"),
+            cache_path: data_dir().join("embedding_cache.bin"),
+            quantize: false,
+        }
+    }
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            provider: "auto".to_string(),
+            model: None,
+            base_url: None,
+        }
+    }
+}
+
+impl LlmConfig {
+    /// Apply `--provider`, `--model` and `--base-url` (or `--flag=value`)
+    /// from command arguments; returns the arguments that remain
+    pub fn apply_args(&mut self, args: &[String]) -> Vec<String> {
+        let mut rest = Vec::new();
+        let mut i = 0;
+        while i < args.len() {
+            let arg = args[i].as_str();
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (arg, None),
+            };
+            if !matches!(flag, "--provider" | "--model" | "--base-url") {
+                rest.push(args[i].clone());
+                i += 1;
+                continue;
+            }
+            let value = match inline {
+                Some(value) => value,
+                None if i + 1 < args.len() => {
+                    i += 1;
+                    args[i].clone()
+                }
+                None => {
+                    // Dangling flag: nothing to apply
+                    i += 1;
+                    continue;
+                }
+            };
+            match flag {
+                "--provider" => self.provider = value,
+                "--model" => self.model = Some(value),
+                _ => self.base_url = Some(value),
+            }
+            i += 1;
+        }
+        rest
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 3030,
+        }
+    }
+}
+
+impl Default for VisualConfig {
+    fn default() -> Self {
+        Self {
+            clip_server_url: "http://localhost:5050".to_string(),
+            clip_model_dir: home_dir().join(".memory-brain").join("models").join("clip-vit-b32"),
+            db_path: home_dir().join(".memory-brain").join("visual.db"),
+        }
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            link: 0.4,
+            search: 0.05,
+            merge: 0.85,
+            recall_limit: 5,
+        }
+    }
+}
+
+impl Config {
+    /// Default config file location (`$MEMORY_BRAIN_CONFIG` or `~/.memory-brain/config.toml`)
+    pub fn default_path() -> PathBuf {
+        std::env::var("MEMORY_BRAIN_CONFIG")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| home_dir().join(".memory-brain").join("config.toml"))
+    }
+
+    /// Load file (if present) and apply environment overrides
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let path = Self::default_path();
+        let mut config = if path.exists() {
+            Self::from_file(&path)?
+        } else {
+            Self::default()
+        };
+        config.apply_env();
+        Ok(config)
+    }
+
+    /// Parse a config file (no environment overrides)
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_toml_str(&text)
+    }
+
+    /// Parse TOML text; missing fields keep their defaults
+    pub fn from_toml_str(text: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Apply overrides from process environment variables
+    pub fn apply_env(&mut self) {
+        self.apply_env_with(|key| std::env::var(key).ok());
+    }
+
+    /// Apply overrides from a variable lookup
+    ///
+    /// | Variable                 | Field                     |
+    /// |--------------------------|---------------------------|
+    /// | `MEMORY_BRAIN_DB_PATH`   | `db_path`                 |
+    /// | `COREVECDB_URL`          | `vecdb_url`               |
+    /// | `MEMORY_BRAIN_EMBEDDER`  | `embedding.provider`      |
+    /// | `EMBEDDING_SERVER_URL`   | `embedding.server_url`    |
+    /// | `GLOVE_PATH`             | `embedding.glove_path`    |
+    /// | `MEMORY_BRAIN_LLM`       | `llm.provider`            |
+    /// | `MEMORY_BRAIN_LLM_MODEL` | `llm.model`               |
+    /// | `MEMORY_BRAIN_HOST`      | `server.host`             |
+    /// | `MEMORY_BRAIN_PORT`      | `server.port`             |
+    /// | `CLIP_SERVER_URL`        | `visual.clip_server_url`  |
+    /// | `CLIP_MODEL_DIR`         | `visual.clip_model_dir`   |
+    /// | `MEMORY_BRAIN_DB`        | `visual.db_path`          |
+    pub fn apply_env_with<F>(&mut self, get: F)
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if let Some(v) = get("MEMORY_BRAIN_DB_PATH") {
+            self.db_path = PathBuf::from(v);
+        }
+        if let Some(v) = get("COREVECDB_URL") {
+            self.vecdb_url = v;
+        }
+        if let Some(v) = get("MEMORY_BRAIN_EMBEDDER") {
+            self.embedding.provider = v;
+        }
+        if let Some(v) = get("EMBEDDING_SERVER_URL") {
+            self.embedding.server_url = v;
+        }
+        if let Some(v) = get("GLOVE_PATH") {
+            self.embedding.glove_path = PathBuf::from(v);
+        }
+        if let Some(v) = get("MEMORY_BRAIN_LLM") {
+            self.llm.provider = v;
+        }
+        if let Some(v) = get("MEMORY_BRAIN_LLM_MODEL") {
+            self.llm.model = Some(v);
+        }
+        if let Some(v) = get("MEMORY_BRAIN_HOST") {
+            self.server.host = v;
+        }
+        if let Some(port) = get("MEMORY_BRAIN_PORT").and_then(|v| v.parse().ok()) {
+            self.server.port = port;
+        }
+        if let Some(v) = get("CLIP_SERVER_URL") {
+            self.visual.clip_server_url = v;
+        }
+        if let Some(v) = get("CLIP_MODEL_DIR") {
+            self.visual.clip_model_dir = PathBuf::from(v);
+        }
+        if let Some(v) = get("MEMORY_BRAIN_DB") {
+            self.visual.db_path = PathBuf::from(v);
+        }
+    }
+
+    /// CoreDB path as a string (for `Brain::with_embedder`)
+    pub fn db_path_str(&self) -> &str {
+        self.db_path.to_str().unwrap_or(".")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const SAMPLE: &str = r#"
+db_path = "/tmp/brain/coredb"
+vecdb_url = "http://vecdb:3100"
+
+[embedding]
+provider = "glove"
+server_url = "http://embed:3200"
+glove_path = "/tmp/glove.txt"
+
+[llm]
+provider = "ollama"
+model = "llama3.1"
+
+[server]
+host = "0.0.0.0"
+port = 8080
+
+[visual]
+clip_server_url = "http://clip:5050"
+db_path = "/tmp/visual.db"
+
+[thresholds]
+link = 0.5
+search = 0.2
+merge = 0.9
+recall_limit = 10
+"#;
+
+    #[test]
+    fn test_file_overrides_defaults() {
+        let config = Config::from_toml_str(SAMPLE).unwrap();
+        let default = Config::default();
+
+        assert_eq!(config.db_path, PathBuf::from("/tmp/brain/coredb"));
+        assert_eq!(config.vecdb_url, "http://vecdb:3100");
+        assert_eq!(config.embedding.provider, "glove");
+        assert_eq!(config.embedding.server_url, "http://embed:3200");
+        assert_eq!(config.embedding.glove_path, PathBuf::from("/tmp/glove.txt"));
+        assert_eq!(config.llm.provider, "ollama");
+        assert_eq!(config.llm.model.as_deref(), Some("llama3.1"));
+        assert_eq!(config.server.host, "0.0.0.0");
+        assert_eq!(config.server.port, 8080);
+        assert_eq!(config.visual.clip_server_url, "http://clip:5050");
+        assert_eq!(config.visual.db_path, PathBuf::from("/tmp/visual.db"));
+        assert_eq!(config.thresholds.link, 0.5);
+        assert_eq!(config.thresholds.search, 0.2);
+        assert_eq!(config.thresholds.merge, 0.9);
+        assert_eq!(config.thresholds.recall_limit, 10);
+
+        assert_ne!(config.db_path, default.db_path);
+        assert_ne!(config.server, default.server);
+        assert_ne!(config.thresholds, default.thresholds);
+    }
+
+    #[test]
+    fn test_partial_file_keeps_defaults() {
+        let config = Config::from_toml_str("[server]\nport = 9000\n").unwrap();
+        assert_eq!(config.server.port, 9000);
+        assert_eq!(config.server.host, ServerConfig::default().host);
+        assert_eq!(config.thresholds, Thresholds::default());
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let mut config = Config::from_toml_str(SAMPLE).unwrap();
+        let env: HashMap<&str, &str> = [
+            ("MEMORY_BRAIN_DB_PATH", "/env/coredb"),
+            ("COREVECDB_URL", "http://env-vecdb:3100"),
+            ("MEMORY_BRAIN_EMBEDDER", "http"),
+            ("EMBEDDING_SERVER_URL", "http://env-embed:3200"),
+            ("GLOVE_PATH", "/env/glove.txt"),
+            ("MEMORY_BRAIN_LLM", "openai"),
+            ("MEMORY_BRAIN_LLM_MODEL", "gpt-4o-mini"),
+            ("MEMORY_BRAIN_HOST", "10.0.0.1"),
+            ("MEMORY_BRAIN_PORT", "4000"),
+            ("CLIP_SERVER_URL", "http://env-clip:5050"),
+            ("MEMORY_BRAIN_DB", "/env/visual.db"),
+        ]
+        .into_iter()
+        .collect();
+
+        config.apply_env_with(|key| env.get(key).map(|v| v.to_string()));
+
+        assert_eq!(config.db_path, PathBuf::from("/env/coredb"));
+        assert_eq!(config.vecdb_url, "http://env-vecdb:3100");
+        assert_eq!(config.embedding.provider, "http");
+        assert_eq!(config.embedding.server_url, "http://env-embed:3200");
+        assert_eq!(config.embedding.glove_path, PathBuf::from("/env/glove.txt"));
+        assert_eq!(config.llm.provider, "openai");
+        assert_eq!(config.llm.model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(config.server.host, "10.0.0.1");
+        assert_eq!(config.server.port, 4000);
+        assert_eq!(config.visual.clip_server_url, "http://env-clip:5050");
+        assert_eq!(config.visual.db_path, PathBuf::from("/env/visual.db"));
+        // No env override for thresholds: file value wins
+        assert_eq!(config.thresholds.recall_limit, 10);
+    }
+
+    #[test]
+    fn test_llm_flags_override_env() {
+        let mut config = Config::from_toml_str(SAMPLE).unwrap();
+        config.apply_env_with(|key| (key == "MEMORY_BRAIN_LLM").then(|| "openai".to_string()));
+
+        let args: Vec<String> = ["what", "--provider", "echo", "--model=tiny", "is", "rust?", "--base-url", "http://llm:8080"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let rest = config.llm.apply_args(&args);
+
+        assert_eq!(rest, vec!["what", "is", "rust?"]);
+        assert_eq!(config.llm.provider, "echo");
+        assert_eq!(config.llm.model.as_deref(), Some("tiny"));
+        assert_eq!(config.llm.base_url.as_deref(), Some("http://llm:8080"));
+        assert_eq!(crate::llm::provider_from_config(&config.llm).name(), "echo");
+    }
+}
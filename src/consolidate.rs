@@ -2,14 +2,29 @@
 //! 
 //! Decides which working memories should move to long-term storage.
 //! Like the brain during sleep, consolidates important memories.
+//!
+//! Classification uses per-type embedding centroids once trained on
+//! labeled examples, and keyword heuristics otherwise.
 
+use crate::embedding::cosine_similarity;
 use crate::types::{Emotion, MemoryItem, MemoryType};
+use crate::working::WorkingMemory;
+use std::collections::HashMap;
+
+/// Running mean of the embeddings labeled with one memory type
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Prototype {
+    centroid: Vec<f32>,
+    count: usize,
+}
 
 pub struct Consolidator {
     /// Minimum strength for auto-consolidation
     strength_threshold: f32,
     /// Repetition threshold (access count)
     repetition_threshold: u32,
+    /// Embedding centroids per long-term memory type
+    prototypes: HashMap<MemoryType, Prototype>,
 }
 
 impl Consolidator {
@@ -17,7 +32,71 @@ impl Consolidator {
         Self {
             strength_threshold: 0.6,
             repetition_threshold: 3,
+            prototypes: HashMap::new(),
+        }
+    }
+
+    /// Update the type centroids from labeled examples
+    ///
+    /// Examples without an embedding, or labeled `Working`, are skipped.
+    pub fn train(&mut self, examples: &[(MemoryItem, MemoryType)]) {
+        for (item, memory_type) in examples {
+            self.observe(item, memory_type.clone());
+        }
+    }
+
+    /// Fold one confirmed classification into its type's centroid
+    pub fn observe(&mut self, item: &MemoryItem, memory_type: MemoryType) {
+        let Some(ref embedding) = item.embedding else { return };
+        if memory_type == MemoryType::Working || embedding.iter().any(|x| !x.is_finite()) {
+            return;
+        }
+        let prototype = self.prototypes.entry(memory_type).or_insert_with(|| Prototype {
+            centroid: vec![0.0; embedding.len()],
+            count: 0,
+        });
+        if prototype.centroid.len() != embedding.len() {
+            // Embedder changed: start this type over
+            *prototype = Prototype { centroid: vec![0.0; embedding.len()], count: 0 };
         }
+        prototype.count += 1;
+        let n = prototype.count as f32;
+        for (c, x) in prototype.centroid.iter_mut().zip(embedding) {
+            *c += (x - *c) / n;
+        }
+    }
+
+    /// Number of labeled examples seen per type
+    pub fn trained_counts(&self) -> HashMap<MemoryType, usize> {
+        self.prototypes.iter().map(|(t, p)| (t.clone(), p.count)).collect()
+    }
+
+    /// Trained centroids, for persisting with the indexes
+    pub(crate) fn prototypes(&self) -> Vec<(MemoryType, Prototype)> {
+        self.prototypes.iter().map(|(t, p)| (t.clone(), p.clone())).collect()
+    }
+
+    /// Replace the trained centroids with persisted ones
+    pub(crate) fn set_prototypes(&mut self, prototypes: Vec<(MemoryType, Prototype)>) {
+        self.prototypes = prototypes.into_iter().collect();
+    }
+
+    /// Nearest type centroid, if at least two types can be compared
+    fn classify_by_embedding(&self, embedding: &[f32]) -> Option<MemoryType> {
+        let scored: Vec<(MemoryType, f32)> = self
+            .prototypes
+            .iter()
+            .filter(|(_, p)| p.centroid.len() == embedding.len())
+            .map(|(t, p)| (t.clone(), cosine_similarity(embedding, &p.centroid)))
+            .filter(|(_, sim)| sim.is_finite())
+            .collect();
+        if scored.len() < 2 {
+            return None;
+        }
+        scored
+            .into_iter()
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(t, _)| t)
     }
 
     /// Decide if a memory should be consolidated to long-term
@@ -40,8 +119,26 @@ impl Consolidator {
         false
     }
 
+    /// Decide if working memory holds more than it can keep and must
+    /// flush items to long-term
+    pub fn should_consolidate_working(&self, working: &WorkingMemory) -> bool {
+        working.len() > working.capacity()
+    }
+
     /// Classify what type of long-term memory this should be
+    ///
+    /// Nearest trained centroid when the item has an embedding, otherwise
+    /// [`Self::classify_heuristic`].
     pub fn classify(&self, item: &MemoryItem) -> MemoryType {
+        item.embedding
+            .as_deref()
+            .and_then(|emb| self.classify_by_embedding(emb))
+            .unwrap_or_else(|| self.classify_heuristic(item))
+    }
+
+    /// Keyword rules: "when … then"/"how to" → procedural, "is/are" →
+    /// semantic, everything else episodic
+    pub fn classify_heuristic(&self, item: &MemoryItem) -> MemoryType {
         let content_lower = item.content.to_lowercase();
 
         // Check for procedural patterns (if/when/how patterns)
@@ -144,6 +241,18 @@ mod tests {
         assert!(!consolidator.should_consolidate(&item));
     }
 
+    #[test]
+    fn test_should_consolidate_working_over_capacity() {
+        let consolidator = Consolidator::new();
+        let mut wm = WorkingMemory::new(2);
+        wm.admit(MemoryItem::new("one", None));
+        wm.admit(MemoryItem::new("two", None));
+        assert!(!consolidator.should_consolidate_working(&wm));
+
+        wm.admit(MemoryItem::new("three", None));
+        assert!(consolidator.should_consolidate_working(&wm));
+    }
+
     #[test]
     fn test_classify_episodic() {
         let consolidator = Consolidator::new();
@@ -171,6 +280,39 @@ mod tests {
         assert_eq!(mem_type, MemoryType::Procedural);
     }
 
+    #[test]
+    fn test_trained_centroids_override_keywords() {
+        use crate::embedding::{Embedder, HashEmbedder};
+
+        let embedder = HashEmbedder::new(256);
+        let labeled = |text: &str, t: MemoryType| {
+            let mut item = MemoryItem::new(text, None);
+            item.embedding = Some(embedder.embed(text));
+            (item, t)
+        };
+
+        let mut consolidator = Consolidator::new();
+        let mut item = MemoryItem::new("I deployed the app and it is live", None);
+        item.embedding = Some(embedder.embed(&item.content));
+        // " is " fools the keyword rules
+        assert_eq!(consolidator.classify(&item), MemoryType::Semantic);
+
+        consolidator.train(&[
+            labeled("I deployed the server yesterday", MemoryType::Episodic),
+            labeled("We pushed the app to production this morning", MemoryType::Episodic),
+            labeled("I deployed a hotfix after lunch", MemoryType::Episodic),
+            labeled("Rust is a systems programming language", MemoryType::Semantic),
+            labeled("Water boils at 100 degrees", MemoryType::Semantic),
+            labeled("HTTP is a stateless protocol", MemoryType::Semantic),
+        ]);
+        assert_eq!(consolidator.trained_counts()[&MemoryType::Episodic], 3);
+        assert_eq!(consolidator.classify(&item), MemoryType::Episodic);
+
+        // No embedding: keyword rules still apply
+        let bare = MemoryItem::new("Pattern: when error occurs, use Result type", None);
+        assert_eq!(consolidator.classify(&bare), MemoryType::Procedural);
+    }
+
     #[test]
     fn test_extract_key_info() {
         let consolidator = Consolidator::new();
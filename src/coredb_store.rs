@@ -101,10 +101,10 @@ impl CoreDBStore {
             self.keyspace,
             memory.id,
             escape_string(&memory.content),
-            format!("{:?}", memory.memory_type),
+            escape_string(&format!("{:?}", memory.memory_type)),
             escape_string(&tags_json),
             memory.strength,
-            format!("{:?}", memory.emotion),
+            escape_string(&format!("{:?}", memory.emotion)),
             memory.created_at.timestamp_millis(),
             memory.accessed_at.timestamp_millis(),
             memory.access_count,
@@ -179,7 +179,7 @@ impl CoreDBStore {
             self.keyspace,
             link.from_id,
             link.to_id,
-            link.relation.as_str(),
+            escape_string(link.relation.as_str()),
             link.weight,
             link.created_at.timestamp_millis(),
         );
@@ -259,7 +259,7 @@ impl CoreDBStore {
 // ============================================================================
 
 fn escape_string(s: &str) -> String {
-    s.replace('\'', "''")
+    crate::storage::escape_cql_string(s)
 }
 
 fn row_to_memory(row: &Row) -> Result<Memory> {
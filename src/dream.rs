@@ -7,17 +7,47 @@
 //! - Fade weak memories
 //! - Generate dream-like narratives
 
-use crate::{Brain, MemoryItem, cosine_similarity};
+use crate::{Brain, MemoryItem, MemoryType, cosine_similarity};
 use chrono::{Utc, Duration};
 use rand::prelude::*;
 use std::collections::HashMap;
 
+/// Recent memories compared pairwise when looking for new links
+const MAX_LINK_CANDIDATES: usize = 50;
+
+/// How aggressively a dream forms and prunes connections
+#[derive(Debug, Clone, PartialEq)]
+pub struct DreamConfig {
+    /// Minimum embedding similarity for REM to link two memories
+    pub connection_threshold: f32,
+    /// Most links created per dream (strongest pairs first)
+    pub max_new_links: usize,
+    /// Links pointing at memories weaker than this are removed on waking
+    pub prune_below_strength: f32,
+    /// Phases to run; light sleep always gathers the memories the others use
+    pub phases: Vec<DreamPhase>,
+}
+
+impl Default for DreamConfig {
+    fn default() -> Self {
+        Self {
+            connection_threshold: 0.3,
+            max_new_links: 10,
+            prune_below_strength: 0.1,
+            phases: vec![DreamPhase::Light, DreamPhase::Deep, DreamPhase::Rem, DreamPhase::Waking],
+        }
+    }
+}
+
 /// Dream state and results
 #[derive(Debug, Clone)]
 pub struct DreamState {
     pub phase: DreamPhase,
     pub memories_processed: usize,
+    /// Links created between similar memories during REM
     pub new_connections: usize,
+    /// Links to weak or deleted memories removed on waking
+    pub pruned_links: usize,
     pub faded_memories: usize,
     pub dream_narrative: String,
     pub insights: Vec<String>,
@@ -52,6 +82,7 @@ pub struct DreamEngine<'a> {
     brain: &'a mut Brain,
     rng: ThreadRng,
     verbose: bool,
+    config: DreamConfig,
 }
 
 impl<'a> DreamEngine<'a> {
@@ -60,6 +91,7 @@ impl<'a> DreamEngine<'a> {
             brain,
             rng: thread_rng(),
             verbose: false,
+            config: DreamConfig::default(),
         }
     }
 
@@ -68,12 +100,22 @@ impl<'a> DreamEngine<'a> {
         self
     }
 
+    pub fn with_config(mut self, config: DreamConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    fn runs(&self, phase: DreamPhase) -> bool {
+        self.config.phases.contains(&phase)
+    }
+
     /// Enter dream mode and process memories
     pub fn dream(&mut self) -> DreamState {
         let mut state = DreamState {
             phase: DreamPhase::Light,
             memories_processed: 0,
             new_connections: 0,
+            pruned_links: 0,
             faded_memories: 0,
             dream_narrative: String::new(),
             insights: Vec::new(),
@@ -93,32 +135,39 @@ impl<'a> DreamEngine<'a> {
         }
 
         // Phase 2: Deep Sleep - Strengthen important memories
-        state.phase = DreamPhase::Deep;
-        let strengthened = self.strengthen_important(&recent_memories);
-        
-        if self.verbose {
-            println!("{} - Strengthened {} important memories", state.phase, strengthened);
+        if self.runs(DreamPhase::Deep) {
+            state.phase = DreamPhase::Deep;
+            let strengthened = self.strengthen_important(&recent_memories);
+            
+            if self.verbose {
+                println!("{} - Strengthened {} important memories", state.phase, strengthened);
+            }
         }
 
         // Phase 3: REM - Creative recombination
-        state.phase = DreamPhase::Rem;
-        let (narrative, connections) = self.rem_dream(&recent_memories);
-        state.dream_narrative = narrative;
-        state.new_connections = connections;
-        
-        if self.verbose {
-            println!("{} - Created {} new connections", state.phase, connections);
-            println!("\n🌙 Dream narrative:\n{}\n", state.dream_narrative);
+        if self.runs(DreamPhase::Rem) {
+            state.phase = DreamPhase::Rem;
+            let (narrative, connections) = self.rem_dream(&recent_memories);
+            state.dream_narrative = narrative;
+            state.new_connections = connections;
+            
+            if self.verbose {
+                println!("{} - Created {} new connections", state.phase, connections);
+                println!("\n🌙 Dream narrative:\n{}\n", state.dream_narrative);
+            }
         }
 
-        // Phase 4: Waking - Generate insights
-        state.phase = DreamPhase::Waking;
-        state.insights = self.generate_insights(&recent_memories);
-        
-        if self.verbose {
-            println!("{} - Generated {} insights", state.phase, state.insights.len());
-            for insight in &state.insights {
-                println!("  💡 {}", insight);
+        // Phase 4: Waking - Generate insights, prune dead links
+        if self.runs(DreamPhase::Waking) {
+            state.phase = DreamPhase::Waking;
+            state.insights = self.generate_insights(&recent_memories);
+            state.pruned_links = self.prune_weak_links();
+            
+            if self.verbose {
+                println!("{} - Generated {} insights, pruned {} links", state.phase, state.insights.len(), state.pruned_links);
+                for insight in &state.insights {
+                    println!("  💡 {}", insight);
+                }
             }
         }
 
@@ -180,7 +229,6 @@ impl<'a> DreamEngine<'a> {
         }
 
         let mut narrative = String::new();
-        let mut connections = 0;
 
         // Select random memories to weave together
         let sample_size = memories.len().min(5);
@@ -208,28 +256,80 @@ impl<'a> DreamEngine<'a> {
             } else {
                 let transition = transitions.choose(&mut self.rng).unwrap();
                 narrative.push_str(&format!(" {} \"{}\"", transition, snippet));
-                connections += 1;
             }
         }
         
         narrative.push_str("...");
 
-        // Find unexpected connections based on embedding similarity
-        if memories.len() >= 2 {
-            for i in 0..memories.len().min(10) {
-                for j in (i + 1)..memories.len().min(10) {
-                    if let (Some(emb_a), Some(emb_b)) = (&memories[i].embedding, &memories[j].embedding) {
-                        let sim = cosine_similarity(emb_a, emb_b);
-                        // Medium similarity = unexpected but related connection
-                        if sim > 0.3 && sim < 0.7 {
-                            connections += 1;
-                        }
+        let connections = self.link_similar(memories);
+        (narrative, connections)
+    }
+
+    /// Link the most similar unlinked pairs above the connection threshold
+    fn link_similar(&mut self, memories: &[MemoryItem]) -> usize {
+        // Working memories aren't persisted yet, so links to them would be lost
+        let candidates: Vec<&MemoryItem> = memories
+            .iter()
+            .filter(|m| m.memory_type != MemoryType::Working && m.embedding.is_some())
+            .take(MAX_LINK_CANDIDATES)
+            .collect();
+
+        let mut pairs: Vec<(usize, usize, f32)> = Vec::new();
+        for i in 0..candidates.len() {
+            for j in (i + 1)..candidates.len() {
+                let (a, b) = (candidates[i], candidates[j]);
+                if a.associations.contains(&b.id) || b.associations.contains(&a.id) {
+                    continue;
+                }
+                if let (Some(emb_a), Some(emb_b)) = (&a.embedding, &b.embedding) {
+                    let sim = cosine_similarity(emb_a, emb_b);
+                    if sim >= self.config.connection_threshold {
+                        pairs.push((i, j, sim));
                     }
                 }
             }
         }
+        pairs.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
 
-        (narrative, connections)
+        let mut created = 0;
+        for (i, j, _) in pairs.into_iter().take(self.config.max_new_links) {
+            let (a, b) = (candidates[i].id, candidates[j].id);
+            if self.brain.link(a, b).is_ok() && self.brain.link(b, a).is_ok() {
+                created += 1;
+            }
+        }
+        created
+    }
+
+    /// Remove associations pointing at weak or deleted memories
+    fn prune_weak_links(&mut self) -> usize {
+        let mut items = Vec::new();
+        for store in [
+            self.brain.semantic.search("", usize::MAX),
+            self.brain.episodic.search("", usize::MAX),
+            self.brain.procedural.search("", usize::MAX),
+        ] {
+            items.extend(store.unwrap_or_default());
+        }
+        let strengths: HashMap<uuid::Uuid, f32> = items.iter().map(|m| (m.id, m.strength)).collect();
+
+        let mut pruned = 0;
+        for mut item in items {
+            let before = item.associations.len();
+            item.associations.retain(|id| {
+                strengths.get(id).map_or(false, |s| *s >= self.config.prune_below_strength)
+            });
+            if item.associations.len() == before {
+                continue;
+            }
+            pruned += before - item.associations.len();
+            let _ = match item.memory_type {
+                MemoryType::Episodic => self.brain.episodic.update(&item),
+                MemoryType::Procedural => self.brain.procedural.update(&item),
+                _ => self.brain.semantic.update(&item),
+            };
+        }
+        pruned
     }
 
     /// Generate insights from memory patterns
@@ -339,4 +439,48 @@ mod tests {
         assert!(state.memories_processed > 0);
         assert!(!state.dream_narrative.is_empty());
     }
+
+    fn brain_with_spread_memories(dir: &std::path::Path) -> Brain {
+        let mut brain = Brain::new(dir.join("links.db").to_str().unwrap()).unwrap();
+        let embeddings = [
+            vec![1.0, 0.0, 0.0],
+            vec![0.95, 0.3, 0.0],
+            vec![0.7, 0.7, 0.0],
+            vec![0.0, 1.0, 0.2],
+            vec![0.0, 0.2, 1.0],
+        ];
+        for (i, emb) in embeddings.into_iter().enumerate() {
+            let mut item = MemoryItem::new(&format!("Dream fragment number {}", i), None);
+            item.embedding = Some(emb);
+            brain.semantic.store(item).unwrap();
+        }
+        brain
+    }
+
+    #[test]
+    fn test_connection_threshold_controls_new_links() {
+        let low_dir = tempdir().unwrap();
+        let mut low_brain = brain_with_spread_memories(low_dir.path());
+        let low = DreamEngine::new(&mut low_brain)
+            .with_config(DreamConfig { connection_threshold: 0.1, max_new_links: 100, ..Default::default() })
+            .dream();
+
+        let high_dir = tempdir().unwrap();
+        let mut high_brain = brain_with_spread_memories(high_dir.path());
+        let high = DreamEngine::new(&mut high_brain)
+            .with_config(DreamConfig { connection_threshold: 0.9, max_new_links: 100, ..Default::default() })
+            .dream();
+
+        assert!(high.new_connections >= 1);
+        assert!(high.new_connections < low.new_connections, "{} vs {}", high.new_connections, low.new_connections);
+        assert_eq!(high.pruned_links, 0);
+
+        // The link cap applies too
+        let capped_dir = tempdir().unwrap();
+        let mut capped_brain = brain_with_spread_memories(capped_dir.path());
+        let capped = DreamEngine::new(&mut capped_brain)
+            .with_config(DreamConfig { connection_threshold: 0.1, max_new_links: 1, ..Default::default() })
+            .dream();
+        assert_eq!(capped.new_connections, 1);
+    }
 }
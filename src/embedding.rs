@@ -5,9 +5,12 @@
 //! Supports multiple backends:
 //! - Simple TF-IDF (built-in, no dependencies)
 //! - Hash-based (fast, consistent)
+//! - External APIs (Ollama / OpenAI embeddings)
 //! - MLX models (requires mlx-rs, Apple Silicon optimized)
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
 
 #[cfg(feature = "mlx")]
 use mlx_rs;
@@ -16,21 +19,82 @@ use mlx_rs;
 pub trait Embedder: Send + Sync {
     /// Convert text to embedding vector
     fn embed(&self, text: &str) -> Vec<f32>;
+
+    /// Embed a search query (defaults to `embed`)
+    ///
+    /// Asymmetric models override this and [`Embedder::embed_document`],
+    /// e.g. to weight or expand query terms differently from stored text.
+    fn embed_query(&self, text: &str) -> Vec<f32> {
+        self.embed(text)
+    }
+
+    /// Embed text that is stored and searched against (defaults to `embed`)
+    fn embed_document(&self, text: &str) -> Vec<f32> {
+        self.embed(text)
+    }
+
+    /// Like [`Embedder::embed_document`], but `Err` when the provider failed
+    ///
+    /// Remote embedders fall back to a zero vector from the infallible
+    /// methods; anything that caches or stores vectors goes through here so
+    /// the fallback never outlives the failed request.
+    fn try_embed_document(&self, text: &str) -> Result<Vec<f32>, String> {
+        Ok(self.embed_document(text))
+    }
     
     /// Embedding dimension
     fn dimension(&self) -> usize;
+
+    /// Identity of the model behind the vectors (defaults to the type name)
+    ///
+    /// Persisted caches record it so vectors from another embedder are
+    /// never mixed in.
+    fn id(&self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
+
+    /// Embed many texts at once
+    ///
+    /// Defaults to one `embed` call per text; providers with a cheaper bulk
+    /// path (e.g. one HTTP request) override it.
+    fn embed_batch(&self, texts: &[&str]) -> Vec<Vec<f32>> {
+        texts.iter().map(|text| self.embed(text)).collect()
+    }
     
     /// Compute cosine similarity between two embeddings
     fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
         cosine_similarity(a, b)
     }
+
+    /// Hit/miss statistics, for embedders that cache (see `CachedEmbedder`)
+    fn cache_stats(&self) -> Option<crate::cache::CacheStats> {
+        None
+    }
 }
 
 /// Simple TF-IDF based embedder (no external dependencies)
+///
+/// Call [`TfIdfEmbedder::fit`] on a corpus to learn the vocabulary and IDF
+/// weights; `save`/`load` persist them as JSON.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TfIdfEmbedder {
     vocabulary: HashMap<String, usize>,
     idf: Vec<f32>,
     dimension: usize,
+    /// IDF for terms not seen during `fit` (hashed into a slot at query time)
+    #[serde(default = "default_idf")]
+    default_idf: f32,
+    /// [`TFIDF_FORMAT_VERSION`] when saved (0: older files)
+    #[serde(default)]
+    format_version: u32,
+}
+
+/// Bumped when the vectors `reindex --embedder tfidf` stores change
+/// (1: documents embedded with `embed_document`, not query weighting)
+pub const TFIDF_FORMAT_VERSION: u32 = 1;
+
+fn default_idf() -> f32 {
+    1.0
 }
 
 impl TfIdfEmbedder {
@@ -40,16 +104,30 @@ impl TfIdfEmbedder {
             vocabulary: HashMap::new(),
             idf: vec![1.0; dimension],
             dimension,
+            default_idf: default_idf(),
+            format_version: TFIDF_FORMAT_VERSION,
         }
     }
 
     /// Create embedder from a corpus (learns vocabulary)
     pub fn from_corpus(texts: &[&str], dimension: usize) -> Self {
+        let mut embedder = Self::new(dimension);
+        embedder.fit(texts);
+        embedder.dimension = embedder.vocabulary.len().min(dimension);
+        embedder
+    }
+
+    /// Learn vocabulary and IDF weights from a corpus
+    ///
+    /// Keeps the `dimension` most frequent terms with
+    /// `idf = ln(n / df) + 1`. Unseen terms get the IDF of a term found in
+    /// a single document, the rarest possible.
+    pub fn fit(&mut self, docs: &[&str]) {
         let mut word_counts: HashMap<String, usize> = HashMap::new();
         let mut doc_freq: HashMap<String, usize> = HashMap::new();
         
         // Count words and document frequencies
-        for text in texts {
+        for text in docs {
             let mut seen = std::collections::HashSet::new();
             for word in tokenize(text) {
                 *word_counts.entry(word.clone()).or_insert(0) += 1;
@@ -59,32 +137,59 @@ impl TfIdfEmbedder {
             }
         }
 
-        // Select top words by frequency
+        // Select top words by frequency (ties alphabetical, so fits are reproducible)
         let mut words: Vec<_> = word_counts.into_iter().collect();
-        words.sort_by(|a, b| b.1.cmp(&a.1));
-        words.truncate(dimension);
+        words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        words.truncate(self.dimension);
 
-        let vocabulary: HashMap<String, usize> = words
+        self.vocabulary = words
             .iter()
             .enumerate()
             .map(|(i, (word, _))| (word.clone(), i))
             .collect();
 
         // Calculate IDF
-        let n = texts.len() as f32;
-        let idf: Vec<f32> = words
+        let n = docs.len().max(1) as f32;
+        self.idf = words
             .iter()
             .map(|(word, _)| {
                 let df = doc_freq.get(word).copied().unwrap_or(1) as f32;
                 (n / df).ln() + 1.0
             })
             .collect();
+        self.default_idf = n.ln() + 1.0;
+    }
 
-        Self {
-            vocabulary,
-            idf,
-            dimension: words.len().min(dimension),
-        }
+    /// IDF weight of a term (`default_idf` if it wasn't seen during `fit`)
+    pub fn idf(&self, term: &str) -> f32 {
+        self.vocabulary
+            .get(&term.to_lowercase())
+            .and_then(|&idx| self.idf.get(idx))
+            .copied()
+            .unwrap_or(self.default_idf)
+    }
+
+    /// Number of learned terms
+    pub fn vocabulary_size(&self) -> usize {
+        self.vocabulary.len()
+    }
+
+    /// Whether the stored vectors predate the current format and need
+    /// `reindex --embedder tfidf` again
+    pub fn is_outdated(&self) -> bool {
+        self.format_version < TFIDF_FORMAT_VERSION
+    }
+
+    /// Save the learned vocabulary and weights as JSON
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Load a vocabulary saved with `save`
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let bytes = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&bytes)?)
     }
 
     /// Add a word to vocabulary (for incremental learning)
@@ -101,8 +206,9 @@ impl TfIdfEmbedder {
     }
 }
 
-impl Embedder for TfIdfEmbedder {
-    fn embed(&self, text: &str) -> Vec<f32> {
+impl TfIdfEmbedder {
+    /// Normalized term-frequency vector, IDF-weighted when `use_idf`
+    fn weighted(&self, text: &str, use_idf: bool) -> Vec<f32> {
         let mut vec = vec![0.0f32; self.dimension];
         let tokens = tokenize(text);
         let total = tokens.len() as f32;
@@ -119,12 +225,19 @@ impl Embedder for TfIdfEmbedder {
 
         // Build TF-IDF vector
         for (word, count) in tf {
-            if let Some(&idx) = self.vocabulary.get(word) {
-                if idx < self.dimension {
-                    let tf_val = count / total;
-                    let idf_val = self.idf.get(idx).copied().unwrap_or(1.0);
-                    vec[idx] = tf_val * idf_val;
+            let tf_val = count / total;
+            match self.vocabulary.get(word) {
+                Some(&idx) if idx < self.dimension => {
+                    let idf_val = if use_idf { self.idf.get(idx).copied().unwrap_or(1.0) } else { 1.0 };
+                    vec[idx] += tf_val * idf_val;
+                }
+                Some(_) => {}
+                // Unknown term: hash into a slot with the default idf
+                None if self.dimension > 0 => {
+                    let idx = simple_hash(word) as usize % self.dimension;
+                    vec[idx] += tf_val * if use_idf { self.default_idf } else { 1.0 };
                 }
+                None => {}
             }
         }
 
@@ -132,6 +245,22 @@ impl Embedder for TfIdfEmbedder {
         normalize(&mut vec);
         vec
     }
+}
+
+/// Queries carry the IDF weighting; documents are raw term frequencies, so
+/// rare query terms decide the match without being counted twice.
+impl Embedder for TfIdfEmbedder {
+    fn embed(&self, text: &str) -> Vec<f32> {
+        self.weighted(text, true)
+    }
+
+    fn embed_query(&self, text: &str) -> Vec<f32> {
+        self.weighted(text, true)
+    }
+
+    fn embed_document(&self, text: &str) -> Vec<f32> {
+        self.weighted(text, false)
+    }
 
     fn dimension(&self) -> usize {
         self.dimension
@@ -193,8 +322,8 @@ impl HttpEmbedder {
         Self::new("http://localhost:3200")
     }
     
-    /// Batch embed multiple texts (more efficient)
-    pub fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+    /// Batch embed multiple texts in one request (more efficient)
+    pub fn try_embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
         let payload = serde_json::json!({
             "texts": texts,
             "return_sparse": false,
@@ -235,7 +364,7 @@ impl HttpEmbedder {
 
 impl Embedder for HttpEmbedder {
     fn embed(&self, text: &str) -> Vec<f32> {
-        match self.embed_batch(&[text]) {
+        match self.try_embed_batch(&[text]) {
             Ok(vecs) if !vecs.is_empty() => vecs.into_iter().next().unwrap(),
             _ => vec![0.0; self.dimension], // Fallback on error
         }
@@ -244,6 +373,221 @@ impl Embedder for HttpEmbedder {
     fn dimension(&self) -> usize {
         self.dimension
     }
+
+    fn try_embed_document(&self, text: &str) -> Result<Vec<f32>, String> {
+        self.try_embed_batch(&[text])?
+            .into_iter()
+            .next()
+            .ok_or_else(|| "Empty response".to_string())
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Vec<Vec<f32>> {
+        match self.try_embed_batch(texts) {
+            Ok(vecs) if vecs.len() == texts.len() => vecs,
+            _ => vec![vec![0.0; self.dimension]; texts.len()], // Fallback on error
+        }
+    }
+}
+
+/// Embedding API flavour spoken by [`ApiEmbedder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKind {
+    /// `POST /api/embeddings` with `{"model", "prompt"}`
+    Ollama,
+    /// `POST /v1/embeddings` with `{"model", "input"}`
+    OpenAi,
+}
+
+/// Embedder backed by an external embedding API (Ollama or OpenAI)
+///
+/// The dimension is learned from the first successful response (asking the
+/// server once if it is needed earlier). Failed requests fall back to a
+/// zero vector from `embed`, like [`HttpEmbedder`]; `try_embed` and
+/// `try_embed_document` return the error instead. Wrap in `CachedEmbedder`
+/// to avoid re-requesting repeated texts.
+pub struct ApiEmbedder {
+    kind: ApiKind,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    agent: ureq::Agent,
+    /// 0 until the first response arrives
+    dimension: AtomicUsize,
+    /// Set once `dimension()` has asked the server, so an unreachable
+    /// server is not re-asked on every call
+    probed: AtomicBool,
+    /// Reported while the server has never answered (0 if unset)
+    fallback_dimension: Option<usize>,
+}
+
+impl ApiEmbedder {
+    /// Ollama embeddings (`http://localhost:11434` by default)
+    pub fn ollama(base_url: &str, model: &str) -> Self {
+        Self::new(ApiKind::Ollama, base_url, model)
+    }
+
+    /// OpenAI-compatible embeddings (`https://api.openai.com` by default)
+    ///
+    /// Reads the key from `OPENAI_API_KEY`; override with `with_api_key`.
+    pub fn openai(base_url: &str, model: &str) -> Self {
+        let embedder = Self::new(ApiKind::OpenAi, base_url, model);
+        match std::env::var("OPENAI_API_KEY") {
+            Ok(key) => embedder.with_api_key(&key),
+            Err(_) => embedder,
+        }
+    }
+
+    pub fn new(kind: ApiKind, base_url: &str, model: &str) -> Self {
+        Self {
+            kind,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: None,
+            agent: ureq::AgentBuilder::new()
+                .timeout_connect(Duration::from_secs(5))
+                .timeout_read(Duration::from_secs(30))
+                .build(),
+            dimension: AtomicUsize::new(0),
+            probed: AtomicBool::new(false),
+            fallback_dimension: None,
+        }
+    }
+
+    /// Send `Authorization: Bearer <key>` with each request
+    pub fn with_api_key(mut self, key: &str) -> Self {
+        self.api_key = Some(key.to_string());
+        self
+    }
+
+    /// Dimension to report while the server can't be reached
+    pub fn with_dimension(mut self, dimension: usize) -> Self {
+        self.fallback_dimension = Some(dimension);
+        self
+    }
+
+    pub fn kind(&self) -> ApiKind {
+        self.kind
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Full endpoint URL for this API flavour
+    fn endpoint(&self) -> String {
+        match self.kind {
+            ApiKind::Ollama => format!("{}/api/embeddings", self.base_url),
+            ApiKind::OpenAi if self.base_url.ends_with("/v1") => format!("{}/embeddings", self.base_url),
+            ApiKind::OpenAi => format!("{}/v1/embeddings", self.base_url),
+        }
+    }
+
+    /// Embed one text, surfacing network and format errors
+    pub fn try_embed(&self, text: &str) -> Result<Vec<f32>, String> {
+        let payload = match self.kind {
+            ApiKind::Ollama => serde_json::json!({ "model": self.model, "prompt": text }),
+            ApiKind::OpenAi => serde_json::json!({ "model": self.model, "input": text }),
+        };
+
+        let mut request = self.agent.post(&self.endpoint()).set("Content-Type", "application/json");
+        if let Some(ref key) = self.api_key {
+            request = request.set("Authorization", &format!("Bearer {}", key));
+        }
+        let resp = request.send_json(&payload).map_err(|e| format!("HTTP error: {}", e))?;
+
+        let body: serde_json::Value = resp.into_json()
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+
+        let vector = match self.kind {
+            ApiKind::Ollama => &body["embedding"],
+            ApiKind::OpenAi => &body["data"][0]["embedding"],
+        };
+        let embedding: Vec<f32> = vector.as_array()
+            .ok_or("Missing 'embedding' field")?
+            .iter()
+            .map(|x| x.as_f64().unwrap_or(0.0) as f32)
+            .collect();
+        if embedding.is_empty() {
+            return Err("Empty embedding".to_string());
+        }
+
+        match self.dimension.compare_exchange(0, embedding.len(), Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => Ok(embedding),
+            Err(known) if known == embedding.len() => Ok(embedding),
+            Err(known) => Err(format!("Dimension changed: expected {}, got {}", known, embedding.len())),
+        }
+    }
+
+    /// Check if the API answers an embedding request
+    pub fn health_check(&self) -> bool {
+        self.try_embed("ping").is_ok()
+    }
+}
+
+impl Embedder for ApiEmbedder {
+    fn embed(&self, text: &str) -> Vec<f32> {
+        match self.try_embed(text) {
+            Ok(embedding) => embedding,
+            Err(_) => vec![0.0; self.dimension()], // Fallback on error
+        }
+    }
+
+    fn try_embed_document(&self, text: &str) -> Result<Vec<f32>, String> {
+        self.try_embed(text)
+    }
+
+    /// Asks the server on first use if no response has arrived yet
+    fn dimension(&self) -> usize {
+        if self.dimension.load(Ordering::Relaxed) == 0 && !self.probed.swap(true, Ordering::Relaxed) {
+            let _ = self.try_embed("ping");
+        }
+        match self.dimension.load(Ordering::Relaxed) {
+            0 => self.fallback_dimension.unwrap_or(0),
+            known => known,
+        }
+    }
+
+    fn id(&self) -> String {
+        format!("{:?}:{}", self.kind, self.model)
+    }
+}
+
+impl<E: Embedder + ?Sized> Embedder for std::sync::Arc<E> {
+    fn embed(&self, text: &str) -> Vec<f32> {
+        (**self).embed(text)
+    }
+
+    fn embed_query(&self, text: &str) -> Vec<f32> {
+        (**self).embed_query(text)
+    }
+
+    fn embed_document(&self, text: &str) -> Vec<f32> {
+        (**self).embed_document(text)
+    }
+
+    fn try_embed_document(&self, text: &str) -> Result<Vec<f32>, String> {
+        (**self).try_embed_document(text)
+    }
+
+    fn dimension(&self) -> usize {
+        (**self).dimension()
+    }
+
+    fn id(&self) -> String {
+        (**self).id()
+    }
+
+    fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        (**self).similarity(a, b)
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Vec<Vec<f32>> {
+        (**self).embed_batch(texts)
+    }
+
+    fn cache_stats(&self) -> Option<crate::cache::CacheStats> {
+        (**self).cache_stats()
+    }
 }
 
 /// MLX-based embedder using learned word embeddings
@@ -442,9 +786,115 @@ pub fn normalize(vec: &mut [f32]) {
     }
 }
 
+/// TF-IDF vector size for the `tfidf` provider
+pub const TFIDF_DIMENSION: usize = 512;
+
+/// Embedder picked by [`embedder_from_config`]
+pub struct ConfiguredEmbedder {
+    pub embedder: std::sync::Arc<dyn Embedder>,
+    /// Disk cache of a reachable API embedder, to persist with
+    /// `persist_to_disk` before exiting
+    pub cache: Option<std::sync::Arc<crate::CachedEmbedder<ApiEmbedder>>>,
+}
+
+/// Build the embedder selected in `config.embedding`
+///
+/// `auto` tries the embedding server, then GloVe, then the test embedder.
+/// API embeddings are wrapped in a cache loaded from `cache_path` (failed
+/// requests are never cached, and the file is only used while the API
+/// answers). Status lines go to stdout unless `quiet`; warnings to stderr.
+pub fn embedder_from_config(config: &crate::Config, quiet: bool) -> ConfiguredEmbedder {
+    use std::sync::Arc;
+
+    let embedding_server_url = config.embedding.server_url.clone();
+    let embedder_choice = config.embedding.provider.to_lowercase();
+
+    let http_embedder = HttpEmbedder::new(&embedding_server_url);
+    let use_http = matches!(embedder_choice.as_str(), "auto" | "http") && http_embedder.health_check();
+    let mut cache = None;
+
+    let embedder: Arc<dyn Embedder> = if use_http {
+        // BGE-M3 server available - use it!
+        if !quiet { println!("🚀 Using BGE-M3 server ({})", embedding_server_url); }
+        Arc::new(http_embedder)
+    } else if embedder_choice == "ollama" || embedder_choice == "openai" {
+        let api = if embedder_choice == "ollama" {
+            ApiEmbedder::ollama(
+                config.embedding.api_url.as_deref().unwrap_or("http://localhost:11434"),
+                config.embedding.model.as_deref().unwrap_or("nomic-embed-text"),
+            )
+        } else {
+            ApiEmbedder::openai(
+                config.embedding.api_url.as_deref().unwrap_or("https://api.openai.com"),
+                config.embedding.model.as_deref().unwrap_or("text-embedding-3-small"),
+            )
+        };
+        let reachable = api.health_check();
+        if !reachable {
+            eprintln!("⚠️ {} embeddings unreachable ({}), memories are stored without embeddings", embedder_choice, api.model());
+        } else if !quiet {
+            println!("🌐 Using {} embeddings ({}, {}d)", embedder_choice, api.model(), api.dimension());
+        }
+        let cached = Arc::new(crate::CachedEmbedder::with_default_cache(api));
+        if reachable {
+            if config.embedding.cache_path.exists() {
+                match cached.load_from_disk(&config.embedding.cache_path) {
+                    Ok(count) => {
+                        if !quiet { println!("💾 Embedding cache loaded ({} entries)", count); }
+                    }
+                    Err(e) => {
+                        if !quiet { eprintln!("⚠️ Embedding cache ignored: {}", e); }
+                    }
+                }
+            }
+            cache = Some(cached.clone());
+        }
+        cached
+    } else if embedder_choice == "tfidf" {
+        let tfidf = match TfIdfEmbedder::load(&config.embedding.tfidf_path) {
+            Ok(tfidf) => {
+                if !quiet { println!("📐 TF-IDF vocabulary loaded ({} terms)", tfidf.vocabulary_size()); }
+                if tfidf.is_outdated() {
+                    eprintln!("⚠️ Stored TF-IDF vectors use query weighting, re-run: memory-brain reindex --embedder tfidf");
+                }
+                tfidf
+            }
+            Err(_) => {
+                if !quiet { eprintln!("⚠️ No TF-IDF vocabulary yet, run: memory-brain reindex --embedder tfidf"); }
+                TfIdfEmbedder::new(TFIDF_DIMENSION)
+            }
+        };
+        Arc::new(tfidf)
+    } else {
+        // Fall back to GloVe or test embedder
+        let glove_path = &config.embedding.glove_path;
+
+        if embedder_choice != "test" && glove_path.exists() {
+            match crate::GloVeEmbedder::load(glove_path, Some(50000)) {
+                Ok(embedder) => {
+                    if !quiet { println!("📚 GloVe embeddings loaded"); }
+                    Arc::new(embedder)
+                }
+                Err(e) => {
+                    if !quiet { eprintln!("⚠️ GloVe load failed: {}", e); }
+                    Arc::new(crate::GloVeEmbedder::test_embedder())
+                }
+            }
+        } else {
+            if !quiet {
+                println!("🧪 Using test embedder (start embedding server for better results)");
+            }
+            Arc::new(crate::GloVeEmbedder::test_embedder())
+        }
+    };
+
+    ConfiguredEmbedder { embedder, cache }
+}
+
 /// Cosine similarity between two vectors
 /// 
-/// Now uses SIMD acceleration (NEON on Apple Silicon, AVX on x86_64)
+/// Now uses SIMD acceleration (NEON on Apple Silicon, AVX on x86_64).
+/// Never NaN: zero-norm or mismatched-length vectors score 0.0.
 #[inline]
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     crate::simd_ops::cosine_similarity_simd(a, b)
@@ -472,6 +922,86 @@ mod tests {
         assert!(sim_same > sim_diff);
     }
 
+    #[test]
+    fn test_cosine_similarity_degenerate_inputs_are_zero() {
+        let a = vec![1.0, 2.0, 3.0];
+
+        // Normal case
+        assert!((cosine_similarity(&a, &a) - 1.0).abs() < 1e-5);
+        assert!((cosine_similarity(&a, &[-1.0, -2.0, -3.0]) + 1.0).abs() < 1e-5);
+
+        // Zero vector, mismatched lengths, non-finite components
+        assert_eq!(cosine_similarity(&a, &[0.0, 0.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0; 3], &[0.0; 3]), 0.0);
+        assert_eq!(cosine_similarity(&a, &[1.0, 2.0]), 0.0);
+        assert_eq!(cosine_similarity(&[], &[]), 0.0);
+        assert_eq!(cosine_similarity(&a, &[f32::NAN, 1.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[f32::MAX; 3], &[f32::MAX; 3]), 0.0);
+    }
+
+    #[test]
+    fn test_api_embedder_against_mock_server_is_cached() {
+        use std::io::{Read, Write};
+        use std::sync::Arc;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let seen = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                seen.fetch_add(1, Ordering::SeqCst);
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                // Read until the JSON body has closed
+                while !String::from_utf8_lossy(&request).trim_end().ends_with('}') {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                assert!(String::from_utf8_lossy(&request).starts_with("POST /api/embeddings"));
+                let body = r#"{"embedding":[0.5,-0.25,1.0]}"#;
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        let api = ApiEmbedder::ollama(&url, "nomic-embed-text");
+        assert_eq!(api.dimension(), 3); // asks the server
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        let embedder = crate::cache::CachedEmbedder::new(api, 16);
+
+        assert_eq!(embedder.embed("rust ownership"), vec![0.5, -0.25, 1.0]);
+        assert_eq!(embedder.dimension(), 3);
+        assert_eq!(embedder.embed("rust ownership"), vec![0.5, -0.25, 1.0]);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_api_embedder_unreachable_falls_back() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let embedder = ApiEmbedder::new(ApiKind::OpenAi, &format!("http://127.0.0.1:{}", port), "text-embedding-3-small")
+            .with_dimension(8);
+
+        assert!(embedder.try_embed("hello").is_err());
+        assert_eq!(embedder.embed("hello"), vec![0.0; 8]);
+        assert_eq!(embedder.endpoint(), format!("http://127.0.0.1:{}/v1/embeddings", port));
+
+        // Failures are never cached
+        let cached = crate::cache::CachedEmbedder::new(embedder, 16);
+        assert!(cached.try_embed_document("hello").is_err());
+        assert_eq!(cached.embed("hello"), vec![0.0; 8]);
+        assert_eq!(cached.stats().size, 0);
+    }
+
     #[test]
     fn test_tfidf_embedder() {
         let corpus = vec![
@@ -489,4 +1019,75 @@ mod tests {
         assert!(v1.iter().any(|&x| x != 0.0));
         assert!(v2.iter().any(|&x| x != 0.0));
     }
+
+    #[test]
+    fn test_tfidf_fit_weights_rare_terms_higher() {
+        let corpus = vec![
+            "rust compiler borrow checker",
+            "rust cargo workspace",
+            "rust macros expansion",
+            "python notebook",
+        ];
+        let mut embedder = TfIdfEmbedder::new(100);
+        embedder.fit(&corpus);
+
+        assert!(embedder.idf("python") > embedder.idf("rust"));
+        // Unknown terms count as rare
+        assert!(embedder.idf("zebra") >= embedder.idf("python"));
+        assert!(embedder.embed("zebra").iter().any(|&x| x != 0.0));
+    }
+
+    #[test]
+    fn test_embed_query_and_document_default_to_embed() {
+        let embedder = HashEmbedder::new(64);
+        let text = "rust ownership rules";
+        assert_eq!(embedder.embed_query(text), embedder.embed(text));
+        assert_eq!(embedder.embed_document(text), embedder.embed(text));
+    }
+
+    #[test]
+    fn test_tfidf_query_is_idf_weighted_document_is_raw_tf() {
+        let corpus = vec![
+            "rust compiler borrow checker",
+            "rust cargo workspace",
+            "rust macros expansion",
+            "python notebook",
+        ];
+        let mut embedder = TfIdfEmbedder::new(100);
+        embedder.fit(&corpus);
+        let (rust, python) = (embedder.vocabulary["rust"], embedder.vocabulary["python"]);
+
+        // Equal counts weigh the same in a document...
+        let document = embedder.embed_document("rust python");
+        assert!((document[rust] - document[python]).abs() < 1e-6);
+
+        // ...while the query favours the rarer term
+        let query = embedder.embed_query("rust python");
+        assert!(query[python] > query[rust]);
+        assert_eq!(query, embedder.embed("rust python"));
+        assert_ne!(query, document);
+    }
+
+    #[test]
+    fn test_tfidf_save_load_roundtrip() {
+        let corpus = vec!["rust is fast", "python is readable", "rust is safe"];
+        let mut embedder = TfIdfEmbedder::new(64);
+        embedder.fit(&corpus);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tfidf.json");
+        embedder.save(&path).unwrap();
+        let loaded = TfIdfEmbedder::load(&path).unwrap();
+
+        assert_eq!(loaded.vocabulary_size(), embedder.vocabulary_size());
+        assert_eq!(loaded.idf("rust"), embedder.idf("rust"));
+        assert_eq!(loaded.embed("safe rust"), embedder.embed("safe rust"));
+        assert!(!loaded.is_outdated());
+
+        // Files from before `embed_document` was used for stored vectors
+        let mut json: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        json.as_object_mut().unwrap().remove("format_version");
+        std::fs::write(&path, json.to_string()).unwrap();
+        assert!(TfIdfEmbedder::load(&path).unwrap().is_outdated());
+    }
 }
@@ -0,0 +1,304 @@
+//! Embedding Export/Import
+//!
+//! Dumps stored embeddings as an `n × dim` float32 matrix for external
+//! analysis (NumPy, pandas, ...) and attaches externally computed
+//! embeddings back onto memories.
+//!
+//! Layout for `export <file>`:
+//! - `<file>` - the matrix. `.npy` files use the NumPy format (`<f4`, C order),
+//!   anything else is raw little-endian f32, row-major
+//! - `<file>.ids` - one memory UUID per line, in row order
+//!
+//! ```python
+//! import numpy as np
+//! emb = np.load("embeddings.npy")            # shape (n, dim)
+//! ids = open("embeddings.npy.ids").read().split()
+//! ```
+
+use crate::{Brain, MemoryItem};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Embeddings of many memories as a dense row-major matrix
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddingMatrix {
+    /// Memory id of each row
+    pub ids: Vec<Uuid>,
+    /// Columns per row
+    pub dim: usize,
+    /// Row-major values (`ids.len() * dim`)
+    pub data: Vec<f32>,
+}
+
+impl EmbeddingMatrix {
+    /// Build from memories, skipping ones without an embedding
+    ///
+    /// Fails if the embeddings don't all share one dimension.
+    pub fn from_memories(items: &[MemoryItem]) -> Result<Self, Box<dyn Error>> {
+        let mut matrix = Self { ids: Vec::new(), dim: 0, data: Vec::new() };
+
+        for item in items {
+            let Some(ref emb) = item.embedding else { continue };
+            if matrix.ids.is_empty() {
+                matrix.dim = emb.len();
+            } else if emb.len() != matrix.dim {
+                return Err(format!(
+                    "Inconsistent embedding dimension: {} has {}, expected {}",
+                    item.id, emb.len(), matrix.dim
+                ).into());
+            }
+            matrix.ids.push(item.id);
+            matrix.data.extend_from_slice(emb);
+        }
+
+        Ok(matrix)
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Row `i` as a slice
+    pub fn row(&self, i: usize) -> &[f32] {
+        &self.data[i * self.dim..(i + 1) * self.dim]
+    }
+
+    /// Write the matrix and its id list (`<path>.ids`)
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        if is_npy(path) {
+            write_npy(path, self.len(), self.dim, &self.data)?;
+        } else {
+            fs::write(path, f32_to_bytes(&self.data))?;
+        }
+
+        let ids: Vec<String> = self.ids.iter().map(|id| id.to_string()).collect();
+        fs::write(ids_path(path), ids.join("\n") + "\n")?;
+        Ok(())
+    }
+
+    /// Read a matrix and its id list (`<path>.ids`)
+    ///
+    /// For raw files the dimension is inferred from the id count.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+
+        let ids = fs::read_to_string(ids_path(path))?
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(Uuid::parse_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (rows, dim, data) = if is_npy(path) {
+            read_npy(path)?
+        } else {
+            let data = bytes_to_f32(&fs::read(path)?)?;
+            if ids.is_empty() {
+                (0, 0, data)
+            } else {
+                if data.len() % ids.len() != 0 {
+                    return Err(format!(
+                        "{} floats can't be split into {} rows",
+                        data.len(), ids.len()
+                    ).into());
+                }
+                (ids.len(), data.len() / ids.len(), data)
+            }
+        };
+
+        if rows != ids.len() {
+            return Err(format!("Matrix has {} rows but {} ids", rows, ids.len()).into());
+        }
+
+        Ok(Self { ids, dim, data })
+    }
+}
+
+/// Id list path for a matrix file (`embeddings.npy` → `embeddings.npy.ids`)
+pub fn ids_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".ids");
+    PathBuf::from(name)
+}
+
+/// Export every long-term memory embedding
+pub fn export_embeddings<P: AsRef<Path>>(brain: &Brain, path: P) -> Result<EmbeddingMatrix, Box<dyn Error>> {
+    let mut items = brain.semantic.search("", usize::MAX)?;
+    items.extend(brain.episodic.search("", usize::MAX)?);
+    items.extend(brain.procedural.search("", usize::MAX)?);
+
+    let matrix = EmbeddingMatrix::from_memories(&items)?;
+    matrix.write(path)?;
+    Ok(matrix)
+}
+
+/// Import result
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportStats {
+    /// Memories whose embedding was replaced
+    pub updated: usize,
+    /// Ids not found in any store
+    pub missing: Vec<Uuid>,
+}
+
+/// Attach embeddings from a matrix file onto memories by id
+///
+/// The matrix dimension must match the brain's embedder, otherwise
+/// recall would compare vectors of different sizes.
+pub fn import_embeddings<P: AsRef<Path>>(brain: &mut Brain, path: P) -> Result<ImportStats, Box<dyn Error>> {
+    let matrix = EmbeddingMatrix::read(path)?;
+
+    let expected = brain.embedder().dimension();
+    if !matrix.is_empty() && matrix.dim != expected {
+        return Err(format!(
+            "Dimension mismatch: file has {}d embeddings, embedder uses {}d",
+            matrix.dim, expected
+        ).into());
+    }
+
+    let mut stats = ImportStats::default();
+    for (i, id) in matrix.ids.iter().enumerate() {
+        if brain.set_embedding(*id, matrix.row(i).to_vec())? {
+            stats.updated += 1;
+        } else {
+            stats.missing.push(*id);
+        }
+    }
+
+    Ok(stats)
+}
+
+fn is_npy(path: &Path) -> bool {
+    path.extension().map_or(false, |e| e.eq_ignore_ascii_case("npy"))
+}
+
+fn f32_to_bytes(data: &[f32]) -> Vec<u8> {
+    data.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn bytes_to_f32(bytes: &[u8]) -> Result<Vec<f32>, Box<dyn Error>> {
+    if bytes.len() % 4 != 0 {
+        return Err(format!("{} bytes is not a whole number of f32 values", bytes.len()).into());
+    }
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+/// Write a 2-D float32 array in NumPy `.npy` format (version 1.0)
+pub fn write_npy(path: &Path, rows: usize, cols: usize, data: &[f32]) -> Result<(), Box<dyn Error>> {
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}",
+        rows, cols
+    );
+    // Pad so magic + version + len + header is a multiple of 64, ending in '\n'
+    let unpadded = NPY_MAGIC.len() + 2 + 2 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    let mut out = Vec::with_capacity(NPY_MAGIC.len() + 4 + header.len() + data.len() * 4);
+    out.extend_from_slice(NPY_MAGIC);
+    out.extend_from_slice(&[1, 0]);
+    out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    out.extend_from_slice(header.as_bytes());
+    out.extend_from_slice(&f32_to_bytes(data));
+
+    fs::write(path, out)?;
+    Ok(())
+}
+
+/// Read a 2-D little-endian float32 `.npy` array: `(rows, cols, data)`
+pub fn read_npy(path: &Path) -> Result<(usize, usize, Vec<f32>), Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+    if bytes.len() < 10 || &bytes[..6] != NPY_MAGIC {
+        return Err("Not a .npy file".into());
+    }
+
+    let (header_len, header_start) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 if bytes.len() >= 12 => (
+            u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize,
+            12,
+        ),
+        v => return Err(format!("Unsupported .npy version {}", v).into()),
+    };
+    let data_start = header_start + header_len;
+    if bytes.len() < data_start {
+        return Err("Truncated .npy header".into());
+    }
+    let header = std::str::from_utf8(&bytes[header_start..data_start])?;
+
+    if !header.contains("'descr': '<f4'") {
+        return Err(format!("Only little-endian float32 (<f4) is supported: {}", header.trim()).into());
+    }
+    if header.contains("'fortran_order': True") {
+        return Err("Fortran-ordered arrays are not supported".into());
+    }
+
+    let shape = header
+        .split("'shape':")
+        .nth(1)
+        .and_then(|s| s.split(')').next())
+        .map(|s| s.trim().trim_start_matches('('))
+        .ok_or("Missing shape in .npy header")?;
+    let dims = shape
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let (rows, cols) = match dims.as_slice() {
+        [rows, cols] => (*rows, *cols),
+        [rows] => (*rows, 1),
+        _ => return Err(format!("Expected a 2-D array, got shape ({})", shape).into()),
+    };
+
+    let data = bytes_to_f32(&bytes[data_start..])?;
+    if data.len() != rows * cols {
+        return Err(format!("Expected {}×{} values, found {}", rows, cols, data.len()).into());
+    }
+
+    Ok((rows, cols, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npy_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("m.npy");
+        let data: Vec<f32> = (0..12).map(|i| i as f32 * 0.5).collect();
+
+        write_npy(&path, 3, 4, &data).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        // Data starts on a 64-byte boundary
+        assert_eq!((bytes.len() - data.len() * 4) % 64, 0);
+
+        let (rows, cols, read) = read_npy(&path).unwrap();
+        assert_eq!((rows, cols), (3, 4));
+        assert_eq!(read, data);
+    }
+
+    #[test]
+    fn test_inconsistent_dimensions_rejected() {
+        let mut a = MemoryItem::new("a", None);
+        a.embedding = Some(vec![0.0; 4]);
+        let mut b = MemoryItem::new("b", None);
+        b.embedding = Some(vec![0.0; 3]);
+
+        assert!(EmbeddingMatrix::from_memories(&[a, b]).is_err());
+    }
+}
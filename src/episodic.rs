@@ -9,6 +9,31 @@
 use crate::types::{MemoryItem, MemoryType};
 use crate::forgetting::ForgettingCurve;
 use crate::storage::Storage;
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeMap;
+
+/// Bucket size for [`EpisodicMemory::timeline_by`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimelineGranularity {
+    #[default]
+    Day,
+    /// Weeks starting on Monday
+    Week,
+    Month,
+}
+
+impl TimelineGranularity {
+    /// First date of the bucket containing `date`
+    pub fn bucket(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            TimelineGranularity::Day => date,
+            TimelineGranularity::Week => {
+                date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            TimelineGranularity::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
 
 pub struct EpisodicMemory {
     storage: Storage,
@@ -20,6 +45,12 @@ impl EpisodicMemory {
         Ok(Self { storage })
     }
 
+    /// Open in `keyspace` with custom CoreDB tuning (see [`Storage::open`])
+    pub fn open(db_path: &str, keyspace: &str, tuning: &coredb::DatabaseConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let storage = Storage::open(db_path, keyspace, "episodic", tuning)?;
+        Ok(Self { storage })
+    }
+
     /// Store an episodic memory
     pub fn store(&mut self, mut item: MemoryItem) -> Result<(), Box<dyn std::error::Error>> {
         item.memory_type = MemoryType::Episodic;
@@ -27,6 +58,55 @@ impl EpisodicMemory {
         Ok(())
     }
 
+    /// Get a memory by id
+    pub fn get(&self, id: &uuid::Uuid) -> Result<Option<MemoryItem>, Box<dyn std::error::Error>> {
+        self.storage.get_by_id(id)
+    }
+
+    /// Overwrite a stored memory (matched by id)
+    pub fn update(&self, item: &MemoryItem) -> Result<(), Box<dyn std::error::Error>> {
+        self.storage.update(item)
+    }
+
+    /// Link `from` → `to` (no-op if `from` isn't stored here)
+    pub fn add_association(&mut self, from: uuid::Uuid, to: uuid::Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        self.storage.add_association(from, to)
+    }
+
+    /// Delete a memory by id (false if it isn't stored here)
+    pub fn delete(&mut self, id: &uuid::Uuid) -> Result<bool, Box<dyn std::error::Error>> {
+        if self.storage.get_by_id(id)?.is_none() {
+            return Ok(false);
+        }
+        self.storage.delete(id)?;
+        Ok(true)
+    }
+
+    /// Bytes of stored embeddings (see [`Storage::embedding_bytes`])
+    pub fn embedding_bytes(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.storage.embedding_bytes()
+    }
+
+    /// Rewrite stored embeddings as int8 (see [`Storage::quantize_stored_embeddings`])
+    pub fn quantize_stored_embeddings(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.storage.quantize_stored_embeddings()
+    }
+
+    /// Store new embeddings as int8 (see [`Storage::set_quantize_embeddings`])
+    pub fn set_quantize_embeddings(&mut self, on: bool) {
+        self.storage.set_quantize_embeddings(on);
+    }
+
+    /// Delete every episodic memory; returns how many were removed
+    pub fn clear(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.storage.clear()
+    }
+
+    /// Number of stored episodic memories
+    pub fn count(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.storage.count()
+    }
+
     /// Search episodic memories
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
         self.storage.search(query, limit)
@@ -41,9 +121,61 @@ impl EpisodicMemory {
         self.storage.get_by_time_range(start, end)
     }
 
-    /// Get recent memories
-    pub fn get_recent(&self, limit: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
-        self.storage.get_recent(limit)
+    /// Memories between `start` and `end`, bucketed by local calendar day
+    pub fn timeline(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<BTreeMap<NaiveDate, Vec<MemoryItem>>, Box<dyn std::error::Error>> {
+        self.timeline_by(start, end, TimelineGranularity::Day)
+    }
+
+    /// Memories between `start` and `end`, bucketed by `granularity`
+    ///
+    /// Keys are the first local date of each bucket; items within a bucket
+    /// are newest first.
+    pub fn timeline_by(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        granularity: TimelineGranularity,
+    ) -> Result<BTreeMap<NaiveDate, Vec<MemoryItem>>, Box<dyn std::error::Error>> {
+        let mut buckets: BTreeMap<NaiveDate, Vec<MemoryItem>> = BTreeMap::new();
+        for item in self.get_by_time_range(start, end)? {
+            let date = item.created_at.with_timezone(&chrono::Local).date_naive();
+            buckets.entry(granularity.bucket(date)).or_default().push(item);
+        }
+        Ok(buckets)
+    }
+
+    /// Memories from earlier years on the reference's month/day (± `window_days`)
+    ///
+    /// Dates are local calendar days, like [`EpisodicMemory::timeline`];
+    /// windows wrap across New Year, and a Feb 29 memory shows on Feb 28 in
+    /// common years. Newest first.
+    pub fn on_this_day(&self, reference: chrono::DateTime<chrono::Utc>, window_days: i64) -> Vec<MemoryItem> {
+        let today = reference.with_timezone(&chrono::Local).date_naive();
+        let window = window_days.max(0);
+        let mut items: Vec<MemoryItem> = self
+            .get_by_time_range(chrono::DateTime::<chrono::Utc>::MIN_UTC, reference)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|item| {
+                let date = item.created_at.with_timezone(&chrono::Local).date_naive();
+                // Anniversaries near today, at least one year after the memory
+                (today.year() - 1..=today.year() + 1)
+                    .filter(|&year| year > date.year())
+                    .filter_map(|year| anniversary(date, year))
+                    .any(|day| (day - today).num_days().abs() <= window)
+            })
+            .collect();
+        items.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        items
+    }
+
+    /// Get recent memories, skipping the newest `offset`
+    pub fn get_recent(&self, limit: usize, offset: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+        self.storage.get_recent(limit, offset)
     }
 
     /// Apply forgetting curve to old memories
@@ -74,3 +206,98 @@ impl EpisodicMemory {
         self.storage.get_associated(id)
     }
 }
+
+/// `date` moved to `year`; Feb 29 becomes Feb 28 in common years
+fn anniversary(date: NaiveDate, year: i32) -> Option<NaiveDate> {
+    date.with_year(year)
+        .or_else(|| NaiveDate::from_ymd_opt(year, date.month(), date.day() - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_timeline_buckets_by_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut episodic = EpisodicMemory::new(dir.path().join("timeline.db").to_str().unwrap()).unwrap();
+
+        let times = [
+            Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 3, 1, 12, 15, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 3, 3, 12, 0, 0).unwrap(),
+        ];
+        for (i, time) in times.iter().enumerate() {
+            let mut item = MemoryItem::new(&format!("Event {}", i), None);
+            item.created_at = *time;
+            episodic.store(item).unwrap();
+        }
+
+        let start = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+        let timeline = episodic.timeline(start, end).unwrap();
+
+        let local = |t: &chrono::DateTime<Utc>| t.with_timezone(&chrono::Local).date_naive();
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[&local(&times[0])].len(), 2);
+        assert_eq!(timeline[&local(&times[2])].len(), 1);
+
+        let by_month = episodic.timeline_by(start, end, TimelineGranularity::Month).unwrap();
+        assert_eq!(by_month.len(), 1);
+        assert_eq!(by_month.values().next().unwrap().len(), 3);
+    }
+
+    fn store_at(episodic: &mut EpisodicMemory, content: &str, y: i32, m: u32, d: u32) {
+        let mut item = MemoryItem::new(content, None);
+        item.created_at = Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap();
+        episodic.store(item).unwrap();
+    }
+
+    #[test]
+    fn test_on_this_day_matches_month_day_across_years() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut episodic = EpisodicMemory::new(dir.path().join("onthisday.db").to_str().unwrap()).unwrap();
+        store_at(&mut episodic, "Moved to Seoul", 2022, 3, 15);
+        store_at(&mut episodic, "Started the new job", 2023, 3, 15);
+        store_at(&mut episodic, "Summer trip", 2023, 6, 1);
+        store_at(&mut episodic, "Coffee this morning", 2024, 3, 15);
+
+        let reference = Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
+        let contents: Vec<String> = episodic.on_this_day(reference, 0).into_iter().map(|m| m.content).collect();
+        assert_eq!(contents, vec!["Started the new job", "Moved to Seoul"]);
+
+        // A day window reaches neighbouring dates, still not months away
+        store_at(&mut episodic, "Birthday dinner", 2021, 3, 16);
+        let contents: Vec<String> = episodic.on_this_day(reference, 1).into_iter().map(|m| m.content).collect();
+        assert_eq!(contents.len(), 3);
+        assert!(!contents.contains(&"Summer trip".to_string()));
+    }
+
+    #[test]
+    fn test_on_this_day_leap_day_and_new_year() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut episodic = EpisodicMemory::new(dir.path().join("leap.db").to_str().unwrap()).unwrap();
+        store_at(&mut episodic, "Leap day hike", 2024, 2, 29);
+        store_at(&mut episodic, "First of March", 2024, 3, 1);
+        store_at(&mut episodic, "New Year's Eve party", 2023, 12, 31);
+        store_at(&mut episodic, "Last night", 2024, 12, 31);
+
+        // Feb 29 shows on Feb 28 of a common year
+        let feb28 = Utc.with_ymd_and_hms(2025, 2, 28, 12, 0, 0).unwrap();
+        let contents: Vec<String> = episodic.on_this_day(feb28, 0).into_iter().map(|m| m.content).collect();
+        assert_eq!(contents, vec!["Leap day hike"]);
+
+        // The window wraps the year, but yesterday isn't a past year
+        let new_year = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let contents: Vec<String> = episodic.on_this_day(new_year, 1).into_iter().map(|m| m.content).collect();
+        assert_eq!(contents, vec!["New Year's Eve party"]);
+    }
+
+    #[test]
+    fn test_week_bucket_starts_on_monday() {
+        let sunday = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        assert_eq!(TimelineGranularity::Week.bucket(sunday), NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+        assert_eq!(TimelineGranularity::Month.bucket(sunday), NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+    }
+}
@@ -0,0 +1,259 @@
+//! Memory Export Formats
+//!
+//! CSV and Markdown serialization for `export --format`, plus the CSV reader
+//! used by `import` so exports round-trip.
+//!
+//! CSV columns: `content,tags,type,created_at,strength`. Fields containing
+//! commas, quotes or newlines are quoted (RFC 4180); tags are `;`-separated.
+
+use crate::cli_json::type_name;
+use crate::types::{MemoryItem, MemoryType};
+use chrono::{DateTime, Utc};
+
+/// CSV header row
+pub const CSV_HEADER: &str = "content,tags,type,created_at,strength";
+
+/// Serialize memories as CSV (with header)
+pub fn to_csv(items: &[MemoryItem]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for item in items {
+        let fields = [
+            item.content.clone(),
+            item.tags.join(";"),
+            type_name(&item.memory_type).to_string(),
+            item.created_at.to_rfc3339(),
+            item.strength.to_string(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Serialize memories as Markdown, one section per memory
+pub fn to_markdown(items: &[MemoryItem]) -> String {
+    let mut out = String::from("# Memories\n");
+    for (i, item) in items.iter().enumerate() {
+        out.push_str(&format!("\n## Memory {}\n\n{}\n\n", i + 1, item.content.trim()));
+        out.push_str(&format!("- **id**: `{}`\n", item.id));
+        out.push_str(&format!("- **type**: {}\n", type_name(&item.memory_type)));
+        if !item.tags.is_empty() {
+            out.push_str(&format!("- **tags**: {}\n", item.tags.join(", ")));
+        }
+        if let Some(ref context) = item.context {
+            out.push_str(&format!("- **context**: {}\n", context));
+        }
+        out.push_str(&format!("- **created_at**: {}\n", item.created_at.to_rfc3339()));
+        out.push_str(&format!("- **strength**: {:.2}\n", item.strength));
+    }
+    out
+}
+
+/// Quote a CSV field if it needs it
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Split CSV text into rows of fields
+///
+/// Handles quoted fields with embedded commas, `""` escapes and newlines.
+pub fn parse_csv(input: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    // Skip blank lines
+    rows.retain(|r| !(r.len() == 1 && r[0].trim().is_empty()));
+    rows
+}
+
+/// One memory read from CSV
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvRecord {
+    pub content: String,
+    pub tags: Vec<String>,
+    pub memory_type: Option<MemoryType>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub strength: Option<f32>,
+}
+
+/// Read memories from CSV, skipping rows [`from_csv_rows`] rejects
+pub fn from_csv(input: &str) -> Vec<CsvRecord> {
+    from_csv_rows(input).into_iter().filter_map(Result::ok).collect()
+}
+
+/// Read memories from CSV, one result per data row
+///
+/// With a header row (first column `content` or `text`) columns are matched
+/// by name; without one they are positional (`content,tags,...`). Tags may
+/// be `;`- or `,`-separated. Content is kept exactly as written; a row
+/// without content or with an unreadable `type`, `created_at` or `strength`
+/// is an error naming the row (data rows counted from 1).
+pub fn from_csv_rows(input: &str) -> Vec<Result<CsvRecord, String>> {
+    let mut rows = parse_csv(input).into_iter().peekable();
+
+    let mut columns: Vec<String> = CSV_HEADER.split(',').map(str::to_string).collect();
+    if let Some(first) = rows.peek() {
+        let lower = first.first().map(|f| f.trim().to_lowercase()).unwrap_or_default();
+        if lower == "content" || lower == "text" {
+            columns = first.iter().map(|c| c.trim().to_lowercase()).collect();
+            columns[0] = "content".to_string();
+            rows.next();
+        }
+    }
+    let col = |name: &str| columns.iter().position(|c| c == name);
+    let (tags_col, type_col, created_col, strength_col) = (col("tags"), col("type"), col("created_at"), col("strength"));
+
+    rows.enumerate()
+        .map(|(i, row)| {
+            let row_no = i + 1;
+            let content = row.first().map(String::as_str).unwrap_or_default();
+            if content.trim().is_empty() {
+                return Err(format!("row {}: empty content", row_no));
+            }
+            // Optional columns; empty cells count as missing
+            let get = |idx: Option<usize>| {
+                idx.and_then(|i| row.get(i)).map(|s| s.trim()).filter(|s| !s.is_empty())
+            };
+            let memory_type = get(type_col)
+                .map(|s| parse_type(s).ok_or_else(|| format!("row {}: unknown type '{}'", row_no, s)))
+                .transpose()?;
+            let created_at = get(created_col)
+                .map(|s| {
+                    DateTime::parse_from_rfc3339(s)
+                        .map(|d| d.with_timezone(&Utc))
+                        .map_err(|_| format!("row {}: invalid created_at '{}'", row_no, s))
+                })
+                .transpose()?;
+            let strength = get(strength_col)
+                .map(|s| s.parse().map_err(|_| format!("row {}: invalid strength '{}'", row_no, s)))
+                .transpose()?;
+
+            Ok(CsvRecord {
+                content: content.to_string(),
+                tags: get(tags_col)
+                    .map(|t| {
+                        t.split(&[',', ';'][..])
+                            .map(|s| s.trim().to_string())
+                            .filter(|s| !s.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                memory_type,
+                created_at,
+                strength,
+            })
+        })
+        .collect()
+}
+
+fn parse_type(name: &str) -> Option<MemoryType> {
+    match name.to_lowercase().as_str() {
+        "working" => Some(MemoryType::Working),
+        "episodic" => Some(MemoryType::Episodic),
+        "semantic" => Some(MemoryType::Semantic),
+        "procedural" => Some(MemoryType::Procedural),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_roundtrip_with_quotes_commas_newlines() {
+        let tricky = MemoryItem::new("He said \"hi\", then left\nsecond line", None)
+            .with_type(MemoryType::Episodic)
+            .with_tags(vec!["quotes".to_string(), "multi line".to_string()]);
+        let plain = MemoryItem::new("Rust uses ownership", None)
+            .with_type(MemoryType::Semantic)
+            .with_tags(vec!["rust".to_string()]);
+
+        let csv = to_csv(&[tricky.clone(), plain.clone()]);
+        let records = from_csv(&csv);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].content, tricky.content);
+        assert_eq!(records[0].tags, tricky.tags);
+        assert_eq!(records[0].memory_type, Some(MemoryType::Episodic));
+        assert_eq!(records[0].created_at.map(|d| d.timestamp()), Some(tricky.created_at.timestamp()));
+        assert_eq!(records[1].content, plain.content);
+        assert_eq!(records[1].strength, Some(plain.strength));
+    }
+
+    #[test]
+    fn test_from_csv_without_header() {
+        let records = from_csv("first memory,a;b\n\"second, quoted\",c\n\n");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].tags, vec!["a", "b"]);
+        assert_eq!(records[1].content, "second, quoted");
+        assert_eq!(records[1].memory_type, None);
+    }
+
+    #[test]
+    fn test_from_csv_rows_keeps_content_and_reports_bad_rows() {
+        let input = "content,tags,type,created_at,strength\n\
+            \"  indented\n  code  \",rust,semantic,,0.5\n\
+            \" \",x,,,\n\
+            fine,,dreams,,\n\
+            later,,,yesterday,\n\
+            weak,,,,very\n";
+        let rows = from_csv_rows(input);
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[0].as_ref().unwrap().content, "  indented\n  code  ");
+        assert_eq!(rows[1], Err("row 2: empty content".to_string()));
+        assert_eq!(rows[2], Err("row 3: unknown type 'dreams'".to_string()));
+        assert!(rows[3].as_ref().unwrap_err().starts_with("row 4: invalid created_at"));
+        assert!(rows[4].as_ref().unwrap_err().starts_with("row 5: invalid strength"));
+        assert_eq!(from_csv(input).len(), 1);
+    }
+
+    #[test]
+    fn test_markdown_sections() {
+        let item = MemoryItem::new("Pattern: use Result", None)
+            .with_type(MemoryType::Procedural)
+            .with_tags(vec!["rust".to_string()]);
+        let md = to_markdown(&[item]);
+
+        assert!(md.contains("## Memory 1\n\nPattern: use Result\n"));
+        assert!(md.contains("- **type**: procedural"));
+        assert!(md.contains("- **tags**: rust"));
+    }
+}
@@ -7,25 +7,104 @@
 //! - R = retention
 //! - t = time since last access
 //! - S = memory strength/stability
+//!
+//! Decay rates can differ per memory type: episodic memories ("what
+//! happened when") fade fastest, procedural habits slowest.
+//!
+//! Reviews are scheduled SM-2 style: 1 day, 6 days, then the previous
+//! interval times an ease factor that grows with strength.
 
-use crate::types::MemoryItem;
-use chrono::Utc;
+use crate::types::{MemoryItem, MemoryType};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// Per-type daily decay rates used by [`ForgettingCurve::per_type`]
+pub const DEFAULT_DECAY_RATES: [(MemoryType, f32); 4] = [
+    (MemoryType::Working, 0.2),
+    (MemoryType::Episodic, 0.15),
+    (MemoryType::Semantic, 0.05),
+    (MemoryType::Procedural, 0.02),
+];
+
+/// Review interval after the first recall (SM-2's I(1))
+const FIRST_INTERVAL_DAYS: f32 = 1.0;
+/// Review interval after the second recall (SM-2's I(2))
+const SECOND_INTERVAL_DAYS: f32 = 6.0;
+/// Memories weaker than this start over at the first interval
+const RELEARN_STRENGTH: f32 = 0.3;
+/// Longest review interval
+const MAX_INTERVAL_DAYS: f32 = 365.0;
 
 pub struct ForgettingCurve {
     /// Base decay rate (higher = faster forgetting)
     base_decay_rate: f32,
+    /// Per-type overrides of `base_decay_rate`
+    decay_rates: HashMap<MemoryType, f32>,
     /// Minimum retention (memories never fully disappear until cleanup)
     min_retention: f32,
 }
 
 impl ForgettingCurve {
+    /// Uniform decay for every memory type
     pub fn new() -> Self {
         Self {
             base_decay_rate: 0.1,  // ~10% decay per day baseline
+            decay_rates: HashMap::new(),
             min_retention: 0.1,
         }
     }
 
+    /// Decay with [`DEFAULT_DECAY_RATES`] (episodic fastest, procedural slowest)
+    pub fn per_type() -> Self {
+        DEFAULT_DECAY_RATES
+            .iter()
+            .fold(Self::new(), |curve, (memory_type, rate)| curve.with_decay(memory_type.clone(), *rate))
+    }
+
+    /// Set the daily decay rate for one memory type
+    pub fn with_decay(mut self, memory_type: MemoryType, rate: f32) -> Self {
+        self.set_decay(memory_type, rate);
+        self
+    }
+
+    /// Set the daily decay rate for one memory type (in place)
+    pub fn set_decay(&mut self, memory_type: MemoryType, rate: f32) {
+        self.decay_rates.insert(memory_type, rate.max(0.0));
+    }
+
+    /// Daily decay rate applied to memories of this type
+    pub fn rate_for(&self, memory_type: &MemoryType) -> f32 {
+        self.decay_rates.get(memory_type).copied().unwrap_or(self.base_decay_rate)
+    }
+
+    /// Parse `episodic=0.1,semantic=0.02` into per-type rates
+    pub fn parse_rates(spec: &str) -> Result<Vec<(MemoryType, f32)>, String> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let (name, rate) = part
+                    .split_once('=')
+                    .ok_or_else(|| format!("Expected type=rate, got '{}'", part))?;
+                let memory_type = match name.trim().to_lowercase().as_str() {
+                    "working" | "w" => MemoryType::Working,
+                    "episodic" | "e" => MemoryType::Episodic,
+                    "semantic" | "s" => MemoryType::Semantic,
+                    "procedural" | "p" => MemoryType::Procedural,
+                    other => return Err(format!("Unknown memory type '{}'", other)),
+                };
+                let rate: f32 = rate
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid decay rate '{}'", rate.trim()))?;
+                if !rate.is_finite() || rate < 0.0 {
+                    return Err(format!("Decay rate must be >= 0, got {}", rate));
+                }
+                Ok((memory_type, rate))
+            })
+            .collect()
+    }
+
     /// Calculate decay factor for a memory (0.0 - 1.0)
     /// Returns the multiplier to apply to strength
     pub fn calculate_decay(&self, item: &MemoryItem) -> f32 {
@@ -44,12 +123,12 @@ impl ForgettingCurve {
         let stability = access_stability * strength_stability * age_stability;
 
         // Ebbinghaus-like decay: R = e^(-t/S)
-        let retention = (-days_since * self.base_decay_rate / stability).exp();
+        let retention = (-days_since * self.rate_for(&item.memory_type) / stability).exp();
 
         retention.max(self.min_retention)
     }
 
-    /// Apply decay to a list of memories
+    /// Apply decay to a list of memories (rate chosen by each item's type)
     pub fn apply_decay(&self, items: &mut Vec<MemoryItem>) {
         for item in items.iter_mut() {
             let decay = self.calculate_decay(item);
@@ -65,10 +144,35 @@ impl ForgettingCurve {
         let stability = (item.access_count as f32).ln().max(1.0) * item.strength;
 
         // t = -S * ln(R) / decay_rate
-        let hours = -stability * target_retention.ln() / self.base_decay_rate * 24.0;
+        let hours = -stability * target_retention.ln() / self.rate_for(&item.memory_type) * 24.0;
         hours.max(1.0) // At least 1 hour
     }
 
+    /// SM-2 review interval: 1 day, 6 days, then ×ease per further recall
+    ///
+    /// The ease factor runs from 1.3 (strength 0) to 2.5 (strength 1), and
+    /// a memory below `RELEARN_STRENGTH` starts over at one day. Types that
+    /// decay slower than the base rate wait proportionally longer.
+    pub fn review_interval(&self, item: &MemoryItem) -> Duration {
+        let days = if item.strength < RELEARN_STRENGTH {
+            FIRST_INTERVAL_DAYS
+        } else {
+            let ease = 1.3 + 1.2 * item.strength.min(1.0);
+            match item.access_count {
+                0 | 1 => FIRST_INTERVAL_DAYS,
+                n => SECOND_INTERVAL_DAYS * ease.powi(n.min(64) as i32 - 2),
+            }
+        };
+        let type_factor = (self.base_decay_rate / self.rate_for(&item.memory_type).max(0.001)).clamp(0.5, 5.0);
+        let days = (days * type_factor).min(MAX_INTERVAL_DAYS);
+        Duration::minutes((days * 24.0 * 60.0) as i64)
+    }
+
+    /// When the memory should next be reviewed (last access + interval)
+    pub fn next_review(&self, item: &MemoryItem) -> DateTime<Utc> {
+        item.last_accessed + self.review_interval(item)
+    }
+
     /// Check if memory needs review
     pub fn needs_review(&self, item: &MemoryItem) -> bool {
         let current_retention = self.calculate_decay(item);
@@ -138,6 +242,38 @@ mod tests {
         assert!(!needs_review || item.strength > 0.3);
     }
 
+    #[test]
+    fn test_episodic_decays_faster_than_semantic() {
+        let curve = ForgettingCurve::per_type();
+        let aged = |memory_type: MemoryType| {
+            let mut item = MemoryItem::new("aging memory", None).with_type(memory_type);
+            item.strength = 0.8;
+            item.last_accessed = Utc::now() - chrono::Duration::days(10);
+            item
+        };
+
+        let mut items = vec![aged(MemoryType::Episodic), aged(MemoryType::Semantic)];
+        curve.apply_decay(&mut items);
+
+        assert!(items[0].strength < items[1].strength);
+        assert!(items[1].strength < 0.8);
+    }
+
+    #[test]
+    fn test_with_decay_overrides_and_parse_rates() {
+        let rates = ForgettingCurve::parse_rates("episodic=0.3, semantic=0.01").unwrap();
+        let curve = rates
+            .into_iter()
+            .fold(ForgettingCurve::new(), |c, (t, r)| c.with_decay(t, r));
+
+        assert_eq!(curve.rate_for(&MemoryType::Episodic), 0.3);
+        assert_eq!(curve.rate_for(&MemoryType::Semantic), 0.01);
+        assert_eq!(curve.rate_for(&MemoryType::Procedural), 0.1); // base rate
+
+        assert!(ForgettingCurve::parse_rates("dreams=0.1").is_err());
+        assert!(ForgettingCurve::parse_rates("episodic=-1").is_err());
+    }
+
     #[test]
     fn test_optimal_review_time() {
         let curve = ForgettingCurve::new();
@@ -148,4 +284,38 @@ mod tests {
         // Should return positive hours
         assert!(review_time >= 1.0);
     }
+
+    #[test]
+    fn test_fresh_item_has_short_review_interval() {
+        let curve = ForgettingCurve::new();
+        let item = MemoryItem::new("just stored", None);
+
+        assert_eq!(curve.review_interval(&item), Duration::days(1));
+        assert!(curve.next_review(&item) <= Utc::now() + Duration::days(1));
+
+        // A weak memory starts over even after many recalls
+        let mut weak = MemoryItem::new("fading", None);
+        weak.access_count = 10;
+        weak.strength = 0.1;
+        assert_eq!(curve.review_interval(&weak), Duration::days(1));
+    }
+
+    #[test]
+    fn test_frequently_recalled_item_has_long_review_interval() {
+        let curve = ForgettingCurve::new();
+        let mut item = MemoryItem::new("recalled a lot", None);
+        item.access_count = 2;
+        assert_eq!(curve.review_interval(&item), Duration::days(6));
+
+        item.access_count = 6;
+        let interval = curve.review_interval(&item);
+        assert!(interval > Duration::days(30), "got {:?}", interval);
+        assert!(interval <= Duration::days(365));
+
+        // Procedural habits (slow decay) wait longer than episodic ones
+        let per_type = ForgettingCurve::per_type();
+        let procedural = item.clone().with_type(MemoryType::Procedural);
+        let episodic = item.with_type(MemoryType::Episodic);
+        assert!(per_type.review_interval(&procedural) > per_type.review_interval(&episodic));
+    }
 }
@@ -4,6 +4,10 @@
 //! Download from: https://nlp.stanford.edu/projects/glove/
 //! 
 //! Recommended: glove.6B.100d.txt (100-dimensional, smaller file)
+//!
+//! With [`GloVeEmbedder::with_subword`], out-of-vocabulary words (rare
+//! terms, typos) are embedded from their hashed character 3-grams instead
+//! of all sharing the average vector.
 
 use std::collections::HashMap;
 use std::fs::File;
@@ -12,12 +16,29 @@ use std::path::Path;
 
 use crate::embedding::{Embedder, normalize, tokenize};
 
+/// Character n-gram length of the subword fallback
+const SUBWORD_NGRAM: usize = 3;
+
+/// Outcome of [`GloVeEmbedder::load_with_stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GloVeLoadStats {
+    /// Vectors kept
+    pub loaded: usize,
+    /// Lines dropped for a wrong dimension or unparsable values
+    pub skipped: usize,
+    pub dimension: usize,
+}
+
 /// GloVe word embedding model
 pub struct GloVeEmbedder {
     embeddings: HashMap<String, Vec<f32>>,
     dimension: usize,
     /// OOV (out-of-vocabulary) embedding - average of all embeddings
     oov_embedding: Vec<f32>,
+    /// Embed OOV words from their character n-grams
+    subword: bool,
+    /// Average vector length in the vocabulary (scale of subword vectors)
+    mean_norm: f32,
 }
 
 impl GloVeEmbedder {
@@ -32,64 +53,91 @@ impl GloVeEmbedder {
     /// let embedder = GloVeEmbedder::load("glove.6B.100d.txt", Some(50000))?;
     /// ```
     pub fn load<P: AsRef<Path>>(path: P, max_words: Option<usize>) -> Result<Self, Box<dyn std::error::Error>> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        
-        let mut embeddings: HashMap<String, Vec<f32>> = HashMap::new();
-        let mut dimension = 0;
-        let mut sum_embedding: Vec<f32> = Vec::new();
-        let mut count = 0;
+        let (embedder, stats) = Self::load_with_stats(path, max_words)?;
+        println!("📚 Loaded {} GloVe embeddings ({}d)", stats.loaded, stats.dimension);
+        if stats.skipped > 0 {
+            eprintln!("⚠️ Skipped {} malformed GloVe lines (truncated download?)", stats.skipped);
+        }
+        Ok(embedder)
+    }
 
-        for (i, line) in reader.lines().enumerate() {
-            if let Some(max) = max_words {
-                if i >= max {
-                    break;
-                }
-            }
+    /// Like [`GloVeEmbedder::load`], validating every line
+    ///
+    /// The dimension comes from the file name (`glove.6B.100d.txt` → 100)
+    /// or else the most common vector length; lines with another length or
+    /// unparsable values, as left by an interrupted download, are skipped
+    /// and counted. Errors if no line has the detected dimension or it
+    /// disagrees with the file name.
+    pub fn load_with_stats<P: AsRef<Path>>(path: P, max_words: Option<usize>) -> Result<(Self, GloVeLoadStats), Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let reader = BufReader::new(File::open(path)?);
 
+        let mut parsed: Vec<(String, Vec<f32>)> = Vec::new();
+        let mut skipped = 0;
+        for line in reader.lines().take(max_words.unwrap_or(usize::MAX)) {
             let line = line?;
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            
-            if parts.len() < 2 {
+            let mut parts = line.split_whitespace();
+            let Some(word) = parts.next() else {
                 continue;
-            }
-
-            let word = parts[0].to_lowercase();
-            let values: Vec<f32> = parts[1..]
-                .iter()
-                .filter_map(|s| s.parse().ok())
+            };
+            let values: Option<Vec<f32>> = parts
+                .map(|s| s.parse::<f32>().ok().filter(|v| v.is_finite()))
                 .collect();
+            match values {
+                Some(values) if !values.is_empty() => parsed.push((word.to_lowercase(), values)),
+                _ => skipped += 1,
+            }
+        }
 
-            if dimension == 0 {
-                dimension = values.len();
-                sum_embedding = vec![0.0; dimension];
+        let detected = most_common_len(&parsed).ok_or("GloVe file has no vectors")?;
+        let dimension = match dimension_from_name(path) {
+            Some(named) if named != detected => {
+                return Err(format!(
+                    "GloVe file name says {}d but its vectors are {}d (corrupt or misnamed file?)",
+                    named, detected
+                )
+                .into());
             }
+            _ => detected,
+        };
 
-            if values.len() == dimension {
-                // Add to running sum for OOV embedding
-                for (i, v) in values.iter().enumerate() {
-                    sum_embedding[i] += v;
-                }
-                count += 1;
-                
-                embeddings.insert(word, values);
+        let mut embeddings: HashMap<String, Vec<f32>> = HashMap::new();
+        let mut sum_embedding = vec![0.0f32; dimension];
+        for (word, values) in parsed {
+            if values.len() != dimension {
+                skipped += 1;
+                continue;
             }
+            // Add to running sum for OOV embedding
+            for (sum, v) in sum_embedding.iter_mut().zip(&values) {
+                *sum += v;
+            }
+            embeddings.insert(word, values);
         }
 
         // Calculate OOV embedding as average
-        let oov_embedding: Vec<f32> = if count > 0 {
-            sum_embedding.iter().map(|v| v / count as f32).collect()
-        } else {
-            vec![0.0; dimension]
-        };
+        let count = embeddings.len().max(1) as f32;
+        let oov_embedding: Vec<f32> = sum_embedding.iter().map(|v| v / count).collect();
 
-        println!("📚 Loaded {} GloVe embeddings ({}d)", embeddings.len(), dimension);
+        let stats = GloVeLoadStats { loaded: embeddings.len(), skipped, dimension };
+        let mean_norm = mean_norm(&embeddings);
+        Ok((
+            Self {
+                embeddings,
+                dimension,
+                oov_embedding,
+                subword: false,
+                mean_norm,
+            },
+            stats,
+        ))
+    }
 
-        Ok(Self {
-            embeddings,
-            dimension,
-            oov_embedding,
-        })
+    /// Embed out-of-vocabulary words from their hashed character 3-grams
+    /// (off by default: OOV words use the vocabulary average)
+    pub fn with_subword(mut self, enabled: bool) -> Self {
+        self.subword = enabled;
+        self
     }
 
     /// Create a small test embedder with hardcoded common word embeddings
@@ -142,10 +190,13 @@ impl GloVeEmbedder {
         let count = embeddings.len() as f32;
         let oov_embedding: Vec<f32> = sum.iter().map(|v| v / count).collect();
         
+        let mean_norm = mean_norm(&embeddings);
         Self {
             embeddings,
             dimension,
             oov_embedding,
+            subword: false,
+            mean_norm,
         }
     }
 
@@ -174,6 +225,33 @@ impl GloVeEmbedder {
             .unwrap_or(&self.oov_embedding)
     }
 
+    /// Stable vector for an out-of-vocabulary word
+    ///
+    /// Averages one pseudo-random ±1 vector per 3-gram of `<word>` (seeded
+    /// by the n-gram's hash), so words sharing n-grams point the same way.
+    /// Scaled to the vocabulary's mean norm.
+    pub fn subword_embedding(&self, word: &str) -> Vec<f32> {
+        let chars: Vec<char> = std::iter::once('<')
+            .chain(word.to_lowercase().chars())
+            .chain(std::iter::once('>'))
+            .collect();
+
+        let mut sum = vec![0.0f32; self.dimension];
+        for gram in chars.windows(SUBWORD_NGRAM) {
+            let seed = fnv1a(gram.iter().collect::<String>().as_bytes());
+            for (i, v) in sum.iter_mut().enumerate() {
+                let bits = splitmix64(seed ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+                *v += if bits & 1 == 0 { 1.0 } else { -1.0 };
+            }
+        }
+
+        normalize(&mut sum);
+        for v in sum.iter_mut() {
+            *v *= self.mean_norm;
+        }
+        sum
+    }
+
     /// Check if word is in vocabulary
     pub fn contains(&self, word: &str) -> bool {
         self.embeddings.contains_key(&word.to_lowercase())
@@ -198,7 +276,15 @@ impl Embedder for GloVeEmbedder {
         let mut count = 0;
 
         for token in &tokens {
-            let emb = self.get_word_embedding(token);
+            let subword;
+            let emb = match self.embeddings.get(token) {
+                Some(emb) => emb.as_slice(),
+                None if self.subword => {
+                    subword = self.subword_embedding(token);
+                    subword.as_slice()
+                }
+                None => &self.oov_embedding,
+            };
             for (i, v) in emb.iter().enumerate() {
                 sum[i] += v;
             }
@@ -220,6 +306,58 @@ impl Embedder for GloVeEmbedder {
     }
 }
 
+/// Average L2 norm of the vocabulary vectors (1.0 if empty)
+fn mean_norm(embeddings: &HashMap<String, Vec<f32>>) -> f32 {
+    if embeddings.is_empty() {
+        return 1.0;
+    }
+    let total: f32 = embeddings
+        .values()
+        .map(|v| v.iter().map(|x| x * x).sum::<f32>().sqrt())
+        .sum();
+    total / embeddings.len() as f32
+}
+
+/// FNV-1a, stable across platforms and Rust versions (unlike `DefaultHasher`)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Most frequent vector length (ties go to the length seen first)
+fn most_common_len(parsed: &[(String, Vec<f32>)]) -> Option<usize> {
+    let mut counts: Vec<(usize, usize)> = Vec::new();
+    for (_, values) in parsed {
+        match counts.iter_mut().find(|(len, _)| *len == values.len()) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((values.len(), 1)),
+        }
+    }
+    counts
+        .iter()
+        .fold(None, |best: Option<(usize, usize)>, &(len, n)| match best {
+            Some((_, best_n)) if best_n >= n => best,
+            _ => Some((len, n)),
+        })
+        .map(|(len, _)| len)
+}
+
+/// Dimension in a GloVe file name, e.g. 100 for `glove.6B.100d.txt`
+fn dimension_from_name(path: &Path) -> Option<usize> {
+    let name = path.file_name()?.to_str()?;
+    name.split(['.', '_', '-'])
+        .filter_map(|part| part.strip_suffix('d'))
+        .find_map(|digits| digits.parse().ok())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,4 +383,46 @@ mod tests {
         // Programming languages should be more similar to each other
         assert!(sim_programming > sim_different);
     }
+
+    #[test]
+    fn test_load_skips_malformed_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("glove.test.3d.txt");
+        std::fs::write(&path, "rust 0.1 0.2 0.3\npython 0.4 0.5\ncargo 0.1 0.2 0.3 0.4\n").unwrap();
+
+        let (embedder, stats) = GloVeEmbedder::load_with_stats(&path, None).unwrap();
+        assert_eq!(stats, GloVeLoadStats { loaded: 1, skipped: 2, dimension: 3 });
+        assert!(embedder.contains("rust"));
+        assert!(!embedder.contains("python"));
+        assert!(!embedder.contains("cargo"));
+        assert_eq!(embedder.embed("python cargo").len(), 3);
+
+        // File name and contents disagree
+        let path = dir.path().join("glove.test.50d.txt");
+        std::fs::write(&path, "rust 0.1 0.2 0.3\n").unwrap();
+        assert!(GloVeEmbedder::load_with_stats(&path, None).is_err());
+    }
+
+    #[test]
+    fn test_subword_fallback_for_oov_words() {
+        let plain = GloVeEmbedder::test_embedder();
+        let embedder = GloVeEmbedder::test_embedder().with_subword(true);
+
+        // Without subwords every OOV word collapses to the same vector
+        assert!(cosine_similarity(&plain.embed("kubernetes"), &plain.embed("photosynthesis")) > 0.99);
+
+        let k1 = embedder.embed("kubernetes");
+        let k2 = embedder.embed("kubernetei");
+        let other = embedder.embed("photosynthesis");
+        assert_eq!(k1.len(), embedder.dimension());
+        assert!(k1.iter().any(|v| *v != 0.0));
+        assert_eq!(k1, embedder.embed("kubernetes"));
+
+        let shared = cosine_similarity(&k1, &k2);
+        let unrelated = cosine_similarity(&k1, &other);
+        assert!(shared > unrelated + 0.3, "shared {:.3} vs unrelated {:.3}", shared, unrelated);
+
+        // Known words are unaffected
+        assert_eq!(embedder.embed("rust programming"), plain.embed("rust programming"));
+    }
 }
@@ -9,6 +9,9 @@ use crate::{Brain, MemoryItem, cosine_similarity};
 use chrono::{Utc, Duration};
 use std::collections::HashMap;
 
+/// Nearest stored memories (via HNSW) compared against new content for novelty
+const NOVELTY_NEIGHBORS: usize = 5;
+
 /// Hippocampus - manages memory formation and consolidation
 pub struct Hippocampus<'a> {
     brain: &'a mut Brain,
@@ -115,11 +118,9 @@ impl<'a> Hippocampus<'a> {
                 // Strengthen both memories
                 let boost = replay_strength * 0.05; // Small but cumulative boost
                 
-                if let Some(m) = self.brain.semantic.search(&prev.id.to_string(), 1)
-                    .ok().and_then(|v| v.into_iter().next())
-                {
-                    let new_strength = (m.strength + boost).min(1.0);
-                    let _ = self.brain.update_strength(&prev.id.to_string(), new_strength);
+                let prev_boosted = self.boost_strength(&prev.id, boost);
+                let curr_boosted = self.boost_strength(&curr.id, boost);
+                if prev_boosted || curr_boosted {
                     connections_strengthened += 1;
                     
                     if boost > 0.03 {
@@ -142,6 +143,18 @@ impl<'a> Hippocampus<'a> {
         }
     }
 
+    /// Add `boost` to a memory's current strength and persist it to the
+    /// store it lives in; false if the memory is gone
+    fn boost_strength(&mut self, id: &uuid::Uuid, boost: f32) -> bool {
+        // Re-read: an earlier window of this replay may have boosted it already
+        let Ok(Some(item)) = self.brain.get_by_id(id) else {
+            return false;
+        };
+        self.brain
+            .update_strength(&id.to_string(), (item.strength + boost).min(1.0))
+            .is_ok()
+    }
+
     // ========================================
     // 2. EPISODE CHAIN - Temporal linking
     // ========================================
@@ -228,7 +241,8 @@ impl<'a> Hippocampus<'a> {
     // ========================================
 
     /// Calculate importance score for new content before storing.
-    /// Based on novelty (how different from existing memories) and emotional intensity.
+    /// Based on novelty (1 - cosine similarity to the closest existing
+    /// memory) and emotional intensity.
     pub fn calculate_importance(&self, content: &str, emotional_valence: f32, tags: &[String]) -> ImportanceScore {
         let base_strength = 0.5;
         
@@ -262,26 +276,23 @@ impl<'a> Hippocampus<'a> {
         }
     }
 
+    /// Distance to the closest memory in any long-term store, looked up in
+    /// the brain's HNSW index so the cost doesn't grow with the database
     fn calculate_novelty(&self, content: &str, _tags: &[String]) -> f32 {
-        // Search for similar existing memories
-        let similar = self.brain.semantic.search(content, 5).unwrap_or_default();
-        
-        if similar.is_empty() {
-            return 1.0; // Completely novel - nothing similar exists
+        let embedding = self.brain.embedder().embed_document(content);
+
+        // The closest existing memory decides: a near-duplicate is not news
+        let max_sim = self.brain.hnsw_index()
+            .search(&embedding, NOVELTY_NEIGHBORS)
+            .into_iter()
+            .map(|(_, sim)| sim)
+            .filter(|sim| sim.is_finite())
+            .reduce(f32::max);
+
+        match max_sim {
+            Some(sim) => (1.0 - sim).clamp(0.0, 1.0),
+            None => 1.0, // Completely novel - nothing similar exists
         }
-        
-        // Average similarity to top matches
-        let avg_sim: f32 = similar.iter()
-            .filter_map(|m| m.embedding.as_ref())
-            .map(|_emb| {
-                // For simplicity, use content-based comparison
-                // (embedding comparison would need the new content's embedding)
-                0.5 // placeholder - will be refined with actual embedding
-            })
-            .sum::<f32>() / similar.len().max(1) as f32;
-        
-        // Novelty = inverse of similarity
-        (1.0 - avg_sim).clamp(0.0, 1.0)
     }
 
     fn calculate_tag_rarity(&self, tags: &[String]) -> f32 {
@@ -321,15 +332,8 @@ impl<'a> Hippocampus<'a> {
         
         // Store the memory (process takes Option<&str> for context, tags set separately)
         let tag_str = tags.as_ref().map(|t| t.join(","));
-        self.brain.process(content, tag_str.as_deref())?;
-        
-        // Find the most recently stored memory and update its strength
-        // (process() adds it, so it's the latest one)
-        if let Ok(items) = self.brain.semantic.search(content, 1) {
-            if let Some(item) = items.first() {
-                let _ = self.brain.update_strength(&item.id.to_string(), importance.strength);
-            }
-        }
+        let item = self.brain.process_returning(content, tag_str.as_deref())?;
+        let _ = self.brain.update_strength(&item.id.to_string(), importance.strength);
         
         Ok(importance)
     }
@@ -389,6 +393,34 @@ mod tests {
         assert!(emotional.strength > 0.5);
     }
 
+    #[test]
+    fn test_novelty_compares_embeddings() {
+        use crate::{GloVeEmbedder, MemoryType};
+        use std::sync::Arc;
+
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("novelty_test.db");
+        let mut brain = Brain::with_embedder(
+            db_path.to_str().unwrap(),
+            Arc::new(GloVeEmbedder::test_embedder()),
+        ).unwrap();
+        brain.store_typed(MemoryItem::new("rust memory safety ownership", None), Some(MemoryType::Semantic)).unwrap();
+
+        let hippo = Hippocampus::new(&mut brain);
+        let duplicate = hippo.calculate_importance("Rust: memory safety, ownership", 0.0, &[]);
+        let unrelated = hippo.calculate_importance("apple silicon gpu", 0.0, &[]);
+
+        assert!(duplicate.novelty < 0.2, "near-duplicate novelty {}", duplicate.novelty);
+        assert!(unrelated.novelty > 0.6, "unrelated novelty {}", unrelated.novelty);
+        assert!(unrelated.strength > duplicate.strength);
+
+        // Memories in other stores count too
+        brain.store_typed(MemoryItem::new("apple silicon gpu", None), Some(MemoryType::Procedural)).unwrap();
+        let hippo = Hippocampus::new(&mut brain);
+        let repeated = hippo.calculate_importance("apple silicon gpu", 0.0, &[]);
+        assert!(repeated.novelty < 0.2, "procedural duplicate novelty {}", repeated.novelty);
+    }
+
     #[test]
     fn test_episode_chains() {
         let dir = tempdir().unwrap();
@@ -421,4 +453,42 @@ mod tests {
         
         assert!(result.replayed >= 3);
     }
+
+    #[test]
+    fn test_replay_persists_boosted_strength() {
+        use crate::{GloVeEmbedder, MemoryType};
+        use std::sync::Arc;
+
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("replay_persist.db");
+        let embedder = Arc::new(GloVeEmbedder::test_embedder());
+
+        let ids = {
+            let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder.clone()).unwrap();
+            let stored: Vec<_> = [
+                ("rust memory safety", MemoryType::Semantic),
+                ("rust ownership memory", MemoryType::Episodic),
+            ]
+            .into_iter()
+            .map(|(content, memory_type)| {
+                let item = MemoryItem::new(content, None).with_tags(vec!["rust".to_string()]);
+                brain.store_typed(item, Some(memory_type)).unwrap().id
+            })
+            .collect();
+            for id in &stored {
+                brain.update_strength(&id.to_string(), 0.5).unwrap();
+            }
+
+            let result = Hippocampus::new(&mut brain).replay(24);
+            assert_eq!(result.connections_strengthened, 1);
+            stored
+        };
+
+        // Boosts reached each memory's own store
+        let brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder).unwrap();
+        let semantic = brain.semantic.get(&ids[0]).unwrap().unwrap();
+        let episodic = brain.episodic.get(&ids[1]).unwrap().unwrap();
+        assert!(semantic.strength > 0.5, "semantic strength {}", semantic.strength);
+        assert!(episodic.strength > 0.5, "episodic strength {}", episodic.strength);
+    }
 }
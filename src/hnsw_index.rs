@@ -3,12 +3,49 @@
 //! Hierarchical Navigable Small World graph for O(log n) approximate nearest neighbor search.
 //! Much faster than brute-force O(n) search for large memory collections.
 
-use hnsw::{Hnsw, Searcher};
+use hnsw::{Hnsw, Params, Searcher};
 use space::Metric;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
+/// Default neighbors per node (`M`)
+pub const DEFAULT_M: usize = 12;
+/// Default candidate list size while building
+pub const DEFAULT_EF_CONSTRUCTION: usize = 200;
+/// Default candidate list size while searching
+pub const DEFAULT_EF_SEARCH: usize = 64;
+
+/// `M` values the index can be built with
+///
+/// `M` is a compile-time parameter of the underlying graph, so only
+/// these sizes are instantiated.
+pub const SUPPORTED_M: [usize; 5] = [8, 12, 16, 24, 32];
+
+/// Fraction of removed graph nodes that triggers a compacting rebuild
+pub const COMPACT_TOMBSTONE_RATIO: f32 = 0.25;
+
+/// Construction and search parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HnswParams {
+    /// Neighbors per node on upper layers (layer 0 uses `2 * m`)
+    pub m: usize,
+    /// Candidate list size while inserting
+    pub ef_construction: usize,
+    /// Candidate list size while searching (at least `k`)
+    pub ef_search: usize,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self {
+            m: DEFAULT_M,
+            ef_construction: DEFAULT_EF_CONSTRUCTION,
+            ef_search: DEFAULT_EF_SEARCH,
+        }
+    }
+}
+
 /// Cosine distance metric for HNSW
 #[derive(Clone, Copy)]
 pub struct CosineDistance;
@@ -33,15 +70,81 @@ impl Metric<Vec<f32>> for CosineDistance {
     }
 }
 
+type Graph<const M: usize, const M0: usize> = Hnsw<CosineDistance, Vec<f32>, rand_pcg::Pcg64, M, M0>;
+
+/// HNSW graph for one of the [`SUPPORTED_M`] sizes
+enum AnyGraph {
+    M8(Graph<8, 16>),
+    M12(Graph<12, 24>),
+    M16(Graph<16, 32>),
+    M24(Graph<24, 48>),
+    M32(Graph<32, 64>),
+}
+
+/// Run `$body` with `$g` bound to the concrete graph
+macro_rules! with_graph {
+    ($graph:expr, $g:ident => $body:expr) => {
+        match $graph {
+            AnyGraph::M8($g) => $body,
+            AnyGraph::M12($g) => $body,
+            AnyGraph::M16($g) => $body,
+            AnyGraph::M24($g) => $body,
+            AnyGraph::M32($g) => $body,
+        }
+    };
+}
+
+impl AnyGraph {
+    fn new(params: &HnswParams) -> Self {
+        use rand::SeedableRng;
+        let rng = rand_pcg::Pcg64::seed_from_u64(42);
+        let p = Params::new().ef_construction(params.ef_construction);
+
+        match params.m {
+            8 => AnyGraph::M8(Hnsw::new_params_and_prng(CosineDistance, p, rng)),
+            16 => AnyGraph::M16(Hnsw::new_params_and_prng(CosineDistance, p, rng)),
+            24 => AnyGraph::M24(Hnsw::new_params_and_prng(CosineDistance, p, rng)),
+            32 => AnyGraph::M32(Hnsw::new_params_and_prng(CosineDistance, p, rng)),
+            _ => AnyGraph::M12(Hnsw::new_params_and_prng(CosineDistance, p, rng)),
+        }
+    }
+
+    fn insert(&mut self, embedding: Vec<f32>) -> usize {
+        let mut searcher = Searcher::default();
+        with_graph!(self, g => g.insert(embedding, &mut searcher))
+    }
+
+    /// Fill `dest` with up to `dest.len()` neighbors; returns how many were found
+    fn nearest(&self, query: &Vec<f32>, ef: usize, dest: &mut [space::Neighbor<u32>]) -> usize {
+        let mut searcher = Searcher::default();
+        with_graph!(self, g => g.nearest(query, ef, &mut searcher, dest).len())
+    }
+
+    fn feature(&self, idx: usize) -> &Vec<f32> {
+        with_graph!(self, g => g.feature(idx))
+    }
+}
+
+/// Closest supported `M` to `m`
+fn snap_m(m: usize) -> usize {
+    *SUPPORTED_M
+        .iter()
+        .min_by_key(|&&s| (s as isize - m as isize).abs())
+        .unwrap_or(&DEFAULT_M)
+}
+
 /// HNSW-based vector index
 pub struct HnswIndex {
     /// The HNSW graph
-    hnsw: Arc<RwLock<Hnsw<CosineDistance, Vec<f32>, rand_pcg::Pcg64, 12, 24>>>,
-    /// Mapping from internal index to UUID
+    hnsw: Arc<RwLock<AnyGraph>>,
+    /// Construction and search parameters
+    params: HnswParams,
+    /// Mapping from internal index to UUID (graph nodes missing here are
+    /// tombstones left by `remove`)
     id_map: Arc<RwLock<HashMap<usize, Uuid>>>,
     /// Reverse mapping from UUID to internal index
     uuid_to_idx: Arc<RwLock<HashMap<Uuid, usize>>>,
-    /// Next available index
+    /// Next available index (number of graph nodes, live or removed)
     next_idx: Arc<RwLock<usize>>,
     /// Dimension of embeddings
     dimension: usize,
@@ -50,11 +153,10 @@ pub struct HnswIndex {
 impl HnswIndex {
     /// Create a new HNSW index
     pub fn new(dimension: usize) -> Self {
-        use rand::SeedableRng;
-        let rng = rand_pcg::Pcg64::seed_from_u64(42);
-        
+        let params = HnswParams::default();
         Self {
-            hnsw: Arc::new(RwLock::new(Hnsw::new_prng(CosineDistance, rng))),
+            hnsw: Arc::new(RwLock::new(AnyGraph::new(&params))),
+            params,
             id_map: Arc::new(RwLock::new(HashMap::new())),
             uuid_to_idx: Arc::new(RwLock::new(HashMap::new())),
             next_idx: Arc::new(RwLock::new(0)),
@@ -62,6 +164,35 @@ impl HnswIndex {
         }
     }
 
+    /// Set construction and search parameters
+    ///
+    /// `m` is snapped to the closest of [`SUPPORTED_M`]. Changing `m` or
+    /// `ef_construction` rebuilds the graph empty, so call this before
+    /// adding vectors.
+    pub fn with_params(mut self, m: usize, ef_construction: usize, ef_search: usize) -> Self {
+        let params = HnswParams {
+            m: snap_m(m),
+            ef_construction: ef_construction.max(1),
+            ef_search: ef_search.max(1),
+        };
+        let rebuild = params.m != self.params.m || params.ef_construction != self.params.ef_construction;
+        self.params = params;
+        if rebuild {
+            self.clear();
+        }
+        self
+    }
+
+    /// Current parameters
+    pub fn params(&self) -> HnswParams {
+        self.params
+    }
+
+    /// Change the search candidate list size (no rebuild needed)
+    pub fn set_ef_search(&mut self, ef_search: usize) {
+        self.params.ef_search = ef_search.max(1);
+    }
+
     /// Add a vector to the index
     pub fn add(&self, id: Uuid, embedding: Vec<f32>) -> Result<(), String> {
         if embedding.len() != self.dimension {
@@ -75,20 +206,19 @@ impl HnswIndex {
         let mut hnsw = self.hnsw.write().unwrap();
         let mut id_map = self.id_map.write().unwrap();
         let mut uuid_to_idx = self.uuid_to_idx.write().unwrap();
-        let _next_idx = self.next_idx.write().unwrap();
+        let mut next_idx = self.next_idx.write().unwrap();
 
         // Check if already exists
         if uuid_to_idx.contains_key(&id) {
             return Ok(()); // Already indexed
         }
 
-        // Create a searcher for insertion
-        let mut searcher = Searcher::default();
         // insert returns the actual index used
-        let idx = hnsw.insert(embedding, &mut searcher);
+        let idx = hnsw.insert(embedding);
 
         id_map.insert(idx, id);
         uuid_to_idx.insert(id, idx);
+        *next_idx = idx + 1;
 
         Ok(())
     }
@@ -105,6 +235,9 @@ impl HnswIndex {
     }
 
     /// Search for k nearest neighbors
+    ///
+    /// Removed vectors are still graph nodes, so the search asks for `k`
+    /// plus the number of tombstones and drops them from the results.
     pub fn search(&self, query: &[f32], k: usize) -> Vec<(Uuid, f32)> {
         use space::Neighbor;
         
@@ -114,17 +247,18 @@ impl HnswIndex {
 
         let hnsw = self.hnsw.read().unwrap();
         let id_map = self.id_map.read().unwrap();
+        let nodes = *self.next_idx.read().unwrap();
+        let fetch = (k + nodes.saturating_sub(id_map.len())).min(nodes.max(k));
 
-        let mut searcher = Searcher::default();
         let query_vec = query.to_vec();
         
         // Prepare output buffer
-        let mut neighbors: Vec<Neighbor<u32>> = vec![Neighbor { index: 0, distance: u32::MAX }; k];
+        let mut neighbors: Vec<Neighbor<u32>> = vec![Neighbor { index: 0, distance: u32::MAX }; fetch];
         
-        // Search returns slice of found neighbors
-        let found = hnsw.nearest(&query_vec, k, &mut searcher, &mut neighbors);
+        // Search fills the buffer with found neighbors
+        let found = hnsw.nearest(&query_vec, self.params.ef_search.max(fetch), &mut neighbors);
 
-        found
+        neighbors[..found]
             .iter()
             .filter_map(|neighbor| {
                 let idx = neighbor.index;
@@ -133,21 +267,79 @@ impl HnswIndex {
                 
                 id_map.get(&idx).map(|uuid| (*uuid, similarity))
             })
+            .take(k)
             .collect()
     }
 
-    /// Remove a vector from the index (mark as deleted)
+    /// Remove a vector from the index
+    ///
+    /// The graph node stays as a tombstone (skipped by `search`) until more
+    /// than [`COMPACT_TOMBSTONE_RATIO`] of the nodes are tombstones, at which
+    /// point the graph is rebuilt from the live vectors.
     pub fn remove(&self, id: &Uuid) -> bool {
-        let mut uuid_to_idx = self.uuid_to_idx.write().unwrap();
+        let removed = {
+            let mut id_map = self.id_map.write().unwrap();
+            let mut uuid_to_idx = self.uuid_to_idx.write().unwrap();
+            match uuid_to_idx.remove(id) {
+                Some(idx) => {
+                    id_map.remove(&idx);
+                    true
+                }
+                None => false,
+            }
+        };
+
+        if removed {
+            let nodes = *self.next_idx.read().unwrap();
+            if self.tombstones() as f32 > nodes as f32 * COMPACT_TOMBSTONE_RATIO {
+                self.compact();
+            }
+        }
+        removed
+    }
+
+    /// Graph nodes whose vectors were removed but not yet compacted away
+    pub fn tombstones(&self) -> usize {
+        let nodes = *self.next_idx.read().unwrap();
+        nodes.saturating_sub(self.id_map.read().unwrap().len())
+    }
+
+    /// Rebuild the graph from the live vectors, dropping every tombstone
+    pub fn compact(&self) {
+        let mut hnsw = self.hnsw.write().unwrap();
         let mut id_map = self.id_map.write().unwrap();
+        let mut uuid_to_idx = self.uuid_to_idx.write().unwrap();
+        let mut next_idx = self.next_idx.write().unwrap();
 
-        if let Some(idx) = uuid_to_idx.remove(id) {
-            id_map.remove(&idx);
-            // Note: HNSW doesn't support true deletion, just remove from mappings
-            true
-        } else {
-            false
+        // Reinsert in the original order so results stay reproducible
+        let mut live: Vec<(usize, Uuid)> = id_map.iter().map(|(idx, id)| (*idx, *id)).collect();
+        live.sort_unstable();
+
+        let mut graph = AnyGraph::new(&self.params);
+        id_map.clear();
+        uuid_to_idx.clear();
+        *next_idx = 0;
+        for (old_idx, id) in live {
+            let idx = graph.insert(hnsw.feature(old_idx).clone());
+            id_map.insert(idx, id);
+            uuid_to_idx.insert(id, idx);
+            *next_idx = idx + 1;
         }
+        *hnsw = graph;
+    }
+
+    /// Replace the whole index with `items`; returns how many were added
+    ///
+    /// Vectors with the wrong dimension are skipped.
+    pub fn rebuild_from<I>(&self, items: I) -> usize
+    where
+        I: IntoIterator<Item = (Uuid, Vec<f32>)>,
+    {
+        self.clear();
+        items
+            .into_iter()
+            .filter(|(id, embedding)| self.add(*id, embedding.clone()).is_ok())
+            .count()
     }
 
     /// Get index statistics
@@ -155,21 +347,87 @@ impl HnswIndex {
         let id_map = self.id_map.read().unwrap();
         IndexStats {
             count: id_map.len(),
+            tombstones: self.next_idx.read().unwrap().saturating_sub(id_map.len()),
             dimension: self.dimension,
+            params: self.params,
+            recall_at_10: None,
+        }
+    }
+
+    /// Statistics including recall@10 measured on `sample` indexed vectors
+    pub fn stats_with_recall(&self, sample: usize) -> IndexStats {
+        let mut stats = self.stats();
+        if stats.count > 0 {
+            stats.recall_at_10 = Some(self.recall_at_k(|q, k| self.brute_force(q, k), sample, 10));
+        }
+        stats
+    }
+
+    /// Exact top-`k` ids by cosine similarity over every indexed vector
+    pub fn brute_force(&self, query: &[f32], k: usize) -> Vec<Uuid> {
+        let hnsw = self.hnsw.read().unwrap();
+        let id_map = self.id_map.read().unwrap();
+
+        let mut scored: Vec<(Uuid, f32)> = id_map
+            .iter()
+            .map(|(idx, id)| (*id, crate::simd_ops::cosine_similarity_simd(query, hnsw.feature(*idx))))
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.into_iter().take(k).map(|(id, _)| id).collect()
+    }
+
+    /// Measure recall@k of the approximate search
+    ///
+    /// Uses `sample` randomly chosen indexed vectors as queries and compares
+    /// each ANN result with `ground_truth(query, k)` (e.g.
+    /// [`HnswIndex::brute_force`]). Returns the mean fraction of true
+    /// neighbors found, or 1.0 for an empty index.
+    pub fn recall_at_k<F>(&self, ground_truth: F, sample: usize, k: usize) -> f32
+    where
+        F: Fn(&[f32], usize) -> Vec<Uuid>,
+    {
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+
+        let queries: Vec<Vec<f32>> = {
+            let hnsw = self.hnsw.read().unwrap();
+            let id_map = self.id_map.read().unwrap();
+            let mut indices: Vec<usize> = id_map.keys().copied().collect();
+            indices.sort_unstable();
+            let mut rng = rand_pcg::Pcg64::seed_from_u64(7);
+            indices
+                .choose_multiple(&mut rng, sample)
+                .map(|idx| hnsw.feature(*idx).clone())
+                .collect()
+        };
+
+        let mut total = 0.0;
+        let mut measured = 0;
+        for query in &queries {
+            let truth: HashSet<Uuid> = ground_truth(query, k).into_iter().collect();
+            if truth.is_empty() {
+                continue;
+            }
+            let found = self
+                .search(query, k)
+                .into_iter()
+                .filter(|(id, _)| truth.contains(id))
+                .count();
+            total += found as f32 / truth.len() as f32;
+            measured += 1;
         }
+
+        if measured == 0 { 1.0 } else { total / measured as f32 }
     }
 
     /// Clear the index
     pub fn clear(&self) {
-        use rand::SeedableRng;
-        
         let mut hnsw = self.hnsw.write().unwrap();
         let mut id_map = self.id_map.write().unwrap();
         let mut uuid_to_idx = self.uuid_to_idx.write().unwrap();
         let mut next_idx = self.next_idx.write().unwrap();
 
-        let rng = rand_pcg::Pcg64::seed_from_u64(42);
-        *hnsw = Hnsw::new_prng(CosineDistance, rng);
+        *hnsw = AnyGraph::new(&self.params);
         id_map.clear();
         uuid_to_idx.clear();
         *next_idx = 0;
@@ -180,12 +438,28 @@ impl HnswIndex {
 #[derive(Debug, Clone)]
 pub struct IndexStats {
     pub count: usize,
+    /// Removed vectors still in the graph (see [`HnswIndex::compact`])
+    pub tombstones: usize,
     pub dimension: usize,
+    pub params: HnswParams,
+    /// Measured recall@10, if requested via [`HnswIndex::stats_with_recall`]
+    pub recall_at_10: Option<f32>,
 }
 
 impl std::fmt::Display for IndexStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "HNSW Index: {} vectors, {}d", self.count, self.dimension)
+        write!(
+            f,
+            "HNSW Index: {} vectors, {}d (M={}, ef_construction={}, ef_search={})",
+            self.count, self.dimension, self.params.m, self.params.ef_construction, self.params.ef_search
+        )?;
+        if self.tombstones > 0 {
+            write!(f, ", {} removed", self.tombstones)?;
+        }
+        if let Some(recall) = self.recall_at_10 {
+            write!(f, ", recall@10 {:.1}%", recall * 100.0)?;
+        }
+        Ok(())
     }
 }
 
@@ -229,6 +503,88 @@ mod tests {
         assert_eq!(stats.dimension, 128);
     }
 
+    #[test]
+    fn test_recall_at_10_on_random_vectors() {
+        let index = crate::bench::random_hnsw_index(1000, 32);
+
+        let recall = index.recall_at_k(|q, k| index.brute_force(q, k), 100, 10);
+        assert!(recall > 0.9, "recall@10 = {}", recall);
+
+        let stats = index.stats_with_recall(20);
+        assert_eq!(stats.params, HnswParams::default());
+        assert!(stats.recall_at_10.unwrap() > 0.9);
+    }
+
+    #[test]
+    fn test_removed_ids_never_returned() {
+        let index = crate::bench::random_hnsw_index(100, 32);
+        let mut ids: Vec<(usize, Uuid)> = index.id_map.read().unwrap().iter().map(|(i, id)| (*i, *id)).collect();
+        ids.sort_unstable();
+        let removed: HashSet<Uuid> = ids.iter().step_by(10).map(|(_, id)| *id).collect();
+        assert_eq!(removed.len(), 10);
+
+        let queries: Vec<Vec<f32>> = {
+            let hnsw = index.hnsw.read().unwrap();
+            ids.iter().map(|(idx, _)| hnsw.feature(*idx).clone()).collect()
+        };
+        for id in &removed {
+            assert!(index.remove(id));
+        }
+        assert!(!index.remove(&ids[0].1));
+        assert_eq!(index.tombstones(), 10);
+        assert_eq!(index.stats().count, 90);
+
+        // Every query, including the removed vectors themselves, skips tombstones
+        for query in &queries {
+            let results = index.search(query, 10);
+            assert_eq!(results.len(), 10);
+            assert!(results.iter().all(|(id, _)| !removed.contains(id)));
+        }
+        let recall = index.recall_at_k(|q, k| index.brute_force(q, k), 90, 10);
+        assert!(recall > 0.9, "recall@10 after removal = {}", recall);
+
+        // The 26th tombstone crosses the 25% ratio and compacts the graph
+        for (_, id) in ids.iter().filter(|(_, id)| !removed.contains(id)).take(16) {
+            index.remove(id);
+        }
+        assert_eq!(index.tombstones(), 0);
+        assert_eq!(index.stats().count, 74);
+        let (pos, (_, survivor)) = ids
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, (_, id))| index.uuid_to_idx.read().unwrap().contains_key(id))
+            .unwrap();
+        assert_eq!(index.search(&queries[pos], 1)[0].0, *survivor);
+    }
+
+    #[test]
+    fn test_rebuild_from_replaces_contents() {
+        let index = HnswIndex::new(4);
+        index.add(Uuid::new_v4(), vec![1.0, 0.0, 0.0, 0.0]).unwrap();
+
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let added = index.rebuild_from(vec![
+            (a, vec![0.0, 1.0, 0.0, 0.0]),
+            (b, vec![0.0, 0.0, 1.0, 0.0]),
+            (Uuid::new_v4(), vec![1.0, 0.0]), // wrong dimension
+        ]);
+        assert_eq!(added, 2);
+        assert_eq!(index.stats().count, 2);
+        assert_eq!(index.search(&[0.0, 0.0, 1.0, 0.0], 1)[0].0, b);
+    }
+
+    #[test]
+    fn test_with_params_snaps_m() {
+        let index = HnswIndex::new(4).with_params(20, 100, 32);
+        assert_eq!(index.params(), HnswParams { m: 16, ef_construction: 100, ef_search: 32 });
+
+        let id = Uuid::new_v4();
+        index.add(id, vec![1.0, 0.0, 0.0, 0.0]).unwrap();
+        assert_eq!(index.search(&[1.0, 0.0, 0.0, 0.0], 1)[0].0, id);
+    }
+
     #[test]
     fn test_cosine_distance() {
         let metric = CosineDistance;
@@ -0,0 +1,162 @@
+//! Memory Import
+//!
+//! JSONL import (one JSON object per line) read through a `BufRead`, so
+//! memory use stays bounded no matter how large the export is. Each line is
+//! either a full [`MemoryItem`] or the [`SimpleMemory`] shape.
+//!
+//! CSV import reads the columns written by [`crate::export::to_csv`].
+//!
+//! Memories are stored with [`Brain::store_batch`] every [`PROGRESS_INTERVAL`]
+//! items, so they are indexed like any other write and a failed item is
+//! reported instead of aborting the import.
+
+use crate::{Brain, MemoryItem, MemoryType};
+use serde::Deserialize;
+use std::error::Error;
+use std::io::BufRead;
+
+/// Progress callback and store batch interval (items)
+pub const PROGRESS_INTERVAL: usize = 100;
+
+/// Minimal import shape: `{"content": "...", "tags": [...], "context": "..."}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimpleMemory {
+    pub content: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub context: Option<String>,
+}
+
+/// Options shared by every imported memory
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    /// Tags appended to every memory
+    pub tags: Vec<String>,
+    /// Type for [`SimpleMemory`] lines (full items keep their own type)
+    pub memory_type: MemoryType,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self { tags: Vec::new(), memory_type: MemoryType::Semantic }
+    }
+}
+
+/// Import result
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportStats {
+    /// Memories stored
+    pub imported: usize,
+    /// Non-empty lines that weren't valid JSON memories, or rejected CSV rows
+    pub skipped: usize,
+    /// Memories the brain failed to store
+    pub failed: usize,
+    /// Why each CSV row was skipped or memory failed
+    pub errors: Vec<String>,
+}
+
+/// Import JSONL line by line, embedding and storing memories in batches as
+/// they are read
+///
+/// `on_progress` is called with the running count after every batch.
+pub fn import_jsonl<R: BufRead>(
+    brain: &mut Brain,
+    reader: R,
+    options: &ImportOptions,
+    mut on_progress: impl FnMut(usize),
+) -> Result<ImportStats, Box<dyn Error>> {
+    let mut stats = ImportStats::default();
+    let mut pending = Vec::with_capacity(PROGRESS_INTERVAL);
+
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut mem = if let Ok(mem) = serde_json::from_str::<MemoryItem>(line) {
+            mem
+        } else if let Ok(simple) = serde_json::from_str::<SimpleMemory>(line) {
+            let mut mem = MemoryItem::new(&simple.content, simple.context.as_deref());
+            mem.tags = simple.tags;
+            mem.memory_type = options.memory_type.clone();
+            mem
+        } else {
+            stats.skipped += 1;
+            continue;
+        };
+
+        // Re-embedded with the current embedder by `store_batch`
+        mem.embedding = None;
+        mem.tags.extend(options.tags.iter().cloned());
+        pending.push(mem);
+
+        if pending.len() == PROGRESS_INTERVAL {
+            store_pending(brain, &mut pending, &mut stats);
+            on_progress(stats.imported);
+        }
+    }
+    store_pending(brain, &mut pending, &mut stats);
+
+    Ok(stats)
+}
+
+/// Import CSV text (see [`crate::export::from_csv`] for the accepted layouts)
+///
+/// Rows with a `type` column keep it; others use `options.memory_type`.
+/// Rows [`crate::export::from_csv_rows`] rejects are skipped and reported
+/// in `errors`.
+pub fn import_csv(
+    brain: &mut Brain,
+    input: &str,
+    options: &ImportOptions,
+    mut on_progress: impl FnMut(usize),
+) -> Result<ImportStats, Box<dyn Error>> {
+    let mut stats = ImportStats::default();
+    let mut pending = Vec::with_capacity(PROGRESS_INTERVAL);
+
+    for record in crate::export::from_csv_rows(input) {
+        let record = match record {
+            Ok(record) => record,
+            Err(reason) => {
+                stats.skipped += 1;
+                stats.errors.push(reason);
+                continue;
+            }
+        };
+        let mut mem = MemoryItem::new(&record.content, None);
+        mem.tags = record.tags;
+        mem.tags.extend(options.tags.iter().cloned());
+        mem.memory_type = record.memory_type.unwrap_or_else(|| options.memory_type.clone());
+        if let Some(created_at) = record.created_at {
+            mem.created_at = created_at;
+        }
+        if let Some(strength) = record.strength {
+            mem.strength = strength.clamp(0.0, 1.0);
+        }
+        pending.push(mem);
+
+        if pending.len() == PROGRESS_INTERVAL {
+            store_pending(brain, &mut pending, &mut stats);
+            on_progress(stats.imported);
+        }
+    }
+    store_pending(brain, &mut pending, &mut stats);
+
+    Ok(stats)
+}
+
+/// Store `pending` with [`Brain::store_batch`] and add the outcome to `stats`
+fn store_pending(brain: &mut Brain, pending: &mut Vec<MemoryItem>, stats: &mut ImportStats) {
+    if pending.is_empty() {
+        return;
+    }
+    let report = brain.store_batch(std::mem::take(pending));
+    stats.imported += report.stored;
+    stats.failed += report.failed.len();
+    for (item, reason) in report.failed {
+        stats.errors.push(format!("memory {}: {}", item.id, reason));
+    }
+}
@@ -1,18 +1,170 @@
 //! Inverted Index for Fast Keyword Search
 //!
 //! Maps keywords to document IDs for O(1) lookup instead of O(n) LIKE search.
+//! Token positions are kept per document for exact phrase queries.
 
 use std::collections::{HashMap, HashSet};
 use std::sync::RwLock;
 use uuid::Uuid;
 
-/// Simple tokenizer - splits text into lowercase words
+use crate::text::Lang;
+
+/// Lowercase words without stop words; Korean particles stripped, CJK as bigrams
+///
+/// Used for queries; documents are indexed with every token.
 fn tokenize(text: &str) -> Vec<String> {
-    text.to_lowercase()
-        .split(|c: char| !c.is_alphanumeric() && c != '_')
-        .filter(|s| s.len() >= 2) // Skip single chars
-        .map(String::from)
-        .collect()
+    crate::text::tokenize(text, Lang::Auto)
+}
+
+/// Every token of `text`, stop words included, in order
+fn index_tokens(text: &str) -> Vec<String> {
+    crate::text::index_tokens_with_offsets(text).into_iter().map(|(token, _)| token).collect()
+}
+
+/// Token positions within one document (keyword -> ascending positions)
+type Positions = HashMap<String, Vec<usize>>;
+
+/// Trigrams of `^word$` (one per character)
+fn trigrams(word: &str) -> Vec<String> {
+    let chars: Vec<char> = std::iter::once('^')
+        .chain(word.chars())
+        .chain(std::iter::once('$'))
+        .collect();
+    chars.windows(3).map(|w| w.iter().collect()).collect()
+}
+
+/// Levenshtein distance between `a` and `b`, or None if it exceeds `max`
+pub fn levenshtein_within(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        let mut row_min = curr[0];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+            row_min = row_min.min(curr[j + 1]);
+        }
+        // Every later row is at least this row's minimum
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    let distance = prev[b.len()];
+    (distance <= max).then_some(distance)
+}
+
+/// Boolean query over indexed keywords
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoolQuery {
+    /// Documents containing every word of the text
+    Term(String),
+    /// Documents containing the words adjacently, in order
+    Phrase(String),
+    /// Documents matching all sub-queries
+    And(Vec<BoolQuery>),
+    /// Documents matching any sub-query
+    Or(Vec<BoolQuery>),
+    /// Documents not matching the sub-query
+    Not(Box<BoolQuery>),
+}
+
+impl BoolQuery {
+    /// Boolean form of a query with `+required` and `-excluded` words
+    pub fn parse(query: &str) -> Self {
+        QueryTerms::parse(query).to_bool_query()
+    }
+
+    pub fn term(text: &str) -> Self {
+        BoolQuery::Term(text.to_string())
+    }
+
+    pub fn phrase(text: &str) -> Self {
+        BoolQuery::Phrase(text.to_string())
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(query: BoolQuery) -> Self {
+        BoolQuery::Not(Box::new(query))
+    }
+}
+
+/// A search query split into ranking text and `+`/`-` word filters
+///
+/// `-word` excludes every memory containing it, `+word` requires it, and
+/// plain words only rank. Exclusion takes precedence: `+rust -rust` matches
+/// nothing. Filter words are matched as tokens against content and tags.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryTerms {
+    /// Plain and required words, used for ranking
+    pub text: String,
+    /// Tokens every result must contain
+    pub required: Vec<String>,
+    /// Tokens no result may contain
+    pub excluded: Vec<String>,
+    /// `+`/`-` words with no searchable token (stop words, single letters),
+    /// which filter nothing
+    pub ignored: Vec<String>,
+}
+
+impl QueryTerms {
+    pub fn parse(query: &str) -> Self {
+        let mut terms = Self::default();
+        let mut text = Vec::new();
+        for word in query.split_whitespace() {
+            if let Some(excluded) = word.strip_prefix('-').filter(|w| !w.is_empty()) {
+                let tokens = tokenize(excluded);
+                if tokens.is_empty() {
+                    terms.ignored.push(word.to_string());
+                }
+                terms.excluded.extend(tokens);
+            } else if let Some(required) = word.strip_prefix('+').filter(|w| !w.is_empty()) {
+                let tokens = tokenize(required);
+                if tokens.is_empty() {
+                    terms.ignored.push(word.to_string());
+                }
+                terms.required.extend(tokens);
+                text.push(required);
+            } else {
+                text.push(word);
+            }
+        }
+        terms.text = text.join(" ");
+        terms
+    }
+
+    /// Whether any `+` or `-` words were given
+    pub fn has_filters(&self) -> bool {
+        !self.required.is_empty() || !self.excluded.is_empty()
+    }
+
+    /// Whether a memory with this content and tags passes the filters
+    pub fn accepts(&self, content: &str, tags: &[String]) -> bool {
+        let mut tokens: HashSet<String> = tokenize(content).into_iter().collect();
+        for tag in tags {
+            tokens.extend(tokenize(tag));
+        }
+        !self.excluded.iter().any(|word| tokens.contains(word))
+            && self.required.iter().all(|word| tokens.contains(word))
+    }
+
+    /// Required words, else any plain word, minus excluded words
+    pub fn to_bool_query(&self) -> BoolQuery {
+        let mut parts: Vec<BoolQuery> = self.required.iter().map(|word| BoolQuery::term(word)).collect();
+        if parts.is_empty() {
+            parts.push(BoolQuery::Or(tokenize(&self.text).iter().map(|word| BoolQuery::term(word)).collect()));
+        }
+        parts.extend(self.excluded.iter().map(|word| BoolQuery::not(BoolQuery::term(word))));
+        BoolQuery::And(parts)
+    }
 }
 
 /// Inverted Index for fast keyword search
@@ -21,6 +173,10 @@ pub struct InvertedIndex {
     index: RwLock<HashMap<String, HashSet<Uuid>>>,
     /// document ID -> set of keywords (for deletion)
     doc_keywords: RwLock<HashMap<Uuid, HashSet<String>>>,
+    /// document ID -> token positions (for phrase search)
+    positions: RwLock<HashMap<Uuid, Positions>>,
+    /// trigram -> keywords containing it (fuzzy search prefilter)
+    trigrams: RwLock<HashMap<String, HashSet<String>>>,
 }
 
 impl InvertedIndex {
@@ -29,53 +185,96 @@ impl InvertedIndex {
         Self {
             index: RwLock::new(HashMap::new()),
             doc_keywords: RwLock::new(HashMap::new()),
+            positions: RwLock::new(HashMap::new()),
+            trigrams: RwLock::new(HashMap::new()),
         }
     }
 
     /// Add a document to the index
+    ///
+    /// Every token is indexed at its real position, stop words included;
+    /// queries drop stop words when matching and scoring.
     pub fn add(&self, id: Uuid, content: &str) {
-        let tokens = tokenize(content);
+        let tokens = index_tokens(content);
         if tokens.is_empty() {
             return;
         }
+        self.insert_tokens(id, tokens);
+    }
 
+    /// Add multiple documents in batch
+    pub fn add_batch(&self, items: &[(Uuid, String)]) {
+        for (id, content) in items {
+            self.insert_tokens(*id, index_tokens(content));
+        }
+    }
+
+    /// Excerpt of `content` around the first occurrence of `term`, with up
+    /// to `radius` characters on each side and `...` where text was cut
+    ///
+    /// `term` is matched by token (case-insensitive, Korean particles
+    /// stripped). Without a match the excerpt starts at the beginning.
+    pub fn snippet(&self, content: &str, term: &str, radius: usize) -> String {
+        let target = tokenize(term).into_iter().next();
+        let hit = target.and_then(|target| {
+            crate::text::tokenize_with_offsets(content, Lang::Auto)
+                .into_iter()
+                .find(|(token, _)| *token == target)
+                .map(|(_, range)| range)
+        });
+        let range = hit.unwrap_or(0..0);
+
+        // Walk `radius` characters out from the match, on char boundaries
+        let start = content[..range.start]
+            .char_indices()
+            .rev()
+            .take(radius)
+            .last()
+            .map_or(range.start, |(i, _)| i);
+        let after = &content[range.end..];
+        let end = range.end + after.char_indices().nth(radius).map_or(after.len(), |(i, _)| i);
+
+        format!(
+            "{}{}{}",
+            if start > 0 { "..." } else { "" },
+            &content[start..end],
+            if end < content.len() { "..." } else { "" }
+        )
+    }
+
+    /// Index a token sequence, recording each token's position
+    fn insert_tokens(&self, id: Uuid, tokens: Vec<String>) {
         let mut index = self.index.write().unwrap();
         let mut doc_keywords = self.doc_keywords.write().unwrap();
+        let mut positions = self.positions.write().unwrap();
+        let mut trigram_index = self.trigrams.write().unwrap();
 
         let mut keywords = HashSet::new();
-        for token in tokens {
+        let mut doc_positions = Positions::new();
+        for (pos, token) in tokens.into_iter().enumerate() {
+            if !index.contains_key(&token) {
+                for gram in trigrams(&token) {
+                    trigram_index.entry(gram).or_default().insert(token.clone());
+                }
+            }
             index
                 .entry(token.clone())
                 .or_insert_with(HashSet::new)
                 .insert(id);
+            doc_positions.entry(token.clone()).or_default().push(pos);
             keywords.insert(token);
         }
         doc_keywords.insert(id, keywords);
-    }
-
-    /// Add multiple documents in batch
-    pub fn add_batch(&self, items: &[(Uuid, String)]) {
-        let mut index = self.index.write().unwrap();
-        let mut doc_keywords = self.doc_keywords.write().unwrap();
-
-        for (id, content) in items {
-            let tokens = tokenize(content);
-            let mut keywords = HashSet::new();
-            
-            for token in tokens {
-                index
-                    .entry(token.clone())
-                    .or_insert_with(HashSet::new)
-                    .insert(*id);
-                keywords.insert(token);
-            }
-            doc_keywords.insert(*id, keywords);
-        }
+        positions.insert(id, doc_positions);
     }
 
     /// Search for documents containing ALL keywords (AND search)
     pub fn search_and(&self, query: &str) -> Vec<Uuid> {
-        let tokens = tokenize(query);
+        self.docs_with_all(tokenize(query))
+    }
+
+    /// Documents containing every token (none for an empty list)
+    fn docs_with_all(&self, tokens: Vec<String>) -> Vec<Uuid> {
         if tokens.is_empty() {
             return Vec::new();
         }
@@ -101,6 +300,12 @@ impl InvertedIndex {
         result.map(|s| s.into_iter().collect()).unwrap_or_default()
     }
 
+    /// Documents containing exactly this indexed token (stop words included)
+    pub fn keyword_docs(&self, keyword: &str) -> Vec<Uuid> {
+        let index = self.index.read().unwrap();
+        index.get(keyword).map(|docs| docs.iter().copied().collect()).unwrap_or_default()
+    }
+
     /// Search for documents containing ANY keyword (OR search)
     pub fn search_or(&self, query: &str) -> Vec<Uuid> {
         let tokens = tokenize(query);
@@ -145,10 +350,109 @@ impl InvertedIndex {
         results
     }
 
+    /// Search for documents containing the phrase's words adjacently, in order
+    ///
+    /// Stop words in the phrase must be present too ("memory safety" does
+    /// not match "memory and safety"). Words shorter than two characters
+    /// are not indexed and are skipped in both the phrase and the documents.
+    pub fn search_phrase(&self, phrase: &str) -> Vec<Uuid> {
+        let tokens = index_tokens(phrase);
+        let candidates = self.docs_with_all(tokens.clone());
+        if tokens.len() <= 1 {
+            return candidates;
+        }
+
+        let positions = self.positions.read().unwrap();
+        candidates
+            .into_iter()
+            .filter(|id| {
+                positions.get(id).map_or(false, |doc| {
+                    let Some(starts) = doc.get(&tokens[0]) else { return false };
+                    starts.iter().any(|&start| {
+                        tokens.iter().enumerate().skip(1).all(|(offset, token)| {
+                            doc.get(token).map_or(false, |p| p.binary_search(&(start + offset)).is_ok())
+                        })
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Search for documents with a keyword within `max_distance` edits of
+    /// any word in `term` (typo tolerant)
+    ///
+    /// Candidate keywords come from a trigram prefilter: each edit changes
+    /// at most three trigrams, so a match shares at least
+    /// `trigrams(word) - 3 * max_distance` of them. Short words where that
+    /// bound is useless fall back to a length-filtered vocabulary scan.
+    pub fn search_fuzzy(&self, term: &str, max_distance: usize) -> Vec<Uuid> {
+        let index = self.index.read().unwrap();
+        let trigram_index = self.trigrams.read().unwrap();
+        let mut result = HashSet::new();
+
+        for word in tokenize(term) {
+            let grams = trigrams(&word);
+            let min_shared = grams.len().saturating_sub(3 * max_distance);
+
+            let candidates: Vec<&String> = if min_shared > 0 {
+                let mut shared: HashMap<&String, usize> = HashMap::new();
+                for gram in &grams {
+                    for keyword in trigram_index.get(gram).into_iter().flatten() {
+                        *shared.entry(keyword).or_insert(0) += 1;
+                    }
+                }
+                shared.into_iter().filter(|(_, n)| *n >= min_shared).map(|(k, _)| k).collect()
+            } else {
+                let len = word.chars().count();
+                index.keys().filter(|k| k.chars().count().abs_diff(len) <= max_distance).collect()
+            };
+
+            for keyword in candidates {
+                if levenshtein_within(&word, keyword, max_distance).is_some() {
+                    if let Some(docs) = index.get(keyword) {
+                        result.extend(docs.iter().copied());
+                    }
+                }
+            }
+        }
+
+        result.into_iter().collect()
+    }
+
+    /// Evaluate a boolean query
+    pub fn search_boolean(&self, query: &BoolQuery) -> Vec<Uuid> {
+        self.eval(query).into_iter().collect()
+    }
+
+    fn eval(&self, query: &BoolQuery) -> HashSet<Uuid> {
+        match query {
+            BoolQuery::Term(text) => self.search_and(text).into_iter().collect(),
+            BoolQuery::Phrase(text) => self.search_phrase(text).into_iter().collect(),
+            BoolQuery::And(parts) => {
+                let mut parts = parts.iter();
+                let Some(first) = parts.next() else { return HashSet::new() };
+                parts.fold(self.eval(first), |acc, part| {
+                    if acc.is_empty() {
+                        return acc;
+                    }
+                    acc.intersection(&self.eval(part)).copied().collect()
+                })
+            }
+            BoolQuery::Or(parts) => parts.iter().flat_map(|part| self.eval(part)).collect(),
+            BoolQuery::Not(inner) => {
+                let excluded = self.eval(inner);
+                let doc_keywords = self.doc_keywords.read().unwrap();
+                doc_keywords.keys().filter(|id| !excluded.contains(id)).copied().collect()
+            }
+        }
+    }
+
     /// Remove a document from the index
     pub fn remove(&self, id: &Uuid) -> bool {
         let mut index = self.index.write().unwrap();
         let mut doc_keywords = self.doc_keywords.write().unwrap();
+        self.positions.write().unwrap().remove(id);
+        let mut trigram_index = self.trigrams.write().unwrap();
 
         if let Some(keywords) = doc_keywords.remove(id) {
             for keyword in keywords {
@@ -157,6 +461,14 @@ impl InvertedIndex {
                     // Clean up empty entries
                     if docs.is_empty() {
                         index.remove(&keyword);
+                        for gram in trigrams(&keyword) {
+                            if let Some(words) = trigram_index.get_mut(&gram) {
+                                words.remove(&keyword);
+                                if words.is_empty() {
+                                    trigram_index.remove(&gram);
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -201,12 +513,44 @@ impl InvertedIndex {
         index.keys().cloned().collect()
     }
 
+    /// Document → token sequence pairs, in document order (for persistence)
+    pub fn documents(&self) -> Vec<(Uuid, Vec<String>)> {
+        let doc_keywords = self.doc_keywords.read().unwrap();
+        let positions = self.positions.read().unwrap();
+        doc_keywords
+            .iter()
+            .map(|(id, keywords)| {
+                let tokens = match positions.get(id) {
+                    Some(doc) => {
+                        let mut placed: Vec<(usize, &String)> = doc
+                            .iter()
+                            .flat_map(|(token, ps)| ps.iter().map(move |&p| (p, token)))
+                            .collect();
+                        placed.sort_unstable();
+                        placed.into_iter().map(|(_, token)| token.clone()).collect()
+                    }
+                    None => keywords.iter().cloned().collect(),
+                };
+                (*id, tokens)
+            })
+            .collect()
+    }
+
+    /// Restore an already tokenized document (see `documents`)
+    pub fn insert_keywords(&self, id: Uuid, keywords: Vec<String>) {
+        self.insert_tokens(id, keywords);
+    }
+
     /// Clear the index
     pub fn clear(&self) {
         let mut index = self.index.write().unwrap();
         let mut doc_keywords = self.doc_keywords.write().unwrap();
+        let mut positions = self.positions.write().unwrap();
+        let mut trigram_index = self.trigrams.write().unwrap();
         index.clear();
         doc_keywords.clear();
+        positions.clear();
+        trigram_index.clear();
     }
 }
 
@@ -285,6 +629,154 @@ mod tests {
         assert_eq!(results.len(), 2);
     }
 
+    #[test]
+    fn test_phrase_requires_adjacent_tokens() {
+        let index = InvertedIndex::new();
+
+        let adjacent = Uuid::new_v4();
+        let apart = Uuid::new_v4();
+        let reversed = Uuid::new_v4();
+        index.add(adjacent, "Rust gives memory safety without GC");
+        index.add(apart, "Memory is cheap, but safety matters");
+        index.add(reversed, "Safety memory ordering");
+
+        assert_eq!(index.search_phrase("memory safety"), vec![adjacent]);
+        assert_eq!(index.search_phrase("Memory, Safety!"), vec![adjacent]);
+        assert!(index.search_phrase("safety without memory").is_empty());
+
+        // Positions survive the persistence round trip
+        let restored = InvertedIndex::new();
+        for (id, tokens) in index.documents() {
+            restored.insert_keywords(id, tokens);
+        }
+        assert_eq!(restored.search_phrase("memory safety"), vec![adjacent]);
+    }
+
+    #[test]
+    fn test_boolean_and_or_not() {
+        let index = InvertedIndex::new();
+
+        let rust = Uuid::new_v4();
+        let both = Uuid::new_v4();
+        let python = Uuid::new_v4();
+        index.add(rust, "Rust ownership rules");
+        index.add(both, "Rust and Python interop");
+        index.add(python, "Python typing rules");
+
+        // AND requires every term
+        let and = index.search_boolean(&BoolQuery::And(vec![BoolQuery::term("rust"), BoolQuery::term("python")]));
+        assert_eq!(and, vec![both]);
+
+        let mut or = index.search_boolean(&BoolQuery::Or(vec![BoolQuery::term("ownership"), BoolQuery::term("typing")]));
+        or.sort();
+        let mut expected = vec![rust, python];
+        expected.sort();
+        assert_eq!(or, expected);
+
+        // NOT excludes documents with the term
+        let not = index.search_boolean(&BoolQuery::And(vec![
+            BoolQuery::term("rust"),
+            BoolQuery::not(BoolQuery::term("python")),
+        ]));
+        assert_eq!(not, vec![rust]);
+
+        let not_phrase = index.search_boolean(&BoolQuery::And(vec![
+            BoolQuery::term("rules"),
+            BoolQuery::not(BoolQuery::phrase("python typing")),
+        ]));
+        assert_eq!(not_phrase, vec![rust]);
+    }
+
+    #[test]
+    fn test_phrase_keeps_stop_word_positions() {
+        let index = InvertedIndex::new();
+
+        let adjacent = Uuid::new_v4();
+        let apart = Uuid::new_v4();
+        index.add(adjacent, "Rust gives memory safety");
+        index.add(apart, "memory and safety are separate");
+
+        assert_eq!(index.search_phrase("memory safety"), vec![adjacent]);
+        assert_eq!(index.search_phrase("memory and safety"), vec![apart]);
+        // Queries still ignore stop words when matching
+        let mut both = index.search_and("the memory");
+        both.sort();
+        let mut expected = vec![adjacent, apart];
+        expected.sort();
+        assert_eq!(both, expected);
+    }
+
+    #[test]
+    fn test_query_terms_exclude_and_require() {
+        let index = InvertedIndex::new();
+
+        let runtime = Uuid::new_v4();
+        let ownership = Uuid::new_v4();
+        let python = Uuid::new_v4();
+        index.add(runtime, "Rust async runtime");
+        index.add(ownership, "Rust ownership");
+        index.add(python, "Python async");
+
+        let terms = QueryTerms::parse("rust -async");
+        assert_eq!(terms.text, "rust");
+        assert_eq!(terms.excluded, vec!["async".to_string()]);
+        assert_eq!(index.search_boolean(&terms.to_bool_query()), vec![ownership]);
+        assert!(!terms.accepts("Rust async runtime", &[]));
+        assert!(!terms.accepts("Rust ownership", &["async".to_string()]));
+
+        let mut required = index.search_boolean(&BoolQuery::parse("+rust async"));
+        required.sort();
+        let mut expected = vec![runtime, ownership];
+        expected.sort();
+        assert_eq!(required, expected);
+
+        // Exclusion wins over a required word
+        assert!(index.search_boolean(&BoolQuery::parse("+rust -rust")).is_empty());
+
+        // Stop words can't filter anything
+        let terms = QueryTerms::parse("rust -the +is");
+        assert!(!terms.has_filters());
+        assert_eq!(terms.ignored, vec!["-the".to_string(), "+is".to_string()]);
+    }
+
+    #[test]
+    fn test_levenshtein_within() {
+        assert_eq!(levenshtein_within("memory", "memory", 1), Some(0));
+        assert_eq!(levenshtein_within("memroy", "memory", 2), Some(2));
+        assert_eq!(levenshtein_within("memroy", "memory", 1), None);
+        assert_eq!(levenshtein_within("memry", "memory", 1), Some(1));
+        assert_eq!(levenshtein_within("기억력", "기억", 1), Some(1));
+    }
+
+    #[test]
+    fn test_fuzzy_single_typo_finds_document() {
+        let index = InvertedIndex::new();
+        let memory = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        index.add(memory, "Rust guarantees memory safety");
+        index.add(other, "Python is dynamically typed");
+
+        assert_eq!(index.search_fuzzy("memry", 1), vec![memory]);     // deletion
+        assert_eq!(index.search_fuzzy("memorry", 1), vec![memory]);   // insertion
+        assert_eq!(index.search_fuzzy("menory", 1), vec![memory]);    // substitution
+        assert_eq!(index.search_fuzzy("rsut", 2), vec![memory]);      // short word, scan path
+    }
+
+    #[test]
+    fn test_fuzzy_excludes_distance_two_at_one() {
+        let index = InvertedIndex::new();
+        let id = Uuid::new_v4();
+        index.add(id, "Rust guarantees memory safety");
+
+        assert!(index.search_fuzzy("mmeroy", 1).is_empty());
+        assert!(index.search_fuzzy("safeyt", 1).is_empty());
+        assert_eq!(index.search_fuzzy("safeyt", 2), vec![id]);
+
+        // Removed keywords leave the fuzzy vocabulary too
+        index.remove(&id);
+        assert!(index.search_fuzzy("memry", 1).is_empty());
+    }
+
     #[test]
     fn test_remove() {
         let index = InvertedIndex::new();
@@ -310,4 +802,29 @@ mod tests {
         assert_eq!(stats.documents, 2);
         assert_eq!(stats.unique_keywords, 3); // hello, world, rust
     }
+
+    #[test]
+    fn test_snippet_windows_first_match() {
+        let index = InvertedIndex::new();
+        let content = format!("{} Rust ownership {}", "a".repeat(50), "b".repeat(50));
+        index.add(Uuid::new_v4(), &content);
+
+        let snippet = index.snippet(&content, "RUST", 10);
+        assert!(snippet.contains("Rust"));
+        assert_eq!(snippet, format!("...{} Rust ownership...", "a".repeat(9)));
+
+        // Short content is returned whole
+        assert_eq!(index.snippet("rust is fast", "rust", 10), "rust is fast");
+    }
+
+    #[test]
+    fn test_snippet_respects_char_boundaries() {
+        let index = InvertedIndex::new();
+        let content = "오늘 회의에서 러스트 프로젝트를 논의했다";
+        let snippet = index.snippet(content, "프로젝트", 3);
+        assert_eq!(snippet, "...스트 프로젝트를 논...");
+
+        // No match: excerpt from the start
+        assert_eq!(index.snippet(content, "python", 2), "오늘...");
+    }
 }
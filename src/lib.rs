@@ -20,6 +20,7 @@ pub mod procedural;
 pub mod consolidate;
 pub mod forgetting;
 pub mod types;
+pub mod config;
 pub mod schema;  // Semantic Schema Layer (CoreDB + CoreVecDB 통합)
 pub mod semantic_store;  // SemanticStore 구현체
 pub mod memory_actor;    // pekko-style Actor
@@ -27,12 +28,17 @@ pub mod coredb_store;    // CoreDB 영속 저장소
 pub mod storage;
 pub mod vecdb_storage;  // CoreVecDB HTTP backend
 pub mod embedding;
+pub mod embedding_io;  // .npy / raw f32 export-import
+pub mod import;  // streaming JSONL / CSV import
+pub mod export;  // CSV / Markdown export
+pub mod cli_json;  // --json output schema
 pub mod glove;
 pub mod llm;
 pub mod audit;
 pub mod cache;
 pub mod hnsw_index;
 pub mod inverted_index;
+pub mod text;  // language-aware tokenizer / stop words
 pub mod bloom_filter;
 pub mod simd_ops;
 pub mod compression;
@@ -40,6 +46,7 @@ pub mod merge;
 pub mod bench;
 pub mod watch;
 pub mod server;
+pub mod metrics;  // Prometheus exposition for `serve`
 pub mod sam;
 pub mod dream;
 pub mod mindmap;
@@ -60,47 +67,286 @@ pub mod hippocampus;
 // coredb_storage merged into storage.rs
 // vecdb_storage - CoreVecDB HTTP backend (no CQL parsing issues!)
 pub use vecdb_storage::VecDbStorage;
+pub use storage::{default_coredb_config, DEFAULT_KEYSPACE};
+pub use coredb::DatabaseConfig;
 
 pub use types::*;
-pub use working::WorkingMemory;
-pub use episodic::EpisodicMemory;
+pub use config::Config;
+pub use working::{EvictionPolicy, WorkingMemory};
+pub use episodic::{EpisodicMemory, TimelineGranularity};
 pub use semantic::SemanticMemory;
 pub use procedural::ProceduralMemory;
 pub use consolidate::Consolidator;
 pub use forgetting::ForgettingCurve;
-pub use embedding::{Embedder, HashEmbedder, TfIdfEmbedder, HttpEmbedder, cosine_similarity};
-pub use glove::GloVeEmbedder;
-pub use llm::{LlmProvider, OllamaProvider, OpenAIProvider, MlxLmProvider, EchoProvider, MemoryChat, auto_detect_provider};
+pub use embedding::{Embedder, HashEmbedder, TfIdfEmbedder, HttpEmbedder, ApiEmbedder, ApiKind, ConfiguredEmbedder, cosine_similarity, embedder_from_config, TFIDF_DIMENSION};
+pub use glove::{GloVeEmbedder, GloVeLoadStats};
+pub use llm::{LlmProvider, OllamaProvider, OpenAIProvider, MlxLmProvider, EchoProvider, MemoryChat, MemoryDirective, auto_detect_provider, provider_from_config};
 pub use cache::{CachedEmbedder, CacheStats, BatchProcessor};
-pub use hnsw_index::{HnswIndex, IndexStats};
-pub use inverted_index::InvertedIndex;
+pub use hnsw_index::{HnswIndex, HnswParams, IndexStats};
+pub use inverted_index::{BoolQuery, InvertedIndex, QueryTerms};
 pub use bloom_filter::{BloomFilter, CountingBloomFilter, BloomStats};
-pub use simd_ops::{cosine_similarity_simd, dot_product_simd, l2_norm_simd, batch_cosine_similarity, top_k_similar};
-pub use compression::{QuantizedEmbedding, CompressedF32, CompressionStats, compress_embeddings, decompress_embeddings};
+pub use simd_ops::{cosine_similarity_simd, dot_product_simd, l2_norm_simd, batch_cosine_similarity, top_k_similar, top_k_similar_above};
+pub use compression::{QuantizedEmbedding, CompressedF32, CompressionStats, compress_embeddings, decompress_embeddings, quantization_stats};
 pub use merge::{MemoryMerger, MergeConfig, MergeResult, analyze_duplicates, merge_duplicates};
-pub use sam::{SamBrain, SamMemory, SamMemoryType, SamBrainStats};
-pub use dream::{DreamEngine, DreamState, DreamPhase};
-pub use mindmap::MindMap;
+pub use sam::{SamBrain, SamMemory, SamMemoryType, SamBrainStats, SamSleepReport};
+pub use dream::{DreamEngine, DreamConfig, DreamState, DreamPhase};
+pub use mindmap::{ClusterMode, MapFilter, MindMap};
 pub use constellation::Constellation;
-pub use predict::{Predictor, Prediction, ForgettingAlert, Pattern};
+pub use predict::{Predictor, Prediction, ForgettingAlert, Pattern, TagMarkov};
 #[cfg(feature = "mlx")]
 pub use embedding::{MlxEmbedder, create_mlx_embedder};
 // CoreDBStorage is now the default Storage
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-/// Check if a word is a stop word (common words to skip in search)
-fn is_stop_word(word: &str) -> bool {
-    const STOP_WORDS: &[&str] = &[
-        "the", "a", "an", "is", "are", "was", "were", "be", "been", "being",
-        "have", "has", "had", "do", "does", "did", "will", "would", "could",
-        "should", "may", "might", "must", "shall", "can", "need", "what",
-        "when", "where", "which", "who", "whom", "this", "that", "these",
-        "those", "with", "from", "about", "into", "through", "during",
-        "before", "after", "above", "below", "between", "under", "again",
-        "like", "know", "think", "want", "tell", "your", "you", "for",
-    ];
-    STOP_WORDS.contains(&word.to_lowercase().as_str())
+/// Below this many indexed vectors `semantic_search_hnsw` uses the exact scan
+const HNSW_MIN_ITEMS: usize = 50;
+
+/// Default per-hop activation decay for [`Brain::spreading_activation`]
+pub const DEFAULT_SPREAD_DECAY: f32 = 0.5;
+
+/// Embedding neighbors followed from each activated memory
+const SPREAD_NEIGHBORS: usize = 5;
+
+/// Most memories expanded per hop (strongest activations first)
+const SPREAD_MAX_FRONTIER: usize = 50;
+
+/// Default relevance/diversity trade-off for MMR re-ranking
+pub const DEFAULT_MMR_LAMBDA: f32 = 0.5;
+
+/// Maximal Marginal Relevance re-ranking
+///
+/// Greedily picks the candidate maximising
+/// `lambda * sim(query, item) - (1 - lambda) * max sim(item, picked)`,
+/// so near-duplicates of an already picked memory lose out to distinct ones.
+/// `lambda = 1.0` is pure relevance, `0.0` pure diversity.
+/// Items without an embedding fall back to their relevance score.
+pub fn mmr_rerank(query_embedding: &[f32], candidates: Vec<MemoryItem>, limit: usize, lambda: f32) -> Vec<MemoryItem> {
+    let lambda = lambda.clamp(0.0, 1.0);
+    let relevance: Vec<f32> = candidates
+        .iter()
+        .map(|item| match item.embedding {
+            Some(ref emb) => cosine_similarity(query_embedding, emb),
+            None => item.relevance_score(),
+        })
+        .collect();
+
+    let mut remaining: Vec<usize> = (0..candidates.len()).collect();
+    let mut picked: Vec<usize> = Vec::with_capacity(limit.min(candidates.len()));
+
+    while picked.len() < limit && !remaining.is_empty() {
+        let mut best_pos = 0;
+        let mut best_score = f32::NEG_INFINITY;
+
+        for (pos, &idx) in remaining.iter().enumerate() {
+            let redundancy = picked
+                .iter()
+                .filter_map(|&p| match (&candidates[idx].embedding, &candidates[p].embedding) {
+                    (Some(a), Some(b)) => Some(cosine_similarity(a, b)),
+                    _ => None,
+                })
+                .fold(0.0f32, f32::max);
+            let score = lambda * relevance[idx] - (1.0 - lambda) * redundancy;
+            if score > best_score {
+                best_score = score;
+                best_pos = pos;
+            }
+        }
+
+        picked.push(remaining.swap_remove(best_pos));
+    }
+
+    let mut slots: Vec<Option<MemoryItem>> = candidates.into_iter().map(Some).collect();
+    picked.into_iter().filter_map(|idx| slots[idx].take()).collect()
+}
+
+/// Filters applied during the scan in [`Brain::recall_with_options`]
+///
+/// Filtering happens before truncation, so `limit` counts matching memories.
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Content must match this regular expression
+    pub regex: Option<String>,
+    /// Every tag must match one of the memory's tags (case-insensitive substring)
+    pub tags: Vec<String>,
+    /// Only memories of this type
+    pub memory_type: Option<MemoryType>,
+    /// Query characters must appear in order in the content (typo tolerant)
+    pub fuzzy: bool,
+    /// Only memories created at or after this time
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only memories created at or before this time
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl SearchOptions {
+    /// True when no filter is set (plain recall)
+    pub fn is_empty(&self) -> bool {
+        self.regex.is_none()
+            && self.tags.is_empty()
+            && self.memory_type.is_none()
+            && !self.fuzzy
+            && self.since.is_none()
+            && self.until.is_none()
+    }
+}
+
+/// Field changes applied by [`Brain::edit`] (`None` leaves a field as is)
+#[derive(Debug, Clone, Default)]
+pub struct MemoryEdit {
+    /// New content; the memory is re-embedded and re-indexed
+    pub content: Option<String>,
+    /// Replacement tag list
+    pub tags: Option<Vec<String>>,
+    /// New strength (clamped to [0, 1])
+    pub strength: Option<f32>,
+    /// New long-term type; the memory moves to that store and the type
+    /// classifier learns from the correction
+    pub memory_type: Option<MemoryType>,
+}
+
+/// Outcome of [`Brain::store_batch`]
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    /// Memories stored and indexed
+    pub stored: usize,
+    /// Items that were not stored, with the reason, ready to retry
+    pub failed: Vec<(MemoryItem, String)>,
+}
+
+impl BatchReport {
+    /// Ids of the items that failed
+    pub fn failed_ids(&self) -> Vec<uuid::Uuid> {
+        self.failed.iter().map(|(item, _)| item.id).collect()
+    }
+}
+
+/// Why a recalled memory matched the query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchSource {
+    /// A query keyword appears in the content
+    Keyword,
+    /// Only embedding similarity
+    Semantic,
+    /// A query keyword matches one of the tags
+    Tag,
+    /// Still held in working memory
+    Working,
+}
+
+/// Per-result explanation from [`Brain::recall_explained`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MatchInfo {
+    /// Query keywords found in the content or tags (lowercase)
+    pub matched_keywords: Vec<String>,
+    /// Cosine similarity between query and memory embeddings
+    pub similarity: f32,
+    pub source: MatchSource,
+}
+
+impl MatchInfo {
+    /// Wrap every case-insensitive occurrence of a matched keyword in `[...]`
+    pub fn highlight(&self, content: &str) -> String {
+        let chars: Vec<char> = content.chars().collect();
+        let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+        let mut marked = vec![false; chars.len()];
+        for keyword in &self.matched_keywords {
+            let keyword: Vec<char> = keyword.chars().collect();
+            if keyword.is_empty() || keyword.len() > lower.len() {
+                continue;
+            }
+            for start in 0..=lower.len() - keyword.len() {
+                if lower[start..start + keyword.len()] == keyword[..] {
+                    marked[start..start + keyword.len()].iter_mut().for_each(|m| *m = true);
+                }
+            }
+        }
+
+        let mut out = String::with_capacity(content.len() + 8);
+        for (i, c) in chars.iter().enumerate() {
+            if marked[i] && (i == 0 || !marked[i - 1]) {
+                out.push('[');
+            }
+            out.push(*c);
+            if marked[i] && (i + 1 == chars.len() || !marked[i + 1]) {
+                out.push(']');
+            }
+        }
+        out
+    }
+}
+
+/// Edits allowed per query word for `--fuzzy` (1 for short words, else 2)
+fn fuzzy_distance(word: &str) -> usize {
+    if word.chars().count() <= 4 { 1 } else { 2 }
+}
+
+/// Simple fuzzy matching - all chars appear in order
+fn fuzzy_match(pattern: &[char], text: &str) -> bool {
+    let mut pattern_idx = 0;
+    for c in text.chars() {
+        if pattern_idx < pattern.len() && c == pattern[pattern_idx] {
+            pattern_idx += 1;
+        }
+    }
+    pattern_idx == pattern.len()
+}
+
+/// Query words worth searching for (no stop words or very short words)
+fn query_keywords(query: &str) -> Vec<String> {
+    text::tokenize(query, text::Lang::Auto)
+        .into_iter()
+        .filter(|w| w.len() > 2)
+        .collect()
+}
+
+/// Parse a time bound: `YYYY-MM-DD` (local midnight) or a relative
+/// offset back from now such as `30m`, `24h`, `7d`, `2w`
+pub fn parse_time_spec(spec: &str) -> Result<chrono::DateTime<chrono::Utc>, Box<dyn std::error::Error>> {
+    parse_time_spec_at(spec, chrono::Utc::now())
+}
+
+/// [`parse_time_spec`] relative to a fixed `now`
+pub fn parse_time_spec_at(
+    spec: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<chrono::DateTime<chrono::Utc>, Box<dyn std::error::Error>> {
+    use chrono::TimeZone;
+
+    let spec = spec.trim();
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0).ok_or("Invalid date")?;
+        // `earliest` resolves DST overlaps; a skipped midnight falls back to UTC
+        return Ok(match chrono::Local.from_local_datetime(&midnight).earliest() {
+            Some(local) => local.with_timezone(&chrono::Utc),
+            None => midnight.and_utc(),
+        });
+    }
+
+    let offset = parse_duration_spec(spec)
+        .map_err(|_| format!("Invalid time '{}' (use YYYY-MM-DD or e.g. 7d, 24h)", spec))?;
+    Ok(now - offset)
+}
+
+/// Parse a duration such as `30m`, `24h`, `7d` or `2w`
+pub fn parse_duration_spec(spec: &str) -> Result<chrono::Duration, Box<dyn std::error::Error>> {
+    let spec = spec.trim();
+    let split = spec.find(|c: char| !c.is_ascii_digit()).unwrap_or(spec.len());
+    let (amount, unit) = spec.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}' (e.g. 7d, 24h)", spec))?;
+    Ok(match unit {
+        "m" => chrono::Duration::minutes(amount),
+        "h" => chrono::Duration::hours(amount),
+        "d" => chrono::Duration::days(amount),
+        "w" => chrono::Duration::weeks(amount),
+        _ => return Err(format!("Invalid time unit in '{}' (use m, h, d or w)", spec).into()),
+    })
 }
 
 /// The unified brain - coordinates all memory systems
@@ -114,30 +360,241 @@ pub struct Brain {
     embedder: Arc<dyn Embedder>,
     /// Inverted index for fast keyword search
     pub keyword_index: InvertedIndex,
-    /// Bloom filter for fast "exists?" checks
-    pub keyword_bloom: BloomFilter,
+    /// Counting bloom filter for fast "exists?" checks; deletes decrement it
+    pub keyword_bloom: CountingBloomFilter,
+    /// Approximate nearest-neighbor index over memory embeddings; read it
+    /// through [`Brain::hnsw_index`], which fills it after `load_indexes`
+    pub hnsw: HnswIndex,
+    /// HNSW still has to be filled from the stores (set by `load_indexes`)
+    hnsw_pending: AtomicBool,
+    /// Persist access count/time for memories returned by `recall`
+    record_access: bool,
+    /// CoreDB directory (data + commitlog), measured by `stats`
+    db_path: PathBuf,
+    /// Sidecar file for the persisted keyword index and bloom filter
+    index_path: PathBuf,
+    /// Indexes changed since the last save (saved on drop)
+    indexes_dirty: bool,
+    /// Minimum similarity for auto-linking new memories
+    link_threshold: f32,
+    /// Embedding dimension recorded in the database when it was opened
+    stored_embedding_dim: Option<usize>,
+    /// Content hash → ids for exact-match dedup (built on first use)
+    content_hashes: Option<HashMap<u64, Vec<uuid::Uuid>>>,
+    /// The bloom filter covers every stored memory (loaded or rebuilt)
+    indexes_loaded: bool,
+    /// Similarity/strength/recency weights of the `recall` ranking
+    recall_weights: RecallWeights,
+    /// Tag transitions between consecutively stored memories
+    tag_markov: predict::TagMarkov,
+    /// Write-ahead log of working memory (`<db_path>.working.wal`); None disables it
+    working_wal: Option<PathBuf>,
+    /// Lines appended to the WAL since it was last rewritten
+    working_wal_entries: usize,
+}
+
+/// The working-memory WAL is compacted once it holds this many times the
+/// working capacity in entries
+const WORKING_WAL_COMPACT_FACTOR: usize = 4;
+
+/// Builder for [`Brain`] with a custom keyspace, embedder or CoreDB tuning
+///
+/// ```ignore
+/// let brain = Brain::builder()
+///     .db_path("/data/coredb")
+///     .keyspace("work_brain")
+///     .working_capacity(9)
+///     .build()?;
+/// ```
+pub struct BrainBuilder {
+    db_path: Option<String>,
+    keyspace: String,
+    embedder: Option<Arc<dyn Embedder>>,
+    working_capacity: Option<usize>,
+    coredb_config: Option<coredb::DatabaseConfig>,
+}
+
+impl BrainBuilder {
+    pub fn new() -> Self {
+        Self {
+            db_path: None,
+            keyspace: storage::DEFAULT_KEYSPACE.to_string(),
+            embedder: None,
+            working_capacity: None,
+            coredb_config: None,
+        }
+    }
+
+    /// CoreDB directory (required)
+    pub fn db_path(mut self, db_path: &str) -> Self {
+        self.db_path = Some(db_path.to_string());
+        self
+    }
+
+    /// Keyspace for the memory tables, so several brains can share one DB
+    pub fn keyspace(mut self, keyspace: &str) -> Self {
+        self.keyspace = keyspace.to_string();
+        self
+    }
+
+    /// Embedder (default: cached 256-d [`HashEmbedder`])
+    pub fn embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// Working memory capacity (default: `MEMORY_BRAIN_WORKING_CAPACITY` or 7)
+    pub fn working_capacity(mut self, capacity: usize) -> Self {
+        self.working_capacity = Some(capacity.max(1));
+        self
+    }
+
+    /// CoreDB tuning (default: [`storage::default_coredb_config`]); the
+    /// directories are always taken from `db_path`
+    pub fn coredb_config(mut self, config: coredb::DatabaseConfig) -> Self {
+        self.coredb_config = Some(config);
+        self
+    }
+
+    pub fn build(self) -> Result<Brain, Box<dyn std::error::Error>> {
+        let db_path = self.db_path.ok_or("Brain::builder: db_path is required")?;
+        // Interpolated into CQL, so only plain identifiers
+        if self.keyspace.is_empty()
+            || !self.keyspace.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!("Invalid keyspace '{}' (use letters, digits and _)", self.keyspace).into());
+        }
+        let embedder = self.embedder.unwrap_or_else(|| {
+            // Use CachedEmbedder wrapping HashEmbedder for better performance
+            Arc::new(CachedEmbedder::with_default_cache(HashEmbedder::new(256)))
+        });
+        // Miller's magic number unless overridden
+        let working_capacity = self.working_capacity.unwrap_or_else(|| {
+            std::env::var("MEMORY_BRAIN_WORKING_CAPACITY")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .filter(|&c: &usize| c > 0)
+                .unwrap_or(working::DEFAULT_WORKING_CAPACITY)
+        });
+        let tuning = self.coredb_config.unwrap_or_else(|| storage::default_coredb_config(&db_path));
+        Brain::open(&db_path, &self.keyspace, embedder, working_capacity, &tuning)
+    }
+}
+
+impl Default for BrainBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Brain {
     pub fn new(db_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        // Use CachedEmbedder wrapping HashEmbedder for better performance
-        let inner = HashEmbedder::new(256);
-        let embedder = Arc::new(CachedEmbedder::with_default_cache(inner));
-        Self::with_embedder(db_path, embedder)
+        Self::builder().db_path(db_path).build()
     }
 
     pub fn with_embedder(db_path: &str, embedder: Arc<dyn Embedder>) -> Result<Self, Box<dyn std::error::Error>> {
-        Ok(Self {
-            working: WorkingMemory::new(7), // Miller's magic number
-            episodic: EpisodicMemory::new(db_path)?,
-            semantic: SemanticMemory::new(db_path)?,
-            procedural: ProceduralMemory::new(db_path)?,
+        Self::builder().db_path(db_path).embedder(embedder).build()
+    }
+
+    /// Start a [`BrainBuilder`]
+    pub fn builder() -> BrainBuilder {
+        BrainBuilder::new()
+    }
+
+    fn open(
+        db_path: &str,
+        keyspace: &str,
+        embedder: Arc<dyn Embedder>,
+        working_capacity: usize,
+        tuning: &coredb::DatabaseConfig,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let dimension = embedder.dimension();
+        let semantic = SemanticMemory::open(db_path, keyspace, tuning)?;
+        let stored_embedding_dim = semantic.embedding_dim()?;
+        // Sidecar files are per keyspace; the default keeps the original names
+        let base = db_path.trim_end_matches('/');
+        let sidecar = if keyspace == storage::DEFAULT_KEYSPACE {
+            base.to_string()
+        } else {
+            format!("{}.{}", base, keyspace)
+        };
+        let mut brain = Self {
+            working: WorkingMemory::new(working_capacity),
+            episodic: EpisodicMemory::open(db_path, keyspace, tuning)?,
+            semantic,
+            procedural: ProceduralMemory::open(db_path, keyspace, tuning)?,
             consolidator: Consolidator::new(),
-            forgetting: ForgettingCurve::new(),
+            forgetting: ForgettingCurve::per_type(),
             embedder,
             keyword_index: InvertedIndex::new(),
-            keyword_bloom: BloomFilter::new(10000, 0.01), // 10K items, 1% FPR
-        })
+            keyword_bloom: CountingBloomFilter::new(10000, 0.01), // 10K items, 1% FPR
+            hnsw: HnswIndex::new(dimension),
+            hnsw_pending: AtomicBool::new(false),
+            record_access: true,
+            db_path: PathBuf::from(db_path),
+            index_path: PathBuf::from(format!("{}.indexes.json", sidecar)),
+            indexes_dirty: false,
+            link_threshold: 0.4,
+            stored_embedding_dim,
+            content_hashes: None,
+            indexes_loaded: false,
+            recall_weights: RecallWeights::default(),
+            tag_markov: predict::TagMarkov::new(),
+            working_wal: Some(PathBuf::from(format!("{}.working.wal", sidecar))),
+            working_wal_entries: 0,
+        };
+        brain.replay_working_wal()?;
+        Ok(brain)
+    }
+
+    /// Create a brain from a loaded [`Config`] (db path and thresholds)
+    pub fn with_config(config: &Config, embedder: Arc<dyn Embedder>) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut brain = Self::with_embedder(config.db_path_str(), embedder)?;
+        brain.link_threshold = config.thresholds.link;
+        brain.set_quantize_embeddings(config.embedding.quantize);
+        Ok(brain)
+    }
+
+    /// Store new embeddings as int8 in every long-term store
+    ///
+    /// Roughly 5x smaller on disk at a small precision cost; existing
+    /// JSON rows keep loading either way.
+    pub fn set_quantize_embeddings(&mut self, on: bool) {
+        self.episodic.set_quantize_embeddings(on);
+        self.semantic.set_quantize_embeddings(on);
+        self.procedural.set_quantize_embeddings(on);
+    }
+
+    /// Bytes of stored embeddings across the long-term stores
+    pub fn embedding_bytes(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        Ok(self.episodic.embedding_bytes()? + self.semantic.embedding_bytes()? + self.procedural.embedding_bytes()?)
+    }
+
+    /// Int8 quantization report for every stored embedding
+    ///
+    /// A dry run only measures; with `execute`, embeddings still stored as
+    /// JSON floats are rewritten in the quantized format (the one
+    /// [`Brain::set_quantize_embeddings`] uses for new writes).
+    pub fn compress_embeddings(&self, execute: bool) -> Result<EmbeddingCompression, Box<dyn std::error::Error>> {
+        let mut items = self.episodic.search("", usize::MAX)?;
+        items.extend(self.semantic.search("", usize::MAX)?);
+        items.extend(self.procedural.search("", usize::MAX)?);
+        let embeddings: Vec<Vec<f32>> = items.into_iter().filter_map(|item| item.embedding).collect();
+
+        let disk_bytes_before = self.embedding_bytes()?;
+        let mut report = EmbeddingCompression {
+            stats: compression::quantization_stats(&embeddings),
+            disk_bytes_before,
+            disk_bytes_after: disk_bytes_before,
+            rewritten: 0,
+        };
+        if execute {
+            report.rewritten = self.episodic.quantize_stored_embeddings()?
+                + self.semantic.quantize_stored_embeddings()?
+                + self.procedural.quantize_stored_embeddings()?;
+            report.disk_bytes_after = self.embedding_bytes()?;
+        }
+        Ok(report)
     }
 
     /// Get the current embedder
@@ -145,89 +602,816 @@ impl Brain {
         &self.embedder
     }
 
+    /// Embedding dimension the database was written with (None if nothing
+    /// embedded has been stored yet)
+    pub fn stored_embedding_dim(&self) -> Option<usize> {
+        self.stored_embedding_dim
+    }
+
+    /// Fail if the database was written with a different embedding dimension
+    ///
+    /// Comparing vectors of different sizes makes similarity scores
+    /// meaningless; fix a mismatch with [`Brain::reembed`].
+    pub fn check_embedding_dim(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let current = self.embedder.dimension();
+        match self.stored_embedding_dim {
+            // 0: the embedder has never answered, so there is nothing to compare
+            Some(stored) if stored != current && current != 0 => Err(format!(
+                "Embedding dimension mismatch: database has {}d embeddings, embedder uses {}d",
+                stored, current
+            ).into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Regenerate every embedding with the current embedder
+    ///
+    /// Re-embeds working and long-term memories, rebuilds the HNSW index and
+    /// records the new dimension. Returns the number of long-term memories updated.
+    pub fn reembed(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut items = self.episodic.search("", usize::MAX)?;
+        items.extend(self.semantic.search("", usize::MAX)?);
+        items.extend(self.procedural.search("", usize::MAX)?);
+
+        let dimension = self.embedder.dimension();
+        self.hnsw = HnswIndex::new(dimension);
+        *self.hnsw_pending.get_mut() = false;
+
+        let mut updated = 0;
+        for item in &items {
+            let embedding = self.embedder.try_embed_document(&item.content)?;
+            if self.set_embedding(item.id, embedding)? {
+                updated += 1;
+            }
+        }
+        for mut item in self.working.get_all() {
+            self.working.remove(&item.id);
+            item.embedding = self.embed_content(&item.content);
+            self.working.admit(item);
+        }
+        self.rewrite_working_wal()?;
+
+        self.semantic.set_embedding_dim(dimension)?;
+        self.stored_embedding_dim = Some(dimension);
+        Ok(updated)
+    }
+
     /// Process new input and update memories
     pub fn process(&mut self, input: &str, context: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
-        // 1. Generate embedding for the input
-        let embedding = self.embedder.embed(input);
+        self.process_returning(input, context).map(|_| ())
+    }
+
+    /// Like [`Brain::process`], returning the new memory with its classified
+    /// type and the ids it was auto-linked to in `associations`
+    ///
+    /// A semantic fact matching an existing one strengthens that memory
+    /// instead; the returned item then carries the existing memory's id, so
+    /// follow-up updates reach the copy that persists.
+    pub fn process_returning(&mut self, input: &str, context: Option<&str>) -> Result<MemoryItem, Box<dyn std::error::Error>> {
+        // 1. Generate embedding for the input (none if the embedder failed)
+        let embedding = self.embed_content(input);
         
         // 2. Create memory item with embedding
         let mut memory_item = MemoryItem::new(input, context);
-        memory_item.embedding = Some(embedding);
+        memory_item.embedding = embedding;
 
         // 3. Classify memory type before consolidation
         memory_item.memory_type = self.consolidator.classify(&memory_item);
 
-        // 4. Add to working memory
-        self.working.push(memory_item.clone());
+        // 4. Add to working memory (the evicted item is consolidated in step 9)
+        let evicted = self.working.push(memory_item.clone());
+        self.append_working_wal(&memory_item)?;
 
-        // 5. Add to keyword index for fast search
-        self.keyword_index.add(memory_item.id, input);
-        
-        // 6. Add keywords to bloom filter for instant "exists?" check
-        for word in input.split_whitespace() {
-            let word = word.trim_matches(|c: char| !c.is_alphanumeric());
-            if word.len() >= 2 {
-                self.keyword_bloom.add_str(word);
-            }
-        }
+        // 5. Add to keyword index and bloom filter for fast search
+        self.index_keywords(memory_item.id, input);
 
-        // 7. 🔗 Auto-link related memories!
+        // 6. 🔗 Auto-link related memories!
         if let Some(ref emb) = memory_item.embedding {
-            let related = self.find_related_memories(emb, 0.4, 5);
+            let related = self.find_related_memories(emb, self.link_threshold, 5);
             for (related_id, similarity) in related {
                 // Only link if similarity is meaningful
-                if similarity > 0.4 {
+                if similarity > self.link_threshold {
                     memory_item.associate(related_id);
                 }
-            }
-        }
+            }
+        }
+
+        // 7. Also store to long-term immediately (for CLI usage where brain is recreated each time)
+        memory_item.id = self.consolidate_memory(memory_item.clone())?;
+        let id = memory_item.id;
+        memory_item.associations.retain(|related_id| *related_id != id);
+
+        // 8. Persist back-links so related memories point at the new one too
+        for related_id in &memory_item.associations {
+            self.link(*related_id, memory_item.id)?;
+        }
+
+        // 9. Move the evicted item to long-term instead of dropping it
+        if let Some(item) = evicted {
+            self.consolidate_evicted(item)?;
+        }
+
+        Ok(memory_item)
+    }
+
+    /// Flush working memories picked by the eviction policy to long-term
+    /// storage while working memory is over capacity
+    ///
+    /// Items not yet in a long-term store are classified and persisted.
+    /// Returns how many items left working memory.
+    pub fn maybe_consolidate(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut flushed = 0;
+        while self.consolidator.should_consolidate_working(&self.working) {
+            let Some(item) = self.working.pop_victim() else {
+                break;
+            };
+            self.consolidate_evicted(item)?;
+            flushed += 1;
+        }
+        if flushed > 0 {
+            self.rewrite_working_wal()?;
+        }
+        Ok(flushed)
+    }
+
+    /// Working-memory write-ahead log, if enabled
+    pub fn working_wal_path(&self) -> Option<&Path> {
+        self.working_wal.as_deref()
+    }
+
+    /// Log working memory to `path` (None disables the log)
+    ///
+    /// The log is on by default, next to the database. It is rewritten
+    /// with the current working memory right away.
+    pub fn set_working_wal(&mut self, path: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+        self.working_wal = path;
+        self.rewrite_working_wal()
+    }
+
+    /// Record an item pushed to working memory
+    fn append_working_wal(&mut self, item: &MemoryItem) -> Result<(), Box<dyn std::error::Error>> {
+        use std::io::Write;
+
+        let Some(ref path) = self.working_wal else {
+            return Ok(());
+        };
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(item)?)?;
+        self.working_wal_entries += 1;
+
+        if self.working_wal_entries > self.working.capacity() * WORKING_WAL_COMPACT_FACTOR {
+            self.rewrite_working_wal()?;
+        }
+        Ok(())
+    }
+
+    /// Replace the WAL with the current working memory (removed when empty)
+    fn rewrite_working_wal(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(ref path) = self.working_wal else {
+            return Ok(());
+        };
+        let items = self.working.get_all();
+        self.working_wal_entries = items.len();
+        if items.is_empty() {
+            return match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            };
+        }
+
+        let mut lines = String::new();
+        for item in &items {
+            lines.push_str(&serde_json::to_string(item)?);
+            lines.push('\n');
+        }
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, lines)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Restore working memory from the WAL left by a brain that never slept
+    ///
+    /// Entries are pushed in order (later copies of an id win), so the
+    /// capacity and eviction policy apply as they did originally. Unreadable
+    /// lines, such as a half-written last line, are skipped. Returns how
+    /// many items were recovered.
+    fn replay_working_wal(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        let Some(ref path) = self.working_wal else {
+            return Ok(0);
+        };
+        let Ok(log) = std::fs::read_to_string(path) else {
+            return Ok(0);
+        };
+
+        for line in log.lines() {
+            let Ok(item) = serde_json::from_str::<MemoryItem>(line) else {
+                continue;
+            };
+            if item.is_expired() {
+                continue;
+            }
+            self.working.remove(&item.id);
+            // Evicted items were consolidated when first evicted
+            let _ = self.working.push(item);
+        }
+        self.rewrite_working_wal()?;
+        Ok(self.working.len())
+    }
+
+    /// Persist an item evicted from working memory unless it's already stored
+    fn consolidate_evicted(&mut self, mut item: MemoryItem) -> Result<(), Box<dyn std::error::Error>> {
+        if self.load_item(&item.id)?.is_none() {
+            item.memory_type = self.consolidator.classify(&item);
+            self.consolidate_memory(item)?;
+        }
+        Ok(())
+    }
+
+    /// Persist a one-way link `from` → `to` in whichever store holds `from`
+    pub fn link(&mut self, from: uuid::Uuid, to: uuid::Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        self.episodic.add_association(from, to)?;
+        self.semantic.add_association(from, to)?;
+        self.procedural.add_association(from, to)?;
+        Ok(())
+    }
+
+    /// Forget a memory by its full id
+    ///
+    /// Removes it from working memory, the keyword index and every long-term
+    /// store. Returns false if no memory had that id.
+    pub fn forget(&mut self, id: uuid::Uuid) -> Result<bool, Box<dyn std::error::Error>> {
+        let removed = self.working.remove(&id);
+        let mut found = removed.is_some();
+        if found {
+            self.rewrite_working_wal()?;
+        }
+        let content = match removed {
+            Some(item) => Some(item.content),
+            None => self.load_item(&id)?.map(|item| item.content),
+        };
+        match content {
+            Some(ref content) => self.unindex_keywords(id, content),
+            None => {
+                self.keyword_index.remove(&id);
+            }
+        }
+        self.hnsw.remove(&id);
+        if let Some(ref mut hashes) = self.content_hashes {
+            for ids in hashes.values_mut() {
+                ids.retain(|m| *m != id);
+            }
+        }
+
+        found |= self.episodic.delete(&id)?;
+        found |= self.semantic.delete(&id)?;
+        found |= self.procedural.delete(&id)?;
+
+        if found {
+            self.indexes_dirty = true;
+        }
+        Ok(found)
+    }
+
+    /// Replace a cluster of duplicates with one merged memory
+    ///
+    /// Rewrites `survivor` into the store for its (possibly changed) type,
+    /// refreshing the keyword and HNSW entries, then deletes every id in
+    /// `removed`. The survivor is written first, so a failed write leaves
+    /// the cluster as it was instead of losing the merged memory.
+    pub fn replace_merged(&mut self, survivor: MemoryItem, removed: &[uuid::Uuid]) -> Result<(), Box<dyn std::error::Error>> {
+        let previous = self.load_item(&survivor.id)?;
+
+        // `update` writes by id, bypassing the semantic store's dedup
+        match survivor.memory_type {
+            MemoryType::Episodic => self.episodic.update(&survivor)?,
+            MemoryType::Procedural => self.procedural.update(&survivor)?,
+            _ => self.semantic.update(&survivor)?,
+        }
+
+        // Drop the old copy if the survivor moved to another store
+        if let Some(ref previous) = previous {
+            if previous.memory_type != survivor.memory_type {
+                match previous.memory_type {
+                    MemoryType::Episodic => self.episodic.delete(&survivor.id)?,
+                    MemoryType::Procedural => self.procedural.delete(&survivor.id)?,
+                    _ => self.semantic.delete(&survivor.id)?,
+                };
+            }
+            self.unindex_keywords(survivor.id, &previous.content);
+        }
+        if self.working.remove(&survivor.id).is_some() {
+            self.rewrite_working_wal()?;
+        }
+        self.index_keywords(survivor.id, &survivor.content);
+        self.hnsw.remove(&survivor.id);
+        if let Some(ref emb) = survivor.embedding {
+            let _ = self.hnsw.add(survivor.id, emb.clone());
+        }
+
+        for id in removed {
+            self.forget(*id)?;
+        }
+        Ok(())
+    }
+
+    /// Store `item` unless a long-term memory has the same content
+    ///
+    /// Content is compared after collapsing whitespace. On a match the
+    /// existing memory is accessed (access count and strength bumped) and
+    /// `(existing_id, false)` is returned; otherwise the item is embedded if
+    /// needed, indexed and stored, returning `(item.id, true)`.
+    pub fn store_or_update(&mut self, mut item: MemoryItem) -> Result<(uuid::Uuid, bool), Box<dyn std::error::Error>> {
+        if let Some(mut existing) = self.find_exact(&item.content)? {
+            existing.access();
+            match existing.memory_type {
+                MemoryType::Episodic => self.episodic.update(&existing)?,
+                MemoryType::Procedural => self.procedural.update(&existing)?,
+                _ => self.semantic.update(&existing)?,
+            }
+            return Ok((existing.id, false));
+        }
+
+        if item.embedding.is_none() {
+            item.embedding = self.embed_content(&item.content);
+        }
+        // `update` writes by id; the semantic store's fuzzy dedup would
+        // otherwise fold the item into a different memory
+        match item.memory_type {
+            MemoryType::Episodic => self.episodic.update(&item)?,
+            MemoryType::Procedural => self.procedural.update(&item)?,
+            _ => {
+                item.memory_type = MemoryType::Semantic;
+                self.semantic.update(&item)?;
+            }
+        }
+
+        // Indexed only once the write succeeded, like `store_batch`
+        self.index_keywords(item.id, &item.content);
+        self.observe_tags(&item.tags);
+        if let Some(ref emb) = item.embedding {
+            let _ = self.hnsw.add(item.id, emb.clone());
+        }
+        Ok((item.id, true))
+    }
+
+    /// Store many items, collecting failures instead of stopping at the first
+    ///
+    /// Items are embedded if needed and written by id into the store for
+    /// their type (`Working` goes to semantic). An item is indexed (keyword,
+    /// bloom, HNSW, tag model) only after its write succeeds, so a failure
+    /// leaves no partial index entry. Embeddings whose dimension differs
+    /// from the embedder's, or with non-finite values, are rejected.
+    pub fn store_batch(&mut self, items: Vec<MemoryItem>) -> BatchReport {
+        let dimension = self.embedder.dimension();
+        let mut report = BatchReport::default();
+
+        for mut item in items {
+            if item.embedding.is_none() {
+                item.embedding = self.embed_content(&item.content);
+            }
+            if let Some(ref emb) = item.embedding {
+                if emb.len() != dimension {
+                    let reason = format!("Embedding dimension {} does not match {}", emb.len(), dimension);
+                    report.failed.push((item, reason));
+                    continue;
+                }
+                if emb.iter().any(|x| !x.is_finite()) {
+                    report.failed.push((item, "Embedding has non-finite values".to_string()));
+                    continue;
+                }
+            }
+
+            if item.memory_type == MemoryType::Working {
+                item.memory_type = MemoryType::Semantic;
+            }
+            let written = match item.memory_type {
+                MemoryType::Episodic => self.episodic.update(&item),
+                MemoryType::Procedural => self.procedural.update(&item),
+                _ => self.semantic.update(&item),
+            };
+            if let Err(e) = written {
+                let reason = e.to_string();
+                report.failed.push((item, reason));
+                continue;
+            }
+
+            self.index_keywords(item.id, &item.content);
+            self.observe_tags(&item.tags);
+            if let Some(ref emb) = item.embedding {
+                let _ = self.hnsw.add(item.id, emb.clone());
+            }
+            report.stored += 1;
+        }
+        report
+    }
+
+    /// Store one memory in the store for `memory_type`, classifying it with
+    /// the consolidator when None, and index it like [`Brain::store_batch`]
+    ///
+    /// Returns the stored item (with its final type and embedding).
+    pub fn store_typed(
+        &mut self,
+        mut item: MemoryItem,
+        memory_type: Option<MemoryType>,
+    ) -> Result<MemoryItem, Box<dyn std::error::Error>> {
+        if item.embedding.is_none() {
+            item.embedding = self.embed_content(&item.content);
+        }
+        let confirmed = memory_type.is_some();
+        item.memory_type = match memory_type {
+            Some(memory_type) => memory_type,
+            None => self.consolidator.classify(&item),
+        };
+
+        let mut report = self.store_batch(vec![item.clone()]);
+        match report.failed.pop() {
+            Some((_, reason)) => Err(reason.into()),
+            None => {
+                // `store_batch` keeps working-classified items long-term as semantic
+                if item.memory_type == MemoryType::Working {
+                    item.memory_type = MemoryType::Semantic;
+                }
+                // An explicit type is a label the classifier can learn from
+                if confirmed {
+                    self.consolidator.observe(&item, item.memory_type.clone());
+                    self.indexes_dirty = true;
+                }
+                Ok(item)
+            }
+        }
+    }
+
+    /// Long-term memory whose content equals `content` (whitespace-normalized)
+    ///
+    /// Looks candidates up by [`types::content_hash`], so repeated calls
+    /// cost one map lookup plus a load per hash match.
+    pub fn find_exact(&mut self, content: &str) -> Result<Option<MemoryItem>, Box<dyn std::error::Error>> {
+        // The bloom filter rules out content with a never-seen word
+        if self.indexes_loaded && !self.bloom_may_contain(content) {
+            return Ok(None);
+        }
+
+        if self.content_hashes.is_none() {
+            let mut hashes: HashMap<u64, Vec<uuid::Uuid>> = HashMap::new();
+            let mut items = self.episodic.search("", usize::MAX)?;
+            items.extend(self.semantic.search("", usize::MAX)?);
+            items.extend(self.procedural.search("", usize::MAX)?);
+            for item in items {
+                hashes.entry(item.dedup_key()).or_default().push(item.id);
+            }
+            self.content_hashes = Some(hashes);
+        }
+
+        let candidates = self
+            .content_hashes
+            .as_ref()
+            .and_then(|h| h.get(&content_hash(content)))
+            .cloned()
+            .unwrap_or_default();
+        let wanted = normalize_content(content);
+        for id in candidates {
+            if let Some(item) = self.load_item(&id)? {
+                if normalize_content(&item.content) == wanted {
+                    return Ok(Some(item));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// False if some indexable word of `content` was never added to the bloom filter
+    fn bloom_may_contain(&self, content: &str) -> bool {
+        bloom_words(content).iter().all(|w| self.keyword_bloom.contains_str(w))
+    }
+
+    /// Whether `word` might appear in some memory
+    ///
+    /// `false` means no stored memory contains it; `true` may be a false
+    /// positive at the filter's configured rate. Deleted memories stop
+    /// counting towards their words.
+    pub fn keyword_might_exist(&self, word: &str) -> bool {
+        let tokens = bloom_words(word);
+        !tokens.is_empty() && tokens.iter().all(|w| self.keyword_bloom.contains_str(w))
+    }
+
+    /// Get a memory by its full id from working or long-term memory
+    pub fn get_by_id(&self, id: &uuid::Uuid) -> Result<Option<MemoryItem>, Box<dyn std::error::Error>> {
+        if let Some(item) = self.working.get_all().into_iter().find(|m| m.id == *id) {
+            return Ok(Some(item));
+        }
+        self.load_item(id)
+    }
+
+    /// Find the single memory whose id starts with `id_prefix`
+    ///
+    /// A full UUID is looked up directly; shorter prefixes scan working,
+    /// episodic, semantic and procedural memory. Returns `None` if nothing
+    /// matches and errors if the prefix is ambiguous.
+    pub fn get_by_id_prefix(&self, id_prefix: &str) -> Result<Option<MemoryItem>, Box<dyn std::error::Error>> {
+        let prefix = id_prefix.trim().to_lowercase();
+        if prefix.is_empty() {
+            return Err("Empty id prefix".into());
+        }
+        if let Ok(id) = uuid::Uuid::parse_str(&prefix) {
+            return self.get_by_id(&id);
+        }
+
+        let mut candidates = self.working.get_all();
+        candidates.extend(self.episodic.search("", usize::MAX)?);
+        candidates.extend(self.semantic.search("", usize::MAX)?);
+        candidates.extend(self.procedural.search("", usize::MAX)?);
+
+        let mut matches: Vec<MemoryItem> = Vec::new();
+        for item in candidates {
+            if item.id.to_string().starts_with(&prefix) && !matches.iter().any(|m| m.id == item.id) {
+                matches.push(item);
+            }
+        }
+
+        match matches.len() {
+            0 => Ok(None),
+            1 => Ok(Some(matches.remove(0))),
+            n => Err(format!("Id prefix '{}' is ambiguous ({} matches)", id_prefix, n).into()),
+        }
+    }
+
+    /// Delete a memory by id prefix
+    ///
+    /// Resolves the full id and removes it from every store, the keyword
+    /// index and the bloom filter. Returns the deleted memory.
+    pub fn delete(&mut self, id_prefix: &str) -> Result<MemoryItem, Box<dyn std::error::Error>> {
+        let item = self
+            .get_by_id_prefix(id_prefix)?
+            .ok_or_else(|| format!("Memory not found: {}", id_prefix))?;
+        if !self.forget(item.id)? {
+            return Err(format!("Memory {} could not be deleted", item.id).into());
+        }
+        Ok(item)
+    }
+
+    /// Edit a memory by id prefix
+    ///
+    /// Applies `changes` to the working and long-term copies. Changed content
+    /// is re-embedded and the keyword and HNSW indexes are updated; a new
+    /// type moves the memory between stores and trains the type classifier.
+    /// Returns the edited memory.
+    pub fn edit(&mut self, id_prefix: &str, changes: &MemoryEdit) -> Result<MemoryItem, Box<dyn std::error::Error>> {
+        if changes.memory_type == Some(MemoryType::Working) {
+            return Err("Memories can only be moved between long-term stores".into());
+        }
+        let found = self
+            .get_by_id_prefix(id_prefix)?
+            .ok_or_else(|| format!("Memory not found: {}", id_prefix))?;
+        let id = found.id;
+
+        let content_changed = changes.content.as_ref().map_or(false, |c| *c != found.content);
+        let embedding = if content_changed {
+            changes.content.as_deref().and_then(|c| self.embed_content(c))
+        } else {
+            None
+        };
+        let apply = |item: &mut MemoryItem| {
+            if let Some(ref content) = changes.content {
+                item.set_content(content);
+            }
+            if let Some(ref tags) = changes.tags {
+                item.tags = tags.clone();
+            }
+            if let Some(strength) = changes.strength {
+                item.strength = strength.clamp(0.0, 1.0);
+            }
+            if let Some(ref memory_type) = changes.memory_type {
+                item.memory_type = memory_type.clone();
+            }
+            // A stale vector would keep matching the old content
+            if content_changed {
+                item.embedding = embedding.clone();
+            }
+        };
+
+        let mut edited = None;
+        if let Some(mut item) = self.working.remove(&id) {
+            apply(&mut item);
+            self.working.admit(item.clone());
+            self.rewrite_working_wal()?;
+            edited = Some(item);
+        }
+        if let Some(mut item) = self.load_item(&id)? {
+            let previous_type = item.memory_type.clone();
+            apply(&mut item);
+            match item.memory_type {
+                MemoryType::Episodic => self.episodic.update(&item)?,
+                MemoryType::Procedural => self.procedural.update(&item)?,
+                _ => self.semantic.update(&item)?,
+            }
+            if previous_type != item.memory_type {
+                match previous_type {
+                    MemoryType::Episodic => self.episodic.delete(&id)?,
+                    MemoryType::Procedural => self.procedural.delete(&id)?,
+                    _ => self.semantic.delete(&id)?,
+                };
+            }
+            edited = Some(item);
+        }
+        let item = edited.ok_or_else(|| format!("Memory not found: {}", id_prefix))?;
+
+        if let Some(ref memory_type) = changes.memory_type {
+            self.consolidator.observe(&item, memory_type.clone());
+            self.indexes_dirty = true;
+        }
+
+        if content_changed {
+            self.unindex_keywords(id, &found.content);
+            self.index_keywords(id, &item.content);
+            self.hnsw.remove(&id);
+            if let Some(ref emb) = item.embedding {
+                let _ = self.hnsw.add(id, emb.clone());
+            }
+        }
+        Ok(item)
+    }
+
+    /// Replace the embedding of a stored memory
+    ///
+    /// Returns false if no long-term memory has that id.
+    pub fn set_embedding(&mut self, id: uuid::Uuid, embedding: Vec<f32>) -> Result<bool, Box<dyn std::error::Error>> {
+        if let Some(mut item) = self.episodic.get(&id)? {
+            item.embedding = Some(embedding);
+            self.episodic.update(&item)?;
+        } else if let Some(mut item) = self.semantic.get(&id)? {
+            item.embedding = Some(embedding);
+            self.semantic.update(&item)?;
+        } else if let Some(mut item) = self.procedural.get(&id)? {
+            item.embedding = Some(embedding);
+            self.procedural.update(&item)?;
+        } else {
+            return Ok(false);
+        }
+        self.hnsw.remove(&id);
+        if let Some(emb) = self.load_item(&id)?.and_then(|item| item.embedding) {
+            let _ = self.hnsw.add(id, emb);
+        }
+        Ok(true)
+    }
+
+    /// Store a visual memory's description as a text memory
+    ///
+    /// The text memory is tagged `visual:<id>` and associated with the image,
+    /// so `recall` can surface photos by what they show.
+    pub fn remember_visual(&mut self, visual: &visual::VisualMemory) -> Result<MemoryItem, Box<dyn std::error::Error>> {
+        let context = format!("image: {}", visual.image_path.display());
+        let mut tags = visual.tags.clone();
+        tags.push(format!("visual:{}", visual.id));
+
+        let mut item = MemoryItem::new(&visual.description, Some(&context))
+            .with_type(MemoryType::Episodic)
+            .with_tags(tags);
+        item.embedding = self.embed_content(&visual.description);
+        item.associate(visual.id);
+
+        self.index_keywords(item.id, &item.content);
+        if let Some(ref emb) = item.embedding {
+            let _ = self.hnsw.add(item.id, emb.clone());
+        }
+        self.episodic.store(item.clone())?;
+
+        Ok(item)
+    }
+
+    /// Add content to the keyword index and bloom filter
+    fn index_keywords(&mut self, id: uuid::Uuid, content: &str) {
+        if let Some(ref mut hashes) = self.content_hashes {
+            hashes.entry(content_hash(content)).or_default().push(id);
+        }
+        self.indexes_dirty = true;
+        self.keyword_index.add(id, content);
+        for word in bloom_words(content) {
+            self.keyword_bloom.add_str(&word);
+        }
+    }
+
+    /// Undo `index_keywords` for `content`, the memory's indexed content
+    fn unindex_keywords(&mut self, id: uuid::Uuid, content: &str) {
+        if self.keyword_index.remove(&id) {
+            for word in bloom_words(content) {
+                self.keyword_bloom.remove_str(&word);
+            }
+            self.indexes_dirty = true;
+        }
+    }
+
+    /// Enable/disable access tracking in `recall` (enabled by default)
+    pub fn set_record_access(&mut self, enabled: bool) {
+        self.record_access = enabled;
+    }
+
+    /// Whether `recall` tracks access (see [`Brain::set_record_access`])
+    pub fn records_access(&self) -> bool {
+        self.record_access
+    }
+
+    /// Teach the type classifier from labeled examples (see [`Consolidator::train`])
+    ///
+    /// Items without an embedding are embedded first. The centroids are
+    /// persisted with the indexes; `store_typed` with an explicit type and
+    /// type changes through `edit` keep training them.
+    pub fn train_classifier(&mut self, examples: &[(MemoryItem, MemoryType)]) {
+        let examples: Vec<(MemoryItem, MemoryType)> = examples
+            .iter()
+            .map(|(item, memory_type)| {
+                let mut item = item.clone();
+                if item.embedding.is_none() {
+                    item.embedding = self.embed_content(&item.content);
+                }
+                (item, memory_type.clone())
+            })
+            .collect();
+        self.consolidator.train(&examples);
+        self.indexes_dirty = true;
+    }
+
+    /// Weights used to rank `recall` results
+    pub fn recall_weights(&self) -> RecallWeights {
+        self.recall_weights
+    }
+
+    /// Change how `recall` weighs query similarity, strength and recency
+    pub fn set_recall_weights(&mut self, weights: RecallWeights) {
+        self.recall_weights = weights;
+    }
 
-        // 8. Also store to long-term immediately (for CLI usage where brain is recreated each time)
-        self.consolidate_memory(memory_item)?;
+    /// Mark a stored memory as accessed
+    ///
+    /// Applies forgetting decay since the last access, then strengthens it
+    /// (bumps `access_count`, sets `last_accessed` to now) and writes it back.
+    /// Returns the updated memory, or None if no long-term memory has that id.
+    pub fn touch(&self, id: uuid::Uuid) -> Result<Option<MemoryItem>, Box<dyn std::error::Error>> {
+        let Some(mut item) = self.load_item(&id)? else {
+            return Ok(None);
+        };
 
-        Ok(())
+        let decay = self.forgetting.calculate_decay(&item);
+        item.decay(decay);
+        item.access();
+
+        match item.memory_type {
+            MemoryType::Episodic => self.episodic.update(&item)?,
+            MemoryType::Procedural => self.procedural.update(&item)?,
+            _ => self.semantic.update(&item)?,
+        }
+        Ok(Some(item))
     }
 
     /// Recall relevant memories for a query
-    pub fn recall(&mut self, query: &str, limit: usize) -> Vec<MemoryItem> {
+    ///
+    /// Returned memories are marked accessed (see [`Brain::touch`]) unless
+    /// disabled with [`Brain::set_record_access`].
+    ///
+    /// Takes `&self`: forgetting decay is applied to the returned copies and
+    /// access stats are written through the stores, so a shared `Brain` can
+    /// serve recalls under a read lock.
+    pub fn recall(&self, query: &str, limit: usize) -> Vec<MemoryItem> {
+        let record_access = self.record_access;
+        self.recall_with_access(query, limit, record_access)
+    }
+
+    /// Recall with explicit access tracking (false for read-only views)
+    ///
+    /// `-word` drops results containing it and `+word` keeps only results
+    /// containing it (see [`QueryTerms`]); filters apply after ranking.
+    pub fn recall_with_access(&self, query: &str, limit: usize, record_access: bool) -> Vec<MemoryItem> {
+        let terms = QueryTerms::parse(query);
+        let query = terms.text.as_str();
         let mut results = Vec::new();
 
         // Generate query embedding for semantic search
-        let query_embedding = self.embedder.embed(query);
+        let query_embedding = self.embedder.embed_query(query);
 
         // 1. Check working memory first (fastest)
         results.extend(self.working.search(query));
 
         // 2. Try inverted index first (O(1) lookup!) 🚀
+        // Hits are fetched by key, never by rescanning a store per id
         let indexed_ids = self.keyword_index.search_ranked(query, limit * 2);
-        if !indexed_ids.is_empty() {
-            // Fetch memories by IDs from semantic store
-            for (id, _score) in &indexed_ids {
-                if let Ok(items) = self.semantic.search("", 1000) {
-                    if let Some(item) = items.into_iter().find(|i| i.id == *id) {
-                        results.push(item);
-                    }
-                }
+        for (id, _score) in &indexed_ids {
+            if let Ok(Some(item)) = self.load_item(id) {
+                results.push(item);
             }
         }
 
         // 3. Fallback: Extract keywords for text search (if index is empty/sparse)
         if results.len() < limit {
-            let keywords: Vec<String> = query
-                .split_whitespace()
-                .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
-                .filter(|w| w.len() > 2)
-                .filter(|w| !is_stop_word(w))
-                .map(|w| w.to_string())
-                .collect();
+            let keywords = query_keywords(query);
 
-            // 4. Bloom filter pre-check: skip keywords that definitely don't exist 🌸
+            // Bloom filter pre-check: skip keywords that definitely don't exist 🌸
             let keywords: Vec<String> = keywords
                 .into_iter()
                 .filter(|k| self.keyword_bloom.contains_str(k))
                 .collect();
 
-            // 5. Search each keyword in memories (LIKE fallback)
+            // Search each keyword in memories (LIKE fallback)
             for keyword in &keywords {
                 if let Ok(episodic) = self.episodic.search(&keyword, limit) {
                     results.extend(episodic);
@@ -238,43 +1422,367 @@ impl Brain {
             }
         }
 
-        // 5. Also try the full query (for exact matches)
+        // 4. Also try the full query (for exact matches)
         if let Ok(semantic) = self.semantic.search(query, limit) {
             results.extend(semantic);
         }
 
-        // 4. Re-rank by embedding similarity
-        for item in results.iter_mut() {
-            if let Some(ref emb) = item.embedding {
-                let sim = cosine_similarity(&query_embedding, emb);
-                // Boost strength by similarity (temporary for sorting)
-                item.strength = item.strength * 0.5 + sim * 0.5;
-            }
-        }
+        // Expired memories stay hidden until `sleep` purges them
+        let now = chrono::Utc::now();
+        results.retain(|item| !item.is_expired_at(now));
 
-        // 5. Apply forgetting curve (boost recently accessed)
+        // 5. Apply forgetting curve (weakens long-unused memories)
         self.forgetting.apply_decay(&mut results);
 
-        // 6. Sort by relevance and recency
-        results.sort_by(|a, b| b.relevance_score().partial_cmp(&a.relevance_score()).unwrap());
+        // 6. Rank by weighted query similarity, strength and recency
+        let weights = self.recall_weights;
+        let mut scored: Vec<(f32, MemoryItem)> = results
+            .into_iter()
+            .map(|item| {
+                let sim = item
+                    .embedding
+                    .as_ref()
+                    .map(|emb| cosine_similarity(&query_embedding, emb))
+                    .filter(|s| s.is_finite())
+                    .unwrap_or(0.0);
+                (weights.score(&item, sim), item)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        let mut results: Vec<MemoryItem> = scored.into_iter().map(|(_, item)| item).collect();
         
-        // 7. Deduplicate by content
+        // 7. Deduplicate by content hash (sorted, so the best-scoring copy stays)
         let mut seen = std::collections::HashSet::new();
-        results.retain(|item| seen.insert(item.content.clone()));
+        results.retain(|item| seen.insert(item.dedup_key()));
+
+        // 8. Apply `+required` / `-excluded` word filters
+        if terms.has_filters() {
+            results.retain(|item| terms.accepts(&item.content, &item.tags));
+        }
         
         results.truncate(limit);
 
+        if record_access {
+            self.record_accesses(&mut results);
+        }
+
+        results
+    }
+
+    /// Like [`Brain::recall`], with a [`MatchInfo`] per result
+    ///
+    /// `recall` only finds keyword hits, so remaining slots are filled from
+    /// [`Brain::semantic_search`]; those embedding-only matches are not
+    /// marked accessed.
+    pub fn recall_explained(&self, query: &str, limit: usize) -> Vec<(MemoryItem, MatchInfo)> {
+        let mut results = self.recall(query, limit);
+        if results.len() < limit {
+            let mut seen: std::collections::HashSet<u64> = results.iter().map(|item| item.dedup_key()).collect();
+            for (item, _) in self.semantic_search(query, limit) {
+                if results.len() >= limit {
+                    break;
+                }
+                if seen.insert(item.dedup_key()) {
+                    results.push(item);
+                }
+            }
+        }
+
+        let explanations = self.explain_matches(query, &results);
+        results.into_iter().zip(explanations).collect()
+    }
+
+    /// Explain why each memory matches `query` (see [`MatchInfo`])
+    pub fn explain_matches(&self, query: &str, items: &[MemoryItem]) -> Vec<MatchInfo> {
+        let keywords: Vec<String> = query_keywords(query).into_iter().map(|k| k.to_lowercase()).collect();
+        let query_embedding = self.embedder.embed_query(query);
+        let working: std::collections::HashSet<uuid::Uuid> = self.working.get_all().iter().map(|item| item.id).collect();
+
+        items
+            .iter()
+            .map(|item| {
+                let content_lower = item.content.to_lowercase();
+                let mut matched_keywords: Vec<String> = keywords
+                    .iter()
+                    .filter(|k| content_lower.contains(k.as_str()))
+                    .cloned()
+                    .collect();
+                let in_content = !matched_keywords.is_empty();
+                for keyword in &keywords {
+                    if !matched_keywords.contains(keyword)
+                        && item.tags.iter().any(|t| t.to_lowercase().contains(keyword.as_str()))
+                    {
+                        matched_keywords.push(keyword.clone());
+                    }
+                }
+
+                let similarity = item
+                    .embedding
+                    .as_ref()
+                    .map(|emb| cosine_similarity(&query_embedding, emb))
+                    .filter(|s| s.is_finite())
+                    .unwrap_or(0.0);
+                let source = if working.contains(&item.id) {
+                    MatchSource::Working
+                } else if in_content {
+                    MatchSource::Keyword
+                } else if !matched_keywords.is_empty() {
+                    MatchSource::Tag
+                } else {
+                    MatchSource::Semantic
+                };
+
+                MatchInfo { matched_keywords, similarity, source }
+            })
+            .collect()
+    }
+
+    /// Recall with regex/tag/type/fuzzy filters
+    ///
+    /// Scans every store and applies the filters before ranking and
+    /// truncation, so matches deep in the database aren't cut off by a
+    /// pre-filter fetch cap. With a regex or fuzzy filter the query is the
+    /// pattern itself; otherwise matches must also share a keyword with the
+    /// query or be embedding-similar to it.
+    pub fn recall_with_options(
+        &mut self,
+        query: &str,
+        limit: usize,
+        opts: &SearchOptions,
+    ) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+        if opts.is_empty() {
+            return Ok(self.recall(query, limit));
+        }
+
+        let regex = opts.regex.as_deref().map(regex::Regex::new).transpose()?;
+        // A regex is matched as written; `-`/`+` words apply otherwise
+        let terms = if regex.is_some() {
+            QueryTerms { text: query.to_string(), ..Default::default() }
+        } else {
+            QueryTerms::parse(query)
+        };
+        let tags: Vec<String> = opts.tags.iter().map(|t| t.to_lowercase()).collect();
+        let fuzzy_chars: Vec<char> = terms.text.to_lowercase().chars().collect();
+        // Typo-tolerant keyword lookup, so misspelled words still retrieve candidates
+        let fuzzy_ids: std::collections::HashSet<uuid::Uuid> = if opts.fuzzy {
+            terms.text
+                .split_whitespace()
+                .flat_map(|word| self.keyword_index.search_fuzzy(word, fuzzy_distance(word)))
+                .collect()
+        } else {
+            std::collections::HashSet::new()
+        };
+
+        let mut candidates = self.working.get_all();
+        if opts.since.is_some() || opts.until.is_some() {
+            // Only rows in the time range are read from the stores
+            let start = opts.since.unwrap_or(chrono::DateTime::<chrono::Utc>::MIN_UTC);
+            let end = opts.until.unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC);
+            candidates.extend(self.episodic.get_by_time_range(start, end)?);
+            candidates.extend(self.semantic.get_by_time_range(start, end)?);
+            candidates.extend(self.procedural.get_by_time_range(start, end)?);
+        } else {
+            candidates.extend(self.episodic.search("", usize::MAX)?);
+            candidates.extend(self.semantic.search("", usize::MAX)?);
+            candidates.extend(self.procedural.search("", usize::MAX)?);
+        }
+
+        candidates.retain(|item| {
+            !matches!(opts.since, Some(since) if item.created_at < since)
+                && !matches!(opts.until, Some(until) if item.created_at > until)
+                && opts.memory_type.as_ref().map_or(true, |t| *t == item.memory_type)
+                && tags.iter().all(|tag| item.tags.iter().any(|t| t.to_lowercase().contains(tag.as_str())))
+                && regex.as_ref().map_or(true, |re| re.is_match(&item.content))
+                && (!opts.fuzzy
+                    || fuzzy_ids.contains(&item.id)
+                    || fuzzy_match(&fuzzy_chars, &item.content.to_lowercase()))
+        });
+
+        // A regex/fuzzy query is the pattern itself, not words to match
+        let require_relevance = regex.is_none() && !opts.fuzzy;
+        Ok(self.rank_candidates(&terms, limit, candidates, require_relevance))
+    }
+
+    /// Recall memories containing `phrase` as adjacent words, in order
+    ///
+    /// Uses the keyword index's token positions; results are ranked like
+    /// [`Brain::recall_with_options`].
+    pub fn recall_phrase(&mut self, phrase: &str, limit: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+        let terms = QueryTerms::parse(phrase);
+        let ids = self.keyword_index.search_phrase(&terms.text);
+        let working = self.working.get_all();
+
+        let mut candidates = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(item) = working.iter().find(|m| m.id == id) {
+                candidates.push(item.clone());
+            } else if let Some(item) = self.load_item(&id)? {
+                candidates.push(item);
+            }
+        }
+
+        Ok(self.rank_candidates(&terms, limit, candidates, false))
+    }
+
+    /// Recall memories created between `start` and `end`
+    ///
+    /// Either bound may be open (`None`). Shorthand for
+    /// [`Brain::recall_with_options`] with only `since`/`until` set; an
+    /// empty query returns the range by relevance score alone.
+    pub fn recall_in_range(
+        &mut self,
+        query: &str,
+        limit: usize,
+        start: Option<chrono::DateTime<chrono::Utc>>,
+        end: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+        let opts = SearchOptions { since: start, until: end, ..Default::default() };
+        self.recall_with_options(query, limit, &opts)
+    }
+
+    /// One page of long-term memories, newest first
+    ///
+    /// Episodic, semantic and procedural memories are merged and ordered by
+    /// `(created_at, id)` descending, so paging with a growing `offset` walks
+    /// every memory exactly once.
+    pub fn list_page(&self, limit: usize, offset: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+        // The first offset+limit rows overall come from each store's first offset+limit
+        let window = offset.saturating_add(limit);
+        let mut items = self.episodic.get_recent(window, 0)?;
+        items.extend(self.semantic.get_recent(window, 0)?);
+        items.extend(self.procedural.get_recent(window, 0)?);
+
+        items.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.id.cmp(&a.id)));
+        Ok(items.into_iter().skip(offset).take(limit).collect())
+    }
+
+    /// Memory counts, index statistics and on-disk size in one snapshot
+    ///
+    /// Counts use each store's `COUNT(*)`; a store that can't be counted
+    /// reports 0.
+    pub fn stats(&self) -> BrainStats {
+        let episodic = self.episodic.count().unwrap_or(0);
+        let semantic = self.semantic.count().unwrap_or(0);
+        let procedural = self.procedural.count().unwrap_or(0);
+        BrainStats {
+            working: self.working.len(),
+            working_capacity: self.working.capacity(),
+            episodic,
+            semantic,
+            procedural,
+            total: episodic + semantic + procedural,
+            embedding_dim: self.embedder.dimension(),
+            index_stats: self.keyword_index.stats(),
+            bloom_stats: self.keyword_bloom.stats(),
+            hnsw_count: self.hnsw_index().stats().count,
+            db_size_bytes: dir_size(&self.db_path),
+        }
+    }
+
+    /// Number of long-term memories (episodic + semantic + procedural)
+    pub fn long_term_count(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        Ok(self.episodic.count()? + self.semantic.count()? + self.procedural.count()?)
+    }
+
+    /// Rank already filtered candidates for the filtered recall variants
+    ///
+    /// Drops candidates rejected by the `+`/`-` words in `terms`. With
+    /// `require_relevance`, candidates must share a keyword with the query
+    /// text or be embedding-similar to it. Then applies decay, ranks by the
+    /// recall weights, dedups, truncates and records access.
+    fn rank_candidates(
+        &mut self,
+        terms: &QueryTerms,
+        limit: usize,
+        candidates: Vec<MemoryItem>,
+        require_relevance: bool,
+    ) -> Vec<MemoryItem> {
+        let query = terms.text.as_str();
+        let keywords = if require_relevance { query_keywords(query) } else { Vec::new() };
+        let query_embedding = (!query.trim().is_empty()).then(|| self.embedder.embed_query(query));
+
+        let now = chrono::Utc::now();
+        let mut seen_ids = std::collections::HashSet::new();
+        let mut results = Vec::new();
+        let mut sims = Vec::new();
+        for item in candidates {
+            if !seen_ids.insert(item.id) || item.is_expired_at(now) || !terms.accepts(&item.content, &item.tags) {
+                continue;
+            }
+            let content_lower = item.content.to_lowercase();
+
+            let sim = query_embedding
+                .as_ref()
+                .zip(item.embedding.as_ref())
+                .map(|(query_emb, emb)| cosine_similarity(query_emb, emb))
+                .filter(|s| s.is_finite());
+            if !keywords.is_empty()
+                && !keywords.iter().any(|k| content_lower.contains(k.as_str()))
+                && sim.map_or(true, |s| s <= 0.05)
+            {
+                continue;
+            }
+
+            results.push(item);
+            sims.push(sim.unwrap_or(0.0));
+        }
+
+        self.forgetting.apply_decay(&mut results);
+        let weights = self.recall_weights;
+        let mut scored: Vec<(f32, MemoryItem)> = results
+            .into_iter()
+            .zip(sims)
+            .map(|(item, sim)| (weights.score(&item, sim), item))
+            .collect();
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        let mut results: Vec<MemoryItem> = scored.into_iter().map(|(_, item)| item).collect();
+
+        let mut seen = std::collections::HashSet::new();
+        results.retain(|item| seen.insert(item.dedup_key()));
+        results.truncate(limit);
+
+        if self.record_access {
+            self.record_accesses(&mut results);
+        }
+
+        results
+    }
+
+    /// Touch each result and copy the persisted access stats back
+    ///
+    /// For callers that re-rank [`Brain::recall_with_access`] candidates
+    /// and only count what they return.
+    pub fn record_accesses(&self, results: &mut [MemoryItem]) {
+        for item in results.iter_mut() {
+            if let Ok(Some(stored)) = self.touch(item.id) {
+                item.access_count = stored.access_count;
+                item.last_accessed = stored.last_accessed;
+            }
+        }
+    }
+
+    /// Recall with MMR re-ranking for diverse results
+    ///
+    /// Fetches a wider candidate pool with `recall`, then trades query
+    /// relevance against similarity to already chosen results (see [`mmr_rerank`]).
+    pub fn recall_diverse(&self, query: &str, limit: usize, lambda: f32) -> Vec<MemoryItem> {
+        let candidates = self.recall_with_access(query, limit * 3, false);
+        let query_embedding = self.embedder.embed_query(query);
+        let mut results = mmr_rerank(&query_embedding, candidates, limit, lambda);
+        if self.record_access {
+            self.record_accesses(&mut results);
+        }
         results
     }
 
     /// Semantic search using embeddings only
     pub fn semantic_search(&self, query: &str, limit: usize) -> Vec<(MemoryItem, f32)> {
-        let query_embedding = self.embedder.embed(query);
+        let query_embedding = self.embedder.embed_query(query);
         let mut results: Vec<(MemoryItem, f32)> = Vec::new();
 
         // Search all memory stores
         if let Ok(items) = self.semantic.search("", 1000) {
-            for item in items {
+            for item in items.into_iter().filter(|item| !item.is_expired()) {
                 if let Some(ref emb) = item.embedding {
                     let similarity = cosine_similarity(&query_embedding, emb);
                     if similarity > 0.05 {
@@ -285,11 +1793,52 @@ impl Brain {
         }
 
         // Sort by similarity
-        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         results.truncate(limit);
         results
     }
 
+    /// Semantic search via the HNSW index (approximate nearest neighbors)
+    ///
+    /// Falls back to the exact [`Brain::semantic_search`] scan while fewer
+    /// than 50 vectors are indexed.
+    pub fn semantic_search_hnsw(&self, query: &str, limit: usize) -> Vec<(MemoryItem, f32)> {
+        if self.hnsw_index().stats().count < HNSW_MIN_ITEMS {
+            return self.semantic_search(query, limit);
+        }
+
+        let query_embedding = self.embedder.embed_query(query);
+        self.hnsw
+            .search(&query_embedding, limit)
+            .into_iter()
+            .filter(|(_, similarity)| *similarity > 0.05)
+            .filter_map(|(id, similarity)| {
+                self.load_item(&id).ok().flatten().map(|item| (item, similarity))
+            })
+            .filter(|(item, _)| !item.is_expired())
+            .collect()
+    }
+
+    /// Fetch a long-term memory by full id from whichever store holds it
+    fn load_item(&self, id: &uuid::Uuid) -> Result<Option<MemoryItem>, Box<dyn std::error::Error>> {
+        if let Some(item) = self.semantic.get(id)? {
+            return Ok(Some(item));
+        }
+        if let Some(item) = self.episodic.get(id)? {
+            return Ok(Some(item));
+        }
+        self.procedural.get(id)
+    }
+
+    /// Embedding to store for `content`, or `None` if the embedder failed
+    ///
+    /// Storing the zero-vector fallback would leave a memory that no query
+    /// can reach by similarity; without one, keyword search still finds it
+    /// and `reindex --reembed` fills it in later.
+    fn embed_content(&self, content: &str) -> Option<Vec<f32>> {
+        self.embedder.try_embed_document(content).ok()
+    }
+
     /// 🔗 Find related memories by embedding similarity
     fn find_related_memories(&self, embedding: &[f32], threshold: f32, limit: usize) -> Vec<(uuid::Uuid, f32)> {
         let mut related = Vec::new();
@@ -324,19 +1873,128 @@ impl Brain {
         related
     }
 
+    /// 🕸️ Multi-hop associative recall ("spreading activation")
+    ///
+    /// Starting from `seed_id` with activation 1.0, each hop passes
+    /// `activation * decay * weight` along explicit associations (weight 1)
+    /// and to the nearest embedding neighbors above the link threshold
+    /// (weight = similarity). Activation arriving over several paths adds
+    /// up. Each memory is expanded at most once and only the strongest
+    /// [`SPREAD_MAX_FRONTIER`] memories are expanded per hop. The seed is
+    /// not part of the result, which is sorted by activation.
+    pub fn spreading_activation(&self, seed_id: uuid::Uuid, depth: usize, decay: f32) -> Vec<(uuid::Uuid, f32)> {
+        let mut activation: HashMap<uuid::Uuid, f32> = HashMap::new();
+        let mut expanded: std::collections::HashSet<uuid::Uuid> = std::collections::HashSet::new();
+        expanded.insert(seed_id);
+        let mut frontier = vec![(seed_id, 1.0f32)];
+
+        for _ in 0..depth {
+            let mut next: HashMap<uuid::Uuid, f32> = HashMap::new();
+            for (id, energy) in frontier {
+                let Ok(Some(item)) = self.get_by_id(&id) else { continue };
+
+                let mut edges: Vec<(uuid::Uuid, f32)> = item.associations.iter().map(|a| (*a, 1.0)).collect();
+                if let Some(ref emb) = item.embedding {
+                    edges.extend(
+                        self.hnsw_index()
+                            .search(emb, SPREAD_NEIGHBORS + 1)
+                            .into_iter()
+                            .filter(|(other, similarity)| *other != id && *similarity > self.link_threshold),
+                    );
+                }
+
+                for (other, weight) in edges {
+                    if other == seed_id {
+                        continue;
+                    }
+                    let passed = energy * decay * weight;
+                    *activation.entry(other).or_insert(0.0) += passed;
+                    if !expanded.contains(&other) {
+                        *next.entry(other).or_insert(0.0) += passed;
+                    }
+                }
+            }
+
+            let mut next: Vec<(uuid::Uuid, f32)> = next.into_iter().collect();
+            next.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            next.truncate(SPREAD_MAX_FRONTIER);
+            expanded.extend(next.iter().map(|(id, _)| *id));
+            frontier = next;
+            if frontier.is_empty() {
+                break;
+            }
+        }
+
+        let mut ranked: Vec<(uuid::Uuid, f32)> = activation.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked
+    }
+
     /// Consolidate memory from working to long-term
-    fn consolidate_memory(&mut self, item: MemoryItem) -> Result<(), Box<dyn std::error::Error>> {
-        match item.memory_type {
-            MemoryType::Episodic => self.episodic.store(item)?,
-            MemoryType::Semantic => self.semantic.store(item)?,
-            MemoryType::Procedural => self.procedural.store(item)?,
-            MemoryType::Working => {} // Stay in working memory
+    ///
+    /// Returns the id the memory is stored under, which differs from the
+    /// item's when a semantic fact merged into an existing one.
+    fn consolidate_memory(&mut self, item: MemoryItem) -> Result<uuid::Uuid, Box<dyn std::error::Error>> {
+        let id = item.id;
+        let embedding = item.embedding.clone();
+        let stored_id = match item.memory_type {
+            MemoryType::Episodic => {
+                self.episodic.store(item)?;
+                id
+            }
+            MemoryType::Semantic => self.semantic.store_merged(item)?,
+            MemoryType::Procedural => {
+                self.procedural.store(item)?;
+                id
+            }
+            MemoryType::Working => return Ok(id), // Stay in working memory
+        };
+        // A merged fact is already indexed under the existing id
+        if stored_id == id {
+            if let Some(emb) = embedding {
+                let _ = self.hnsw.add(id, emb);
+            }
         }
-        Ok(())
+        Ok(stored_id)
+    }
+
+    /// Delete every memory whose `expires_at` has passed
+    ///
+    /// Removes them from working memory, the long-term stores and the
+    /// keyword/HNSW indexes. Returns how many were deleted.
+    pub fn purge_expired(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        let now = chrono::Utc::now();
+        let mut expired: Vec<uuid::Uuid> = self
+            .working
+            .get_all()
+            .into_iter()
+            .filter(|item| item.is_expired_at(now))
+            .map(|item| item.id)
+            .collect();
+        for items in [
+            self.episodic.search("", usize::MAX)?,
+            self.semantic.search("", usize::MAX)?,
+            self.procedural.search("", usize::MAX)?,
+        ] {
+            expired.extend(items.into_iter().filter(|item| item.is_expired_at(now)).map(|item| item.id));
+        }
+        expired.sort();
+        expired.dedup();
+
+        let mut purged = 0;
+        for id in expired {
+            if self.forget(id)? {
+                purged += 1;
+            }
+        }
+        Ok(purged)
     }
 
     /// Sleep phase - consolidate and clean up memories
     pub fn sleep(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        // 0. Drop expired memories before anything is consolidated
+        self.purge_expired()?;
+
         // 1. Move important working memories to long-term
         let important = self.working.get_important();
         for item in important {
@@ -344,118 +2002,497 @@ impl Brain {
         }
 
         // 2. Apply forgetting to old memories
-        self.episodic.apply_forgetting(&self.forgetting)?;
-        self.semantic.apply_forgetting(&self.forgetting)?;
+        self.apply_forgetting()?;
 
-        // 3. Clear working memory
+        // 3. Clear working memory (and its WAL: everything is consolidated)
         self.working.clear();
+        self.rewrite_working_wal()?;
 
         Ok(())
     }
 
-    /// Rebuild keyword index and bloom filter from existing memories
-    /// 
-    /// Call this after loading a database to populate the in-memory indexes.
+    /// Forgetting curve used by `recall` and `sleep`
+    pub fn forgetting(&self) -> &ForgettingCurve {
+        &self.forgetting
+    }
+
+    /// Override the daily decay rate for one memory type
+    pub fn set_decay_rate(&mut self, memory_type: MemoryType, rate: f32) {
+        self.forgetting.set_decay(memory_type, rate);
+    }
+
+    /// Long-term memories whose next review falls before `until`, most
+    /// overdue first, with their scheduled review time
+    pub fn due_for_review(
+        &self,
+        until: chrono::DateTime<chrono::Utc>,
+        limit: usize,
+    ) -> Result<Vec<(MemoryItem, chrono::DateTime<chrono::Utc>)>, Box<dyn std::error::Error>> {
+        let mut due: Vec<_> = self
+            .list_page(self.long_term_count()?, 0)?
+            .into_iter()
+            .filter(|item| !item.is_expired())
+            .map(|item| {
+                let at = self.forgetting.next_review(&item);
+                (item, at)
+            })
+            .filter(|(_, at)| *at <= until)
+            .collect();
+        due.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.id.cmp(&b.0.id)));
+        due.truncate(limit);
+        Ok(due)
+    }
+
+    /// Decay stored episodic, semantic and procedural memories, deleting forgotten ones
+    pub fn apply_forgetting(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.episodic.apply_forgetting(&self.forgetting)?;
+        self.semantic.apply_forgetting(&self.forgetting)?;
+        self.procedural.apply_forgetting(&self.forgetting)?;
+        Ok(())
+    }
+
     /// Update the strength of a memory by its ID (partial match)
     pub fn update_strength(&mut self, id_prefix: &str, new_strength: f32) -> Result<(), Box<dyn std::error::Error>> {
         let strength = new_strength.clamp(0.0, 1.0);
-        
-        // Search through all memory stores
-        // Check episodic
-        if let Ok(items) = self.episodic.search("", 100000) {
-            for mut item in items {
-                if item.id.to_string().starts_with(id_prefix) {
-                    item.strength = strength;
-                    let _ = self.episodic.store(item);
-                    return Ok(());
-                }
-            }
+
+        // Resolve the id, then update the long-term copy
+        let found = self
+            .get_by_id_prefix(id_prefix)?
+            .and_then(|m| self.load_item(&m.id).transpose())
+            .transpose()?;
+        let Some(mut item) = found else {
+            return Err(format!("Memory not found: {}", id_prefix).into());
+        };
+        item.strength = strength;
+
+        match item.memory_type {
+            MemoryType::Episodic => self.episodic.update(&item)?,
+            MemoryType::Procedural => self.procedural.update(&item)?,
+            _ => self.semantic.update(&item)?,
         }
-        // Check semantic
-        if let Ok(items) = self.semantic.search("", 100000) {
-            for mut item in items {
-                if item.id.to_string().starts_with(id_prefix) {
-                    item.strength = strength;
-                    let _ = self.semantic.store(item);
-                    return Ok(());
-                }
+        Ok(())
+    }
+
+    /// Execute CQL query through the underlying CoreDB (via semantic store's storage)
+    pub fn storage_execute_cql(&self, query: &str) -> Result<String, String> {
+        self.semantic.execute_cql_html(query)
+    }
+
+    /// Tag-transition model used by `Predictor::predict_next`
+    pub fn tag_markov(&self) -> &predict::TagMarkov {
+        &self.tag_markov
+    }
+
+    /// Feed the tags of a newly stored memory to the tag-transition model
+    ///
+    /// Called by `store_or_update`; callers that write to a store directly
+    /// should call it themselves. Persisted with the indexes.
+    pub fn observe_tags(&mut self, tags: &[String]) {
+        if !tags.is_empty() {
+            self.tag_markov.observe(tags);
+            self.indexes_dirty = true;
+        }
+    }
+
+    /// Sidecar file holding the persisted indexes (`<db_path>.indexes.json`)
+    pub fn index_path(&self) -> &Path {
+        &self.index_path
+    }
+
+    /// Persist the keyword index and bloom filter next to the database
+    ///
+    /// Also records the store counts so `load_indexes` can detect drift.
+    pub fn save_indexes(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let snapshot = self.index_snapshot()?;
+        std::fs::write(&self.index_path, serde_json::to_vec(&snapshot)?)?;
+        self.indexes_dirty = false;
+        Ok(())
+    }
+
+    /// Write the index sidecar (if incremental updates changed it) and
+    /// compact the working-memory WAL (if it holds superseded entries)
+    ///
+    /// Dropping a brain does the same but ignores errors; call this (or
+    /// [`Brain::close`]) to find out whether the state reached disk.
+    pub fn save(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.indexes_dirty {
+            self.save_indexes()?;
+        }
+        if self.working_wal_entries > self.working.len() {
+            self.rewrite_working_wal()?;
+        }
+        Ok(())
+    }
+
+    /// [`Brain::save`] and drop the brain
+    pub fn close(mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.save()
+    }
+
+    fn index_snapshot(&self) -> Result<IndexSnapshot, Box<dyn std::error::Error>> {
+        let bloom = self.keyword_bloom.stats();
+        Ok(IndexSnapshot {
+            episodic_count: self.episodic.count()?,
+            semantic_count: self.semantic.count()?,
+            procedural_count: self.procedural.count()?,
+            documents: self.keyword_index.documents(),
+            positional: true,
+            tokenizer_version: text::TOKENIZER_VERSION,
+            bloom_size: bloom.size,
+            bloom_hashes: bloom.num_hashes,
+            bloom_items: bloom.items_added,
+            bloom_bits: self.keyword_bloom.to_bytes(),
+            tag_markov: Some(self.tag_markov.clone()),
+            classifier: self.consolidator.prototypes(),
+        })
+    }
+
+    /// Replace the keyword index, bloom filter and tag model; false if the
+    /// bloom bits are invalid or the snapshot predates token positions, the
+    /// current tokenizer or the tag model
+    fn apply_index_snapshot(&mut self, snapshot: IndexSnapshot) -> bool {
+        if !snapshot.positional || snapshot.tokenizer_version != text::TOKENIZER_VERSION {
+            return false;
+        }
+        let Some(tag_markov) = snapshot.tag_markov else {
+            return false;
+        };
+        let Ok(bloom) = CountingBloomFilter::from_bytes(
+            snapshot.bloom_size,
+            snapshot.bloom_hashes,
+            snapshot.bloom_items,
+            &snapshot.bloom_bits,
+        ) else {
+            return false;
+        };
+
+        self.keyword_index.clear();
+        for (id, keywords) in snapshot.documents {
+            self.keyword_index.insert_keywords(id, keywords);
+        }
+        self.keyword_bloom = bloom;
+        self.tag_markov = tag_markov;
+        true
+    }
+
+    /// Write every long-term memory and the index state to one archive
+    ///
+    /// Items are stored verbatim (ids, timestamps, strengths, associations,
+    /// embeddings). The file is written to a temporary path and renamed, so
+    /// an interrupted snapshot never replaces a good one.
+    pub fn snapshot<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let snapshot = BrainSnapshot {
+            version: SNAPSHOT_VERSION,
+            created_at: chrono::Utc::now(),
+            embedding_dim: self.semantic.embedding_dim()?,
+            episodic: self.episodic.search("", usize::MAX)?,
+            semantic: self.semantic.search("", usize::MAX)?,
+            procedural: self.procedural.search("", usize::MAX)?,
+            indexes: self.index_snapshot()?,
+        };
+
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&snapshot)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Replace the database contents with a snapshot
+    ///
+    /// The archive is read and validated before anything is deleted. All
+    /// stores (and working memory) are wiped and repopulated exactly, so
+    /// restoring the same file twice gives the same state.
+    pub fn restore<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        let snapshot: BrainSnapshot = serde_json::from_slice(&std::fs::read(path)?)?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(format!(
+                "Snapshot version {} is newer than supported version {}",
+                snapshot.version, SNAPSHOT_VERSION
+            ).into());
+        }
+
+        self.working.clear();
+        self.rewrite_working_wal()?;
+        self.episodic.clear()?;
+        self.semantic.clear()?;
+        self.procedural.clear()?;
+        self.content_hashes = None;
+
+        // `update` writes by id, bypassing the semantic store's dedup
+        for item in &snapshot.episodic {
+            self.episodic.update(item)?;
+        }
+        for item in &snapshot.semantic {
+            self.semantic.update(item)?;
+        }
+        for item in &snapshot.procedural {
+            self.procedural.update(item)?;
+        }
+        if let Some(dim) = snapshot.embedding_dim {
+            self.semantic.set_embedding_dim(dim)?;
+        }
+        self.stored_embedding_dim = snapshot.embedding_dim;
+
+        self.hnsw.clear();
+        *self.hnsw_pending.get_mut() = false;
+        let all = snapshot.episodic.iter().chain(&snapshot.semantic).chain(&snapshot.procedural);
+        for item in all {
+            if let Some(ref emb) = item.embedding {
+                let _ = self.hnsw.add(item.id, emb.clone());
             }
         }
-        // Check procedural
-        if let Ok(items) = self.procedural.search("", 100000) {
-            for mut item in items {
-                if item.id.to_string().starts_with(id_prefix) {
-                    item.strength = strength;
-                    let _ = self.procedural.store(item);
-                    return Ok(());
-                }
+
+        if self.apply_index_snapshot(snapshot.indexes) {
+            self.save_indexes()?;
+        } else {
+            self.rebuild_indexes()?;
+        }
+        Ok(())
+    }
+
+    /// Load the persisted keyword index and bloom filter
+    ///
+    /// Returns `None` (leaving the indexes untouched) when the sidecar file is
+    /// missing, unreadable, or its memory counts differ from the database.
+    /// The HNSW index is not persisted; it is refilled from the stored
+    /// embeddings on first use ([`Brain::hnsw_index`]), so the reported
+    /// `hnsw_count` is 0.
+    pub fn load_indexes(&mut self) -> Result<Option<RebuildStats>, Box<dyn std::error::Error>> {
+        let Ok(bytes) = std::fs::read(&self.index_path) else {
+            return Ok(None);
+        };
+        let Ok(mut snapshot) = serde_json::from_slice::<IndexSnapshot>(&bytes) else {
+            return Ok(None);
+        };
+        // The classifier doesn't depend on store contents; keep it even
+        // when the rest of the snapshot is stale
+        self.consolidator.set_prototypes(std::mem::take(&mut snapshot.classifier));
+
+        let counts = (self.episodic.count()?, self.semantic.count()?, self.procedural.count()?);
+        if counts != (snapshot.episodic_count, snapshot.semantic_count, snapshot.procedural_count) {
+            return Ok(None);
+        }
+        if !self.apply_index_snapshot(snapshot) {
+            return Ok(None);
+        }
+        self.hnsw.clear();
+        *self.hnsw_pending.get_mut() = true;
+        self.indexes_dirty = false;
+        self.indexes_loaded = true;
+
+        Ok(Some(RebuildStats {
+            episodic_count: counts.0,
+            semantic_count: counts.1,
+            procedural_count: counts.2,
+            index_stats: self.keyword_index.stats(),
+            bloom_stats: self.keyword_bloom.stats(),
+            hnsw_count: self.hnsw.stats().count,
+        }))
+    }
+
+    /// The HNSW index, first filled from the stored embeddings if
+    /// `load_indexes` left that for later
+    pub fn hnsw_index(&self) -> &HnswIndex {
+        if self.hnsw_pending.swap(false, Ordering::AcqRel) && self.fill_hnsw().is_err() {
+            self.hnsw_pending.store(true, Ordering::Release);
+        }
+        &self.hnsw
+    }
+
+    /// Add every store's embeddings to HNSW (already indexed ids are skipped)
+    fn fill_hnsw(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let stores = [
+            self.episodic.search("", usize::MAX)?,
+            self.semantic.search("", usize::MAX)?,
+            self.procedural.search("", usize::MAX)?,
+        ];
+        for item in stores.into_iter().flatten() {
+            if let Some(embedding) = item.embedding {
+                let _ = self.hnsw.add(item.id, embedding);
             }
         }
-        
-        Err(format!("Memory not found: {}", id_prefix).into())
+        Ok(())
     }
 
-    /// Execute CQL query through the underlying CoreDB (via semantic store's storage)
-    pub fn storage_execute_cql(&self, query: &str) -> Result<String, String> {
-        self.semantic.execute_cql_html(query)
+    /// Load persisted indexes, falling back to a full rebuild when they are
+    /// missing or out of sync with the database
+    pub fn load_or_rebuild_indexes(&mut self) -> Result<RebuildStats, Box<dyn std::error::Error>> {
+        match self.load_indexes()? {
+            Some(stats) => Ok(stats),
+            None => self.rebuild_indexes(),
+        }
     }
 
+    /// Rebuild keyword index and bloom filter from existing memories
+    /// 
+    /// Call this after loading a database to populate the in-memory indexes.
     pub fn rebuild_indexes(&mut self) -> Result<RebuildStats, Box<dyn std::error::Error>> {
         let mut stats = RebuildStats::default();
 
         // Clear existing indexes
         self.keyword_index.clear();
         self.keyword_bloom.clear();
+        self.hnsw.clear();
+        *self.hnsw_pending.get_mut() = false;
+        self.content_hashes = None;
+        self.indexes_loaded = true;
+        let mut tag_history = Vec::new();
 
-        // Load all episodic memories
-        if let Ok(items) = self.episodic.search("", 100000) {
+        // Load all episodic memories (uncapped, like the counts `load_indexes` compares)
+        if let Ok(items) = self.episodic.search("", usize::MAX) {
             for item in &items {
                 self.keyword_index.add(item.id, &item.content);
-                for word in item.content.split_whitespace() {
-                    let word = word.trim_matches(|c: char| !c.is_alphanumeric());
-                    if word.len() >= 2 {
-                        self.keyword_bloom.add_str(word);
-                    }
+                if let Some(ref emb) = item.embedding {
+                    let _ = self.hnsw.add(item.id, emb.clone());
+                }
+                for word in bloom_words(&item.content) {
+                    self.keyword_bloom.add_str(&word);
                 }
+                tag_history.push((item.created_at, item.tags.clone()));
             }
             stats.episodic_count = items.len();
         }
 
         // Load all semantic memories
-        if let Ok(items) = self.semantic.search("", 100000) {
+        if let Ok(items) = self.semantic.search("", usize::MAX) {
             for item in &items {
                 self.keyword_index.add(item.id, &item.content);
-                for word in item.content.split_whitespace() {
-                    let word = word.trim_matches(|c: char| !c.is_alphanumeric());
-                    if word.len() >= 2 {
-                        self.keyword_bloom.add_str(word);
-                    }
+                if let Some(ref emb) = item.embedding {
+                    let _ = self.hnsw.add(item.id, emb.clone());
                 }
+                for word in bloom_words(&item.content) {
+                    self.keyword_bloom.add_str(&word);
+                }
+                tag_history.push((item.created_at, item.tags.clone()));
             }
             stats.semantic_count = items.len();
         }
 
         // Load all procedural memories
-        if let Ok(items) = self.procedural.search("", 100000) {
+        if let Ok(items) = self.procedural.search("", usize::MAX) {
             for item in &items {
                 self.keyword_index.add(item.id, &item.content);
-                for word in item.content.split_whitespace() {
-                    let word = word.trim_matches(|c: char| !c.is_alphanumeric());
-                    if word.len() >= 2 {
-                        self.keyword_bloom.add_str(word);
-                    }
+                if let Some(ref emb) = item.embedding {
+                    let _ = self.hnsw.add(item.id, emb.clone());
+                }
+                for word in bloom_words(&item.content) {
+                    self.keyword_bloom.add_str(&word);
                 }
+                tag_history.push((item.created_at, item.tags.clone()));
             }
             stats.procedural_count = items.len();
         }
 
+        self.tag_markov = predict::TagMarkov::from_history(tag_history);
+
         stats.index_stats = self.keyword_index.stats();
         stats.bloom_stats = self.keyword_bloom.stats();
+        stats.hnsw_count = self.hnsw.stats().count;
+
+        self.save_indexes()?;
 
         Ok(stats)
     }
 }
 
+impl Drop for Brain {
+    fn drop(&mut self) {
+        // Best effort; `save`/`close` report failures
+        let _ = self.save();
+    }
+}
+
+/// Words of `content` tracked by the keyword bloom filter (the index's tokens)
+fn bloom_words(content: &str) -> Vec<String> {
+    text::tokenize(content, text::Lang::Auto)
+}
+
+/// Current `Brain::snapshot` archive format
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Archive written by `Brain::snapshot`
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BrainSnapshot {
+    version: u32,
+    created_at: chrono::DateTime<chrono::Utc>,
+    embedding_dim: Option<usize>,
+    episodic: Vec<MemoryItem>,
+    semantic: Vec<MemoryItem>,
+    procedural: Vec<MemoryItem>,
+    indexes: IndexSnapshot,
+}
+
+/// On-disk copy of the keyword index and bloom filter
+#[derive(serde::Serialize, serde::Deserialize)]
+struct IndexSnapshot {
+    episodic_count: usize,
+    semantic_count: usize,
+    procedural_count: usize,
+    documents: Vec<(uuid::Uuid, Vec<String>)>,
+    /// `documents` hold token sequences in order (older files: keyword sets)
+    #[serde(default)]
+    positional: bool,
+    /// `text::TOKENIZER_VERSION` the documents were tokenized with (0: older files)
+    #[serde(default)]
+    tokenizer_version: u32,
+    bloom_size: usize,
+    bloom_hashes: usize,
+    bloom_items: usize,
+    bloom_bits: Vec<u8>,
+    /// Tag-transition model (None: older files, rebuilt from history)
+    #[serde(default)]
+    tag_markov: Option<predict::TagMarkov>,
+    /// Type classifier centroids (empty: older files or never trained)
+    #[serde(default)]
+    classifier: Vec<(MemoryType, consolidate::Prototype)>,
+}
+
+/// Snapshot from [`Brain::stats`]
+#[derive(Debug, Clone, Default)]
+pub struct BrainStats {
+    pub working: usize,
+    pub working_capacity: usize,
+    pub episodic: usize,
+    pub semantic: usize,
+    pub procedural: usize,
+    /// Long-term memories (episodic + semantic + procedural)
+    pub total: usize,
+    pub embedding_dim: usize,
+    pub index_stats: inverted_index::IndexStats,
+    pub bloom_stats: BloomStats,
+    /// Vectors in the HNSW index
+    pub hnsw_count: usize,
+    /// Bytes under the CoreDB directory, if it could be read
+    pub db_size_bytes: Option<u64>,
+}
+
+/// Report from [`Brain::compress_embeddings`]
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingCompression {
+    /// f32 vs int8 sizes and mean reconstruction error
+    pub stats: CompressionStats,
+    /// `embedding` column bytes before and after (equal on a dry run)
+    pub disk_bytes_before: usize,
+    pub disk_bytes_after: usize,
+    /// Rows rewritten in the quantized format
+    pub rewritten: usize,
+}
+
+/// Total size of the files under `path` (a single file's size for a file)
+fn dir_size(path: &Path) -> Option<u64> {
+    let meta = std::fs::metadata(path).ok()?;
+    if !meta.is_dir() {
+        return Some(meta.len());
+    }
+    let mut total = 0;
+    for entry in std::fs::read_dir(path).ok()?.flatten() {
+        total += dir_size(&entry.path()).unwrap_or(0);
+    }
+    Some(total)
+}
+
 /// Statistics from rebuild_indexes
 #[derive(Debug, Default)]
 pub struct RebuildStats {
@@ -464,6 +2501,8 @@ pub struct RebuildStats {
     pub procedural_count: usize,
     pub index_stats: inverted_index::IndexStats,
     pub bloom_stats: BloomStats,
+    /// Vectors in the HNSW index
+    pub hnsw_count: usize,
 }
 
 impl std::fmt::Display for RebuildStats {
@@ -475,6 +2514,7 @@ impl std::fmt::Display for RebuildStats {
         writeln!(f, "  Total:      {} memories", self.episodic_count + self.semantic_count + self.procedural_count)?;
         writeln!(f, "")?;
         writeln!(f, "  {}", self.index_stats)?;
+        writeln!(f, "  HNSW: {} vectors", self.hnsw_count)?;
         write!(f, "  {}", self.bloom_stats)
     }
 }
@@ -5,17 +5,213 @@
 //! - Ollama (local API)
 //! - OpenAI-compatible APIs
 
+use std::collections::VecDeque;
+use std::io::Read;
 use std::process::Command;
+use std::time::Duration;
+
+/// Default per-read timeout for LLM HTTP requests (generation can be slow)
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Timeout for availability checks
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// LLM HTTP errors
+#[derive(Debug)]
+pub enum LlmError {
+    /// Could not connect to the server
+    Connection(String),
+    /// Server answered with a non-success status
+    Http { status: u16, body: String },
+    /// Response didn't have the expected shape
+    InvalidResponse(String),
+    /// Error reported by the API in a successful response
+    Api(String),
+    /// No response within the configured timeout
+    Timeout,
+}
+
+impl std::fmt::Display for LlmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LlmError::Connection(msg) => write!(f, "Connection error: {}", msg),
+            LlmError::Http { status, body } => write!(f, "HTTP {}: {}", status, body),
+            LlmError::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
+            LlmError::Api(msg) => write!(f, "API error: {}", msg),
+            LlmError::Timeout => write!(f, "LLM request timed out"),
+        }
+    }
+}
+
+impl std::error::Error for LlmError {}
+
+impl From<ureq::Error> for LlmError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(status, response) => LlmError::Http {
+                status,
+                body: response.into_string().unwrap_or_default(),
+            },
+            ureq::Error::Transport(transport) => {
+                let timed_out = std::error::Error::source(&transport)
+                    .and_then(|s| s.downcast_ref::<std::io::Error>())
+                    .map_or(false, |io| {
+                        matches!(io.kind(), std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock)
+                    });
+                if timed_out {
+                    LlmError::Timeout
+                } else {
+                    LlmError::Connection(transport.to_string())
+                }
+            }
+        }
+    }
+}
+
+/// HTTP agent with connect and per-read timeouts
+///
+/// A per-read timeout (rather than an overall deadline) lets long
+/// streamed responses finish while still failing on a stalled server.
+fn http_agent(timeout: Duration) -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(timeout)
+        .timeout_read(timeout)
+        .build()
+}
+
+/// POST a JSON body and return the successful response
+fn post_json(
+    agent: &ureq::Agent,
+    url: &str,
+    auth: Option<&str>,
+    body: &serde_json::Value,
+) -> Result<ureq::Response, LlmError> {
+    let mut request = agent.post(url).set("Content-Type", "application/json");
+    if let Some(key) = auth {
+        request = request.set("Authorization", &format!("Bearer {}", key));
+    }
+    Ok(request.send_json(body)?)
+}
+
+/// Decode a JSON response body
+fn read_json(response: ureq::Response) -> Result<serde_json::Value, LlmError> {
+    response.into_json().map_err(|e| {
+        let timed_out = matches!(e.kind(), std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock);
+        if timed_out { LlmError::Timeout } else { LlmError::InvalidResponse(e.to_string()) }
+    })
+}
 
 /// LLM provider trait
 pub trait LlmProvider: Send + Sync {
     /// Generate a response for a prompt
     fn generate(&self, prompt: &str, max_tokens: usize) -> Result<String, Box<dyn std::error::Error>>;
+
+    /// Generate a response, passing text chunks to `on_chunk` as they arrive
+    ///
+    /// Returns the full response. The default calls `generate` once and
+    /// delivers the whole text as a single chunk.
+    fn generate_stream(
+        &self,
+        prompt: &str,
+        max_tokens: usize,
+        on_chunk: &mut dyn FnMut(&str),
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let response = self.generate(prompt, max_tokens)?;
+        on_chunk(&response);
+        Ok(response)
+    }
     
     /// Get provider name
     fn name(&self) -> &str;
 }
 
+/// One decoded line of a streaming response
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamLine {
+    /// Generated text in this line (may be empty)
+    pub text: String,
+    /// The server signalled the end of the stream
+    pub done: bool,
+}
+
+/// Parse one Ollama NDJSON line (`{"response": "...", "done": false}`)
+pub fn parse_ollama_line(line: &str) -> Result<StreamLine, Box<dyn std::error::Error>> {
+    let value: serde_json::Value = serde_json::from_str(line)?;
+    if let Some(error) = value["error"].as_str() {
+        return Err(format!("Ollama error: {}", error).into());
+    }
+    Ok(StreamLine {
+        text: value["response"].as_str().unwrap_or("").to_string(),
+        done: value["done"].as_bool().unwrap_or(false),
+    })
+}
+
+/// Parse one OpenAI SSE line (`data: {...}` / `data: [DONE]`)
+///
+/// Non-data lines (comments, `event:`) yield an empty chunk.
+pub fn parse_openai_sse_line(line: &str) -> Result<StreamLine, Box<dyn std::error::Error>> {
+    let Some(payload) = line.strip_prefix("data:").map(str::trim) else {
+        return Ok(StreamLine { text: String::new(), done: false });
+    };
+    if payload == "[DONE]" {
+        return Ok(StreamLine { text: String::new(), done: true });
+    }
+    let value: serde_json::Value = serde_json::from_str(payload)?;
+    if let Some(error) = value["error"]["message"].as_str() {
+        return Err(format!("OpenAI error: {}", error).into());
+    }
+    Ok(StreamLine {
+        text: value["choices"][0]["delta"]["content"].as_str().unwrap_or("").to_string(),
+        done: value["choices"][0]["finish_reason"].is_string(),
+    })
+}
+
+/// Read a line-delimited stream, calling `on_chunk` for each piece of text
+///
+/// Bytes are buffered until a full line arrives, so JSON (or a multi-byte
+/// character) split across reads is decoded intact. Returns the concatenated text.
+pub fn read_line_stream<R: Read>(
+    mut reader: R,
+    parse: fn(&str) -> Result<StreamLine, Box<dyn std::error::Error>>,
+    on_chunk: &mut dyn FnMut(&str),
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut full = String::new();
+    let mut pending: Vec<u8> = Vec::new();
+    let mut buf = [0u8; 4096];
+
+    // Returns true once the stream reported it is done
+    let mut handle = |line: &[u8], full: &mut String| -> Result<bool, Box<dyn std::error::Error>> {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(false);
+        }
+        let decoded = parse(line)?;
+        if !decoded.text.is_empty() {
+            on_chunk(&decoded.text);
+            full.push_str(&decoded.text);
+        }
+        Ok(decoded.done)
+    };
+
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        pending.extend_from_slice(&buf[..n]);
+        while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=pos).collect();
+            if handle(&line, &mut full)? {
+                return Ok(full);
+            }
+        }
+    }
+    // Last line without a trailing newline
+    handle(&pending, &mut full)?;
+    Ok(full)
+}
+
 /// MLX-LM provider (uses Python mlx-lm CLI)
 pub struct MlxLmProvider {
     model: String,
@@ -98,26 +294,41 @@ print(response)
 pub struct OllamaProvider {
     model: String,
     base_url: String,
+    timeout: Duration,
+    agent: ureq::Agent,
 }
 
 impl OllamaProvider {
     pub fn new(model: &str) -> Self {
-        Self {
-            model: model.to_string(),
-            base_url: "http://localhost:11434".to_string(),
-        }
+        Self::with_url(model, "http://localhost:11434")
     }
 
     pub fn with_url(model: &str, base_url: &str) -> Self {
         Self {
             model: model.to_string(),
-            base_url: base_url.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            timeout: DEFAULT_TIMEOUT,
+            agent: http_agent(DEFAULT_TIMEOUT),
         }
     }
 
+    /// Set the connect/read timeout (default [`DEFAULT_TIMEOUT`])
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self.agent = http_agent(timeout);
+        self
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
     /// Check if Ollama is running
     pub fn is_available() -> bool {
-        reqwest_sync_get("http://localhost:11434/api/tags").is_ok()
+        ureq::get("http://localhost:11434/api/tags")
+            .timeout(PROBE_TIMEOUT)
+            .call()
+            .is_ok()
     }
 }
 
@@ -133,23 +344,34 @@ impl LlmProvider for OllamaProvider {
             }
         });
 
-        // Use curl for simplicity (avoid adding reqwest dependency)
-        let output = Command::new("curl")
-            .args([
-                "-s",
-                "-X", "POST",
-                &url,
-                "-H", "Content-Type: application/json",
-                "-d", &body.to_string(),
-            ])
-            .output()?;
-
-        if output.status.success() {
-            let response: serde_json::Value = serde_json::from_slice(&output.stdout)?;
-            Ok(response["response"].as_str().unwrap_or("").to_string())
-        } else {
-            Err("Ollama request failed".into())
+        let response = read_json(post_json(&self.agent, &url, None, &body)?)?;
+        if let Some(error) = response["error"].as_str() {
+            return Err(LlmError::Api(error.to_string()).into());
         }
+        let text = response["response"]
+            .as_str()
+            .ok_or_else(|| LlmError::InvalidResponse("missing 'response' field".to_string()))?;
+        Ok(text.to_string())
+    }
+
+    fn generate_stream(
+        &self,
+        prompt: &str,
+        max_tokens: usize,
+        on_chunk: &mut dyn FnMut(&str),
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let url = format!("{}/api/generate", self.base_url);
+        let body = serde_json::json!({
+            "model": self.model,
+            "prompt": prompt,
+            "stream": true,
+            "options": {
+                "num_predict": max_tokens
+            }
+        });
+
+        let response = post_json(&self.agent, &url, None, &body)?;
+        read_line_stream(response.into_reader(), parse_ollama_line, on_chunk)
     }
 
     fn name(&self) -> &str {
@@ -162,25 +384,36 @@ pub struct OpenAIProvider {
     model: String,
     api_key: String,
     base_url: String,
+    timeout: Duration,
+    agent: ureq::Agent,
 }
 
 impl OpenAIProvider {
     pub fn new(model: &str, api_key: &str) -> Self {
-        Self {
-            model: model.to_string(),
-            api_key: api_key.to_string(),
-            base_url: "https://api.openai.com/v1".to_string(),
-        }
+        Self::with_base_url(model, api_key, "https://api.openai.com/v1")
     }
 
     pub fn with_base_url(model: &str, api_key: &str, base_url: &str) -> Self {
         Self {
             model: model.to_string(),
             api_key: api_key.to_string(),
-            base_url: base_url.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            timeout: DEFAULT_TIMEOUT,
+            agent: http_agent(DEFAULT_TIMEOUT),
         }
     }
 
+    /// Set the connect/read timeout (default [`DEFAULT_TIMEOUT`])
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self.agent = http_agent(timeout);
+        self
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
     /// Create from environment variable
     pub fn from_env(model: &str) -> Option<Self> {
         std::env::var("OPENAI_API_KEY").ok().map(|key| Self::new(model, &key))
@@ -196,26 +429,31 @@ impl LlmProvider for OpenAIProvider {
             "max_tokens": max_tokens
         });
 
-        let output = Command::new("curl")
-            .args([
-                "-s",
-                "-X", "POST",
-                &url,
-                "-H", "Content-Type: application/json",
-                "-H", &format!("Authorization: Bearer {}", self.api_key),
-                "-d", &body.to_string(),
-            ])
-            .output()?;
-
-        if output.status.success() {
-            let response: serde_json::Value = serde_json::from_slice(&output.stdout)?;
-            let content = response["choices"][0]["message"]["content"]
-                .as_str()
-                .unwrap_or("");
-            Ok(content.to_string())
-        } else {
-            Err("OpenAI request failed".into())
+        let response = read_json(post_json(&self.agent, &url, Some(&self.api_key), &body)?)?;
+        if let Some(error) = response["error"]["message"].as_str() {
+            return Err(LlmError::Api(error.to_string()).into());
         }
+        let content = response["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| LlmError::InvalidResponse("missing choices[0].message.content".to_string()))?;
+        Ok(content.to_string())
+    }
+
+    fn generate_stream(
+        &self,
+        prompt: &str,
+        max_tokens: usize,
+        on_chunk: &mut dyn FnMut(&str),
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let body = serde_json::json!({
+            "model": self.model,
+            "messages": [{"role": "user", "content": prompt}],
+            "max_tokens": max_tokens,
+            "stream": true
+        });
+        let response = post_json(&self.agent, &url, Some(&self.api_key), &body)?;
+        read_line_stream(response.into_reader(), parse_openai_sse_line, on_chunk)
     }
 
     fn name(&self) -> &str {
@@ -240,12 +478,181 @@ impl LlmProvider for EchoProvider {
 
 use crate::Brain;
 
+/// Memory operation requested by the model in its response
+///
+/// The model emits one directive per line:
+/// - `STORE: <fact>` - remember a new fact
+/// - `FORGET: <id>` - delete a memory; only full memory ids are accepted
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemoryDirective {
+    Store(String),
+    Forget(uuid::Uuid),
+    /// FORGET without a full memory id (ignored to avoid accidental deletes)
+    InvalidForget(String),
+}
+
+/// Split a model response into visible text and memory directives
+pub fn parse_directives(response: &str) -> (String, Vec<MemoryDirective>) {
+    let mut text = Vec::new();
+    let mut directives = Vec::new();
+
+    for line in response.lines() {
+        let trimmed = line.trim();
+        if let Some(fact) = trimmed.strip_prefix("STORE:") {
+            let fact = fact.trim();
+            if !fact.is_empty() {
+                directives.push(MemoryDirective::Store(fact.to_string()));
+            }
+        } else if let Some(target) = trimmed.strip_prefix("FORGET:") {
+            let target = target.trim();
+            // Ids are shown in brackets in the context, and copied with them
+            let id = target.strip_prefix('[').and_then(|t| t.strip_suffix(']')).unwrap_or(target);
+            match uuid::Uuid::parse_str(id.trim()) {
+                Ok(id) => directives.push(MemoryDirective::Forget(id)),
+                Err(_) => directives.push(MemoryDirective::InvalidForget(target.to_string())),
+            }
+        } else {
+            text.push(line);
+        }
+    }
+
+    (text.join("\n").trim().to_string(), directives)
+}
+
+/// Special tokens that end a response (see `MemoryChat::finish_response`)
+const STOP_TOKENS: [&str; 2] = ["<|eot_id|>", "<|start_header_id|>"];
+
+/// Whether a trimmed response line is a STORE/FORGET directive
+fn is_directive(trimmed: &str) -> bool {
+    trimmed.starts_with("STORE:") || trimmed.starts_with("FORGET:")
+}
+
+/// Cleans a streamed response chunk by chunk the way
+/// `MemoryChat::finish_response` cleans the full text: output stops at the
+/// first special token and directive lines are never shown
+///
+/// Text is held back only while it could still turn out to be a directive
+/// or a special token.
+struct StreamCleaner {
+    /// Drop STORE/FORGET lines
+    directives: bool,
+    /// Current, unfinished line
+    line: String,
+    /// Bytes of `line` already passed on
+    emitted: usize,
+    /// Nothing has been passed on yet (leading whitespace is skipped)
+    start: bool,
+    /// A special token was seen
+    done: bool,
+}
+
+impl StreamCleaner {
+    fn new(directives: bool) -> Self {
+        Self { directives, line: String::new(), emitted: 0, start: true, done: false }
+    }
+
+    fn push(&mut self, chunk: &str, out: &mut dyn FnMut(&str)) {
+        if self.done {
+            return;
+        }
+        self.line.push_str(chunk);
+
+        if let Some(stop) = STOP_TOKENS.iter().filter_map(|t| self.line.find(t)).min() {
+            self.line.truncate(stop);
+            self.done = true;
+            self.finish(out);
+            return;
+        }
+
+        while let Some(newline) = self.line.find('\n') {
+            if !(self.directives && is_directive(self.line[..newline].trim())) {
+                self.emit(newline + 1, out);
+            }
+            self.line.drain(..=newline);
+            self.emitted = 0;
+        }
+
+        // The rest of the line is shown once it can't be a directive, up to
+        // a possible start of a special token
+        let trimmed = self.line.trim_start();
+        let undecided = self.directives
+            && (is_directive(trimmed) || ["STORE:", "FORGET:"].iter().any(|d| d.starts_with(trimmed)));
+        if !undecided {
+            let held = self.line.rfind('<')
+                .filter(|&i| STOP_TOKENS.iter().any(|t| t.starts_with(&self.line[i..])))
+                .unwrap_or(self.line.len());
+            self.emit(held, out);
+        }
+    }
+
+    /// Pass on whatever is left once the response is complete
+    fn finish(&mut self, out: &mut dyn FnMut(&str)) {
+        if !(self.directives && is_directive(self.line.trim())) {
+            self.emit(self.line.len(), out);
+        }
+        self.line.clear();
+        self.emitted = 0;
+    }
+
+    /// Pass on `line[emitted..end]`
+    fn emit(&mut self, end: usize, out: &mut dyn FnMut(&str)) {
+        if end <= self.emitted {
+            return;
+        }
+        let mut text = &self.line[self.emitted..end];
+        if self.start {
+            text = text.trim_start();
+        }
+        if !text.is_empty() {
+            out(text);
+            self.start = false;
+        }
+        self.emitted = end;
+    }
+}
+
+/// Most memories `summarize_memories_mapreduce` recalls for one topic
+pub const MAPREDUCE_RECALL_LIMIT: usize = 500;
+
+/// Default cap on memories `summarize_all`/`summarize_tag` cover (the newest ones)
+pub const DEFAULT_SUMMARY_LIMIT: usize = 500;
+
+/// Default cap on memories per map-step prompt
+pub const DEFAULT_MEMORIES_PER_CHUNK: usize = 20;
+
+/// Default minimum query similarity for a memory to enter the chat prompt
+pub const DEFAULT_CONTEXT_THRESHOLD: f32 = 0.1;
+
+/// Map-reduce summary plus how much went into it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkedSummary {
+    pub summary: String,
+    /// Memories fed to the map step
+    pub memories: usize,
+    /// Map-step prompts issued
+    pub chunks: usize,
+}
+
 /// Memory-augmented LLM chat
 pub struct MemoryChat {
     brain: Brain,
     llm: Box<dyn LlmProvider>,
     system_prompt: String,
     memory_limit: usize,
+    /// Recalled memories must be more similar to the question than this
+    context_threshold: f32,
+    /// Let the model emit STORE/FORGET directives
+    memory_tools: bool,
+    /// Approximate token budget per summarization prompt
+    summary_budget: usize,
+    /// Most memories `summarize_all`/`summarize_tag` cover
+    summary_limit: usize,
+    /// Recent (user, assistant) turns, oldest first
+    history: VecDeque<(String, String)>,
+    /// Turns kept and replayed in the chat prompt
+    history_limit: usize,
+    /// Approximate token budget for the whole chat prompt
+    prompt_budget: usize,
 }
 
 impl MemoryChat {
@@ -255,9 +662,42 @@ impl MemoryChat {
             llm,
             system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
             memory_limit: 5,
+            context_threshold: DEFAULT_CONTEXT_THRESHOLD,
+            memory_tools: true,
+            summary_budget: 1500,
+            summary_limit: DEFAULT_SUMMARY_LIMIT,
+            history: VecDeque::new(),
+            history_limit: 6,
+            prompt_budget: 3000,
         }
     }
 
+    /// Number of previous turns included in the prompt (0 disables history)
+    pub fn with_history_limit(mut self, turns: usize) -> Self {
+        self.history_limit = turns;
+        while self.history.len() > turns {
+            self.history.pop_front();
+        }
+        self
+    }
+
+    /// Approximate token budget for a chat prompt; the oldest turns are
+    /// dropped first when history would exceed it
+    pub fn with_prompt_budget(mut self, tokens: usize) -> Self {
+        self.prompt_budget = tokens.max(1);
+        self
+    }
+
+    /// Forget the conversation so far (stored memories are kept)
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Previous (user, assistant) turns, oldest first
+    pub fn history(&self) -> &VecDeque<(String, String)> {
+        &self.history
+    }
+
     pub fn with_system_prompt(mut self, prompt: &str) -> Self {
         self.system_prompt = prompt.to_string();
         self
@@ -268,66 +708,223 @@ impl MemoryChat {
         self
     }
 
+    /// Minimum embedding similarity between the question and a recalled
+    /// memory for it to be put in the prompt (default [`DEFAULT_CONTEXT_THRESHOLD`])
+    ///
+    /// Memories without an embedding are kept when they share a keyword
+    /// with the question instead.
+    pub fn with_context_threshold(mut self, threshold: f32) -> Self {
+        self.context_threshold = threshold;
+        self
+    }
+
+    /// Set the approximate token budget per summarization prompt
+    pub fn with_summary_budget(mut self, tokens: usize) -> Self {
+        self.summary_budget = tokens.max(1);
+        self
+    }
+
+    /// Set how many of the newest memories `summarize_all`/`summarize_tag`
+    /// cover (default [`DEFAULT_SUMMARY_LIMIT`])
+    pub fn with_summary_limit(mut self, limit: usize) -> Self {
+        self.summary_limit = limit.max(1);
+        self
+    }
+
+    /// Enable/disable STORE/FORGET directives (enabled by default)
+    pub fn with_memory_tools(mut self, enabled: bool) -> Self {
+        self.memory_tools = enabled;
+        self
+    }
+
     /// Chat with memory-augmented context
     pub fn chat(&mut self, user_input: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let full_prompt = self.build_prompt(user_input);
+
+        // 4. Generate response (shorter for better results)
+        let response = self.llm.generate(&full_prompt, 200)?;
+
+        let (response, confirmations) = self.finish_response(user_input, &response)?;
+        if confirmations.is_empty() {
+            Ok(response)
+        } else {
+            Ok(format!("{}\n\n{}", response, confirmations.join("\n")).trim().to_string())
+        }
+    }
+
+    /// Like [`MemoryChat::chat`], but streams the response through `on_chunk`
+    ///
+    /// Chunks are cleaned as they arrive: nothing after a special token and
+    /// no STORE/FORGET lines. Directive confirmations are sent as a final
+    /// chunk. Returns the same cleaned text as `chat`.
+    pub fn chat_stream(
+        &mut self,
+        user_input: &str,
+        on_chunk: &mut dyn FnMut(&str),
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let full_prompt = self.build_prompt(user_input);
+        let mut cleaner = StreamCleaner::new(self.memory_tools);
+        let response = self.llm.generate_stream(&full_prompt, 200, &mut |chunk| cleaner.push(chunk, on_chunk))?;
+        cleaner.finish(on_chunk);
+
+        let (response, confirmations) = self.finish_response(user_input, &response)?;
+        if confirmations.is_empty() {
+            Ok(response)
+        } else {
+            let confirmations = confirmations.join("\n");
+            on_chunk(&format!("\n\n{}", confirmations));
+            Ok(format!("{}\n\n{}", response, confirmations).trim().to_string())
+        }
+    }
+
+    /// Recall memories for `user_input` and build the full prompt
+    fn build_prompt(&mut self, user_input: &str) -> String {
         // 1. Recall relevant memories
-        let memories = self.brain.recall(user_input, self.memory_limit);
-        
+        let recalled = self.brain.recall(user_input, self.memory_limit);
+        let matches = self.brain.explain_matches(user_input, &recalled);
+
         // Debug
         if std::env::var("DEBUG").is_ok() {
             eprintln!("=== RECALL for '{}' ===", user_input);
-            eprintln!("Found {} memories", memories.len());
-            for m in &memories {
-                eprintln!("  - {}", m.content);
+            eprintln!("Found {} memories", recalled.len());
+            for (m, info) in recalled.iter().zip(&matches) {
+                eprintln!("  - ({:.2}) {}", info.similarity, m.content);
             }
         }
-        
-        // 2. Build context from memories
+
+        // Keyword hits that are unrelated to the question are only noise;
+        // without an embedding there is nothing to compare, so a shared
+        // keyword decides
+        let memories: Vec<crate::MemoryItem> = recalled
+            .into_iter()
+            .zip(matches)
+            .filter(|(m, info)| match m.embedding {
+                Some(_) => info.similarity > self.context_threshold,
+                None => !info.matched_keywords.is_empty(),
+            })
+            .map(|(m, _)| m)
+            .collect();
+
+        // 2. Build context from memories (omitted when nothing qualifies)
         let memory_context = if memories.is_empty() {
-            "No relevant memories found.".to_string()
+            String::new()
         } else {
             let mem_texts: Vec<String> = memories
                 .iter()
-                .map(|m| format!("- {}", m.content))
+                .map(|m| {
+                    if self.memory_tools {
+                        format!("- [{}] {}", m.id, m.content)
+                    } else {
+                        format!("- {}", m.content)
+                    }
+                })
                 .collect();
-            format!("Relevant memories about the user:\n{}", mem_texts.join("\n"))
+            format!("\n\nRelevant memories about the user:\n{}", mem_texts.join("\n"))
         };
 
         // 3. Build full prompt (Llama 3 format)
-        let full_prompt = format!(
-            "<|begin_of_text|><|start_header_id|>system<|end_header_id|>\n\n{}\n\n{}<|eot_id|><|start_header_id|>user<|end_header_id|>\n\n{}<|eot_id|><|start_header_id|>assistant<|end_header_id|>\n\n",
+        let tools_prompt = if self.memory_tools { MEMORY_TOOLS_PROMPT } else { "" };
+        let system = format!(
+            "<|begin_of_text|><|start_header_id|>system<|end_header_id|>\n\n{}{}{}<|eot_id|>",
             self.system_prompt,
-            memory_context,
+            tools_prompt,
+            memory_context
+        );
+        let current = format!(
+            "<|start_header_id|>user<|end_header_id|>\n\n{}<|eot_id|><|start_header_id|>assistant<|end_header_id|>\n\n",
             user_input
         );
 
+        // Previous turns, newest first until the budget runs out
+        let mut remaining = (self.prompt_budget * CHARS_PER_TOKEN).saturating_sub(system.len() + current.len());
+        let mut turns: Vec<String> = Vec::new();
+        for (user, assistant) in self.history.iter().rev().take(self.history_limit) {
+            let turn = format!(
+                "<|start_header_id|>user<|end_header_id|>\n\n{}<|eot_id|><|start_header_id|>assistant<|end_header_id|>\n\n{}<|eot_id|>",
+                user, assistant
+            );
+            if turn.len() > remaining {
+                break;
+            }
+            remaining -= turn.len();
+            turns.push(turn);
+        }
+        turns.reverse();
+
+        let full_prompt = format!("{}{}{}", system, turns.concat(), current);
+
         // Debug: print prompt if DEBUG env var is set
         if std::env::var("DEBUG").is_ok() {
             eprintln!("\n=== PROMPT ===\n{}\n=== END PROMPT ===\n", full_prompt);
         }
 
-        // 4. Generate response (shorter for better results)
-        let response = self.llm.generate(&full_prompt, 200)?;
-        
+        full_prompt
+    }
+
+    /// Clean up a raw response, run its directives and remember the exchange
+    ///
+    /// Returns the cleaned response and directive confirmations.
+    fn finish_response(&mut self, user_input: &str, response: &str) -> Result<(String, Vec<String>), Box<dyn std::error::Error>> {
         // 5. Clean up response (remove any continuation markers)
         let response = response
             .split("<|eot_id|>")
             .next()
-            .unwrap_or(&response)
+            .unwrap_or(response)
             .split("<|start_header_id|>")
             .next()
-            .unwrap_or(&response)
+            .unwrap_or(response)
             .trim()
             .to_string();
 
-        // 5. Store the interaction as episodic memory
+        // 6. Execute memory directives (STORE/FORGET)
+        let (response, confirmations) = if self.memory_tools {
+            let (text, directives) = parse_directives(&response);
+            (text, self.execute_directives(&directives)?)
+        } else {
+            (response, Vec::new())
+        };
+
+        // 7. Store the interaction as episodic memory
         let interaction = format!("User asked: {} | Response: {}", 
             truncate(user_input, 50), 
             truncate(&response, 100)
         );
         self.brain.process(&interaction, Some("chat"))?;
 
-        Ok(response)
+        if self.history_limit > 0 {
+            self.history.push_back((user_input.to_string(), response.clone()));
+            while self.history.len() > self.history_limit {
+                self.history.pop_front();
+            }
+        }
+
+        Ok((response, confirmations))
+    }
+
+    /// Run memory directives against the brain, returning confirmations
+    pub fn execute_directives(&mut self, directives: &[MemoryDirective]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut confirmations = Vec::new();
+
+        for directive in directives {
+            match directive {
+                MemoryDirective::Store(fact) => {
+                    self.brain.process(fact, Some("chat"))?;
+                    confirmations.push(format!("📝 Stored: {}", fact));
+                }
+                MemoryDirective::Forget(id) => {
+                    if self.brain.forget(*id)? {
+                        confirmations.push(format!("🗑️ Forgot: {}", id));
+                    } else {
+                        confirmations.push(format!("⚠️ No memory with id {}", id));
+                    }
+                }
+                MemoryDirective::InvalidForget(target) => {
+                    confirmations.push(format!("⚠️ FORGET ignored: '{}' is not a full memory id", target));
+                }
+            }
+        }
+
+        Ok(confirmations)
     }
 
     /// Ask the LLM to summarize memories on a topic
@@ -352,6 +949,103 @@ impl MemoryChat {
         self.llm.generate(&prompt, 200)
     }
 
+    /// Summarize up to [`MAPREDUCE_RECALL_LIMIT`] memories on a topic
+    ///
+    /// Unlike `summarize_memories` (10 memories, one prompt), recalled
+    /// memories are summarized in chunks of at most `max_per_chunk` that
+    /// also fit the summary token budget, then the chunk summaries are
+    /// combined. The result reports how many memories were covered.
+    pub fn summarize_memories_mapreduce(&mut self, topic: &str, max_per_chunk: usize) -> Result<ChunkedSummary, Box<dyn std::error::Error>> {
+        let memories = self.brain.recall(topic, MAPREDUCE_RECALL_LIMIT);
+        self.summarize_chunks(&format!("'{}'", topic), &memories, max_per_chunk)
+    }
+
+    /// Summarize the newest stored memories, up to the summary limit
+    /// (map-reduce over chunks)
+    pub fn summarize_all(&mut self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut memories = self.brain.list_page(self.summary_limit, 0)?;
+        memories.reverse();
+        self.summarize_chunked("all memories", &memories)
+    }
+
+    /// Summarize the newest memories carrying a tag (compared whole,
+    /// case-insensitively), up to the summary limit (map-reduce over chunks)
+    pub fn summarize_tag(&mut self, tag: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let tag_lower = tag.to_lowercase();
+        let mut memories = self.long_term_memories()?;
+        memories.retain(|m| m.tags.iter().any(|t| t.to_lowercase() == tag_lower));
+        let older = memories.len().saturating_sub(self.summary_limit);
+        memories.drain(..older);
+        self.summarize_chunked(&format!("tag '{}'", tag), &memories)
+    }
+
+    /// All memories from the long-term stores, oldest first
+    fn long_term_memories(&self) -> Result<Vec<crate::MemoryItem>, Box<dyn std::error::Error>> {
+        let mut memories = self.brain.semantic.search("", usize::MAX)?;
+        memories.extend(self.brain.episodic.search("", usize::MAX)?);
+        memories.extend(self.brain.procedural.search("", usize::MAX)?);
+        memories.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(memories)
+    }
+
+    fn summarize_chunked(&self, label: &str, memories: &[crate::MemoryItem]) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(self.summarize_chunks(label, memories, usize::MAX)?.summary)
+    }
+
+    /// Map: summarize budget-sized chunks of at most `max_per_chunk`
+    /// memories. Reduce: summarize the summaries until one remains.
+    fn summarize_chunks(&self, label: &str, memories: &[crate::MemoryItem], max_per_chunk: usize) -> Result<ChunkedSummary, Box<dyn std::error::Error>> {
+        if memories.is_empty() {
+            return Ok(ChunkedSummary {
+                summary: format!("No memories found for {}", label),
+                memories: 0,
+                chunks: 0,
+            });
+        }
+
+        let max_chars = self.summary_budget * CHARS_PER_TOKEN;
+        let lines: Vec<String> = memories
+            .iter()
+            .map(|m| format!("- {}", m.content.chars().take(max_chars).collect::<String>()))
+            .collect();
+
+        // Map
+        let chunks: Vec<Vec<String>> = chunk_by_budget(&lines, self.summary_budget, 1)
+            .into_iter()
+            .flat_map(|chunk| chunk.chunks(max_per_chunk.max(1)).map(<[String]>::to_vec).collect::<Vec<_>>())
+            .collect();
+        let chunk_count = chunks.len();
+        let mut summaries = Vec::new();
+        for chunk in chunks {
+            let prompt = format!(
+                "Summarize these memories about {}:\n{}\n\nSummary:",
+                label,
+                chunk.join("\n")
+            );
+            summaries.push(self.llm.generate(&prompt, 200)?.trim().to_string());
+        }
+
+        // Reduce (at least two summaries per prompt, so every round shrinks)
+        while summaries.len() > 1 {
+            let mut combined = Vec::new();
+            for chunk in chunk_by_budget(&summaries, self.summary_budget, 2) {
+                let prompt = format!(
+                    "Combine these partial summaries of {} into one summary:\n{}\n\nSummary:",
+                    label,
+                    chunk.join("\n\n")
+                );
+                combined.push(self.llm.generate(&prompt, 300)?.trim().to_string());
+            }
+            summaries = combined;
+        }
+
+        Ok(ChunkedSummary {
+            summary: summaries.pop().unwrap_or_default(),
+            memories: memories.len(),
+            chunks: chunk_count,
+        })
+    }
+
     /// Extract and store key facts from text
     pub fn extract_and_store(&mut self, text: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
         let prompt = format!(
@@ -393,12 +1087,50 @@ impl MemoryChat {
     pub fn brain_mut(&mut self) -> &mut Brain {
         &mut self.brain
     }
+
+    /// End the chat, handing back the brain
+    pub fn into_brain(self) -> Brain {
+        self.brain
+    }
 }
 
 const DEFAULT_SYSTEM_PROMPT: &str = r#"You are a helpful AI assistant with a memory system.
 When relevant memories are provided, use them to personalize your response.
 Be concise and directly answer the question. Do not continue the conversation or ask follow-up questions."#;
 
+const MEMORY_TOOLS_PROMPT: &str = r#"
+
+You can manage memories by adding lines at the end of your answer:
+STORE: <fact to remember>
+FORGET: <memory id in brackets, copied exactly>
+Only use FORGET when the user explicitly asks to forget something."#;
+
+/// Rough chars-per-token ratio for budgeting prompts
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Group texts into chunks of at most `budget` (estimated) tokens.
+/// A chunk is only closed once it holds `min_per_chunk` texts.
+fn chunk_by_budget(texts: &[String], budget: usize, min_per_chunk: usize) -> Vec<Vec<String>> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut current_tokens = 0;
+
+    for text in texts {
+        let tokens = text.len() / CHARS_PER_TOKEN + 1;
+        if current.len() >= min_per_chunk && current_tokens + tokens > budget {
+            chunks.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+        current.push(text.clone());
+        current_tokens += tokens;
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
 fn truncate(s: &str, max: usize) -> String {
     if s.len() <= max {
         s.to_string()
@@ -407,47 +1139,65 @@ fn truncate(s: &str, max: usize) -> String {
     }
 }
 
-/// Simple sync HTTP GET (for checking Ollama)
-fn reqwest_sync_get(url: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let output = Command::new("curl")
-        .args(["-s", "-o", "/dev/null", "-w", "%{http_code}", url])
-        .output()?;
-    
-    let code = String::from_utf8_lossy(&output.stdout);
-    if code.starts_with('2') {
-        Ok(())
-    } else {
-        Err("Request failed".into())
-    }
-}
-
 // ============ Auto-detect best provider ============
 
 /// Auto-detect the best available LLM provider
 pub fn auto_detect_provider() -> Box<dyn LlmProvider> {
     // 1. Check for Ollama (most common local option)
     if OllamaProvider::is_available() {
-        println!("🦙 Using Ollama");
+        eprintln!("🦙 Using Ollama");
         return Box::new(OllamaProvider::new("llama3.2"));
     }
 
     // 2. Check for MLX-LM
     if MlxLmProvider::is_available() {
-        println!("🍎 Using MLX-LM");
+        eprintln!("🍎 Using MLX-LM");
         return Box::new(MlxLmProvider::new("mlx-community/Llama-3.2-1B-Instruct-4bit"));
     }
 
     // 3. Check for OpenAI API key
     if let Some(provider) = OpenAIProvider::from_env("gpt-4o-mini") {
-        println!("🤖 Using OpenAI API");
+        eprintln!("🤖 Using OpenAI API");
         return Box::new(provider);
     }
 
     // 4. Fallback to echo
-    println!("⚠️ No LLM found, using echo mode");
+    eprintln!("⚠️ No LLM found, using echo mode");
     Box::new(EchoProvider)
 }
 
+/// Build the LLM provider selected in the config (`auto` falls back to detection)
+///
+/// `base_url` applies to ollama and OpenAI-compatible servers.
+pub fn provider_from_config(config: &crate::config::LlmConfig) -> Box<dyn LlmProvider> {
+    let model = config.model.as_deref();
+    let base_url = config.base_url.as_deref();
+    match config.provider.to_lowercase().as_str() {
+        "ollama" => {
+            let model = model.unwrap_or("llama3.2");
+            Box::new(match base_url {
+                Some(url) => OllamaProvider::with_url(model, url),
+                None => OllamaProvider::new(model),
+            })
+        }
+        "mlx" | "mlx-lm" => Box::new(MlxLmProvider::new(
+            model.unwrap_or("mlx-community/Llama-3.2-1B-Instruct-4bit"),
+        )),
+        "openai" => match OpenAIProvider::from_env(model.unwrap_or("gpt-4o-mini")) {
+            Some(provider) => match base_url {
+                Some(url) => Box::new(OpenAIProvider::with_base_url(&provider.model, &provider.api_key, url)),
+                None => Box::new(provider),
+            },
+            None => {
+                eprintln!("⚠️ OPENAI_API_KEY not set, using echo mode");
+                Box::new(EchoProvider)
+            }
+        },
+        "echo" => Box::new(EchoProvider),
+        _ => auto_detect_provider(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -458,4 +1208,496 @@ mod tests {
         let response = provider.generate("Hello world", 100).unwrap();
         assert!(response.contains("Hello world"));
     }
+
+    #[test]
+    fn test_default_generate_stream_matches_generate() {
+        let provider = EchoProvider;
+        let mut chunks = Vec::new();
+        let streamed = provider
+            .generate_stream("Hello world", 100, &mut |c| chunks.push(c.to_string()))
+            .unwrap();
+
+        assert!(!chunks.is_empty());
+        assert_eq!(chunks.concat(), streamed);
+        assert_eq!(streamed, provider.generate("Hello world", 100).unwrap());
+    }
+
+    /// Reader that returns at most 3 bytes per read, splitting lines and characters
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.0.len().min(buf.len()).min(3);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_read_line_stream_ollama_across_buffer_boundaries() {
+        let body = concat!(
+            "{\"response\":\"Hel\",\"done\":false}\n",
+            "{\"response\":\"lo, \",\"done\":false}\n",
+            "{\"response\":\"세계\",\"done\":false}\n",
+            "{\"response\":\"\",\"done\":true}\n",
+            "{\"response\":\"ignored\",\"done\":false}\n",
+        );
+        let mut chunks = Vec::new();
+        let text = read_line_stream(Trickle(body.as_bytes()), parse_ollama_line, &mut |c| chunks.push(c.to_string()))
+            .unwrap();
+
+        assert_eq!(text, "Hello, 세계");
+        assert_eq!(chunks, vec!["Hel", "lo, ", "세계"]);
+    }
+
+    #[test]
+    fn test_read_line_stream_openai_sse() {
+        let body = concat!(
+            ": keep-alive\n\n",
+            "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Rust \"}}]}\r\n\r\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"rocks\"}}]}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let mut chunks = Vec::new();
+        let text = read_line_stream(Trickle(body.as_bytes()), parse_openai_sse_line, &mut |c| chunks.push(c.to_string()))
+            .unwrap();
+
+        assert_eq!(text, "Rust rocks");
+        assert_eq!(chunks.concat(), text);
+    }
+
+    /// Serve one canned HTTP response on a local port
+    ///
+    /// Returns the base URL and a handle yielding the raw request.
+    fn mock_server(status: &str, body: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        use std::io::Write;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let status = status.to_string();
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            // Read headers, then the Content-Length body
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text
+                        .lines()
+                        .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length || n == 0 {
+                        break;
+                    }
+                }
+            }
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_ollama_provider_against_mock_server() {
+        let (url, server) = mock_server("200 OK", r#"{"model":"llama3.2","response":"Paris","done":true}"#);
+        let provider = OllamaProvider::with_url("llama3.2", &url);
+
+        let response = provider.generate("Capital of France?", 16).unwrap();
+        assert_eq!(response, "Paris");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /api/generate"));
+        assert!(request.contains("Capital of France?"));
+        assert!(request.contains("\"num_predict\":16"));
+    }
+
+    #[test]
+    fn test_ollama_provider_http_error_status() {
+        let (url, server) = mock_server("404 Not Found", r#"{"error":"model not found"}"#);
+        let provider = OllamaProvider::with_url("missing", &url);
+
+        let err = provider.generate("hi", 8).unwrap_err();
+        server.join().unwrap();
+        match err.downcast_ref::<LlmError>() {
+            Some(LlmError::Http { status, body }) => {
+                assert_eq!(*status, 404);
+                assert!(body.contains("model not found"));
+            }
+            other => panic!("expected HTTP error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ollama_provider_timeouts() {
+        let timeout = Duration::from_millis(200);
+
+        // Accepts connections (via the backlog) but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let provider = OllamaProvider::with_url("llama3.2", &url).with_timeout(timeout);
+        assert_eq!(provider.timeout(), timeout);
+
+        let started = std::time::Instant::now();
+        let err = provider.generate("hi", 8).unwrap_err();
+        assert!(matches!(err.downcast_ref::<LlmError>(), Some(LlmError::Timeout)), "got {}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        drop(listener);
+
+        // Nothing listening at all
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let provider = OllamaProvider::with_url("llama3.2", &format!("http://127.0.0.1:{}", port)).with_timeout(timeout);
+        let started = std::time::Instant::now();
+        assert!(provider.generate("hi", 8).is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    /// Mock LLM that always returns the same response
+    struct ScriptedProvider(String);
+
+    impl LlmProvider for ScriptedProvider {
+        fn generate(&self, _prompt: &str, _max_tokens: usize) -> Result<String, Box<dyn std::error::Error>> {
+            Ok(self.0.clone())
+        }
+
+        fn name(&self) -> &str {
+            "scripted"
+        }
+    }
+
+    /// Mock LLM that streams a fixed response a few bytes at a time
+    struct ChunkedProvider(String);
+
+    impl LlmProvider for ChunkedProvider {
+        fn generate(&self, _prompt: &str, _max_tokens: usize) -> Result<String, Box<dyn std::error::Error>> {
+            Ok(self.0.clone())
+        }
+
+        fn generate_stream(
+            &self,
+            _prompt: &str,
+            _max_tokens: usize,
+            on_chunk: &mut dyn FnMut(&str),
+        ) -> Result<String, Box<dyn std::error::Error>> {
+            for chunk in self.0.as_bytes().chunks(3) {
+                on_chunk(std::str::from_utf8(chunk).unwrap());
+            }
+            Ok(self.0.clone())
+        }
+
+        fn name(&self) -> &str {
+            "chunked"
+        }
+    }
+
+    #[test]
+    fn test_chat_stream_hides_directives_and_special_tokens() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let brain = Brain::with_embedder(dir.path().join("stream.db").to_str().unwrap(), embedder).unwrap();
+        let llm = ChunkedProvider(
+            "\nNoted, you like tea.\nSTORE: User likes green tea\nAnything else? <3<|eot_id|><|start_header_id|>user".to_string(),
+        );
+        let mut chat = MemoryChat::new(brain, Box::new(llm));
+
+        let mut streamed = String::new();
+        let response = chat.chat_stream("I like green tea", &mut |c| streamed.push_str(c)).unwrap();
+
+        assert_eq!(response, "Noted, you like tea.\nAnything else? <3\n\n📝 Stored: User likes green tea");
+        assert_eq!(streamed, response);
+        assert!(!streamed.contains("STORE:") && !streamed.contains("<|"));
+    }
+
+    /// Mock LLM that counts calls and records prompts
+    struct CountingProvider {
+        prompts: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl LlmProvider for CountingProvider {
+        fn generate(&self, prompt: &str, _max_tokens: usize) -> Result<String, Box<dyn std::error::Error>> {
+            let mut prompts = self.prompts.lock().unwrap();
+            prompts.push(prompt.to_string());
+            Ok(format!("summary {}", prompts.len()))
+        }
+
+        fn name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    #[test]
+    fn test_summarize_tag_map_reduce() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let mut brain = Brain::with_embedder(dir.path().join("sum.db").to_str().unwrap(), embedder).unwrap();
+        for i in 0..40 {
+            let item = crate::MemoryItem::new(&format!("Work note {}: shipped the quarterly report draft", i), None)
+                .with_tags(vec!["work".to_string()]);
+            brain.semantic.store(item).unwrap();
+        }
+        brain.semantic.store(crate::MemoryItem::new("Holiday plans for the beach", None)).unwrap();
+
+        let prompts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let llm = CountingProvider { prompts: prompts.clone() };
+        let mut chat = MemoryChat::new(brain, Box::new(llm)).with_summary_budget(100);
+
+        let summary = chat.summarize_tag("work").unwrap();
+        let prompts = prompts.lock().unwrap();
+
+        // Several map calls plus at least one reduce call
+        let map_calls = prompts.iter().filter(|p| p.starts_with("Summarize these memories")).count();
+        let reduce_calls = prompts.iter().filter(|p| p.starts_with("Combine these partial summaries")).count();
+        assert!(map_calls > 1);
+        assert!(reduce_calls >= 1);
+        assert_eq!(summary, format!("summary {}", prompts.len()));
+
+        // Every tagged memory was covered, the untagged one was not
+        let all = prompts.join("\n");
+        assert!((0..40).all(|i| all.contains(&format!("Work note {}:", i))));
+        assert!(!all.contains("Holiday plans"));
+    }
+
+    #[test]
+    fn test_memories_without_embeddings_enter_the_prompt_by_keyword() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let mut brain = Brain::with_embedder(dir.path().join("noemb.db").to_str().unwrap(), embedder).unwrap();
+        for content in ["My cat is named Miso", "The dentist appointment is on Friday"] {
+            let mut item = brain.store_typed(crate::MemoryItem::new(content, None), Some(crate::MemoryType::Semantic)).unwrap();
+            // As if the embedder had been down when it was stored
+            item.embedding = None;
+            brain.semantic.update(&item).unwrap();
+        }
+
+        let prompts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let llm = CountingProvider { prompts: prompts.clone() };
+        let mut chat = MemoryChat::new(brain, Box::new(llm));
+        chat.chat("What is my cat called?").unwrap();
+
+        let prompt = prompts.lock().unwrap()[0].clone();
+        assert!(prompt.contains("My cat is named Miso"));
+        assert!(!prompt.contains("dentist"));
+    }
+
+    #[test]
+    fn test_summarize_all_covers_only_the_newest_memories() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let mut brain = Brain::with_embedder(dir.path().join("all.db").to_str().unwrap(), embedder).unwrap();
+        let now = chrono::Utc::now();
+        for i in 0..30 {
+            let mut item = crate::MemoryItem::new(&format!("Journal entry {}: a quiet day", i), None);
+            item.created_at = now - chrono::Duration::minutes(30 - i);
+            brain.semantic.store(item).unwrap();
+        }
+
+        let prompts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let llm = CountingProvider { prompts: prompts.clone() };
+        let mut chat = MemoryChat::new(brain, Box::new(llm)).with_summary_limit(10);
+        chat.summarize_all().unwrap();
+
+        let all = prompts.lock().unwrap().join("\n");
+        assert!((20..30).all(|i| all.contains(&format!("Journal entry {}:", i))));
+        assert!((0..20).all(|i| !all.contains(&format!("Journal entry {}:", i))));
+    }
+
+    #[test]
+    fn test_summarize_tag_skips_near_miss_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let mut brain = Brain::with_embedder(dir.path().join("tag.db").to_str().unwrap(), embedder).unwrap();
+        for (content, tag) in [("Sprint review on Monday", "Work"), ("Math homework is due", "homework"), ("Router firmware updated", "network")] {
+            brain.semantic.store(crate::MemoryItem::new(content, None).with_tags(vec![tag.to_string()])).unwrap();
+        }
+
+        let prompts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let llm = CountingProvider { prompts: prompts.clone() };
+        let mut chat = MemoryChat::new(brain, Box::new(llm));
+        chat.summarize_tag("work").unwrap();
+
+        let all = prompts.lock().unwrap().join("\n");
+        assert!(all.contains("Sprint review on Monday"));
+        assert!(!all.contains("homework"));
+        assert!(!all.contains("Router firmware"));
+    }
+
+    #[test]
+    fn test_summarize_topic_map_reduce_reports_coverage() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let mut brain = Brain::with_embedder(dir.path().join("topic.db").to_str().unwrap(), embedder).unwrap();
+        for i in 0..24 {
+            brain.semantic.store(crate::MemoryItem::new(&format!("Rust tip {}: borrow instead of cloning", i), None)).unwrap();
+        }
+
+        let prompts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let llm = CountingProvider { prompts: prompts.clone() };
+        let mut chat = MemoryChat::new(brain, Box::new(llm));
+
+        let report = chat.summarize_memories_mapreduce("rust", 5).unwrap();
+        let prompts = prompts.lock().unwrap();
+        assert_eq!(report.memories, 24);
+        assert_eq!(report.chunks, 5);
+
+        // One map prompt per chunk, none over the per-chunk cap
+        let map_prompts: Vec<&String> = prompts.iter().filter(|p| p.starts_with("Summarize these memories")).collect();
+        assert_eq!(map_prompts.len(), 5);
+        assert!(map_prompts.iter().all(|p| p.matches("Rust tip").count() <= 5));
+
+        // The final combine prompt carries every chunk's output
+        let last = prompts.last().unwrap();
+        assert!(last.starts_with("Combine these partial summaries"));
+        assert!((1..=5).all(|i| last.contains(&format!("summary {}", i))));
+        assert_eq!(report.summary, format!("summary {}", prompts.len()));
+    }
+
+    #[test]
+    fn test_chat_prompt_includes_previous_turn() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let brain = Brain::with_embedder(dir.path().join("history.db").to_str().unwrap(), embedder).unwrap();
+
+        let prompts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let llm = CountingProvider { prompts: prompts.clone() };
+        let mut chat = MemoryChat::new(brain, Box::new(llm)).with_memory_tools(false);
+
+        // Replayed turns, not the "User asked: ..." episodic memory of them
+        let user_turn = "user<|end_header_id|>\n\nWhich crate should I use for HTTP?<|eot_id|>";
+        let assistant_turn = "assistant<|end_header_id|>\n\nsummary 1<|eot_id|>";
+
+        chat.chat("Which crate should I use for HTTP?").unwrap();
+        chat.chat("and what about that?").unwrap();
+        {
+            let prompts = prompts.lock().unwrap();
+            assert!(!prompts[0].contains(assistant_turn));
+            assert!(prompts[1].contains(user_turn));
+            assert!(prompts[1].contains(assistant_turn));
+            // The current question comes after the replayed turn
+            assert!(prompts[1].find(assistant_turn).unwrap() < prompts[1].find("and what about that?").unwrap());
+        }
+
+        chat.clear_history();
+        chat.chat("fresh start").unwrap();
+        assert!(!prompts.lock().unwrap()[2].contains(assistant_turn));
+    }
+
+    #[test]
+    fn test_chat_history_respects_limit_and_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let brain = Brain::with_embedder(dir.path().join("budget.db").to_str().unwrap(), embedder).unwrap();
+
+        let prompts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let llm = CountingProvider { prompts: prompts.clone() };
+        let mut chat = MemoryChat::new(brain, Box::new(llm))
+            .with_memory_tools(false)
+            .with_history_limit(2);
+
+        for i in 0..4 {
+            chat.chat(&format!("question {}", i)).unwrap();
+        }
+        assert_eq!(chat.history().len(), 2);
+        assert_eq!(chat.history()[0].0, "question 2");
+
+        // A tiny budget leaves no room for old turns
+        let mut chat = chat.with_prompt_budget(1);
+        chat.chat("question 4").unwrap();
+        let last = prompts.lock().unwrap().last().unwrap().clone();
+        assert!(!last.contains("user<|end_header_id|>\n\nquestion 3<|eot_id|>"));
+        assert!(last.contains("user<|end_header_id|>\n\nquestion 4<|eot_id|>"));
+    }
+
+    /// Questions (ending in '?') and statements land on orthogonal axes
+    struct AxisEmbedder;
+
+    impl crate::Embedder for AxisEmbedder {
+        fn embed(&self, text: &str) -> Vec<f32> {
+            if text.trim_end().ends_with('?') {
+                vec![0.0, 1.0]
+            } else {
+                vec![1.0, 0.0]
+            }
+        }
+
+        fn dimension(&self) -> usize {
+            2
+        }
+    }
+
+    #[test]
+    fn test_chat_omits_memories_below_context_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut brain = Brain::with_embedder(dir.path().join("gate.db").to_str().unwrap(), std::sync::Arc::new(AxisEmbedder)).unwrap();
+        brain
+            .store_typed(crate::MemoryItem::new("apple pie recipe from grandma", None), Some(crate::MemoryType::Semantic))
+            .unwrap();
+
+        let prompts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let llm = CountingProvider { prompts: prompts.clone() };
+        let mut chat = MemoryChat::new(brain, Box::new(llm)).with_memory_tools(false);
+
+        // "apple" is a keyword hit, but the question is orthogonal to the memory
+        chat.chat("apple stock price?").unwrap();
+        {
+            let prompts = prompts.lock().unwrap();
+            assert!(!prompts[0].contains("apple pie"));
+            assert!(!prompts[0].contains("Relevant memories"));
+            assert!(!prompts[0].contains("\n- "));
+        }
+
+        // Without the gate the same recall is injected
+        let mut chat = chat.with_context_threshold(-1.0);
+        chat.chat("apple stock price?").unwrap();
+        let last = prompts.lock().unwrap().last().unwrap().clone();
+        assert!(last.contains("- apple pie recipe from grandma"));
+    }
+
+    #[test]
+    fn test_parse_directives_requires_full_id() {
+        let id = uuid::Uuid::new_v4();
+        let response = format!("Done.\nSTORE: Likes tea\nFORGET: {}\nFORGET: {}", id, &id.to_string()[..8]);
+        let (text, directives) = parse_directives(&response);
+
+        assert_eq!(text, "Done.");
+        assert_eq!(directives[0], MemoryDirective::Store("Likes tea".to_string()));
+        assert_eq!(directives[1], MemoryDirective::Forget(id));
+        assert!(matches!(directives[2], MemoryDirective::InvalidForget(_)));
+    }
+
+    #[test]
+    fn test_parse_directives_accepts_bracketed_id() {
+        let id = uuid::Uuid::new_v4();
+        let (_, directives) = parse_directives(&format!("Forgotten.\nFORGET: [{}]\nFORGET: [{}]", id, &id.to_string()[..8]));
+
+        assert_eq!(directives[0], MemoryDirective::Forget(id));
+        assert!(matches!(directives[1], MemoryDirective::InvalidForget(_)));
+    }
+
+    #[test]
+    fn test_chat_store_directive_persists_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let brain = Brain::with_embedder(dir.path().join("chat.db").to_str().unwrap(), embedder).unwrap();
+        let llm = ScriptedProvider("Got it!\nSTORE: The user's favorite color is teal".to_string());
+        let mut chat = MemoryChat::new(brain, Box::new(llm));
+
+        let response = chat.chat("Remember that my favorite color is teal").unwrap();
+        assert!(response.starts_with("Got it!"));
+        assert!(response.contains("📝 Stored: The user's favorite color is teal"));
+        assert!(!response.contains("STORE:"));
+
+        let stored = chat.brain().semantic.search("favorite color is teal", 5).unwrap();
+        assert!(stored.iter().any(|m| m.content == "The user's favorite color is teal"));
+    }
 }
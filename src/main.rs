@@ -2,7 +2,7 @@
 //!
 //! Human-inspired memory system with semantic search.
 
-use memory_brain::{Brain, GloVeEmbedder, HttpEmbedder, VecDbStorage, MemoryItem, MemoryType, MemoryChat, auto_detect_provider};
+use memory_brain::{Brain, Config, Embedder, Emotion, TfIdfEmbedder, TFIDF_DIMENSION, VecDbStorage, MemoryItem, MemoryType, MemoryChat, LlmProvider, SearchOptions, provider_from_config};
 use std::env;
 use std::io::{self, Write};
 use std::sync::Arc;
@@ -10,6 +10,12 @@ use std::cell::RefCell;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Recall results longer than this (chars) print a snippet instead
+const SNIPPET_MIN_CHARS: usize = 200;
+
+/// Characters kept on each side of the matched term in a snippet
+const SNIPPET_RADIUS: usize = 80;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
 
@@ -18,64 +24,46 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    // Check for global flags
-    let quiet = args.contains(&"--quiet".to_string()) || args.contains(&"-q".to_string());
+    // Check for global flags (--json implies --quiet: only JSON on stdout)
+    let json = args.contains(&"--json".to_string());
+    let quiet = json || args.contains(&"--quiet".to_string()) || args.contains(&"-q".to_string());
     
-    // Remove only global flags (-q, --quiet), keep command-specific flags
+    // Remove only global flags (-q, --quiet, --json), keep command-specific flags
     let args: Vec<String> = args.into_iter()
-        .filter(|a| a != "-q" && a != "--quiet")
+        .filter(|a| a != "-q" && a != "--quiet" && a != "--json")
         .collect();
 
-    let db_path = dirs::data_local_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("memory-brain")
-        .join("coredb");
+    // Load config once: env > ~/.memory-brain/config.toml > defaults
+    // (command flags override on top)
+    let config = Config::load()?;
+    let db_path = config.db_path.clone();
 
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
 
     // Initialize embedder
-    // Priority: 1) EMBEDDING_SERVER_URL env, 2) localhost:3200 if running, 3) GloVe, 4) test
-    let embedding_server_url = env::var("EMBEDDING_SERVER_URL")
-        .unwrap_or_else(|_| "http://localhost:3200".to_string());
-    
-    let http_embedder = HttpEmbedder::new(&embedding_server_url);
-    
-    let mut brain = if http_embedder.health_check() {
-        // BGE-M3 server available - use it!
-        if !quiet { println!("🚀 Using BGE-M3 server ({})", embedding_server_url); }
-        Brain::with_embedder(db_path.to_str().unwrap(), Arc::new(http_embedder))?
-    } else {
-        // Fall back to GloVe or test embedder
-        let glove_path = dirs::data_local_dir()
-            .unwrap_or_else(|| std::path::PathBuf::from("."))
-            .join("memory-brain")
-            .join("glove.6B.100d.txt");
-        
-        if glove_path.exists() {
-            match GloVeEmbedder::load(&glove_path, Some(50000)) {
-                Ok(embedder) => {
-                    if !quiet { println!("📚 GloVe embeddings loaded"); }
-                    Brain::with_embedder(db_path.to_str().unwrap(), Arc::new(embedder))?
-                }
-                Err(e) => {
-                    if !quiet { eprintln!("⚠️ GloVe load failed: {}", e); }
-                    let embedder = GloVeEmbedder::test_embedder();
-                    Brain::with_embedder(db_path.to_str().unwrap(), Arc::new(embedder))?
-                }
-            }
-        } else {
-            let embedder = GloVeEmbedder::test_embedder();
-            if !quiet {
-                println!("🧪 Using test embedder (start embedding server for better results)");
-            }
-            Brain::with_embedder(db_path.to_str().unwrap(), Arc::new(embedder))?
-        }
+    // Priority (provider = auto): 1) embedding server if running, 2) GloVe, 3) test
+    let configured = memory_brain::embedder_from_config(&config, quiet);
+    // API embeddings are cached on disk between runs, saved on every exit path
+    let _embedding_cache = EmbeddingCacheGuard {
+        cache: configured.cache,
+        path: config.embedding.cache_path.clone(),
     };
+    let mut brain = Brain::with_config(&config, configured.embedder)?;
+
+    // Vectors of different sizes can't be compared; warn even in quiet mode
+    let reembedding = matches!(args.get(1).map(|s| s.as_str()), Some("rebuild") | Some("reindex"))
+        && args.iter().any(|a| a == "--reembed");
+    if let Err(e) = brain.check_embedding_dim() {
+        if !reembedding {
+            eprintln!("⚠️ {}", e);
+            eprintln!("   Re-embed with the current embedder: memory-brain reindex --reembed");
+        }
+    }
 
-    // Auto-rebuild indexes for fast search (O(1) keyword lookup)
-    let rebuild_stats = brain.rebuild_indexes()?;
+    // Load persisted indexes for fast search (O(1) keyword lookup), rebuilding if stale
+    let rebuild_stats = brain.load_or_rebuild_indexes()?;
     if !quiet && rebuild_stats.episodic_count + rebuild_stats.semantic_count > 0 {
         println!("🔍 Index loaded: {} memories, {} keywords", 
             rebuild_stats.episodic_count + rebuild_stats.semantic_count + rebuild_stats.procedural_count,
@@ -84,19 +72,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     match args.get(1).map(|s| s.as_str()) {
         Some("store") | Some("s") | Some("add") | Some("a") => {
-            cmd_store(&mut brain, &args[2..], quiet)?;
+            cmd_store(&mut brain, &config, &args[2..], quiet)?;
         }
 
         Some("recall") | Some("r") | Some("find") | Some("f") => {
-            cmd_recall(&mut brain, &args[2..], quiet)?;
+            cmd_recall(&mut brain, &config, &args[2..], quiet, json)?;
         }
 
         Some("search") | Some("sem") => {
-            cmd_semantic_search(&mut brain, &args[2..], quiet)?;
+            cmd_semantic_search(&mut brain, &config, &args[2..], quiet, json)?;
         }
 
         Some("list") | Some("ls") | Some("l") => {
-            cmd_list(&brain, &args[2..], quiet)?;
+            cmd_list(&brain, &args[2..], quiet, json)?;
         }
 
         Some("show") | Some("cat") => {
@@ -113,7 +101,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         Some("dream") => {
-            cmd_dream(&mut brain, quiet)?;
+            cmd_dream(&mut brain, &args[2..], quiet)?;
         }
 
         Some("map") | Some("mindmap") => {
@@ -129,15 +117,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         Some("forget") | Some("forgetting") => {
-            cmd_forgetting(&brain, quiet)?;
+            cmd_forgetting(&mut brain, &args[2..], quiet)?;
+        }
+
+        Some("review") => {
+            cmd_review(&brain, &args[2..], quiet, json)?;
+        }
+
+        Some("onthisday") | Some("otd") => {
+            cmd_on_this_day(&brain, &args[2..], quiet, json)?;
         }
 
         Some("patterns") => {
-            cmd_patterns(&brain, quiet)?;
+            cmd_patterns(&brain, &args[2..], quiet)?;
         }
 
         Some("stats") | Some("status") | Some("info") => {
-            cmd_stats(&brain, quiet)?;
+            cmd_stats(&brain, &config, quiet, json)?;
         }
 
         Some("audit") => {
@@ -155,32 +151,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         Some("tui") | Some("dashboard") | Some("ui") => {
-            // Load memories for TUI from semantic memory
-            let memory_data: Vec<(String, String, String)> = if let Ok(items) = brain.semantic.search("", 100) {
-                items.iter().map(|m| {
-                    (
-                        m.id.to_string(),
-                        m.content.clone(),
-                        m.tags.join(", "),
-                    )
-                }).collect()
-            } else {
-                Vec::new()
-            };
-            
-            memory_brain::tui::run_tui(memory_data)?;
+            memory_brain::tui::run_tui(&mut brain)?;
         }
 
         Some("rebuild") | Some("reindex") => {
-            cmd_rebuild(&mut brain, quiet)?;
+            cmd_rebuild(&mut brain, &config, &args[2..], quiet)?;
         }
 
         Some("merge") | Some("dedup") => {
-            cmd_merge(&mut brain, &args[2..], quiet)?;
+            cmd_merge(&mut brain, &config, &args[2..], quiet)?;
+        }
+
+        Some("compress") => {
+            cmd_compress(&brain, &args[2..], quiet, json)?;
         }
 
         Some("bench") | Some("benchmark") => {
-            cmd_bench(quiet)?;
+            cmd_bench(&brain, &args[2..], quiet)?;
         }
 
         Some("watch") | Some("monitor") => {
@@ -199,32 +186,44 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             cmd_import(&mut brain, &args[2..], quiet)?;
         }
 
+        Some("snapshot") | Some("backup") => {
+            cmd_snapshot(&brain, &args[2..], quiet)?;
+        }
+
+        Some("restore") => {
+            cmd_restore(&mut brain, &args[2..], quiet)?;
+        }
+
+        Some("embeddings") | Some("emb") => {
+            cmd_embeddings(&mut brain, &args[2..], quiet)?;
+        }
+
         Some("interactive") | Some("i") | Some("repl") => {
-            cmd_interactive(&mut brain)?;
+            cmd_interactive(&mut brain, &config)?;
         }
 
         Some("chat") | Some("c") => {
-            cmd_chat(brain, &args[2..], quiet)?;
+            brain = cmd_chat(brain, &config, &args[2..], quiet)?;
         }
 
         Some("ask") => {
-            cmd_ask(brain, &args[2..], quiet)?;
+            brain = cmd_ask(brain, &config, &args[2..], quiet)?;
         }
 
         Some("learn") => {
-            cmd_learn(brain, &args[2..], quiet)?;
+            brain = cmd_learn(brain, &config, &args[2..], quiet)?;
         }
 
         Some("summarize") | Some("sum") => {
-            cmd_summarize(brain, &args[2..], quiet)?;
+            brain = cmd_summarize(brain, &config, &args[2..], quiet)?;
         }
 
         Some("sam") => {
-            cmd_sam(&args[2..], db_path.to_str().unwrap_or("."), quiet)?;
+            cmd_sam(&args[2..], config.db_path_str(), quiet)?;
         }
 
         Some("serve") | Some("server") => {
-            return cmd_serve(&args[2..]);
+            return cmd_serve(config, &args[2..]);
         }
 
         Some("version") | Some("-v") | Some("--version") => {
@@ -236,7 +235,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         Some("visual") | Some("vis") | Some("img") => {
-            cmd_visual(&args[2..], quiet)?;
+            cmd_visual(&mut brain, &config, &args[2..], quiet)?;
         }
 
         Some("describe") | Some("vlm") => {
@@ -262,14 +261,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    Ok(())
+    // Persist indexes now so a failed write is reported, not lost in Drop
+    brain.close()
+}
+
+/// Saves the API embedding cache when dropped, so `main`'s early returns
+/// and errors keep what the run embedded
+struct EmbeddingCacheGuard {
+    cache: Option<Arc<memory_brain::CachedEmbedder<memory_brain::ApiEmbedder>>>,
+    path: std::path::PathBuf,
+}
+
+impl Drop for EmbeddingCacheGuard {
+    fn drop(&mut self) {
+        // Save only when this run embedded something new
+        if let Some(ref cache) = self.cache {
+            if cache.stats().misses > 0 {
+                if let Err(e) = cache.persist_to_disk(&self.path) {
+                    eprintln!("⚠️ Embedding cache not saved: {}", e);
+                }
+            }
+        }
+    }
 }
 
 // ============ Commands ============
 
-fn cmd_store(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_store(brain: &mut Brain, config: &Config, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
     if args.is_empty() {
-        eprintln!("Usage: memory-brain store <text> [--type semantic|episodic|procedural] [--tags tag1,tag2]");
+        eprintln!("Usage: memory-brain store <text> [--type semantic|episodic|procedural] [--tags tag1,tag2] [--emotion positive|negative|surprise|neutral] [--ttl 7d] [--dedup]");
         return Ok(());
     }
 
@@ -277,10 +297,31 @@ fn cmd_store(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<
     let mut memory_type = MemoryType::Semantic;
     let mut tags: Vec<String> = Vec::new();
     let mut content_parts: Vec<&str> = Vec::new();
+    let mut dedup = false;
+    let mut emotion: Option<Emotion> = None;
+    let mut ttl: Option<chrono::Duration> = None;
 
     let mut i = 0;
     while i < args.len() {
         match args[i].as_str() {
+            "--ttl" => {
+                if i + 1 < args.len() {
+                    ttl = Some(memory_brain::parse_duration_spec(&args[i + 1])?);
+                    i += 2;
+                    continue;
+                }
+            }
+            "--emotion" | "-e" => {
+                if i + 1 < args.len() {
+                    emotion = Emotion::parse(&args[i + 1]);
+                    if emotion.is_none() {
+                        eprintln!("❌ Unknown emotion '{}' (positive|negative|surprise|neutral)", args[i + 1]);
+                        return Ok(());
+                    }
+                    i += 2;
+                    continue;
+                }
+            }
             "--type" | "-t" => {
                 if i + 1 < args.len() {
                     memory_type = match args[i + 1].to_lowercase().as_str() {
@@ -300,6 +341,9 @@ fn cmd_store(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<
                     continue;
                 }
             }
+            "--dedup" | "--dedup-on-store" => {
+                dedup = true;
+            }
             s if s.starts_with("--") => {
                 // Skip unknown flags
                 i += 1;
@@ -318,26 +362,36 @@ fn cmd_store(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<
     }
 
     // Generate embedding and store
-    let embedding = brain.embedder().embed(&content);
+    let embedding = brain.embedder().try_embed_document(&content).ok();
+    let emotion = emotion.unwrap_or_else(|| memory_brain::detect_emotion(&content));
     let mut item = MemoryItem::new(&content, None)
         .with_type(memory_type.clone())
-        .with_tags(tags.clone());
-    item.embedding = Some(embedding.clone());
+        .with_tags(tags.clone())
+        .with_emotion(emotion);
+    item.embedding = embedding.clone();
+    if let Some(ttl) = ttl {
+        item = item.with_ttl(ttl);
+    }
 
-    // Store in CoreDB (legacy)
-    match memory_type {
-        MemoryType::Episodic => brain.episodic.store(item.clone())?,
-        MemoryType::Semantic => brain.semantic.store(item.clone())?,
-        MemoryType::Procedural => brain.procedural.store(item.clone())?,
-        _ => brain.semantic.store(item.clone())?,
+    if dedup {
+        let (id, inserted) = brain.store_or_update(item.clone())?;
+        if !inserted {
+            if !quiet {
+                println!("♻️  Already stored ({}), strengthened: {}", &id.to_string()[..8], truncate(&content, 50));
+            }
+            return Ok(());
+        }
+    } else {
+        // Indexed like every other write, so the sidecar stays in sync
+        let mut report = brain.store_batch(vec![item.clone()]);
+        if let Some((_, reason)) = report.failed.pop() {
+            return Err(reason.into());
+        }
     }
 
     // 🚀 Also store in CoreVecDB if available
-    let vecdb_url = env::var("COREVECDB_URL")
-        .unwrap_or_else(|_| "http://localhost:3100".to_string());
-    
-    if let Ok(vecdb) = VecDbStorage::new(&vecdb_url, Some("memories")) {
-        match vecdb.store(&item, &embedding) {
+    if let (Some(embedding), Ok(vecdb)) = (&embedding, VecDbStorage::new(&config.vecdb_url, Some("memories"))) {
+        match vecdb.store(&item, embedding) {
             Ok(vec_id) => {
                 if !quiet {
                     print!("📦 VecDB ID: {} ", vec_id);
@@ -360,6 +414,9 @@ fn cmd_store(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<
             print!(" [{}]", tags.join(", "));
         }
         println!(": {}", truncate(&content, 50));
+        if let Some(at) = item.expires_at {
+            println!("⏳ Expires: {}", at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+        }
     }
 
     Ok(())
@@ -373,6 +430,7 @@ fn cmd_batch(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<
     // Parse args
     let mut input_file: Option<String> = None;
     let mut tags: Vec<String> = Vec::new();
+    let mut dedup = false;
 
     let mut i = 0;
     while i < args.len() {
@@ -391,6 +449,9 @@ fn cmd_batch(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<
                     continue;
                 }
             }
+            "--dedup" | "--dedup-on-store" => {
+                dedup = true;
+            }
             _ => {}
         }
         i += 1;
@@ -398,6 +459,7 @@ fn cmd_batch(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<
 
     let start = Instant::now();
     let mut count = 0;
+    let mut duplicates = 0;
     let mut errors = 0;
 
     // Read from file or stdin
@@ -416,27 +478,48 @@ fn cmd_batch(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<
         return Ok(());
     }
 
-    // Generate embeddings in batch (reserved for batch embedding optimization)
-    let _texts: Vec<&str> = lines.iter().map(|s| s.as_str()).collect();
-    
-    // Store each memory
-    for content in &lines {
-        let embedding = brain.embedder().embed(content);
+    // Embed everything up front; repeated lines hit the cache
+    let texts: Vec<&str> = lines.iter().map(|s| s.as_str()).collect();
+    let processor = memory_brain::BatchProcessor::new(brain.embedder().clone(), lines.len(), 64);
+    let embeddings = processor.process_batch(&texts, |_, embedding| embedding);
+
+    let items: Vec<MemoryItem> = lines.iter().zip(embeddings).map(|(content, embedding)| {
         let mut item = MemoryItem::new(content, None)
             .with_type(MemoryType::Semantic)
             .with_tags(tags.clone());
         item.embedding = Some(embedding);
-
-        match brain.semantic.store(item) {
-            Ok(_) => {
-                count += 1;
-                memory_brain::audit::log_store(content, &tags);
-            }
-            Err(e) => {
-                errors += 1;
-                if !quiet { eprintln!("❌ Error storing: {}", e); }
+        item
+    }).collect();
+
+    if dedup {
+        // Store each memory unless the same content is already stored
+        for item in items {
+            let content = item.content.clone();
+            match brain.store_or_update(item) {
+                Ok((_, false)) => duplicates += 1,
+                Ok((_, true)) => {
+                    count += 1;
+                    memory_brain::audit::log_store(&content, &tags);
+                }
+                Err(e) => {
+                    errors += 1;
+                    if !quiet { eprintln!("❌ Error storing: {}", e); }
+                }
             }
         }
+    } else {
+        // Failures are reported; everything else is stored and indexed
+        let contents: Vec<(uuid::Uuid, String)> = items.iter().map(|i| (i.id, i.content.clone())).collect();
+        let report = brain.store_batch(items);
+        let failed = report.failed_ids();
+        for (_, content) in contents.iter().filter(|(id, _)| !failed.contains(id)) {
+            memory_brain::audit::log_store(content, &tags);
+        }
+        for (item, reason) in &report.failed {
+            if !quiet { eprintln!("❌ Error storing \"{}\": {}", truncate(&item.content, 40), reason); }
+        }
+        count = report.stored;
+        errors = report.failed.len();
     }
 
     let elapsed = start.elapsed();
@@ -444,31 +527,80 @@ fn cmd_batch(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<
         println!("✅ Batch complete: {} stored, {} errors in {:.2}s ({:.0} items/sec)",
             count, errors, elapsed.as_secs_f64(),
             count as f64 / elapsed.as_secs_f64());
+        if dedup {
+            println!("   ♻️  {} duplicates strengthened instead of stored", duplicates);
+        }
+        println!("   {}", processor.stats());
     }
 
     Ok(())
 }
 
-fn cmd_recall(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+/// `recall <id> --associative`: memories reached by spreading activation
+fn cmd_recall_associative(brain: &Brain, id_prefix: &str, depth: usize, limit: usize, quiet: bool, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(seed) = brain.get_by_id_prefix(id_prefix)? else {
+        eprintln!("❌ No memory found with id: {}", id_prefix);
+        return Ok(());
+    };
+
+    let mut activated: Vec<(MemoryItem, f32)> = brain
+        .spreading_activation(seed.id, depth, memory_brain::DEFAULT_SPREAD_DECAY)
+        .into_iter()
+        .filter_map(|(id, score)| brain.get_by_id(&id).ok().flatten().map(|item| (item, score)))
+        .collect();
+    activated.truncate(limit);
+
+    if json {
+        println!("{}", memory_brain::cli_json::search_results_to_json(&activated)?);
+        return Ok(());
+    }
+
+    if !quiet {
+        println!("🕸️ Activated from \"{}\" ({} hops):\n", truncate(&seed.content, 50), depth);
+    }
+    if activated.is_empty() && !quiet {
+        println!("   (no associated memories)");
+    }
+    for (i, (mem, score)) in activated.iter().enumerate() {
+        println!("{}. [{}] {} (activation {:.2})", i + 1, type_emoji(&mem.memory_type), mem.content, score);
+        println!("   #{}", &mem.id.to_string()[..8]);
+    }
+    Ok(())
+}
+
+fn cmd_recall(brain: &mut Brain, config: &Config, args: &[String], quiet: bool, json: bool) -> Result<(), Box<dyn std::error::Error>> {
     if args.is_empty() {
         eprintln!("Usage: memory-brain recall <query> [options]");
         eprintln!("Options:");
-        eprintln!("  --limit N, -n N    Max results (default: 5)");
+        eprintln!("  --limit N, -n N    Max results (default: {})", config.thresholds.recall_limit);
         eprintln!("  --tag TAG          Filter by tag");
         eprintln!("  --regex            Use regex matching");
         eprintln!("  --fuzzy            Fuzzy search (typo tolerant)");
+        eprintln!("  --phrase \"TEXT\"    Exact phrase (adjacent words, in order)");
         eprintln!("  --type TYPE        Filter by type (semantic/episodic/procedural)");
         eprintln!("  --vecdb            Use CoreVecDB vector search (default: auto)");
         eprintln!("  --no-vecdb         Disable CoreVecDB search");
+        eprintln!("  --diverse[=L]      MMR re-ranking for diverse results (L: relevance weight, default: {})", memory_brain::DEFAULT_MMR_LAMBDA);
+        eprintln!("  --weights W        Ranking weights, e.g. sim=0.7,strength=0.2,recency=0.1");
+        eprintln!("  --since WHEN       Only memories created after WHEN (YYYY-MM-DD, 7d, 24h)");
+        eprintln!("  --until WHEN       Only memories created before WHEN");
+        eprintln!("  --associative      Treat the query as a memory id and spread activation from it");
+        eprintln!("  --depth N          Hops for --associative (default: 2)");
         return Ok(());
     }
 
-    let mut limit = 5;
+    let mut limit = config.thresholds.recall_limit;
+    let mut associative = false;
+    let mut depth = 2;
     let mut tag_filter: Option<String> = None;
     let mut type_filter: Option<MemoryType> = None;
     let mut use_regex = false;
     let mut use_fuzzy = false;
+    let mut phrase: Option<String> = None;
     let mut use_vecdb: Option<bool> = None;  // None = auto (try if available)
+    let mut diverse: Option<f32> = None;  // MMR lambda
+    let mut since: Option<chrono::DateTime<chrono::Utc>> = None;
+    let mut until: Option<chrono::DateTime<chrono::Utc>> = None;
     let mut query_parts: Vec<&str> = Vec::new();
 
     let mut i = 0;
@@ -476,7 +608,7 @@ fn cmd_recall(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
         match args[i].as_str() {
             "--limit" | "-n" => {
                 if i + 1 < args.len() {
-                    limit = args[i + 1].parse().unwrap_or(5);
+                    limit = args[i + 1].parse().unwrap_or(config.thresholds.recall_limit);
                     i += 2;
                     continue;
                 }
@@ -510,6 +642,30 @@ fn cmd_recall(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
                 i += 1;
                 continue;
             }
+            "--associative" | "-a" => {
+                associative = true;
+                i += 1;
+                continue;
+            }
+            "--phrase" | "-p" => {
+                if i + 1 < args.len() {
+                    phrase = Some(args[i + 1].clone());
+                    i += 2;
+                    continue;
+                }
+            }
+            s if s.starts_with("--phrase=") => {
+                phrase = Some(s.trim_start_matches("--phrase=").to_string());
+                i += 1;
+                continue;
+            }
+            "--depth" => {
+                if i + 1 < args.len() {
+                    depth = args[i + 1].parse().unwrap_or(2);
+                    i += 2;
+                    continue;
+                }
+            }
             "--vecdb" => {
                 use_vecdb = Some(true);
                 i += 1;
@@ -520,6 +676,52 @@ fn cmd_recall(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
                 i += 1;
                 continue;
             }
+            "--diverse" | "-d" => {
+                diverse = Some(memory_brain::DEFAULT_MMR_LAMBDA);
+                i += 1;
+                continue;
+            }
+            "--weights" => {
+                if i + 1 < args.len() {
+                    brain.set_recall_weights(memory_brain::RecallWeights::parse(&args[i + 1])?);
+                    i += 2;
+                    continue;
+                }
+            }
+            s if s.starts_with("--weights=") => {
+                brain.set_recall_weights(memory_brain::RecallWeights::parse(s.trim_start_matches("--weights="))?);
+                i += 1;
+                continue;
+            }
+            "--since" => {
+                if i + 1 < args.len() {
+                    since = Some(memory_brain::parse_time_spec(&args[i + 1])?);
+                    i += 2;
+                    continue;
+                }
+            }
+            "--until" => {
+                if i + 1 < args.len() {
+                    until = Some(memory_brain::parse_time_spec(&args[i + 1])?);
+                    i += 2;
+                    continue;
+                }
+            }
+            s if s.starts_with("--since=") => {
+                since = Some(memory_brain::parse_time_spec(s.trim_start_matches("--since="))?);
+                i += 1;
+                continue;
+            }
+            s if s.starts_with("--until=") => {
+                until = Some(memory_brain::parse_time_spec(s.trim_start_matches("--until="))?);
+                i += 1;
+                continue;
+            }
+            s if s.starts_with("--diverse=") => {
+                diverse = Some(s.trim_start_matches("--diverse=").parse().unwrap_or(memory_brain::DEFAULT_MMR_LAMBDA));
+                i += 1;
+                continue;
+            }
             s if s.starts_with("--tag=") => {
                 tag_filter = Some(s.trim_start_matches("--tag=").to_string());
                 i += 1;
@@ -541,32 +743,81 @@ fn cmd_recall(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
         i += 1;
     }
 
-    let query = query_parts.join(" ");
-    
-    // Get more results initially for filtering
-    let fetch_limit = if tag_filter.is_some() || type_filter.is_some() || use_regex || use_fuzzy {
-        limit * 10
+    let mut query = query_parts.join(" ");
+    if query.is_empty() {
+        if let Some(ref phrase) = phrase {
+            query = phrase.clone();
+        }
+    }
+
+    if associative {
+        return cmd_recall_associative(brain, &query, depth, limit, quiet, json);
+    }
+
+    // `-word`/`+word` filters, parsed once and applied on every path below
+    // (a regex is matched as written)
+    let terms = if use_regex {
+        memory_brain::QueryTerms { text: query.clone(), ..Default::default() }
+    } else {
+        memory_brain::QueryTerms::parse(&query)
+    };
+    if !terms.ignored.is_empty() && !quiet {
+        eprintln!("⚠️ Ignoring filter words with nothing to match: {}", terms.ignored.join(", "));
+    }
+
+    let opts = SearchOptions {
+        regex: if use_regex && !query.is_empty() { Some(query.clone()) } else { None },
+        tags: tag_filter.iter().cloned().collect(),
+        memory_type: type_filter.clone(),
+        fuzzy: use_fuzzy && !query.is_empty(),
+        since,
+        until,
+    };
+
+    let fetch_limit = if diverse.is_some() {
+        limit * 3  // MMR needs a wider candidate pool
     } else {
         limit
     };
     
     // 🚀 CoreVecDB vector search (if available and not disabled)
-    let vecdb_url = std::env::var("COREVECDB_URL")
-        .unwrap_or_else(|_| "http://localhost:3100".to_string());
+    let vecdb_url = &config.vecdb_url;
     
     let should_use_vecdb = use_vecdb.unwrap_or(true);  // Default: try VecDB
     let mut vecdb_used = false;
+
+    // With --diverse only the picked results count as accessed, not the
+    // wider candidate pool: plain recall re-ranks inside the brain, the
+    // phrase/filter scans fetch without access and record it after MMR
+    let records_access = brain.records_access();
+    let defer_access = diverse.is_some() && records_access && (phrase.is_some() || !opts.is_empty());
+    if defer_access {
+        brain.set_record_access(false);
+    }
+    let mut reranked = false;
+    let mut plain_recall = |brain: &Brain| match diverse {
+        Some(lambda) => {
+            reranked = true;
+            brain.recall_diverse(&query, limit, lambda)
+        }
+        None => brain.recall(&query, fetch_limit),
+    };
     
-    let mut memories: Vec<MemoryItem> = if should_use_vecdb && !query.is_empty() {
+    let mut memories: Vec<MemoryItem> = if let Some(ref phrase) = phrase {
+        if !opts.is_empty() && !quiet {
+            eprintln!("⚠️ --tag/--type/--regex/--fuzzy/--since/--until are ignored with --phrase");
+        }
+        brain.recall_phrase(phrase, fetch_limit)?
+    } else if !opts.is_empty() {
+        // Filters run during the scan so `limit` counts matching memories
+        brain.recall_with_options(&query, fetch_limit, &opts)?
+    } else if should_use_vecdb && !query.is_empty() {
         // Try VecDB first
-        if let Ok(vecdb) = VecDbStorage::new(&vecdb_url, Some("memories")) {
-            // Get query embedding
-            let query_embedding = brain.embedder().embed(&query);
+        if let Ok(vecdb) = VecDbStorage::new(vecdb_url, Some("memories")) {
+            // Get query embedding (filter words are applied below)
+            let query_embedding = brain.embedder().embed_query(&terms.text);
             
-            // Convert type filter to string
-            let type_filter_str = type_filter.as_ref().map(|t| format!("{:?}", t));
-            
-            match vecdb.search_memories(&query_embedding, fetch_limit, type_filter_str.as_deref()) {
+            match vecdb.search_memories(&query_embedding, fetch_limit, None) {
                 Ok(results) => {
                     vecdb_used = true;
                     if !quiet {
@@ -578,57 +829,47 @@ fn cmd_recall(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
                     if !quiet {
                         eprintln!("⚠️ VecDB search failed: {}, falling back to Brain", e);
                     }
-                    brain.recall(&query, fetch_limit)
+                    plain_recall(brain)
                 }
             }
         } else {
             // VecDB not available, use Brain
-            brain.recall(&query, fetch_limit)
+            plain_recall(brain)
         }
     } else {
-        brain.recall(&query, fetch_limit)
+        plain_recall(brain)
     };
+    brain.set_record_access(records_access);
 
-    // Apply regex filter
-    if use_regex && !query.is_empty() {
-        if let Ok(re) = regex::Regex::new(&query) {
-            memories.retain(|m| re.is_match(&m.content));
-        }
-    }
-
-    // Apply fuzzy filter
-    if use_fuzzy && !query.is_empty() {
-        let query_lower = query.to_lowercase();
-        let query_chars: Vec<char> = query_lower.chars().collect();
-        
-        memories.retain(|m| {
-            let content_lower = m.content.to_lowercase();
-            // Simple fuzzy: all query chars appear in order
-            fuzzy_match(&query_chars, &content_lower)
-        });
-    }
-
-    // Apply tag filter
-    if let Some(ref tag) = tag_filter {
-        let tag_lower = tag.to_lowercase();
-        memories.retain(|m| m.tags.iter().any(|t| t.to_lowercase().contains(&tag_lower)));
+    // VecDB results skip the Brain's filtering
+    if terms.has_filters() {
+        memories.retain(|mem| terms.accepts(&mem.content, &mem.tags));
     }
 
-    // Apply type filter
-    if let Some(ref mem_type) = type_filter {
-        memories.retain(|m| std::mem::discriminant(&m.memory_type) == std::mem::discriminant(mem_type));
+    // MMR re-ranking (picks `limit` diverse results)
+    if let Some(lambda) = diverse.filter(|_| !reranked) {
+        let query_embedding = brain.embedder().embed_query(&terms.text);
+        memories = memory_brain::mmr_rerank(&query_embedding, memories, limit, lambda);
     }
 
     // Truncate to limit
     memories.truncate(limit);
+    if defer_access {
+        brain.record_accesses(&mut memories);
+    }
 
     // Audit log
     memory_brain::audit::log_recall(&query, memories.len());
 
+    if json {
+        println!("{}", memory_brain::cli_json::memories_to_json(&memories)?);
+        return Ok(());
+    }
+
     if memories.is_empty() {
         if !quiet { 
             println!("🔍 No memories found for: {}", query);
-            if tag_filter.is_some() || type_filter.is_some() || use_regex || use_fuzzy {
+            if !opts.is_empty() {
                 println!("   (filters applied)");
             }
         }
@@ -647,15 +888,43 @@ fn cmd_recall(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
             if use_fuzzy {
                 print!(" [fuzzy]");
             }
+            if diverse.is_some() {
+                print!(" [diverse]");
+            }
             println!(":\n");
         }
+        // Regex queries are patterns, not words to highlight
+        let matches = if use_regex { Vec::new() } else { brain.explain_matches(&terms.text, &memories) };
         for (i, mem) in memories.iter().enumerate() {
-            println!("{}. [{}] {}", i + 1, type_emoji(&mem.memory_type), mem.content);
+            let content = match matches.get(i) {
+                // Long memories show only the excerpt around the first keyword
+                Some(info) => match info.matched_keywords.first() {
+                    Some(keyword) if mem.content.chars().count() > SNIPPET_MIN_CHARS => {
+                        info.highlight(&brain.keyword_index.snippet(&mem.content, keyword, SNIPPET_RADIUS))
+                    }
+                    _ => info.highlight(&mem.content),
+                },
+                None => mem.content.clone(),
+            };
+            println!("{}. [{}] {}", i + 1, type_emoji(&mem.memory_type), content);
             println!("   Strength: {:.0}% | Accessed: {} | #{}", 
                 mem.strength * 100.0,
                 mem.last_accessed.format("%Y-%m-%d"),
                 &mem.id.to_string()[..8]
             );
+            if let Some(info) = matches.get(i) {
+                let source = match info.source {
+                    memory_brain::MatchSource::Keyword => "keyword",
+                    memory_brain::MatchSource::Semantic => "semantic",
+                    memory_brain::MatchSource::Tag => "tag",
+                    memory_brain::MatchSource::Working => "working",
+                };
+                print!("   Match: {} | Similarity: {:.0}%", source, info.similarity * 100.0);
+                if !info.matched_keywords.is_empty() {
+                    print!(" | Keywords: {}", info.matched_keywords.join(", "));
+                }
+                println!();
+            }
             if !mem.tags.is_empty() {
                 println!("   Tags: {}", mem.tags.join(", "));
             }
@@ -666,25 +935,14 @@ fn cmd_recall(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
     Ok(())
 }
 
-/// Simple fuzzy matching - all chars appear in order
-fn fuzzy_match(pattern: &[char], text: &str) -> bool {
-    let mut pattern_idx = 0;
-    for c in text.chars() {
-        if pattern_idx < pattern.len() && c == pattern[pattern_idx] {
-            pattern_idx += 1;
-        }
-    }
-    pattern_idx == pattern.len()
-}
-
-fn cmd_semantic_search(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_semantic_search(brain: &Brain, config: &Config, args: &[String], quiet: bool, json: bool) -> Result<(), Box<dyn std::error::Error>> {
     if args.is_empty() {
         eprintln!("Usage: memory-brain search <query> [--limit N] [--threshold 0.1]");
         return Ok(());
     }
 
-    let mut limit = 5;
-    let mut threshold = 0.05;
+    let mut limit = config.thresholds.recall_limit;
+    let mut threshold = config.thresholds.search;
     let mut query_parts: Vec<&str> = Vec::new();
 
     let mut i = 0;
@@ -699,7 +957,7 @@ fn cmd_semantic_search(brain: &Brain, args: &[String], quiet: bool) -> Result<()
             }
             "--threshold" | "-t" => {
                 if i + 1 < args.len() {
-                    threshold = args[i + 1].parse().unwrap_or(0.05);
+                    threshold = args[i + 1].parse().unwrap_or(config.thresholds.search);
                     i += 2;
                     continue;
                 }
@@ -715,6 +973,11 @@ fn cmd_semantic_search(brain: &Brain, args: &[String], quiet: bool) -> Result<()
     // Filter by threshold
     let results: Vec<_> = results.into_iter().filter(|(_, sim)| *sim >= threshold).collect();
 
+    if json {
+        println!("{}", memory_brain::cli_json::search_results_to_json(&results)?);
+        return Ok(());
+    }
+
     if results.is_empty() {
         if !quiet { println!("🔍 No similar memories found for: {}", query); }
     } else {
@@ -730,7 +993,7 @@ fn cmd_semantic_search(brain: &Brain, args: &[String], quiet: bool) -> Result<()
     Ok(())
 }
 
-fn cmd_list(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_list(brain: &Brain, args: &[String], quiet: bool, json: bool) -> Result<(), Box<dyn std::error::Error>> {
     let mut limit = 10;
     let mut memory_type: Option<MemoryType> = None;
 
@@ -761,39 +1024,38 @@ fn cmd_list(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn s
         i += 1;
     }
 
-    if !quiet { println!("📋 Recent memories:\n"); }
-
-    let mut count = 0;
+    let mut memories: Vec<MemoryItem> = Vec::new();
 
     // Get from semantic memory
     if memory_type.is_none() || matches!(memory_type, Some(MemoryType::Semantic)) {
         if let Ok(items) = brain.semantic.search("", limit) {
-            for mem in items {
-                println!("  {} {} #{}", 
-                    type_emoji(&mem.memory_type),
-                    truncate(&mem.content, 60),
-                    &mem.id.to_string()[..8]
-                );
-                count += 1;
-            }
+            memories.extend(items);
         }
     }
 
     // Get from episodic memory
     if memory_type.is_none() || matches!(memory_type, Some(MemoryType::Episodic)) {
-        if let Ok(items) = brain.episodic.get_recent(limit) {
-            for mem in items {
-                println!("  {} {} #{}", 
-                    type_emoji(&mem.memory_type),
-                    truncate(&mem.content, 60),
-                    &mem.id.to_string()[..8]
-                );
-                count += 1;
-            }
+        if let Ok(items) = brain.episodic.get_recent(limit, 0) {
+            memories.extend(items);
         }
     }
 
-    if count == 0 {
+    if json {
+        println!("{}", memory_brain::cli_json::memories_to_json(&memories)?);
+        return Ok(());
+    }
+
+    if !quiet { println!("📋 Recent memories:\n"); }
+
+    for mem in &memories {
+        println!("  {} {} #{}", 
+            type_emoji(&mem.memory_type),
+            truncate(&mem.content, 60),
+            &mem.id.to_string()[..8]
+        );
+    }
+
+    if memories.is_empty() {
         println!("  (no memories yet)");
     }
 
@@ -807,51 +1069,43 @@ fn cmd_show(brain: &Brain, args: &[String], _quiet: bool) -> Result<(), Box<dyn
     }
 
     let id_prefix = &args[0];
-    
-    // Search for matching ID
-    if let Ok(items) = brain.semantic.search("", 1000) {
-        for mem in items {
-            if mem.id.to_string().starts_with(id_prefix) {
-                println!("🧠 Memory Details\n");
-                println!("ID:       {}", mem.id);
-                println!("Type:     {:?}", mem.memory_type);
-                println!("Content:  {}", mem.content);
-                if let Some(ctx) = &mem.context {
-                    println!("Context:  {}", ctx);
-                }
-                println!("Created:  {}", mem.created_at.format("%Y-%m-%d %H:%M:%S"));
-                println!("Accessed: {}", mem.last_accessed.format("%Y-%m-%d %H:%M:%S"));
-                println!("Count:    {} times", mem.access_count);
-                println!("Strength: {:.1}%", mem.strength * 100.0);
-                if !mem.tags.is_empty() {
-                    println!("Tags:     {}", mem.tags.join(", "));
-                }
-                if mem.embedding.is_some() {
-                    println!("Embedding: ✓ ({}d)", brain.embedder().dimension());
-                }
-                
-                // 🔗 Show associations
-                if !mem.associations.is_empty() {
-                    println!("\n🔗 Linked Memories ({}):", mem.associations.len());
-                    for assoc_id in &mem.associations {
-                        // Try to find the associated memory
-                        if let Ok(all_items) = brain.semantic.search("", 1000) {
-                            if let Some(linked) = all_items.iter().find(|m| m.id == *assoc_id) {
-                                println!("   → {} - {}", 
-                                    &assoc_id.to_string()[..8],
-                                    truncate(&linked.content, 50));
-                            } else {
-                                println!("   → {} (not found)", &assoc_id.to_string()[..8]);
-                            }
-                        }
-                    }
-                }
-                return Ok(());
+
+    let Some(mem) = brain.get_by_id_prefix(id_prefix)? else {
+        eprintln!("❌ Memory not found: {}", id_prefix);
+        return Ok(());
+    };
+
+    println!("🧠 Memory Details\n");
+    println!("ID:       {}", mem.id);
+    println!("Type:     {:?}", mem.memory_type);
+    println!("Content:  {}", mem.content);
+    if let Some(ctx) = &mem.context {
+        println!("Context:  {}", ctx);
+    }
+    println!("Created:  {}", mem.created_at.format("%Y-%m-%d %H:%M:%S"));
+    println!("Accessed: {}", mem.last_accessed.format("%Y-%m-%d %H:%M:%S"));
+    println!("Count:    {} times", mem.access_count);
+    println!("Strength: {:.1}%", mem.strength * 100.0);
+    if !mem.tags.is_empty() {
+        println!("Tags:     {}", mem.tags.join(", "));
+    }
+    if mem.embedding.is_some() {
+        println!("Embedding: ✓ ({}d)", brain.embedder().dimension());
+    }
+
+    // 🔗 Show associations
+    if !mem.associations.is_empty() {
+        println!("\n🔗 Linked Memories ({}):", mem.associations.len());
+        for assoc_id in &mem.associations {
+            match brain.get_by_id(assoc_id)? {
+                Some(linked) => println!("   → {} - {}",
+                    &assoc_id.to_string()[..8],
+                    truncate(&linked.content, 50)),
+                None => println!("   → {} (not found)", &assoc_id.to_string()[..8]),
             }
         }
     }
 
-    eprintln!("❌ Memory not found: {}", id_prefix);
     Ok(())
 }
 
@@ -864,68 +1118,104 @@ fn cmd_delete(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
     let id_prefix = &args[0];
     let force = args.contains(&"--force".to_string()) || args.contains(&"-f".to_string());
 
-    // Find matching memory
-    if let Ok(items) = brain.semantic.search("", 1000) {
-        for mem in items {
-            if mem.id.to_string().starts_with(id_prefix) {
-                if !force {
-                    print!("Delete '{}...'? [y/N] ", truncate(&mem.content, 30));
-                    io::stdout().flush()?;
-                    let mut input = String::new();
-                    io::stdin().read_line(&mut input)?;
-                    if !input.trim().eq_ignore_ascii_case("y") {
-                        println!("Cancelled");
-                        return Ok(());
-                    }
-                }
-                
-                // TODO: Add delete method to storage
-                if !quiet { println!("🗑️ Deleted: {}", truncate(&mem.content, 40)); }
-                return Ok(());
-            }
+    // Resolve the prefix first so we can confirm (errors if missing/ambiguous)
+    let Some(mem) = brain.get_by_id_prefix(id_prefix)? else {
+        return Err(format!("Memory not found: {}", id_prefix).into());
+    };
+    if !force {
+        print!("Delete '{}...'? [y/N] ", truncate(&mem.content, 30));
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Cancelled");
+            return Ok(());
         }
     }
 
-    eprintln!("❌ Memory not found: {}", id_prefix);
+    let deleted = brain.delete(&mem.id.to_string())?;
+    if !quiet { println!("🗑️ Deleted: {}", truncate(&deleted.content, 40)); }
     Ok(())
 }
 
-fn cmd_stats(brain: &Brain, quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_stats(brain: &Brain, config: &Config, quiet: bool, json: bool) -> Result<(), Box<dyn std::error::Error>> {
     if !quiet { println!("🧠 Brain Statistics\n"); }
 
     // CoreVecDB stats (primary)
-    let vecdb_url = std::env::var("COREVECDB_URL")
-        .unwrap_or_else(|_| "http://localhost:3100".to_string());
-    
-    let vecdb_count = if let Ok(vecdb) = VecDbStorage::new(&vecdb_url, Some("memories")) {
+    let vecdb_count = if let Ok(vecdb) = VecDbStorage::new(&config.vecdb_url, Some("memories")) {
         vecdb.stats().map(|(count, _)| count).unwrap_or(0)
     } else {
         0
     };
 
-    let working_count = brain.working.len();
-    let semantic_count = brain.semantic.search("", 10000).map(|v| v.len()).unwrap_or(0);
-    let episodic_count = brain.episodic.get_recent(10000).map(|v| v.len()).unwrap_or(0);
+    let stats = brain.stats();
+
+    if json {
+        let stats = memory_brain::cli_json::JsonStats {
+            vecdb_vectors: vecdb_count,
+            working: stats.working,
+            working_capacity: stats.working_capacity,
+            semantic: stats.semantic,
+            episodic: stats.episodic,
+            procedural: stats.procedural,
+            embedding_dim: stats.embedding_dim,
+            db_size_bytes: stats.db_size_bytes,
+        };
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
 
     println!("  CoreVecDB:       {} vectors ✨", vecdb_count);
-    println!("  Working Memory:  {} / 7 slots", working_count);
-    println!("  Semantic (legacy): {} items", semantic_count);
-    println!("  Episodic (legacy): {} items", episodic_count);
-    println!("  Embedding Dim:   {}d", brain.embedder().dimension());
-    
-    let db_path = dirs::data_local_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("memory-brain")
-        .join("coredb");
+    println!("  Working Memory:  {} / {} slots", stats.working, stats.working_capacity);
+    println!("  Semantic:        {} items", stats.semantic);
+    println!("  Episodic:        {} items", stats.episodic);
+    println!("  Procedural:      {} items", stats.procedural);
+    println!("  Total:           {} items", stats.total);
+    println!("  Keyword Index:   {} keywords / {} docs", stats.index_stats.unique_keywords, stats.index_stats.documents);
+    println!("  Embedding Dim:   {}d", stats.embedding_dim);
     
-    if let Ok(meta) = std::fs::metadata(&db_path) {
-        println!("  Database Size:   {:.1} KB", meta.len() as f64 / 1024.0);
+    if let Some(bytes) = stats.db_size_bytes {
+        println!("  Database Size:   {:.1} KB", bytes as f64 / 1024.0);
     }
 
     Ok(())
 }
 
-fn cmd_rebuild(brain: &mut Brain, quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_rebuild(brain: &mut Brain, config: &Config, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut embedder: Option<String> = None;
+    let mut reembed = false;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--reembed" => reembed = true,
+            "--embedder" | "-e" if i + 1 < args.len() => {
+                embedder = Some(args[i + 1].to_lowercase());
+                i += 1;
+            }
+            s if s.starts_with("--embedder=") => {
+                embedder = Some(s.trim_start_matches("--embedder=").to_lowercase());
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    match embedder.as_deref() {
+        None => {}
+        Some("tfidf") => reindex_tfidf(brain, config, quiet)?,
+        Some(other) => {
+            eprintln!("❌ Unknown embedder for reindex: {} (supported: tfidf)", other);
+            return Ok(());
+        }
+    }
+
+    if reembed && embedder.is_none() {
+        let updated = brain.reembed()?;
+        if !quiet {
+            println!("🔁 Re-embedded {} memories ({}d)", updated, brain.embedder().dimension());
+        }
+    }
+
     if !quiet {
         println!("🔧 Rebuilding indexes from database...");
     }
@@ -941,11 +1231,36 @@ fn cmd_rebuild(brain: &mut Brain, quiet: bool) -> Result<(), Box<dyn std::error:
     Ok(())
 }
 
+/// Fit TF-IDF on every long-term memory, save the vocabulary and re-embed
+fn reindex_tfidf(brain: &mut Brain, config: &Config, quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut items = brain.episodic.search("", usize::MAX)?;
+    items.extend(brain.semantic.search("", usize::MAX)?);
+    items.extend(brain.procedural.search("", usize::MAX)?);
+
+    let docs: Vec<&str> = items.iter().map(|m| m.content.as_str()).collect();
+    let mut tfidf = TfIdfEmbedder::new(TFIDF_DIMENSION);
+    tfidf.fit(&docs);
+    tfidf.save(&config.embedding.tfidf_path)?;
+
+    for item in &items {
+        brain.set_embedding(item.id, tfidf.embed_document(&item.content))?;
+    }
+    brain.semantic.set_embedding_dim(tfidf.dimension())?;
+
+    if !quiet {
+        println!("📐 TF-IDF fitted on {} memories ({} terms) → {}",
+            items.len(), tfidf.vocabulary_size(), config.embedding.tfidf_path.display());
+        println!("   Use it with embedding.provider = \"tfidf\" (or MEMORY_BRAIN_EMBEDDER=tfidf)");
+    }
+    Ok(())
+}
+
 fn cmd_watch(brain: &Brain, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     use memory_brain::watch::{MemoryWatcher, WatchConfig};
     
     let mut interval_ms = 1000u64;
     let mut detailed = false;
+    let mut alert_on_forgetting = false;
     
     for arg in args {
         if arg.starts_with("--interval=") || arg.starts_with("-i=") {
@@ -954,6 +1269,8 @@ fn cmd_watch(brain: &Brain, args: &[String]) -> Result<(), Box<dyn std::error::E
                 .unwrap_or(1000);
         } else if arg == "--detailed" || arg == "-d" {
             detailed = true;
+        } else if arg == "--alerts" || arg == "-a" {
+            alert_on_forgetting = true;
         }
     }
     
@@ -962,6 +1279,7 @@ fn cmd_watch(brain: &Brain, args: &[String]) -> Result<(), Box<dyn std::error::E
         detailed,
         clear_screen: true,
         max_iterations: 0,
+        alert_on_forgetting,
     };
     
     MemoryWatcher::with_config(brain, config).run()?;
@@ -969,9 +1287,37 @@ fn cmd_watch(brain: &Brain, args: &[String]) -> Result<(), Box<dyn std::error::E
     Ok(())
 }
 
-fn cmd_bench(quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_bench(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
     use memory_brain::bench;
     
+    // --dim / --n: only compare cosine implementations
+    let mut dim = None;
+    let mut n = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--dim" | "-d" if i + 1 < args.len() => {
+                dim = Some(args[i + 1].parse::<usize>()?);
+                i += 2;
+            }
+            "--n" | "-n" if i + 1 < args.len() => {
+                n = Some(args[i + 1].parse::<usize>()?);
+                i += 2;
+            }
+            _ => i += 1,
+        }
+    }
+    
+    if dim.is_some() || n.is_some() {
+        let dim = dim.unwrap_or_else(|| brain.embedder().dimension());
+        let cmp = bench::compare_cosine(dim, n.unwrap_or(1000));
+        println!("{}", cmp);
+        if !cmp.is_accurate() {
+            return Err(format!("SIMD and scalar cosine differ by {:.2e}", cmp.max_abs_error).into());
+        }
+        return Ok(());
+    }
+    
     if !quiet {
         println!("⚡ Memory Brain Benchmark\n");
         
@@ -985,25 +1331,38 @@ fn cmd_bench(quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
     
     bench::run_benchmarks(!quiet);
     
+    // The dimension this brain actually uses
+    if !quiet {
+        println!("\n{}", bench::compare_cosine(brain.embedder().dimension(), 1000));
+    }
+    
     Ok(())
 }
 
-fn cmd_merge(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_merge(brain: &mut Brain, config: &Config, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
     use memory_brain::merge::{MemoryMerger, MergeConfig};
     
     // Parse arguments
-    let mut threshold = 0.85f32;
+    let mut threshold = config.thresholds.merge;
     let mut dry_run = true; // Default to dry run for safety
-    
-    for arg in args {
-        if arg.starts_with("--threshold=") {
+    let mut cross_type = false;
+    let mut show = 10;
+    
+    for (i, arg) in args.iter().enumerate() {
+        if arg.starts_with("--show=") {
+            show = arg.trim_start_matches("--show=").parse().unwrap_or(show);
+        } else if arg == "--show" {
+            show = args.get(i + 1).and_then(|v| v.parse().ok()).unwrap_or(show);
+        } else if arg.starts_with("--threshold=") {
             threshold = arg.trim_start_matches("--threshold=")
                 .parse()
-                .unwrap_or(0.85);
+                .unwrap_or(config.thresholds.merge);
         } else if arg == "--execute" || arg == "-x" {
             dry_run = false;
         } else if arg == "--dry-run" || arg == "-n" {
             dry_run = true;
+        } else if arg == "--cross-type" {
+            cross_type = true;
         }
     }
 
@@ -1014,21 +1373,29 @@ fn cmd_merge(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<
             println!("🔗 Merging duplicate memories...");
         }
         println!("  Threshold: {:.0}%", threshold * 100.0);
+        if cross_type {
+            println!("  Mode: across memory types");
+        } else {
+            println!("  Mode: same type only (--cross-type to merge across types)");
+        }
     }
 
     let config = MergeConfig {
         similarity_threshold: threshold,
         dry_run,
+        cross_type,
         ..Default::default()
     };
 
     let mut merger = MemoryMerger::with_config(brain, config);
-    let result = merger.find_similar();
+    let result = merger.find_similar()?;
 
     if !quiet {
         println!("{}", result);
         
         if dry_run && result.mergeable_count > 0 {
+            println!("🔍 Pairs that would merge:");
+            print!("{}", result.pairs_preview(show));
             println!("\n💡 Run with --execute (-x) to actually merge");
         }
     } else {
@@ -1038,16 +1405,87 @@ fn cmd_merge(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<
     Ok(())
 }
 
-fn cmd_dream(brain: &mut Brain, quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
-    use memory_brain::DreamEngine;
+fn cmd_compress(brain: &Brain, args: &[String], quiet: bool, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let execute = args.iter().any(|a| a == "--execute" || a == "-x");
+
+    if !quiet && !json {
+        if execute {
+            println!("🗜️  Quantizing stored embeddings to int8...");
+        } else {
+            println!("🔍 Measuring embedding compression (dry run)...");
+        }
+    }
+
+    let report = brain.compress_embeddings(execute)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+            "items": report.stats.items_compressed,
+            "original_bytes": report.stats.original_bytes,
+            "quantized_bytes": report.stats.compressed_bytes,
+            "savings_percent": report.stats.savings_percent(),
+            "mean_error": report.stats.mean_error,
+            "disk_bytes_before": report.disk_bytes_before,
+            "disk_bytes_after": report.disk_bytes_after,
+            "rewritten": report.rewritten,
+        }))?);
+        return Ok(());
+    }
+
+    if quiet {
+        println!("{}", report.rewritten);
+        return Ok(());
+    }
+
+    println!("  {}", report.stats);
+    println!("  Stored embedding column: {:.1} KB", report.disk_bytes_before as f64 / 1024.0);
+    if execute {
+        println!("  ✅ Rewrote {} embeddings: {:.1} KB → {:.1} KB",
+            report.rewritten,
+            report.disk_bytes_before as f64 / 1024.0,
+            report.disk_bytes_after as f64 / 1024.0
+        );
+        println!("\n💡 Set quantize = true under [embedding] to store new memories quantized too");
+    } else if report.stats.items_compressed > 0 {
+        println!("\n💡 Run with --execute (-x) to rewrite them");
+    }
+
+    Ok(())
+}
+
+fn cmd_dream(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+    use memory_brain::{DreamConfig, DreamEngine};
+    
+    let mut config = DreamConfig::default();
+    let mut i = 0;
+    while i < args.len() {
+        let value = args.get(i + 1);
+        match args[i].as_str() {
+            "--threshold" => {
+                config.connection_threshold = value.and_then(|v| v.parse().ok()).ok_or("--threshold needs a number")?;
+                i += 1;
+            }
+            "--max-links" => {
+                config.max_new_links = value.and_then(|v| v.parse().ok()).ok_or("--max-links needs a number")?;
+                i += 1;
+            }
+            "--prune" => {
+                config.prune_below_strength = value.and_then(|v| v.parse().ok()).ok_or("--prune needs a number")?;
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
     
-    let mut engine = DreamEngine::new(brain).verbose(!quiet);
+    let mut engine = DreamEngine::new(brain).verbose(!quiet).with_config(config);
     let state = engine.dream();
     
     if !quiet {
         println!("\n🌙 Dream Summary:");
         println!("  Memories processed: {}", state.memories_processed);
         println!("  New connections: {}", state.new_connections);
+        println!("  Pruned links: {}", state.pruned_links);
         println!("  Faded memories: {}", state.faded_memories);
         
         if !state.insights.is_empty() {
@@ -1090,9 +1528,45 @@ fn cmd_predict(brain: &Brain, quiet: bool) -> Result<(), Box<dyn std::error::Err
     Ok(())
 }
 
-fn cmd_forgetting(brain: &Brain, quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
-    use memory_brain::Predictor;
-    
+fn cmd_forgetting(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+    use memory_brain::{ForgettingCurve, Predictor};
+
+    let mut apply = false;
+    let mut i = 0;
+    while i < args.len() {
+        let rates = match args[i].as_str() {
+            "--rates" if i + 1 < args.len() => {
+                i += 1;
+                Some(args[i].as_str())
+            }
+            s if s.starts_with("--rates=") => Some(s.trim_start_matches("--rates=")),
+            "--apply" => {
+                apply = true;
+                None
+            }
+            _ => None,
+        };
+        if let Some(spec) = rates {
+            for (memory_type, rate) in ForgettingCurve::parse_rates(spec)? {
+                brain.set_decay_rate(memory_type, rate);
+            }
+        }
+        i += 1;
+    }
+
+    if !quiet {
+        let curve = brain.forgetting();
+        println!("📉 감쇠율 (일): episodic {:.3}, semantic {:.3}, procedural {:.3}",
+            curve.rate_for(&MemoryType::Episodic),
+            curve.rate_for(&MemoryType::Semantic),
+            curve.rate_for(&MemoryType::Procedural));
+    }
+
+    if apply {
+        brain.apply_forgetting()?;
+        if !quiet { println!("🧹 망각 곡선 적용 완료"); }
+    }
+
     let predictor = Predictor::new(brain);
     let alerts = predictor.forgetting_alerts(10);
     
@@ -1114,17 +1588,138 @@ fn cmd_forgetting(brain: &Brain, quiet: bool) -> Result<(), Box<dyn std::error::
                 alert.strength * 100.0
             );
         }
-        println!("\n💡 팁: 이 기억들을 recall해서 강화하세요!");
+        println!("\n💡 팁: 이 기억들을 recall해서 강화하세요!");
+    }
+    
+    Ok(())
+}
+
+fn cmd_review(brain: &Brain, args: &[String], quiet: bool, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut limit = 20;
+    let mut i = 0;
+    while i < args.len() {
+        if matches!(args[i].as_str(), "--limit" | "-n") && i + 1 < args.len() {
+            limit = args[i + 1].parse().unwrap_or(20);
+            i += 1;
+        }
+        i += 1;
+    }
+
+    // Everything scheduled up to the end of today
+    let now = chrono::Utc::now();
+    let end_of_today = now.date_naive().and_hms_opt(23, 59, 59).unwrap().and_utc();
+    let due = brain.due_for_review(end_of_today, limit)?;
+
+    if json {
+        let rows: Vec<_> = due
+            .iter()
+            .map(|(mem, at)| serde_json::json!({ "memory": mem, "review_at": at }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+
+    if due.is_empty() {
+        if !quiet {
+            println!("✨ 오늘 복습할 기억이 없어");
+        }
+        return Ok(());
+    }
+
+    if !quiet {
+        println!("📚 오늘 복습할 기억 ({}개):\n", due.len());
+    }
+    for (mem, at) in &due {
+        let overdue_days = (now - *at).num_days();
+        let (urgency, when) = if overdue_days >= 1 {
+            ("🔴", format!("{}일 지남", overdue_days))
+        } else if *at <= now {
+            ("🟡", "오늘".to_string())
+        } else {
+            ("🟢", format!("{} 예정", at.format("%H:%M")))
+        };
+        println!("  {} [{}] {} ({}, 강도 {:.0}%, #{})",
+            urgency,
+            type_emoji(&mem.memory_type),
+            truncate(&mem.content, 50),
+            when,
+            mem.strength * 100.0,
+            &mem.id.to_string()[..8]
+        );
+    }
+    if !quiet {
+        println!("\n💡 팁: recall로 떠올리면 다음 복습 간격이 늘어나요");
+    }
+
+    Ok(())
+}
+
+fn cmd_on_this_day(brain: &Brain, args: &[String], quiet: bool, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut window = 0;
+    let mut reference = chrono::Utc::now();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--window" | "-w" if i + 1 < args.len() => {
+                window = args[i + 1].parse().unwrap_or(0);
+                i += 1;
+            }
+            "--date" | "-d" if i + 1 < args.len() => {
+                // Noon local time, so the calendar day survives the UTC conversion
+                let date = chrono::NaiveDate::parse_from_str(&args[i + 1], "%Y-%m-%d")
+                    .map_err(|e| format!("Invalid date '{}': {} (expected YYYY-MM-DD)", args[i + 1], e))?;
+                reference = date
+                    .and_hms_opt(12, 0, 0)
+                    .and_then(|t| t.and_local_timezone(chrono::Local).single())
+                    .map(|t| t.with_timezone(&chrono::Utc))
+                    .unwrap_or(reference);
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let memories = brain.episodic.on_this_day(reference, window);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&memories)?);
+        return Ok(());
+    }
+
+    let today = reference.with_timezone(&chrono::Local).date_naive();
+    if memories.is_empty() {
+        if !quiet {
+            println!("📅 {} — 지난 해 오늘의 기억이 없어", today.format("%m월 %d일"));
+        }
+        return Ok(());
     }
-    
+
+    if !quiet {
+        println!("📅 {} — 지난 해 오늘 ({}개):\n", today.format("%m월 %d일"), memories.len());
+    }
+    for mem in &memories {
+        let date = mem.created_at.with_timezone(&chrono::Local).date_naive();
+        let years_ago = today.years_since(date).unwrap_or(0).max(1);
+        println!("  🕰️  {} ({}년 전) {} (#{})",
+            date.format("%Y-%m-%d"),
+            years_ago,
+            truncate(&mem.content, 60),
+            &mem.id.to_string()[..8]
+        );
+    }
+
     Ok(())
 }
 
-fn cmd_patterns(brain: &Brain, quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_patterns(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
     use memory_brain::Predictor;
     
-    let predictor = Predictor::new(brain);
-    let patterns = predictor.discover_patterns();
+    let patterns = if args.iter().any(|a| a == "--procedural" || a == "-p") {
+        brain.procedural.extract_patterns()?
+    } else {
+        Predictor::new(brain).discover_patterns()
+    };
     
     if patterns.is_empty() {
         if !quiet {
@@ -1150,14 +1745,17 @@ fn cmd_patterns(brain: &Brain, quiet: bool) -> Result<(), Box<dyn std::error::Er
 }
 
 fn cmd_map(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
-    use memory_brain::MindMap;
+    use memory_brain::{ClusterMode, MapFilter, MindMap};
     
     let mut format = "html";
+    let mut cluster = "tag".to_string();
+    let mut k = memory_brain::mindmap::DEFAULT_CLUSTERS;
     let mut output = "memory_map.html";
     let mut limit = 100;
     let mut threshold = 0.3;
     let mut open_browser = false;
     let mut query: Option<String> = None;
+    let mut filter = MapFilter::default();
     
     let mut i = 0;
     while i < args.len() {
@@ -1167,6 +1765,8 @@ fn cmd_map(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn st
                     format = match args[i + 1].as_str() {
                         "dot" => "dot",
                         "mermaid" => "mermaid",
+                        "graphml" => "graphml",
+                        "json" => "json",
                         _ => "html",
                     };
                     i += 2;
@@ -1201,6 +1801,54 @@ fn cmd_map(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn st
                     continue;
                 }
             }
+            "--cluster" => {
+                if i + 1 < args.len() {
+                    cluster = args[i + 1].clone();
+                    i += 2;
+                    continue;
+                }
+            }
+            "--k" | "-k" => {
+                if i + 1 < args.len() {
+                    k = args[i + 1].parse().map_err(|_| format!("Invalid --k: {}", args[i + 1]))?;
+                    i += 2;
+                    continue;
+                }
+            }
+            "--tag" => {
+                if i + 1 < args.len() {
+                    filter.tags.push(args[i + 1].clone());
+                    i += 2;
+                    continue;
+                }
+            }
+            "--since" => {
+                if i + 1 < args.len() {
+                    filter.since = Some(memory_brain::parse_time_spec(&args[i + 1])?);
+                    i += 2;
+                    continue;
+                }
+            }
+            "--until" => {
+                if i + 1 < args.len() {
+                    filter.until = Some(memory_brain::parse_time_spec(&args[i + 1])?);
+                    i += 2;
+                    continue;
+                }
+            }
+            "--type" => {
+                if i + 1 < args.len() {
+                    let memory_type = match args[i + 1].to_lowercase().as_str() {
+                        "semantic" | "sem" => MemoryType::Semantic,
+                        "episodic" | "epi" => MemoryType::Episodic,
+                        "procedural" | "proc" => MemoryType::Procedural,
+                        other => return Err(format!("Unknown --type: {} (expected semantic|episodic|procedural)", other).into()),
+                    };
+                    filter.types.push(memory_type);
+                    i += 2;
+                    continue;
+                }
+            }
             "--open" => {
                 open_browser = true;
             }
@@ -1222,7 +1870,9 @@ fn cmd_map(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn st
         }
     }
     
-    let map = MindMap::from_brain_filtered(brain, query.as_deref(), limit, threshold);
+    let cluster = ClusterMode::parse(&cluster, k)
+        .ok_or_else(|| format!("Unknown --cluster mode: {} (expected tag|embedding)", cluster))?;
+    let map = MindMap::from_brain_clustered(brain, query.as_deref(), &filter, limit, threshold, cluster);
     
     let _content = match format {
         "dot" => {
@@ -1235,6 +1885,25 @@ fn cmd_map(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn st
             }
             content
         }
+        "graphml" => {
+            let out = if output == "memory_map.html" { "memory_map.graphml" } else { output };
+            let content = map.to_graphml();
+            std::fs::write(out, &content)?;
+            if !quiet {
+                println!("✅ GraphML file saved to {}", out);
+                println!("   Open it in Gephi or Cytoscape");
+            }
+            content
+        }
+        "json" => {
+            let out = if output == "memory_map.html" { "memory_map.json" } else { output };
+            let content = serde_json::to_string_pretty(&map.to_json())?;
+            std::fs::write(out, &content)?;
+            if !quiet {
+                println!("✅ JSON graph saved to {}", out);
+            }
+            content
+        }
         "mermaid" => {
             let out = if output == "memory_map.html" { "memory_map.md" } else { output };
             let content = format!("```mermaid\n{}\n```", map.to_mermaid());
@@ -1326,33 +1995,140 @@ fn cmd_constellation(brain: &Brain, args: &[String], quiet: bool) -> Result<(),
 }
 
 fn cmd_export(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
-    let output_path = args.get(0).map(|s| s.as_str()).unwrap_or("memories.json");
+    let mut output_path: Option<&str> = None;
+    let mut format: Option<String> = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" | "-f" if i + 1 < args.len() => {
+                format = Some(args[i + 1].to_lowercase());
+                i += 1;
+            }
+            s if s.starts_with("--format=") => {
+                format = Some(s.trim_start_matches("--format=").to_lowercase());
+            }
+            s if output_path.is_none() && !s.starts_with('-') => output_path = Some(s),
+            _ => {}
+        }
+        i += 1;
+    }
+    let output_path = output_path.unwrap_or("memories.json");
+
+    // Format from --format, else the extension (JSON by default)
+    let format = format.unwrap_or_else(|| {
+        match std::path::Path::new(output_path).extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => "csv".to_string(),
+            Some(ext) if ext.eq_ignore_ascii_case("md") => "markdown".to_string(),
+            _ => "json".to_string(),
+        }
+    });
     
     let mut all_memories: Vec<MemoryItem> = Vec::new();
     
     if let Ok(items) = brain.semantic.search("", 10000) {
         all_memories.extend(items);
     }
-    if let Ok(items) = brain.episodic.get_recent(10000) {
+    if let Ok(items) = brain.episodic.get_recent(10000, 0) {
+        all_memories.extend(items);
+    }
+    if let Ok(items) = brain.procedural.search("", 10000) {
         all_memories.extend(items);
     }
 
-    let json = serde_json::to_string_pretty(&all_memories)?;
-    std::fs::write(output_path, json)?;
+    let output = match format.as_str() {
+        "csv" => memory_brain::export::to_csv(&all_memories),
+        "markdown" | "md" => memory_brain::export::to_markdown(&all_memories),
+        "json" => serde_json::to_string_pretty(&all_memories)?,
+        other => return Err(format!("Unknown export format: {} (json|csv|markdown)", other).into()),
+    };
+    std::fs::write(output_path, output)?;
 
     if !quiet { println!("📤 Exported {} memories to {}", all_memories.len(), output_path); }
     Ok(())
 }
 
+fn cmd_snapshot(brain: &Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(path) = args.first() else {
+        eprintln!("Usage: memory-brain snapshot <file>");
+        return Ok(());
+    };
+
+    brain.snapshot(path)?;
+    if !quiet {
+        println!("💾 Snapshot written to {}", path);
+    }
+    Ok(())
+}
+
+fn cmd_restore(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(path) = args.first() else {
+        eprintln!("Usage: memory-brain restore <file>");
+        return Ok(());
+    };
+
+    brain.restore(path)?;
+    if !quiet {
+        println!("♻️  Restored from {}", path);
+        println!("  Episodic:   {}", brain.episodic.count()?);
+        println!("  Semantic:   {}", brain.semantic.count()?);
+        println!("  Procedural: {}", brain.procedural.count()?);
+    }
+    Ok(())
+}
+
+fn cmd_embeddings(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+    use memory_brain::embedding_io::{export_embeddings, ids_path, import_embeddings};
+
+    let usage = || {
+        eprintln!("Usage: memory-brain embeddings <export|import> <file>");
+        eprintln!("  <file>.npy  NumPy float32 matrix (n × dim)");
+        eprintln!("  <file>      any other extension: raw little-endian f32");
+        eprintln!("  Ids are written to / read from <file>.ids (one per line)");
+    };
+
+    let (Some(action), Some(path)) = (args.first(), args.get(1)) else {
+        usage();
+        return Ok(());
+    };
+    let path = std::path::Path::new(path);
+
+    match action.as_str() {
+        "export" => {
+            let matrix = export_embeddings(brain, path)?;
+            if !quiet {
+                println!("📤 Exported {} × {} embeddings to {}", matrix.len(), matrix.dim, path.display());
+                println!("   Ids: {}", ids_path(path).display());
+            }
+        }
+        "import" => {
+            let stats = import_embeddings(brain, path)?;
+            if !quiet {
+                println!("📥 Updated {} memory embeddings from {}", stats.updated, path.display());
+                if !stats.missing.is_empty() {
+                    println!("   ⚠️ {} ids not found", stats.missing.len());
+                }
+            }
+        }
+        _ => usage(),
+    }
+
+    Ok(())
+}
+
 fn cmd_import(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
     let input_path = args.get(0).ok_or("No input file specified")?;
     
     // Parse options
     let mut default_tags: Vec<String> = Vec::new();
     let mut memory_type = MemoryType::Semantic;
-    
-    for arg in args.iter().skip(1) {
-        if arg.starts_with("--tags=") {
+    let mut format: Option<String> = None;
+    
+    for (i, arg) in args.iter().enumerate().skip(1) {
+        if arg == "--format" {
+            format = args.get(i + 1).map(|f| f.to_lowercase());
+        } else if arg.starts_with("--format=") {
+            format = Some(arg.trim_start_matches("--format=").to_lowercase());
+        } else if arg.starts_with("--tags=") {
             default_tags = arg.trim_start_matches("--tags=")
                 .split(',')
                 .map(|s| s.trim().to_string())
@@ -1365,17 +2141,43 @@ fn cmd_import(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
         }
     }
     
-    // Detect format from extension
+    // Detect format from --format or the extension
     let path = std::path::Path::new(input_path);
-    let extension = path.extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
+    let extension = format.unwrap_or_else(|| {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase()
+    });
     
     let mut count = 0;
     let mut errors = 0;
+    // Stored together at the end (jsonl/csv stream on their own)
+    let mut batch: Vec<MemoryItem> = Vec::new();
     
     match extension.as_str() {
+        "jsonl" | "ndjson" => {
+            // JSONL: streamed line by line, never loads the whole file
+            use memory_brain::import::{import_jsonl, ImportOptions};
+
+            let reader = std::io::BufReader::new(std::fs::File::open(input_path)?);
+            let options = ImportOptions { tags: default_tags.clone(), memory_type: memory_type.clone() };
+            let stats = import_jsonl(brain, reader, &options, |n| {
+                if !quiet {
+                    print!("\r📥 Imported {} memories...", n);
+                    let _ = std::io::stdout().flush();
+                }
+            })?;
+            count = stats.imported;
+            errors = stats.skipped + stats.failed;
+            if !quiet {
+                for reason in &stats.errors {
+                    eprintln!("❌ Failed {}", reason);
+                }
+            }
+        }
+
+
         "json" => {
             // JSON import (array of MemoryItem or simple objects)
             let json = std::fs::read_to_string(input_path)?;
@@ -1383,92 +2185,43 @@ fn cmd_import(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
             // Try full MemoryItem format first
             if let Ok(memories) = serde_json::from_str::<Vec<MemoryItem>>(&json) {
                 for mut mem in memories {
-                    mem.embedding = Some(brain.embedder().embed(&mem.content));
+                    mem.embedding = brain.embedder().try_embed_document(&mem.content).ok();
                     mem.tags.extend(default_tags.clone());
-                    
-                    match mem.memory_type {
-                        MemoryType::Episodic => brain.episodic.store(mem)?,
-                        MemoryType::Semantic => brain.semantic.store(mem)?,
-                        MemoryType::Procedural => brain.procedural.store(mem)?,
-                        _ => brain.semantic.store(mem)?,
-                    }
-                    count += 1;
-                    if !quiet && count % 100 == 0 {
-                        print!("\r📥 Imported {} memories...", count);
-                        std::io::stdout().flush()?;
-                    }
+                    batch.push(mem);
                 }
             } else {
                 // Try simple format: [{"content": "...", "tags": [...]}]
-                #[derive(serde::Deserialize)]
-                struct SimpleMemory {
-                    content: String,
-                    #[serde(default)]
-                    tags: Vec<String>,
-                    #[serde(default)]
-                    context: Option<String>,
-                }
+                use memory_brain::import::SimpleMemory;
                 
                 let simple: Vec<SimpleMemory> = serde_json::from_str(&json)?;
                 for item in simple {
                     let mut mem = MemoryItem::new(&item.content, item.context.as_deref());
-                    mem.embedding = Some(brain.embedder().embed(&item.content));
+                    mem.embedding = brain.embedder().try_embed_document(&item.content).ok();
                     mem.tags = item.tags;
                     mem.tags.extend(default_tags.clone());
                     mem.memory_type = memory_type.clone();
-                    
-                    match memory_type {
-                        MemoryType::Episodic => brain.episodic.store(mem)?,
-                        MemoryType::Semantic => brain.semantic.store(mem)?,
-                        MemoryType::Procedural => brain.procedural.store(mem)?,
-                        _ => brain.semantic.store(mem)?,
-                    }
-                    count += 1;
+                    batch.push(mem);
                 }
             }
         }
         
         "csv" => {
-            // CSV import: content,tags (comma-separated)
+            // CSV import: content,tags[,type,created_at,strength] (as written by export)
+            use memory_brain::import::{import_csv, ImportOptions};
+
             let content = std::fs::read_to_string(input_path)?;
-            let mut lines = content.lines();
-            
-            // Skip header if it looks like one
-            if let Some(first) = lines.next() {
-                let lower = first.to_lowercase();
-                if !lower.contains("content") && !lower.contains("text") {
-                    // Not a header, process it
-                    if let Some(mem) = parse_csv_line(first, &default_tags, memory_type.clone(), brain) {
-                        match memory_type {
-                            MemoryType::Episodic => brain.episodic.store(mem)?,
-                            MemoryType::Semantic => brain.semantic.store(mem)?,
-                            MemoryType::Procedural => brain.procedural.store(mem)?,
-                            _ => brain.semantic.store(mem)?,
-                        }
-                        count += 1;
-                    }
-                }
-            }
-            
-            for line in lines {
-                if line.trim().is_empty() {
-                    continue;
-                }
-                if let Some(mem) = parse_csv_line(line, &default_tags, memory_type.clone(), brain) {
-                    match memory_type {
-                        MemoryType::Episodic => brain.episodic.store(mem)?,
-                        MemoryType::Semantic => brain.semantic.store(mem)?,
-                        MemoryType::Procedural => brain.procedural.store(mem)?,
-                        _ => brain.semantic.store(mem)?,
-                    }
-                    count += 1;
-                } else {
-                    errors += 1;
+            let options = ImportOptions { tags: default_tags.clone(), memory_type: memory_type.clone() };
+            let stats = import_csv(brain, &content, &options, |n| {
+                if !quiet {
+                    print!("\r📥 Imported {} memories...", n);
+                    let _ = std::io::stdout().flush();
                 }
-                
-                if !quiet && count % 100 == 0 {
-                    print!("\r📥 Imported {} memories...", count);
-                    std::io::stdout().flush()?;
+            })?;
+            count = stats.imported;
+            errors = stats.skipped + stats.failed;
+            if !quiet {
+                for reason in &stats.errors {
+                    eprintln!("❌ Skipped {}", reason);
                 }
             }
         }
@@ -1486,26 +2239,23 @@ fn cmd_import(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
                 }
                 
                 let mut mem = MemoryItem::new(text, None);
-                mem.embedding = Some(brain.embedder().embed(text));
+                mem.embedding = brain.embedder().try_embed_document(text).ok();
                 mem.tags = default_tags.clone();
                 mem.memory_type = memory_type.clone();
-                
-                match memory_type {
-                    MemoryType::Episodic => brain.episodic.store(mem)?,
-                    MemoryType::Semantic => brain.semantic.store(mem)?,
-                    MemoryType::Procedural => brain.procedural.store(mem)?,
-                    _ => brain.semantic.store(mem)?,
-                }
-                count += 1;
-                
-                if !quiet && count % 100 == 0 {
-                    print!("\r📥 Imported {} memories...", count);
-                    std::io::stdout().flush()?;
-                }
+                batch.push(mem);
             }
         }
     }
 
+    if !batch.is_empty() {
+        let report = brain.store_batch(batch);
+        count += report.stored;
+        errors += report.failed.len();
+        for (item, reason) in &report.failed {
+            if !quiet { eprintln!("❌ Error importing \"{}\": {}", truncate(&item.content, 40), reason); }
+        }
+    }
+
     if !quiet {
         println!("\r📥 Imported {} memories from {}        ", count, input_path);
         if errors > 0 {
@@ -1515,52 +2265,21 @@ fn cmd_import(brain: &mut Brain, args: &[String], quiet: bool) -> Result<(), Box
     Ok(())
 }
 
-fn parse_csv_line(line: &str, default_tags: &[String], memory_type: MemoryType, brain: &Brain) -> Option<MemoryItem> {
-    // Simple CSV parsing (content,tags)
-    // Handle quoted strings
-    let parts: Vec<&str> = if line.starts_with('"') {
-        // Quoted content
-        if let Some(end_quote) = line[1..].find('"') {
-            let content = &line[1..end_quote + 1];
-            let rest = &line[end_quote + 2..];
-            let tags_str = rest.trim_start_matches(',').trim();
-            vec![content, tags_str]
-        } else {
-            vec![line]
-        }
-    } else {
-        line.splitn(2, ',').collect()
-    };
-    
-    let content = parts.get(0)?.trim();
-    if content.is_empty() {
-        return None;
-    }
-    
-    let mut mem = MemoryItem::new(content, None);
-    mem.embedding = Some(brain.embedder().embed(content));
-    mem.memory_type = memory_type;
-    
-    // Parse tags if present
-    if let Some(tags_str) = parts.get(1) {
-        mem.tags = tags_str
-            .split(&[',', ';'][..])
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
-    }
-    mem.tags.extend(default_tags.iter().cloned());
-    
-    Some(mem)
+/// LLM chosen by `--provider/--model/--base-url` over the config; returns
+/// the remaining arguments
+fn llm_from_args(config: &Config, args: &[String]) -> (Box<dyn LlmProvider>, Vec<String>) {
+    let mut llm_config = config.llm.clone();
+    let rest = llm_config.apply_args(args);
+    (provider_from_config(&llm_config), rest)
 }
 
-fn cmd_chat(brain: Brain, _args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
-    let llm = auto_detect_provider();
+fn cmd_chat(brain: Brain, config: &Config, args: &[String], quiet: bool) -> Result<Brain, Box<dyn std::error::Error>> {
+    let (llm, _) = llm_from_args(config, args);
     let mut chat = MemoryChat::new(brain, llm);
 
     if !quiet {
         println!("🧠 Memory-Augmented Chat");
-        println!("Type 'quit' to exit, 'memories' to show context\n");
+        println!("Type 'quit' to exit, 'memories' to show context, 'clear' to reset the conversation\n");
     }
 
     loop {
@@ -1582,6 +2301,11 @@ fn cmd_chat(brain: Brain, _args: &[String], quiet: bool) -> Result<(), Box<dyn s
                 println!("👋 Goodbye!");
                 break;
             }
+            "clear" | "reset" => {
+                chat.clear_history();
+                println!("🧹 Conversation cleared\n");
+                continue;
+            }
             "memories" | "mem" => {
                 println!("\n📚 Recent memories in context:");
                 let memories = chat.brain_mut().recall("", 5);
@@ -1594,27 +2318,33 @@ fn cmd_chat(brain: Brain, _args: &[String], quiet: bool) -> Result<(), Box<dyn s
             _ => {}
         }
 
-        match chat.chat(input) {
-            Ok(response) => {
-                println!("\n🤖 {}\n", response);
+        print!("\n🤖 ");
+        io::stdout().flush()?;
+        let result = chat.chat_stream(input, &mut |chunk| {
+            print!("{}", chunk);
+            let _ = io::stdout().flush();
+        });
+        match result {
+            Ok(_) => {
+                println!("\n");
             }
             Err(e) => {
-                eprintln!("❌ Error: {}", e);
+                eprintln!("\n❌ Error: {}", e);
             }
         }
     }
 
-    Ok(())
+    Ok(chat.into_brain())
 }
 
-fn cmd_ask(brain: Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_ask(brain: Brain, config: &Config, args: &[String], quiet: bool) -> Result<Brain, Box<dyn std::error::Error>> {
+    let (llm, args) = llm_from_args(config, args);
     if args.is_empty() {
-        eprintln!("Usage: memory-brain ask <question>");
-        return Ok(());
+        eprintln!("Usage: memory-brain ask <question> [--provider P] [--model M] [--base-url URL]");
+        return Ok(brain);
     }
 
     let question = args.join(" ");
-    let llm = auto_detect_provider();
     let mut chat = MemoryChat::new(brain, llm);
 
     match chat.chat(&question) {
@@ -1630,17 +2360,17 @@ fn cmd_ask(brain: Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std
         }
     }
 
-    Ok(())
+    Ok(chat.into_brain())
 }
 
-fn cmd_learn(brain: Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_learn(brain: Brain, config: &Config, args: &[String], quiet: bool) -> Result<Brain, Box<dyn std::error::Error>> {
+    let (llm, args) = llm_from_args(config, args);
     if args.is_empty() {
         eprintln!("Usage: memory-brain learn <text to extract facts from>");
-        return Ok(());
+        return Ok(brain);
     }
 
     let text = args.join(" ");
-    let llm = auto_detect_provider();
     let mut chat = MemoryChat::new(brain, llm);
 
     if !quiet { println!("📖 Extracting facts..."); }
@@ -1659,20 +2389,62 @@ fn cmd_learn(brain: Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn s
         }
     }
 
-    Ok(())
+    Ok(chat.into_brain())
 }
 
-fn cmd_summarize(brain: Brain, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_summarize(brain: Brain, config: &Config, args: &[String], quiet: bool) -> Result<Brain, Box<dyn std::error::Error>> {
+    let (llm, args) = llm_from_args(config, args);
     if args.is_empty() {
         eprintln!("Usage: memory-brain summarize <topic>");
-        return Ok(());
+        eprintln!("       memory-brain summarize <topic> --all     Summarize every recalled memory on a topic");
+        eprintln!("       memory-brain summarize --all             Summarize every memory");
+        eprintln!("       memory-brain summarize --tag TAG [--all] Summarize all memories with a tag");
+        return Ok(brain);
+    }
+
+    let mut tag: Option<String> = None;
+    let mut all = false;
+    let mut topic_parts: Vec<&str> = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--all" | "-a" => all = true,
+            "--tag" | "-t" if i + 1 < args.len() => {
+                tag = Some(args[i + 1].clone());
+                i += 1;
+            }
+            s if s.starts_with("--tag=") => tag = Some(s.trim_start_matches("--tag=").to_string()),
+            s => topic_parts.push(s),
+        }
+        i += 1;
     }
 
-    let topic = args.join(" ");
-    let llm = auto_detect_provider();
     let mut chat = MemoryChat::new(brain, llm);
 
-    match chat.summarize_memories(&topic) {
+    // --tag / --all cover the newest matching memories (map-reduce)
+    let topic_text = topic_parts.join(" ");
+    let (topic, result) = match (tag, all) {
+        (Some(tag), _) => (format!("tag:{}", tag), chat.summarize_tag(&tag)),
+        (None, true) if !topic_text.is_empty() => {
+            let result = chat
+                .summarize_memories_mapreduce(&topic_text, memory_brain::llm::DEFAULT_MEMORIES_PER_CHUNK)
+                .map(|report| {
+                    if !quiet {
+                        println!("📚 Summarized {} memories in {} chunks", report.memories, report.chunks);
+                    }
+                    report.summary
+                });
+            (topic_text, result)
+        }
+        (None, true) => ("all memories".to_string(), chat.summarize_all()),
+        (None, false) => {
+            let result = chat.summarize_memories(&topic_text);
+            (topic_text, result)
+        }
+    };
+
+    match result {
         Ok(summary) => {
             if !quiet {
                 println!("📝 Summary of '{}':\n{}", topic, summary);
@@ -1685,10 +2457,10 @@ fn cmd_summarize(brain: Brain, args: &[String], quiet: bool) -> Result<(), Box<d
         }
     }
 
-    Ok(())
+    Ok(chat.into_brain())
 }
 
-fn cmd_interactive(brain: &mut Brain) -> Result<(), Box<dyn std::error::Error>> {
+fn cmd_interactive(brain: &mut Brain, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
     println!("🧠 Memory Brain Interactive Mode");
     println!("Commands: store, recall, search, list, stats, help, quit\n");
 
@@ -1723,28 +2495,28 @@ fn cmd_interactive(brain: &mut Brain) -> Result<(), Box<dyn std::error::Error>>
             }
             "store" | "s" => {
                 if parts.len() > 1 {
-                    cmd_store(brain, &parts[1..], false)?;
+                    cmd_store(brain, config, &parts[1..], false)?;
                 }
             }
             "recall" | "r" => {
                 if parts.len() > 1 {
-                    cmd_recall(brain, &parts[1..], false)?;
+                    cmd_recall(brain, config, &parts[1..], false, false)?;
                 }
             }
             "search" | "sem" => {
                 if parts.len() > 1 {
-                    cmd_semantic_search(brain, &parts[1..], false)?;
+                    cmd_semantic_search(brain, config, &parts[1..], false, false)?;
                 }
             }
             "list" | "ls" | "l" => {
-                cmd_list(brain, &parts[1..], false)?;
+                cmd_list(brain, &parts[1..], false, false)?;
             }
             "stats" | "status" => {
-                cmd_stats(brain, false)?;
+                cmd_stats(brain, config, false, false)?;
             }
             _ => {
                 // Default: treat as store
-                cmd_store(brain, &parts, false)?;
+                cmd_store(brain, config, &parts, false)?;
             }
         }
         println!();
@@ -1804,15 +2576,24 @@ MEMORY COMMANDS:
     show, cat         Show memory details by ID
     delete, rm        Delete a memory
     stats, status     Show brain statistics
-    export            Export memories to JSON
-    import            Import memories from JSON
+    export            Export memories (--format json|csv|markdown)
+    import            Import memories (json, jsonl, csv, txt, md)
+    embeddings        Export/import embeddings (.npy or raw f32 + id list)
+    snapshot          Write a lossless backup of every memory and index
+    restore           Replace the database with a snapshot
+    compress          Report int8 embedding savings (--execute to rewrite stored embeddings)
+    merge, dedup      Preview near-duplicate pairs (--show N, --threshold=0.9, --execute to merge)
     sleep             Run memory consolidation
+    review            Memories due for spaced-repetition review today
+    onthisday, otd    Episodic memories from this date in past years (--window N, --date YYYY-MM-DD)
 
 LLM COMMANDS:
     chat, c           Interactive chat with memory context
     ask <question>    One-shot question with memory context
     learn <text>      Extract and store facts from text
-    summarize <topic> Summarize memories on a topic
+    summarize <topic> Summarize memories on a topic (--all, --tag TAG for everything)
+                      LLM commands take --provider ollama|openai|mlx|echo,
+                      --model NAME and --base-url URL (default: config/auto-detect)
 
 VISUAL / VLM COMMANDS:
     visual store      Store image with CLIP embedding
@@ -1822,10 +2603,12 @@ VISUAL / VLM COMMANDS:
 
 OTHER:
     interactive, i    Interactive REPL mode (no LLM)
+    reindex           Rebuild indexes (--reembed, --embedder tfidf)
     help              Show this help
 
 OPTIONS:
     -q, --quiet       Suppress startup messages
+    --json            JSON output for recall, search, list, stats
     -n, --limit N     Limit results (default: 5)
     -t, --type TYPE   Memory type: semantic|episodic|procedural
     --tags TAG1,TAG2  Add tags to memory
@@ -1837,6 +2620,7 @@ EXAMPLES:
     memory-brain ask "What do I know about Rust?"
     memory-brain learn "Python was created by Guido van Rossum in 1991"
     memory-brain summarize "programming languages"
+    memory-brain summarize --tag work --all
 
 LLM BACKENDS (auto-detected):
     1. Ollama (local)  - ollama run llama3.2
@@ -1856,9 +2640,9 @@ SERVER MODE:
 }
 
 /// Start HTTP server
-fn cmd_serve(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
-    let mut host = "127.0.0.1".to_string();
-    let mut port: u16 = 3030;
+fn cmd_serve(mut config: Config, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut host = config.server.host.clone();
+    let mut port: u16 = config.server.port;
 
     let mut i = 0;
     while i < args.len() {
@@ -1872,7 +2656,7 @@ fn cmd_serve(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
             }
             "--port" | "-p" => {
                 if i + 1 < args.len() {
-                    port = args[i + 1].parse().unwrap_or(3030);
+                    port = args[i + 1].parse().unwrap_or(config.server.port);
                     i += 2;
                     continue;
                 }
@@ -1882,18 +2666,17 @@ fn cmd_serve(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
         i += 1;
     }
 
-    let db_path = dirs::data_local_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("memory-brain")
-        .join("coredb");
+    // CLI flags override config/env
+    config.server.host = host;
+    config.server.port = port;
 
-    if let Some(parent) = db_path.parent() {
+    if let Some(parent) = config.db_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
 
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
-        memory_brain::server::start_server(&host, port, db_path.to_str().unwrap()).await
+        memory_brain::server::start_server(&config).await
     })
 }
 
@@ -1911,6 +2694,9 @@ fn cmd_sam(args: &[String], db_path: &str, quiet: bool) -> Result<(), Box<dyn st
         eprintln!("  sam project <name> <details>");
         eprintln!("  sam recall <query>      - Search Sam's memories");
         eprintln!("  sam stats               - Show Sam's brain stats");
+        eprintln!("  sam sleep               - Apply forgetting (conversations fade fastest)");
+        eprintln!("  sam export <file.json>  - Export Sam's memories (backup/migration)");
+        eprintln!("  sam import <file.json>  - Import memories from a sam export");
         return Ok(());
     }
     
@@ -2035,6 +2821,35 @@ fn cmd_sam(args: &[String], db_path: &str, quiet: bool) -> Result<(), Box<dyn st
             println!("{}", stats);
         }
         
+        "sleep" => {
+            let report = sam.sleep()?;
+            if !quiet {
+                println!("😴 Sam slept: {} memories faded, {} forgotten", report.decayed, report.forgotten);
+            }
+        }
+        
+        "export" => {
+            let Some(path) = args.get(1) else {
+                eprintln!("Usage: sam export <file.json>");
+                return Ok(());
+            };
+            let count = sam.export(path)?;
+            if !quiet {
+                println!("📤 Exported {} Sam memories to {}", count, path);
+            }
+        }
+        
+        "import" => {
+            let Some(path) = args.get(1) else {
+                eprintln!("Usage: sam import <file.json>");
+                return Ok(());
+            };
+            let count = sam.import(path)?;
+            if !quiet {
+                println!("📥 Imported {} Sam memories from {}", count, path);
+            }
+        }
+        
         _ => {
             eprintln!("Unknown sam command: {}", args[0]);
             eprintln!("Run 'memory-brain sam' for help");
@@ -2046,24 +2861,17 @@ fn cmd_sam(args: &[String], db_path: &str, quiet: bool) -> Result<(), Box<dyn st
 
 // ============ Visual Memory Commands ============
 
-fn cmd_visual(args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
-    use memory_brain::clip_onnx::{MockClipProvider, ClipServerProvider};
+fn cmd_visual(brain: &mut Brain, config: &Config, args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+    use memory_brain::clip_onnx::{MockClipProvider, ClipServerProvider, FallbackClipProvider, OnnxClipProvider};
     use memory_brain::visual::ClipProvider;
     use memory_brain::visual_storage::VisualStorage;
     use memory_brain::vlm::{OllamaVlm, VlmProvider};
     use std::sync::Arc;
     use tokio::sync::RwLock;
     
-    // Default CLIP server URL
-    let server_url = std::env::var("CLIP_SERVER_URL")
-        .unwrap_or_else(|_| "http://localhost:5050".to_string());
-    
-    // DB path
-    let db_path = std::env::var("MEMORY_BRAIN_DB")
-        .unwrap_or_else(|_| {
-            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-            format!("{}/.memory-brain/visual.db", home)
-        });
+    // CLIP server URL and DB path (config file, CLIP_SERVER_URL / MEMORY_BRAIN_DB env)
+    let server_url = config.visual.clip_server_url.clone();
+    let db_path = config.visual.db_path.to_string_lossy().to_string();
     
     if args.is_empty() {
         println!("🖼️ Visual Memory - Brain-inspired image storage");
@@ -2071,12 +2879,15 @@ fn cmd_visual(args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Er
         println!("Usage:");
         println!("  memory-brain visual store <image_path> [--desc \"description\"] [--tags tag1,tag2]");
         println!("  memory-brain visual store <image_path> --auto          # VLM auto-description");
+        println!("  memory-brain visual store <image_path> --auto --remember  # Also recallable as text");
         println!("  memory-brain visual recall <query>     # Search images by text");
         println!("  memory-brain visual similar <image>    # Find similar images");
         println!("  memory-brain visual list               # List all visual memories");
         println!("  memory-brain visual show <id>          # Show memory details");
+        println!("  memory-brain visual delete <id>        # Delete a visual memory");
         println!("  memory-brain visual stats              # Show statistics");
         println!();
+        println!("CLIP Model:  {} (set CLIP_MODEL_DIR, needs --features clip-onnx)", config.visual.clip_model_dir.display());
         println!("CLIP Server: {} (set CLIP_SERVER_URL to change)", server_url);
         println!("DB: {} (set MEMORY_BRAIN_DB to change)", db_path);
         println!();
@@ -2088,20 +2899,44 @@ fn cmd_visual(args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Er
         return Ok(());
     }
     
-    // Try to connect to CLIP server, fallback to mock
-    let clip: Arc<dyn ClipProvider> = match ClipServerProvider::new(&server_url) {
-        Ok(provider) => {
-            if !quiet {
-                eprintln!("🔗 CLIP server: {}", server_url);
+    // Prefer local ONNX CLIP when the model is installed
+    let model_dir = &config.visual.clip_model_dir;
+    let onnx = if OnnxClipProvider::is_available(model_dir) {
+        match OnnxClipProvider::new(model_dir) {
+            Ok(provider) => Some(provider),
+            Err(e) => {
+                if !quiet {
+                    eprintln!("⚠️ ONNX CLIP unavailable ({}), trying CLIP server", e);
+                }
+                None
             }
-            Arc::new(provider)
         }
-        Err(_) => {
-            if !quiet {
-                eprintln!("⚠️ CLIP server unavailable, using hash embeddings (install clip_server.py for real CLIP)");
-            }
-            Arc::new(MockClipProvider::new(512))
+    } else {
+        None
+    };
+    
+    // Otherwise try to connect to CLIP server; searches fall back to mock
+    // (also if the server drops mid-session, after retrying with backoff)
+    let mut real_clip: Option<Arc<dyn ClipProvider>> = None;
+    let clip: Arc<dyn ClipProvider> = if let Some(provider) = onnx {
+        if !quiet {
+            eprintln!("🧠 ONNX CLIP: {}", model_dir.display());
+        }
+        let provider: Arc<dyn ClipProvider> = Arc::new(provider);
+        real_clip = Some(provider.clone());
+        provider
+    } else if let Ok(provider) = ClipServerProvider::new(&server_url) {
+        if !quiet {
+            eprintln!("🔗 CLIP server: {}", server_url);
         }
+        let provider: Arc<dyn ClipProvider> = Arc::new(provider);
+        real_clip = Some(provider.clone());
+        Arc::new(FallbackClipProvider::new(provider, Arc::new(MockClipProvider::new(512))))
+    } else {
+        if !quiet {
+            eprintln!("⚠️ CLIP server unavailable, using hash embeddings (install clip_server.py or an ONNX CLIP model for real CLIP)");
+        }
+        Arc::new(MockClipProvider::new(512))
     };
     
     // Create async runtime for CoreDB operations
@@ -2110,7 +2945,7 @@ fn cmd_visual(args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Er
     match args[0].as_str() {
         "store" | "add" => {
             if args.len() < 2 {
-                eprintln!("Usage: memory-brain visual store <image_path> [--desc \"...\"] [--tags ...] [--auto]");
+                eprintln!("Usage: memory-brain visual store <image_path> [--desc \"...\"] [--tags ...] [--auto] [--remember]");
                 return Ok(());
             }
             
@@ -2119,8 +2954,19 @@ fn cmd_visual(args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Er
                 eprintln!("❌ Image not found: {}", args[1]);
                 return Ok(());
             }
+
+            // Hash embeddings would never match real CLIP queries later, so
+            // only real CLIP embeddings are stored (no mock fallback)
+            let Some(clip) = real_clip else {
+                return Err(format!(
+                    "CLIP not available (no ONNX model at {}, no server at {}); refusing to store hash embeddings",
+                    model_dir.display(),
+                    server_url
+                ).into());
+            };
             
             let auto_describe = args.iter().any(|a| a == "--auto" || a == "-a");
+            let remember_text = args.iter().any(|a| a == "--remember" || a == "-r");
             
             // Parse description (or auto-generate)
             let desc = if auto_describe {
@@ -2171,7 +3017,7 @@ fn cmd_visual(args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Er
             let _embedding = clip.embed_image(image_path)?;
             
             // Store in CoreDB
-            rt.block_on(async {
+            let (storage, memory) = rt.block_on(async {
                 let db = Arc::new(RwLock::new(
                     open_visual_db(&db_path).await
                 ));
@@ -2189,18 +3035,30 @@ fn cmd_visual(args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Er
                     emotion,
                 ).await.expect("Failed to store visual memory");
                 
+                (storage, memory)
+            });
+            
+            if !quiet {
+                println!("✅ Stored visual memory: {}", image_path.display());
+                println!("   Description: {}", desc);
+                if !tags.is_empty() {
+                    println!("   Tags: {}", tags.join(", "));
+                }
+                if !memory.linked_visuals.is_empty() {
+                    println!("   Linked to {} similar images", memory.linked_visuals.len());
+                }
+                println!("   ID: {}", memory.id);
+            }
+            
+            // Index the description in the text brain (outside the visual runtime,
+            // since Brain storage drives its own runtime)
+            if remember_text {
+                let item = brain.remember_visual(&memory)?;
+                rt.block_on(storage.link_text_memory(memory.id, item.id))?;
                 if !quiet {
-                    println!("✅ Stored visual memory: {}", image_path.display());
-                    println!("   Description: {}", desc);
-                    if !tags.is_empty() {
-                        println!("   Tags: {}", tags.join(", "));
-                    }
-                    if !memory.linked_visuals.is_empty() {
-                        println!("   Linked to {} similar images", memory.linked_visuals.len());
-                    }
-                    println!("   ID: {}", memory.id);
+                    println!("   Text memory: #{}", &item.id.to_string()[..8]);
                 }
-            });
+            }
         }
         
         "recall" | "search" | "find" => {
@@ -2376,6 +3234,36 @@ fn cmd_visual(args: &[String], quiet: bool) -> Result<(), Box<dyn std::error::Er
             });
         }
         
+        "delete" | "rm" => {
+            let Some(id_str) = args.get(1) else {
+                eprintln!("Usage: memory-brain visual delete <id>");
+                return Ok(());
+            };
+            let Ok(id) = uuid::Uuid::parse_str(id_str) else {
+                eprintln!("❌ Invalid UUID: {}", id_str);
+                return Ok(());
+            };
+            
+            rt.block_on(async {
+                let db = Arc::new(RwLock::new(
+                    open_visual_db(&db_path).await
+                ));
+                let storage = VisualStorage::new(db, clip.clone(), "visual_brain").await
+                    .expect("Failed to create VisualStorage");
+                let _ = storage.load_cache().await;
+                
+                match storage.delete(id).await {
+                    Ok(true) => {
+                        if !quiet {
+                            println!("🗑️  Deleted visual memory: {}", id);
+                        }
+                    }
+                    Ok(false) => println!("❌ Memory not found: {}", id_str),
+                    Err(e) => eprintln!("❌ Error: {}", e),
+                }
+            });
+        }
+        
         "stats" => {
             rt.block_on(async {
                 let db = Arc::new(RwLock::new(
@@ -3,7 +3,7 @@
 //! Find and merge similar memories to reduce redundancy.
 //! Uses cosine similarity to detect near-duplicates.
 
-use crate::{Brain, MemoryItem, cosine_similarity};
+use crate::{Brain, MemoryItem, MemoryType, cosine_similarity};
 use std::collections::HashSet;
 use uuid::Uuid;
 
@@ -21,6 +21,13 @@ pub struct MergeConfig {
     pub merge_tags: bool,
     /// Dry run mode (don't actually merge)
     pub dry_run: bool,
+    /// Cluster memories across episodic, semantic and procedural stores
+    ///
+    /// The survivor takes the most permanent type in its cluster
+    /// (procedural > semantic > episodic). Off by default: only memories
+    /// of the same type are merged, so an episode never silently folds
+    /// into a fact (`merge --cross-type` opts in).
+    pub cross_type: bool,
 }
 
 impl Default for MergeConfig {
@@ -31,6 +38,7 @@ impl Default for MergeConfig {
             keep_newest: true,
             merge_tags: true,
             dry_run: false,
+            cross_type: false,
         }
     }
 }
@@ -65,6 +73,38 @@ pub struct MergeResult {
     pub space_saved_bytes: usize,
     /// Clusters with details
     pub clusters: Vec<MemoryCluster>,
+    /// Every (cluster seed, member, similarity) pair that put a memory in a cluster
+    pub pairs: Vec<(Uuid, Uuid, f32)>,
+}
+
+impl MergeResult {
+    /// Content of a clustered memory
+    fn content_of(&self, id: &Uuid) -> Option<&str> {
+        self.clusters
+            .iter()
+            .flat_map(|c| std::iter::once(&c.primary).chain(c.similar.iter()))
+            .find(|m| m.id == *id)
+            .map(|m| m.content.as_str())
+    }
+
+    /// The first `limit` pairs, one line each with both contents and similarity
+    pub fn pairs_preview(&self, limit: usize) -> String {
+        let mut out = String::new();
+        for (a, b, similarity) in self.pairs.iter().take(limit) {
+            out.push_str(&format!(
+                "  {:.0}%  \"{}\" (#{})\n       ↔ \"{}\" (#{})\n",
+                similarity * 100.0,
+                truncate(self.content_of(a).unwrap_or("?"), 50),
+                &a.to_string()[..8],
+                truncate(self.content_of(b).unwrap_or("?"), 50),
+                &b.to_string()[..8]
+            ));
+        }
+        if self.pairs.len() > limit {
+            out.push_str(&format!("  ... and {} more pairs (--show N)\n", self.pairs.len() - limit));
+        }
+        out
+    }
 }
 
 impl std::fmt::Display for MergeResult {
@@ -123,7 +163,10 @@ impl<'a> MemoryMerger<'a> {
     }
 
     /// Find similar memories and optionally merge them
-    pub fn find_similar(&mut self) -> MergeResult {
+    ///
+    /// Fails on the first cluster that could not be merged; clusters
+    /// merged before it stay merged.
+    pub fn find_similar(&mut self) -> Result<MergeResult, Box<dyn std::error::Error>> {
         let mut result = MergeResult::default();
         
         // Collect all memories with embeddings
@@ -135,6 +178,9 @@ impl<'a> MemoryMerger<'a> {
         if let Ok(items) = self.brain.episodic.search("", 10000) {
             memories.extend(items);
         }
+        if let Ok(items) = self.brain.procedural.search("", 10000) {
+            memories.extend(items);
+        }
 
         // Filter to only those with embeddings
         let memories: Vec<MemoryItem> = memories
@@ -143,15 +189,16 @@ impl<'a> MemoryMerger<'a> {
             .collect();
 
         if memories.len() < 2 {
-            return result;
+            return Ok(result);
         }
 
         // Find clusters using greedy clustering
-        let clusters = self.cluster_similar(&memories);
+        let (clusters, pairs) = self.cluster_similar(&memories);
         
         result.clusters_found = clusters.len();
         result.mergeable_count = clusters.iter().map(|c| c.similar.len()).sum();
         result.clusters = clusters;
+        result.pairs = pairs;
 
         // Estimate space savings
         for cluster in &result.clusters {
@@ -162,15 +209,18 @@ impl<'a> MemoryMerger<'a> {
 
         // Perform actual merge if not dry run
         if !self.config.dry_run {
-            result.merged_count = self.execute_merge(&result.clusters);
+            result.merged_count = self.execute_merge(&result.clusters)?;
         }
 
-        result
+        Ok(result)
     }
 
     /// Cluster similar memories together
-    fn cluster_similar(&self, memories: &[MemoryItem]) -> Vec<MemoryCluster> {
+    ///
+    /// Also returns the (seed, member, similarity) pairs behind each cluster.
+    fn cluster_similar(&self, memories: &[MemoryItem]) -> (Vec<MemoryCluster>, Vec<(Uuid, Uuid, f32)>) {
         let mut clusters: Vec<MemoryCluster> = Vec::new();
+        let mut pairs: Vec<(Uuid, Uuid, f32)> = Vec::new();
         let mut assigned: HashSet<Uuid> = HashSet::new();
 
         for i in 0..memories.len() {
@@ -185,6 +235,9 @@ impl<'a> MemoryMerger<'a> {
                 if assigned.contains(&memories[j].id) {
                     continue;
                 }
+                if !self.config.cross_type && memories[j].memory_type != memories[i].memory_type {
+                    continue;
+                }
 
                 if let Some(emb_j) = &memories[j].embedding {
                     let similarity = cosine_similarity(emb_i, emb_j);
@@ -198,6 +251,7 @@ impl<'a> MemoryMerger<'a> {
 
             if similar.len() >= self.config.min_cluster_size - 1 {
                 assigned.insert(memories[i].id);
+                pairs.extend(similar.iter().map(|(m, s)| (memories[i].id, m.id, *s)));
                 
                 let avg_sim = if similar.is_empty() {
                     1.0
@@ -227,36 +281,46 @@ impl<'a> MemoryMerger<'a> {
             }
         }
 
-        clusters
+        (clusters, pairs)
     }
 
     /// Execute the merge operation
-    fn execute_merge(&mut self, clusters: &[MemoryCluster]) -> usize {
+    ///
+    /// Each cluster collapses into its primary, which is rewritten with the
+    /// combined fields; the other members are deleted from their stores and
+    /// indexes.
+    fn execute_merge(&mut self, clusters: &[MemoryCluster]) -> Result<usize, Box<dyn std::error::Error>> {
         let mut merged_count = 0;
 
         for cluster in clusters {
-            // Merge tags if configured
-            if self.config.merge_tags {
-                let mut all_tags: HashSet<String> = cluster.primary.tags.iter().cloned().collect();
-                for item in &cluster.similar {
-                    all_tags.extend(item.tags.iter().cloned());
-                }
-                // Note: We'd need to update the primary's tags in the database
-                // This is simplified - full implementation would update DB
-            }
+            let survivor = self.combine(cluster);
+            let removed: Vec<Uuid> = cluster.similar.iter().map(|m| m.id).collect();
+
+            self.brain.replace_merged(survivor, &removed)?;
+            merged_count += removed.len();
+        }
 
-            // Mark similar memories for deletion (keep primary)
-            // Note: Full implementation would delete from storage
-            // For now, we just count them as merged
-            for _item in &cluster.similar {
-                // TODO: Implement storage.delete() access
-                // The memories are identified, but actual deletion needs
-                // direct storage access or a Brain::delete_memory() method
-                merged_count += 1;
+        Ok(merged_count)
+    }
+
+    /// Fold a cluster into a single memory based on its primary
+    fn combine(&self, cluster: &MemoryCluster) -> MemoryItem {
+        let mut survivor = cluster.primary.clone();
+        let primary_tags = survivor.tags.clone();
+
+        for item in &cluster.similar {
+            if self.config.cross_type && permanence(&item.memory_type) > permanence(&survivor.memory_type) {
+                survivor.memory_type = item.memory_type.clone();
             }
+            survivor.merge_into(item.clone());
+        }
+        if !self.config.merge_tags {
+            survivor.tags = primary_tags;
         }
 
-        merged_count
+        // Links between cluster members now point at the survivor itself
+        survivor.associations.retain(|a| !cluster.similar.iter().any(|m| m.id == *a));
+        survivor
     }
 
     /// Set similarity threshold
@@ -272,8 +336,18 @@ impl<'a> MemoryMerger<'a> {
     }
 }
 
+/// How long-lived a memory type is; cross-type merges keep the highest
+fn permanence(memory_type: &MemoryType) -> u8 {
+    match memory_type {
+        MemoryType::Working => 0,
+        MemoryType::Episodic => 1,
+        MemoryType::Semantic => 2,
+        MemoryType::Procedural => 3,
+    }
+}
+
 /// Quick function to analyze duplicates
-pub fn analyze_duplicates(brain: &mut Brain, threshold: f32) -> MergeResult {
+pub fn analyze_duplicates(brain: &mut Brain, threshold: f32) -> Result<MergeResult, Box<dyn std::error::Error>> {
     MemoryMerger::new(brain)
         .threshold(threshold)
         .dry_run(true)
@@ -281,7 +355,7 @@ pub fn analyze_duplicates(brain: &mut Brain, threshold: f32) -> MergeResult {
 }
 
 /// Quick function to merge duplicates
-pub fn merge_duplicates(brain: &mut Brain, threshold: f32) -> MergeResult {
+pub fn merge_duplicates(brain: &mut Brain, threshold: f32) -> Result<MergeResult, Box<dyn std::error::Error>> {
     MemoryMerger::new(brain)
         .threshold(threshold)
         .dry_run(false)
@@ -305,6 +379,80 @@ mod tests {
         assert!(config.keep_newest);
         assert!(config.merge_tags);
         assert!(!config.dry_run);
+        assert!(!config.cross_type);
+    }
+
+    #[test]
+    fn test_cross_type_merge_keeps_one_survivor() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let mut brain = Brain::with_embedder(dir.path().join("merge.db").to_str().unwrap(), embedder.clone()).unwrap();
+
+        let fact = "The staging database runs on port 5433";
+        let mut semantic = MemoryItem::new(fact, None).with_tags(vec!["infra".to_string()]);
+        semantic.embedding = Some(crate::Embedder::embed(embedder.as_ref(), fact));
+        semantic.access_count = 2;
+        semantic.strength = 0.4;
+        let mut episodic = MemoryItem::new(fact, None).with_tags(vec!["chat".to_string()]);
+        episodic.embedding = semantic.embedding.clone();
+        episodic.access_count = 3;
+        episodic.strength = 0.9;
+        brain.semantic.store(semantic.clone()).unwrap();
+        brain.episodic.store(episodic.clone()).unwrap();
+
+        // Same-type mode leaves them alone
+        let result = MemoryMerger::new(&mut brain).threshold(0.9).dry_run(true).find_similar().unwrap();
+        assert_eq!(result.clusters_found, 0);
+
+        let config = MergeConfig { similarity_threshold: 0.9, cross_type: true, ..Default::default() };
+        let result = MemoryMerger::with_config(&mut brain, config).find_similar().unwrap();
+        assert_eq!(result.merged_count, 1);
+
+        let mut survivors = brain.semantic.search("", 10).unwrap();
+        survivors.extend(brain.episodic.search("", 10).unwrap());
+        assert_eq!(survivors.len(), 1);
+        let survivor = &survivors[0];
+        assert_eq!(survivor.memory_type, MemoryType::Semantic);
+        assert!(survivor.tags.contains(&"infra".to_string()));
+        assert!(survivor.tags.contains(&"chat".to_string()));
+        assert_eq!(survivor.access_count, 5);
+        assert_eq!(survivor.strength, 0.9);
+        assert_eq!(brain.keyword_index.search_or("staging"), vec![survivor.id]);
+    }
+
+    #[test]
+    fn test_dry_run_lists_near_duplicate_pair() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let mut brain = Brain::with_embedder(dir.path().join("pairs.db").to_str().unwrap(), embedder.clone()).unwrap();
+
+        let mut ids = Vec::new();
+        for content in [
+            "The staging database runs on port 5433",
+            "Staging database runs on port 5433",
+            "Grandma bakes apple pie",
+        ] {
+            let mut item = MemoryItem::new(content, None);
+            item.embedding = Some(crate::Embedder::embed(embedder.as_ref(), content));
+            ids.push(item.id);
+            brain.semantic.store(item).unwrap();
+        }
+
+        let result = MemoryMerger::new(&mut brain).threshold(0.9).dry_run(true).find_similar().unwrap();
+        assert_eq!(result.merged_count, 0);
+        assert_eq!(result.pairs.len(), 1);
+        let (a, b, similarity) = result.pairs[0];
+        let mut pair = vec![a, b];
+        pair.sort();
+        let mut expected = vec![ids[0], ids[1]];
+        expected.sort();
+        assert_eq!(pair, expected);
+        assert!(similarity >= 0.9);
+
+        let preview = result.pairs_preview(10);
+        assert!(preview.contains("staging database"));
+        assert!(!preview.contains("apple pie"));
+        assert!(result.pairs_preview(0).contains("1 more pairs"));
     }
 
     #[test]
@@ -315,6 +463,7 @@ mod tests {
             merged_count: 0,
             space_saved_bytes: 2048,
             clusters: vec![],
+            pairs: vec![],
         };
         
         let display = format!("{}", result);
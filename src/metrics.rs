@@ -0,0 +1,119 @@
+//! Prometheus metrics for the HTTP server
+//!
+//! Request latencies are recorded per route into fixed-bucket histograms by
+//! a middleware; everything else is read from the brain when `/metrics` is
+//! scraped. Output follows the text exposition format (version 0.0.4).
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+/// Content type of the text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Upper bounds (seconds) of the request latency buckets
+pub const LATENCY_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0];
+
+/// Cumulative counts per bucket, plus the implicit `+Inf` bucket
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn record(&mut self, seconds: f64) {
+        for (bucket, &le) in self.buckets.iter_mut().zip(LATENCY_BUCKETS.iter()) {
+            if seconds <= le {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+/// Request latency histograms keyed by route, then method
+#[derive(Default)]
+pub struct RequestMetrics {
+    routes: Mutex<BTreeMap<String, BTreeMap<String, Histogram>>>,
+}
+
+impl RequestMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one request; only the first request of a route allocates
+    pub fn observe(&self, route: &str, method: &str, seconds: f64) {
+        let mut routes = self.routes.lock().unwrap();
+        if let Some(histogram) = routes.get_mut(route).and_then(|methods| methods.get_mut(method)) {
+            histogram.record(seconds);
+            return;
+        }
+        routes
+            .entry(route.to_string())
+            .or_default()
+            .entry(method.to_string())
+            .or_default()
+            .record(seconds);
+    }
+
+    /// Append the `memory_brain_request_duration_seconds` histogram
+    pub fn render(&self, out: &mut String) {
+        let name = "memory_brain_request_duration_seconds";
+        write_header(out, name, "histogram", "HTTP request latency by route");
+        let routes = self.routes.lock().unwrap();
+        for (route, method, histogram) in routes
+            .iter()
+            .flat_map(|(route, methods)| methods.iter().map(move |(method, h)| (route, method, h)))
+        {
+            for (&count, &le) in histogram.buckets.iter().zip(LATENCY_BUCKETS.iter()) {
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{{route=\"{}\",method=\"{}\",le=\"{}\"}} {}",
+                    name, escape_label(route), method, le, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_bucket{{route=\"{}\",method=\"{}\",le=\"+Inf\"}} {}",
+                name, escape_label(route), method, histogram.count
+            );
+            let _ = writeln!(out, "{}_sum{{route=\"{}\",method=\"{}\"}} {}", name, escape_label(route), method, histogram.sum);
+            let _ = writeln!(out, "{}_count{{route=\"{}\",method=\"{}\"}} {}", name, escape_label(route), method, histogram.count);
+        }
+    }
+}
+
+/// `# HELP` and `# TYPE` lines for a metric family
+pub fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// One sample line, e.g. `name{type="semantic"} 42`
+pub fn write_sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        out.push('{');
+        for (i, (key, val)) in labels.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{}=\"{}\"", key, escape_label(val));
+        }
+        out.push('}');
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+/// Escape backslashes, quotes and newlines in a label value
+fn escape_label(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains(['\\', '"', '\n']) {
+        value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n").into()
+    } else {
+        value.into()
+    }
+}
@@ -3,7 +3,8 @@
 //! Generates an interactive HTML visualization of memory connections
 //! using D3.js force-directed graph.
 
-use crate::{Brain, MemoryItem, cosine_similarity};
+use crate::{Brain, MemoryItem, MemoryType, cosine_similarity};
+use chrono::{DateTime, Utc};
 use std::collections::{HashMap, HashSet};
 
 /// Node in the mind map
@@ -32,6 +33,64 @@ pub struct MindMap {
     pub edges: Vec<MapEdge>,
 }
 
+/// Default number of k-means clusters for `ClusterMode::Embedding`
+pub const DEFAULT_CLUSTERS: usize = 8;
+
+/// Iteration cap for k-means (it usually settles well before this)
+const KMEANS_ITERATIONS: usize = 25;
+
+/// How nodes are assigned to color groups
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClusterMode {
+    /// One group per first tag (untagged memories share "general")
+    #[default]
+    FirstTag,
+    /// k-means over embeddings into at most `k` groups
+    Embedding(usize),
+}
+
+impl ClusterMode {
+    /// Parse `tag` or `embedding` (which uses `k` clusters)
+    pub fn parse(mode: &str, k: usize) -> Option<Self> {
+        match mode {
+            "tag" | "tags" => Some(Self::FirstTag),
+            "embedding" | "kmeans" => Some(Self::Embedding(k.max(1))),
+            _ => None,
+        }
+    }
+}
+
+/// Which memories a mind map may include (empty = all)
+#[derive(Debug, Clone, Default)]
+pub struct MapFilter {
+    /// Every tag must match one of the memory's tags (case-insensitive substring)
+    pub tags: Vec<String>,
+    /// Only memories created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only memories created at or before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Only memories of these types
+    pub types: Vec<MemoryType>,
+}
+
+impl MapFilter {
+    /// True when no filter is set
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.since.is_none() && self.until.is_none() && self.types.is_empty()
+    }
+
+    pub fn matches(&self, item: &MemoryItem) -> bool {
+        let tags_match = self.tags.iter().all(|tag| {
+            let tag = tag.to_lowercase();
+            item.tags.iter().any(|t| t.to_lowercase().contains(&tag))
+        });
+        tags_match
+            && !matches!(self.since, Some(since) if item.created_at < since)
+            && !matches!(self.until, Some(until) if item.created_at > until)
+            && (self.types.is_empty() || self.types.contains(&item.memory_type))
+    }
+}
+
 impl MindMap {
     /// Build mind map from brain memories
     pub fn from_brain(brain: &Brain, limit: usize, threshold: f32) -> Self {
@@ -48,20 +107,10 @@ impl MindMap {
             return Self { nodes, edges };
         }
 
-        // Assign groups based on tags
-        let mut tag_groups: HashMap<String, usize> = HashMap::new();
-        let mut next_group = 0;
+        let groups = assign_groups(&memories, ClusterMode::FirstTag);
 
         // Create nodes
-        for memory in &memories {
-            let primary_tag = memory.tags.first().cloned().unwrap_or_else(|| "general".to_string());
-            
-            let group = *tag_groups.entry(primary_tag.clone()).or_insert_with(|| {
-                let g = next_group;
-                next_group += 1;
-                g
-            });
-
+        for (memory, group) in memories.iter().zip(groups) {
             let label = truncate(&memory.content, 20);
             
             nodes.push(MapNode {
@@ -108,64 +157,72 @@ impl MindMap {
         Self { nodes, edges }
     }
 
-    /// Build mind map with optional query filter
-    /// When query is provided, shows only matching memories and their connections
-    pub fn from_brain_filtered(brain: &Brain, query: Option<&str>, limit: usize, threshold: f32) -> Self {
+    /// Build mind map from the memories passing `filter`
+    ///
+    /// The filter is applied before nodes and edges are built, so edges
+    /// only connect memories that passed it.
+    pub fn from_brain_filtered(brain: &Brain, filter: &MapFilter, limit: usize, threshold: f32) -> Self {
+        Self::from_brain_clustered(brain, None, filter, limit, threshold, ClusterMode::FirstTag)
+    }
+
+    /// Build mind map with optional query and filter, grouped by `cluster`
+    ///
+    /// When query is provided, shows only matching memories and their
+    /// connections. Candidates come from every long-term store (query hits,
+    /// or the newest memories without a query) whether or not a filter is
+    /// set; the filter only narrows them.
+    pub fn from_brain_clustered(
+        brain: &Brain,
+        query: Option<&str>,
+        filter: &MapFilter,
+        limit: usize,
+        threshold: f32,
+        cluster: ClusterMode,
+    ) -> Self {
         let mut nodes = Vec::new();
         let mut edges = Vec::new();
         let mut memories: Vec<MemoryItem> = Vec::new();
         let mut matched_ids: HashSet<String> = HashSet::new();
+        // Filtered maps fetch every candidate, then keep `limit` that pass
+        let fetch_limit = if filter.is_empty() { limit } else { usize::MAX };
 
         // Gather memories based on query
         match query {
             Some(q) if !q.is_empty() => {
-                // Search for matching memories
-                if let Ok(items) = brain.semantic.search(q, limit) {
-                    for item in &items {
-                        matched_ids.insert(item.id.to_string());
-                    }
-                    memories.extend(items);
-                }
-                
-                // Also search episodic
-                if let Ok(items) = brain.episodic.search(q, limit) {
-                    for item in items {
-                        if !matched_ids.contains(&item.id.to_string()) {
-                            matched_ids.insert(item.id.to_string());
-                            memories.push(item);
-                        }
+                // Search for matching memories in every store
+                let results = [
+                    brain.semantic.search(q, fetch_limit),
+                    brain.episodic.search(q, fetch_limit),
+                    brain.procedural.search(q, fetch_limit),
+                ];
+                for item in results.into_iter().flatten().flatten() {
+                    if matched_ids.insert(item.id.to_string()) {
+                        memories.push(item);
                     }
                 }
             }
             _ => {
-                // No query - get all memories
-                if let Ok(items) = brain.semantic.search("", limit) {
+                // No query - newest memories of every type
+                if let Ok(items) = brain.list_page(fetch_limit, 0) {
                     memories.extend(items);
                 }
             }
         }
 
+        memories.retain(|memory| filter.matches(memory));
+        memories.truncate(limit);
+
         if memories.is_empty() {
             return Self { nodes, edges };
         }
 
-        // Assign groups based on tags
-        let mut tag_groups: HashMap<String, usize> = HashMap::new();
-        let mut next_group = 0;
+        let groups = assign_groups(&memories, cluster);
 
         // Track which memories matched the query (for highlighting)
         let is_query_mode = query.is_some() && !query.unwrap_or("").is_empty();
 
         // Create nodes
-        for memory in &memories {
-            let primary_tag = memory.tags.first().cloned().unwrap_or_else(|| "general".to_string());
-            
-            let group = *tag_groups.entry(primary_tag.clone()).or_insert_with(|| {
-                let g = next_group;
-                next_group += 1;
-                g
-            });
-
+        for (memory, group) in memories.iter().zip(groups) {
             let label = truncate(&memory.content, 20);
             
             // In query mode, matched nodes are larger
@@ -727,6 +784,75 @@ impl MindMap {
         dot
     }
 
+    /// Generate GraphML (Gephi, Cytoscape, yEd)
+    ///
+    /// Nodes carry `label`, `content`, `group`, `size` and comma-separated
+    /// `tags`; edges carry `weight`.
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="label" for="node" attr.name="label" attr.type="string"/>
+  <key id="content" for="node" attr.name="content" attr.type="string"/>
+  <key id="group" for="node" attr.name="group" attr.type="int"/>
+  <key id="size" for="node" attr.name="size" attr.type="double"/>
+  <key id="tags" for="node" attr.name="tags" attr.type="string"/>
+  <key id="weight" for="edge" attr.name="weight" attr.type="double"/>
+  <graph id="MindMap" edgedefault="undirected">
+"#);
+
+        for node in &self.nodes {
+            xml.push_str(&format!(
+                "    <node id=\"{}\">\n      <data key=\"label\">{}</data>\n      <data key=\"content\">{}</data>\n      <data key=\"group\">{}</data>\n      <data key=\"size\">{}</data>\n      <data key=\"tags\">{}</data>\n    </node>\n",
+                escape_xml(&node.id),
+                escape_xml(&node.label),
+                escape_xml(&node.content),
+                node.group,
+                node.size,
+                escape_xml(&node.tags.join(",")),
+            ));
+        }
+
+        for (i, edge) in self.edges.iter().enumerate() {
+            xml.push_str(&format!(
+                "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n      <data key=\"weight\">{:.3}</data>\n    </edge>\n",
+                i,
+                escape_xml(&edge.source),
+                escape_xml(&edge.target),
+                edge.weight,
+            ));
+        }
+
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    /// Node/link JSON (`{nodes, links}`), the shape served by `/mindmap/data`
+    pub fn to_json(&self) -> serde_json::Value {
+        let nodes: Vec<serde_json::Value> = self.nodes.iter().map(|n| {
+            serde_json::json!({
+                "id": n.id,
+                "label": n.label,
+                "content": n.content,
+                "group": n.group,
+                "size": n.size,
+                "tags": n.tags,
+            })
+        }).collect();
+
+        let links: Vec<serde_json::Value> = self.edges.iter().map(|e| {
+            serde_json::json!({
+                "source": e.source,
+                "target": e.target,
+                "weight": e.weight,
+            })
+        }).collect();
+
+        serde_json::json!({
+            "nodes": nodes,
+            "links": links,
+        })
+    }
+
     /// Generate Mermaid format
     pub fn to_mermaid(&self) -> String {
         let mut mermaid = String::from("graph LR\n");
@@ -754,6 +880,119 @@ impl MindMap {
     }
 }
 
+/// Group index for each memory under `mode`
+///
+/// In `Embedding` mode, memories without an embedding (or with one of a
+/// different dimension) fall back to tag groups numbered after the clusters.
+fn assign_groups(memories: &[MemoryItem], mode: ClusterMode) -> Vec<usize> {
+    let mut groups: Vec<Option<usize>> = vec![None; memories.len()];
+    let mut next_group = 0;
+
+    if let ClusterMode::Embedding(k) = mode {
+        let dim = memories
+            .iter()
+            .filter_map(|m| m.embedding.as_ref())
+            .map(|e| e.len())
+            .find(|&d| d > 0);
+        if let Some(dim) = dim {
+            let (indices, points): (Vec<usize>, Vec<&[f32]>) = memories
+                .iter()
+                .enumerate()
+                .filter_map(|(i, m)| m.embedding.as_deref().filter(|e| e.len() == dim).map(|e| (i, e)))
+                .unzip();
+            let labels = kmeans(&points, k, KMEANS_ITERATIONS);
+            next_group = labels.iter().max().map_or(0, |m| m + 1);
+            for (i, label) in indices.into_iter().zip(labels) {
+                groups[i] = Some(label);
+            }
+        }
+    }
+
+    let mut tag_groups: HashMap<String, usize> = HashMap::new();
+    groups
+        .into_iter()
+        .zip(memories)
+        .map(|(group, memory)| {
+            group.unwrap_or_else(|| {
+                let primary_tag = memory.tags.first().cloned().unwrap_or_else(|| "general".to_string());
+                *tag_groups.entry(primary_tag).or_insert_with(|| {
+                    let g = next_group;
+                    next_group += 1;
+                    g
+                })
+            })
+        })
+        .collect()
+}
+
+/// k-means over `points` by cosine distance; one cluster label per point
+///
+/// Seeds deterministically (first point, then repeatedly the point farthest
+/// from every centroid), so the same memories always get the same colors.
+/// Labels are numbered by first appearance and empty clusters are dropped.
+pub fn kmeans(points: &[&[f32]], k: usize, max_iterations: usize) -> Vec<usize> {
+    let k = k.min(points.len());
+    if k == 0 {
+        return Vec::new();
+    }
+    let distance = |a: &[f32], b: &[f32]| 1.0 - cosine_similarity(a, b);
+    let nearest = |p: &[f32], centroids: &[Vec<f32>]| {
+        centroids
+            .iter()
+            .map(|c| distance(p, c))
+            .enumerate()
+            .fold((0, f32::INFINITY), |best, (c, d)| if d < best.1 { (c, d) } else { best })
+    };
+
+    let mut centroids: Vec<Vec<f32>> = vec![points[0].to_vec()];
+    while centroids.len() < k {
+        let (far, dist) = points
+            .iter()
+            .map(|p| nearest(p, &centroids).1)
+            .enumerate()
+            .fold((0, f32::NEG_INFINITY), |best, (i, d)| if d > best.1 { (i, d) } else { best });
+        if dist <= f32::EPSILON {
+            break; // every remaining point sits on a centroid
+        }
+        centroids.push(points[far].to_vec());
+    }
+
+    let mut labels: Vec<usize> = points.iter().map(|p| nearest(p, &centroids).0).collect();
+    for _ in 0..max_iterations {
+        // Move each centroid to the mean of its points
+        let dim = centroids[0].len();
+        let mut sums = vec![vec![0.0f32; dim]; centroids.len()];
+        let mut counts = vec![0usize; centroids.len()];
+        for (p, &label) in points.iter().zip(&labels) {
+            counts[label] += 1;
+            for (s, v) in sums[label].iter_mut().zip(p.iter()) {
+                *s += v;
+            }
+        }
+        for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+            if count > 0 {
+                *centroid = sum.into_iter().map(|s| s / count as f32).collect();
+            }
+        }
+
+        let next: Vec<usize> = points.iter().map(|p| nearest(p, &centroids).0).collect();
+        if next == labels {
+            break;
+        }
+        labels = next;
+    }
+
+    // Renumber by first appearance so unused centroids leave no gaps
+    let mut renumber: HashMap<usize, usize> = HashMap::new();
+    labels
+        .into_iter()
+        .map(|label| {
+            let next = renumber.len();
+            *renumber.entry(label).or_insert(next)
+        })
+        .collect()
+}
+
 fn truncate(s: &str, max: usize) -> String {
     let chars: Vec<char> = s.chars().collect();
     if chars.len() <= max {
@@ -775,6 +1014,17 @@ fn escape_dot(s: &str) -> String {
     s.replace('"', "\\\"").replace('\n', " ")
 }
 
+fn escape_xml(s: &str) -> String {
+    s.chars()
+        .filter(|&c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
 fn escape_mermaid(s: &str) -> String {
     s.replace('"', "'").replace('\n', " ")
 }
@@ -801,6 +1051,145 @@ mod tests {
         assert!(map.nodes.len() >= 3, "Expected >= 3 nodes, got {}", map.nodes.len());
     }
 
+    #[test]
+    fn test_embedding_clusters_group_similar_untagged_memories() {
+        use crate::{Embedder, GloVeEmbedder};
+
+        let embedder = GloVeEmbedder::test_embedder();
+        let memories: Vec<MemoryItem> = [
+            "rust programming code",
+            "apple silicon gpu hardware",
+            "python programming language",
+        ]
+        .iter()
+        .map(|text| {
+            let mut item = MemoryItem::new(text, None);
+            item.embedding = Some(embedder.embed(text));
+            item
+        })
+        .collect();
+
+        // Untagged: first-tag mode lumps everything into "general"
+        assert_eq!(assign_groups(&memories, ClusterMode::FirstTag), vec![0, 0, 0]);
+
+        let groups = assign_groups(&memories, ClusterMode::Embedding(2));
+        assert_eq!(groups[0], groups[2]);
+        assert_ne!(groups[0], groups[1]);
+
+        // Memories without embeddings fall back to tag groups after the clusters
+        let mut mixed = memories.clone();
+        mixed.push(MemoryItem::new("no embedding", None).with_tags(vec!["misc".to_string()]));
+        let groups = assign_groups(&mixed, ClusterMode::Embedding(2));
+        assert_eq!(groups[3], 2);
+    }
+
+    #[test]
+    fn test_tag_filter_keeps_only_matching_nodes_and_edges() {
+        let dir = tempdir().unwrap();
+        let mut brain = Brain::new(dir.path().to_str().unwrap()).unwrap();
+
+        let tagged = |content: &str, tag: &str| MemoryItem::new(content, None).with_tags(vec![tag.to_string()]);
+        let work = [
+            brain.store_typed(tagged("Sprint planning for the release", "work"), Some(MemoryType::Semantic)).unwrap().id,
+            brain.store_typed(tagged("Release checklist for the sprint", "work"), Some(MemoryType::Episodic)).unwrap().id,
+        ];
+        brain.store_typed(tagged("Sprint training at the park", "hobby"), Some(MemoryType::Semantic)).unwrap();
+
+        let filter = MapFilter { tags: vec!["work".to_string()], ..Default::default() };
+        let map = MindMap::from_brain_filtered(&brain, &filter, 100, -1.0);
+
+        let ids: HashSet<String> = work.iter().map(|id| id.to_string()).collect();
+        assert_eq!(map.nodes.iter().map(|n| n.id.clone()).collect::<HashSet<_>>(), ids);
+        assert_eq!(map.edges.len(), 1);
+        assert!(map.edges.iter().all(|e| ids.contains(&e.source) && ids.contains(&e.target)));
+
+        // Type filters narrow further
+        let filter = MapFilter { types: vec![MemoryType::Episodic], ..filter };
+        let map = MindMap::from_brain_filtered(&brain, &filter, 100, -1.0);
+        assert_eq!(map.nodes.len(), 1);
+        assert!(map.edges.is_empty());
+    }
+
+    #[test]
+    fn test_query_and_filter_share_one_candidate_set() {
+        let dir = tempdir().unwrap();
+        let mut brain = Brain::new(dir.path().to_str().unwrap()).unwrap();
+
+        let habit = brain
+            .store_typed(MemoryItem::new("Run cargo fmt before every commit", None), Some(MemoryType::Procedural))
+            .unwrap()
+            .id;
+        brain.store_typed(MemoryItem::new("Cargo is the Rust package manager", None), Some(MemoryType::Semantic)).unwrap();
+        brain.store_typed(MemoryItem::new("Fixed the cargo build this morning", None), Some(MemoryType::Episodic)).unwrap();
+
+        // Procedural memories are found by query
+        let procedural = MapFilter { types: vec![MemoryType::Procedural], ..Default::default() };
+        let map = MindMap::from_brain_clustered(&brain, Some("cargo"), &procedural, 100, -1.0, ClusterMode::FirstTag);
+        assert_eq!(map.nodes.iter().map(|n| n.id.clone()).collect::<Vec<_>>(), vec![habit.to_string()]);
+
+        // A filter that matches everything changes nothing
+        let everything = MapFilter { since: Some(Utc::now() - chrono::Duration::days(1)), ..Default::default() };
+        for query in [None, Some("cargo")] {
+            let ids = |filter: &MapFilter| {
+                let map = MindMap::from_brain_clustered(&brain, query, filter, 100, -1.0, ClusterMode::FirstTag);
+                map.nodes.into_iter().map(|n| n.id).collect::<HashSet<_>>()
+            };
+            assert_eq!(ids(&MapFilter::default()).len(), 3);
+            assert_eq!(ids(&MapFilter::default()), ids(&everything));
+        }
+    }
+
+    fn sample_map() -> MindMap {
+        let node = |id: &str, content: &str, group| MapNode {
+            id: id.to_string(),
+            label: truncate(content, 20),
+            content: content.to_string(),
+            group,
+            size: 10.0,
+            tags: vec!["rust".to_string(), "a&b".to_string()],
+        };
+        MindMap {
+            nodes: vec![
+                node("1", "Rust <generics> & \"traits\"", 0),
+                node("2", "Ownership rules", 0),
+                node("3", "Python's GIL", 1),
+            ],
+            edges: vec![
+                MapEdge { source: "1".to_string(), target: "2".to_string(), weight: 0.8 },
+                MapEdge { source: "2".to_string(), target: "3".to_string(), weight: 0.41 },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_graphml_is_valid_xml() {
+        let map = sample_map();
+        let xml = map.to_graphml();
+        let doc = roxmltree::Document::parse(&xml).expect("GraphML should parse");
+
+        let nodes: Vec<_> = doc.descendants().filter(|n| n.has_tag_name("node")).collect();
+        let edges: Vec<_> = doc.descendants().filter(|n| n.has_tag_name("edge")).collect();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(edges.len(), 2);
+
+        let content = nodes[0]
+            .children()
+            .find(|d| d.attribute("key") == Some("content"))
+            .and_then(|d| d.text())
+            .unwrap();
+        assert_eq!(content, "Rust <generics> & \"traits\"");
+        assert_eq!(edges[0].attribute("source"), Some("1"));
+    }
+
+    #[test]
+    fn test_json_node_and_link_counts() {
+        let json = sample_map().to_json();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(json["links"].as_array().unwrap().len(), 2);
+        assert_eq!(json["nodes"][2]["group"], 1);
+        assert_eq!(json["links"][0]["target"], "2");
+    }
+
     #[test]
     fn test_html_generation() {
         let map = MindMap {
@@ -5,9 +5,137 @@
 //! - Alert about memories that might be forgotten
 //! - Discover recurring patterns and habits
 
+use crate::text::{self, Lang};
 use crate::{Brain, MemoryItem};
-use chrono::{Utc, Datelike, Timelike, Weekday};
-use std::collections::HashMap;
+use chrono::{DateTime, Utc, Datelike, Timelike, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Default number of memories scanned per store
+pub const DEFAULT_SCAN_LIMIT: usize = 1000;
+
+/// Weight kept by a tag's older transitions each time it is followed again
+pub const MARKOV_DECAY: f32 = 0.98;
+
+/// Additive (Laplace) smoothing for transitions never observed
+pub const MARKOV_SMOOTHING: f32 = 0.1;
+
+/// Decayed transition counts below this are dropped
+const MARKOV_MIN_COUNT: f32 = 0.01;
+
+/// Observed transitions needed before `predict_next` reports one
+const MIN_TAG_TRANSITIONS: f32 = 1.5;
+
+/// Tag-transition Markov chain over the order memories were stored in
+///
+/// Updated incrementally with [`TagMarkov::observe`] and persisted with the
+/// brain's index sidecar. Each tag of a memory counts as a transition to
+/// every (other) tag of the next tagged memory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagMarkov {
+    /// from tag → (next tag → decayed count)
+    transitions: HashMap<String, HashMap<String, f32>>,
+    /// Tags of the most recently observed memory
+    last_tags: Vec<String>,
+}
+
+impl TagMarkov {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replay `(created_at, tags)` pairs in time order
+    pub fn from_history(mut history: Vec<(DateTime<Utc>, Vec<String>)>) -> Self {
+        history.sort_by_key(|(at, _)| *at);
+        let mut markov = Self::new();
+        for (_, tags) in &history {
+            markov.observe(tags);
+        }
+        markov
+    }
+
+    /// Record the tags of a newly stored memory (untagged memories are skipped)
+    ///
+    /// Older transitions out of each previous tag decay by [`MARKOV_DECAY`],
+    /// so recent habits outweigh old ones.
+    pub fn observe(&mut self, tags: &[String]) {
+        if tags.is_empty() {
+            return;
+        }
+        let previous = std::mem::replace(&mut self.last_tags, tags.to_vec());
+        for from in &previous {
+            let row = self.transitions.entry(from.clone()).or_default();
+            for count in row.values_mut() {
+                *count *= MARKOV_DECAY;
+            }
+            row.retain(|_, count| *count >= MARKOV_MIN_COUNT);
+            for to in tags.iter().filter(|to| *to != from) {
+                *row.entry(to.clone()).or_insert(0.0) += 1.0;
+            }
+        }
+    }
+
+    /// Tags of the most recently observed memory
+    pub fn last_tags(&self) -> &[String] {
+        &self.last_tags
+    }
+
+    /// Decayed number of times `from` was followed by `to`
+    pub fn transition_count(&self, from: &str, to: &str) -> f32 {
+        self.transitions
+            .get(from)
+            .and_then(|row| row.get(to))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Up to `k` likeliest next tags with their probability, best first
+    ///
+    /// Probabilities are smoothed per current tag and averaged over the
+    /// current tags that have outgoing transitions. Current tags are never
+    /// predicted; unknown current tags give an empty result.
+    pub fn predict_next_tags<S: AsRef<str>>(&self, current_tags: &[S], k: usize) -> Vec<(String, f32)> {
+        let current: Vec<&str> = current_tags.iter().map(|t| t.as_ref()).collect();
+        let rows: Vec<&HashMap<String, f32>> = current
+            .iter()
+            .filter_map(|t| self.transitions.get(*t))
+            .filter(|row| !row.is_empty())
+            .collect();
+        if rows.is_empty() {
+            return Vec::new();
+        }
+
+        let vocabulary: HashSet<&str> = self
+            .transitions
+            .iter()
+            .flat_map(|(from, row)| std::iter::once(from.as_str()).chain(row.keys().map(|t| t.as_str())))
+            .filter(|t| !current.contains(t))
+            .collect();
+        let v = vocabulary.len() as f32;
+
+        let mut scored: Vec<(String, f32)> = vocabulary
+            .into_iter()
+            .map(|tag| {
+                let p: f32 = rows
+                    .iter()
+                    .map(|row| {
+                        let total: f32 = row.values().sum();
+                        let count = row.get(tag).copied().unwrap_or(0.0);
+                        (count + MARKOV_SMOOTHING) / (total + MARKOV_SMOOTHING * v)
+                    })
+                    .sum();
+                (tag.to_string(), p / rows.len() as f32)
+            })
+            .collect();
+        scored.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        scored.truncate(k);
+        scored
+    }
+}
 
 /// Prediction result
 #[derive(Debug, Clone)]
@@ -33,6 +161,17 @@ pub enum AlertUrgency {
     High,     // Almost forgotten!
 }
 
+impl AlertUrgency {
+    /// Severity for comparisons (Low 1 .. High 3; 0 means no alert)
+    pub fn level(&self) -> u8 {
+        match self {
+            AlertUrgency::Low => 1,
+            AlertUrgency::Medium => 2,
+            AlertUrgency::High => 3,
+        }
+    }
+}
+
 impl std::fmt::Display for AlertUrgency {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -55,11 +194,41 @@ pub struct Pattern {
 /// Prediction engine
 pub struct Predictor<'a> {
     brain: &'a Brain,
+    scan_limit: usize,
 }
 
 impl<'a> Predictor<'a> {
     pub fn new(brain: &'a Brain) -> Self {
-        Self { brain }
+        Self { brain, scan_limit: DEFAULT_SCAN_LIMIT }
+    }
+
+    /// Bound how many memories are read from each store
+    pub fn with_scan_limit(mut self, limit: usize) -> Self {
+        self.scan_limit = limit;
+        self
+    }
+
+    /// Memories from the episodic, semantic and procedural stores
+    ///
+    /// Reads at most `limit_per_store` from each store; a memory present in
+    /// several stores is returned once.
+    pub fn get_all_memories(&self, limit_per_store: usize) -> Vec<MemoryItem> {
+        let mut memories = Vec::new();
+        let mut seen = HashSet::new();
+
+        let stores = [
+            self.brain.episodic.search("", limit_per_store),
+            self.brain.semantic.search("", limit_per_store),
+            self.brain.procedural.search("", limit_per_store),
+        ];
+        for items in stores.into_iter().flatten() {
+            for item in items {
+                if seen.insert(item.id) {
+                    memories.push(item);
+                }
+            }
+        }
+        memories
     }
 
     /// Predict what might happen next based on patterns
@@ -67,7 +236,7 @@ impl<'a> Predictor<'a> {
         let mut predictions = Vec::new();
         
         // Get all memories for analysis
-        let memories = self.get_all_memories();
+        let memories = self.get_all_memories(self.scan_limit);
         if memories.is_empty() {
             return predictions;
         }
@@ -76,16 +245,15 @@ impl<'a> Predictor<'a> {
         let time_predictions = self.analyze_time_patterns(&memories);
         predictions.extend(time_predictions);
 
-        // Analyze tag patterns
-        let tag_predictions = self.analyze_tag_patterns(&memories);
-        predictions.extend(tag_predictions);
+        // Tag transitions (incrementally maintained by the brain)
+        predictions.extend(self.analyze_tag_patterns());
 
         // Analyze content patterns
         let content_predictions = self.analyze_content_patterns(&memories);
         predictions.extend(content_predictions);
 
         // Sort by confidence
-        predictions.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+        predictions.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
         predictions.truncate(limit);
 
         predictions
@@ -96,7 +264,7 @@ impl<'a> Predictor<'a> {
         let mut alerts = Vec::new();
         let now = Utc::now();
 
-        let memories = self.get_all_memories();
+        let memories = self.get_all_memories(self.scan_limit);
 
         for memory in memories {
             let days_since = (now - memory.last_accessed).num_days();
@@ -146,7 +314,7 @@ impl<'a> Predictor<'a> {
     /// Discover recurring patterns
     pub fn discover_patterns(&self) -> Vec<Pattern> {
         let mut patterns = Vec::new();
-        let memories = self.get_all_memories();
+        let memories = self.get_all_memories(self.scan_limit);
 
         // Time-based patterns
         if let Some(pattern) = self.find_time_pattern(&memories) {
@@ -171,14 +339,6 @@ impl<'a> Predictor<'a> {
 
     // ============ Internal Analysis Methods ============
 
-    fn get_all_memories(&self) -> Vec<MemoryItem> {
-        let mut memories = Vec::new();
-        if let Ok(items) = self.brain.semantic.search("", 1000) {
-            memories.extend(items);
-        }
-        memories
-    }
-
     fn analyze_time_patterns(&self, memories: &[MemoryItem]) -> Vec<Prediction> {
         let mut predictions = Vec::new();
         let now = Utc::now();
@@ -217,50 +377,26 @@ impl<'a> Predictor<'a> {
         predictions
     }
 
-    fn analyze_tag_patterns(&self, memories: &[MemoryItem]) -> Vec<Prediction> {
-        let mut predictions = Vec::new();
-
-        // Find tag sequences (what follows what)
-        let mut tag_sequences: HashMap<String, HashMap<String, usize>> = HashMap::new();
-        
-        let sorted_memories: Vec<_> = {
-            let mut m = memories.to_vec();
-            m.sort_by_key(|m| m.created_at);
-            m
-        };
-
-        for i in 0..sorted_memories.len().saturating_sub(1) {
-            for prev_tag in &sorted_memories[i].tags {
-                for next_tag in &sorted_memories[i + 1].tags {
-                    if prev_tag != next_tag {
-                        *tag_sequences
-                            .entry(prev_tag.clone())
-                            .or_default()
-                            .entry(next_tag.clone())
-                            .or_insert(0) += 1;
-                    }
-                }
-            }
-        }
-
-        // Find most recent tag
-        if let Some(recent) = sorted_memories.last() {
-            for tag in &recent.tags {
-                if let Some(follows) = tag_sequences.get(tag) {
-                    if let Some((next_tag, count)) = follows.iter().max_by_key(|(_, c)| *c) {
-                        if *count >= 2 {
-                            predictions.push(Prediction {
-                                content: format!("'{}' 다음에는 보통 '{}'를 해", tag, next_tag),
-                                confidence: (*count as f32 / 10.0).min(0.8),
-                                reason: format!("{}번의 패턴 발견", count),
-                            });
-                        }
-                    }
-                }
-            }
-        }
-
-        predictions
+    fn analyze_tag_patterns(&self) -> Vec<Prediction> {
+        let markov = self.brain.tag_markov();
+        let current = markov.last_tags();
+
+        markov
+            .predict_next_tags(current, 3)
+            .into_iter()
+            .filter_map(|(next_tag, p)| {
+                // Strongest observed predecessor among the current tags
+                let (tag, count) = current
+                    .iter()
+                    .map(|t| (t, markov.transition_count(t, &next_tag)))
+                    .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))?;
+                (count >= MIN_TAG_TRANSITIONS).then(|| Prediction {
+                    content: format!("'{}' 다음에는 보통 '{}'를 해", tag, next_tag),
+                    confidence: p.min(0.9),
+                    reason: format!("{}번의 패턴 발견", count.round()),
+                })
+            })
+            .collect()
     }
 
     fn analyze_content_patterns(&self, memories: &[MemoryItem]) -> Vec<Prediction> {
@@ -351,13 +487,12 @@ impl<'a> Predictor<'a> {
     fn find_word_pattern(&self, memories: &[MemoryItem]) -> Option<Pattern> {
         let mut word_counts: HashMap<String, usize> = HashMap::new();
         
-        let stop_words = ["the", "is", "at", "which", "on", "a", "an", "and", "or", "but",
-                          "in", "with", "to", "for", "of", "테스트", "데이터", "메모리"];
+        // Too generic in this app's own memories to count as a pattern
+        let noise_words = ["테스트", "데이터", "메모리"];
 
         for memory in memories {
-            for word in memory.content.split_whitespace() {
-                let word = word.to_lowercase().trim_matches(|c: char| !c.is_alphanumeric()).to_string();
-                if word.len() > 2 && !stop_words.contains(&word.as_str()) {
+            for word in text::tokenize(&memory.content, Lang::Auto) {
+                if word.len() > 2 && !noise_words.contains(&word.as_str()) {
                     *word_counts.entry(word).or_insert(0) += 1;
                 }
             }
@@ -451,4 +586,53 @@ mod tests {
         let patterns = predictor.discover_patterns();
         // Should find some patterns
     }
+
+    #[test]
+    fn test_tag_markov_ranks_repeated_successor_first() {
+        let tags = |t: &str| vec![t.to_string()];
+        let mut markov = TagMarkov::new();
+        for _ in 0..5 {
+            for t in ["A", "B", "C"] {
+                markov.observe(&tags(t));
+            }
+        }
+        // One stray transition and an untagged memory
+        markov.observe(&tags("A"));
+        markov.observe(&tags("D"));
+        markov.observe(&[]);
+
+        let next = markov.predict_next_tags(&["A"], 3);
+        assert_eq!(next[0].0, "B");
+        assert!(next[0].1 > next[1].1);
+        // Smoothing: the never-seen A → C still gets some mass
+        assert!(next.iter().any(|(t, p)| t == "C" && *p > 0.0));
+        assert!(next.iter().all(|(t, _)| t != "A"));
+        assert!(markov.predict_next_tags(&["unknown"], 3).is_empty());
+
+        // Persisted and reloaded via serde
+        let reloaded: TagMarkov = serde_json::from_str(&serde_json::to_string(&markov).unwrap()).unwrap();
+        assert_eq!(reloaded.predict_next_tags(&["A"], 1)[0].0, "B");
+        assert_eq!(reloaded.last_tags(), ["D".to_string()]);
+    }
+
+    #[test]
+    fn test_forgetting_alerts_include_episodic() {
+        let dir = tempdir().unwrap();
+        let embedder = std::sync::Arc::new(crate::GloVeEmbedder::test_embedder());
+        let mut brain = Brain::with_embedder(dir.path().join("alerts.db").to_str().unwrap(), embedder).unwrap();
+
+        let mut old = MemoryItem::new("Dinner with grandma at the harbour", None);
+        old.strength = 0.1;
+        old.last_accessed = Utc::now() - chrono::Duration::days(60);
+        brain.episodic.store(old.clone()).unwrap();
+        brain.semantic.store(MemoryItem::new("Water boils at 100 degrees", None)).unwrap();
+
+        let predictor = Predictor::new(&brain);
+        let alerts = predictor.forgetting_alerts(10);
+        assert!(alerts.iter().any(|a| a.memory.id == old.id && a.urgency == AlertUrgency::High));
+
+        // The scan limit applies per store
+        assert_eq!(predictor.get_all_memories(1).len(), 2);
+        assert!(Predictor::new(&brain).with_scan_limit(0).forgetting_alerts(10).is_empty());
+    }
 }
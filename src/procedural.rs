@@ -7,8 +7,24 @@
 //! - Strengthens with repetition
 
 use crate::types::{MemoryItem, MemoryType};
+use crate::forgetting::ForgettingCurve;
 use crate::storage::Storage;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Occurrences needed before a code shape is reported as a template
+pub const DEFAULT_MIN_OCCURRENCES: usize = 2;
+
+/// Snippets with fewer tokens than this are too short to be a template
+const MIN_SNIPPET_TOKENS: usize = 5;
+
+/// Keywords kept verbatim in code shapes (common across Rust, Python, JS, Go, ...)
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "catch", "class", "const", "continue", "def", "else",
+    "elif", "enum", "except", "fn", "for", "func", "function", "if", "impl", "import", "in",
+    "let", "match", "mut", "pub", "return", "self", "static", "struct", "trait", "try",
+    "type", "use", "var", "while", "with", "yield",
+];
 
 /// A procedural pattern (trigger → action)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,6 +77,12 @@ impl ProceduralMemory {
         Ok(Self { storage })
     }
 
+    /// Open in `keyspace` with custom CoreDB tuning (see [`Storage::open`])
+    pub fn open(db_path: &str, keyspace: &str, tuning: &coredb::DatabaseConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let storage = Storage::open(db_path, keyspace, "procedural", tuning)?;
+        Ok(Self { storage })
+    }
+
     /// Store a procedural memory (pattern)
     pub fn store(&mut self, mut item: MemoryItem) -> Result<(), Box<dyn std::error::Error>> {
         item.memory_type = MemoryType::Procedural;
@@ -68,6 +90,20 @@ impl ProceduralMemory {
         Ok(())
     }
 
+    /// Get recent procedural memories, skipping the newest `offset`
+    pub fn get_recent(&self, limit: usize, offset: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+        self.storage.get_recent(limit, offset)
+    }
+
+    /// Get memories created within a time range
+    pub fn get_by_time_range(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+        self.storage.get_by_time_range(start, end)
+    }
+
     /// Learn a new pattern
     pub fn learn_pattern(&mut self, pattern: Pattern) -> Result<(), Box<dyn std::error::Error>> {
         let content = serde_json::to_string(&pattern)?;
@@ -78,6 +114,71 @@ impl ProceduralMemory {
         Ok(())
     }
 
+    /// Get a memory by id
+    pub fn get(&self, id: &uuid::Uuid) -> Result<Option<MemoryItem>, Box<dyn std::error::Error>> {
+        self.storage.get_by_id(id)
+    }
+
+    /// Overwrite a stored memory (matched by id)
+    pub fn update(&self, item: &MemoryItem) -> Result<(), Box<dyn std::error::Error>> {
+        self.storage.update(item)
+    }
+
+    /// Link `from` → `to` (no-op if `from` isn't stored here)
+    pub fn add_association(&mut self, from: uuid::Uuid, to: uuid::Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        self.storage.add_association(from, to)
+    }
+
+    /// Delete a memory by id (false if it isn't stored here)
+    pub fn delete(&mut self, id: &uuid::Uuid) -> Result<bool, Box<dyn std::error::Error>> {
+        if self.storage.get_by_id(id)?.is_none() {
+            return Ok(false);
+        }
+        self.storage.delete(id)?;
+        Ok(true)
+    }
+
+    /// Bytes of stored embeddings (see [`Storage::embedding_bytes`])
+    pub fn embedding_bytes(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.storage.embedding_bytes()
+    }
+
+    /// Rewrite stored embeddings as int8 (see [`Storage::quantize_stored_embeddings`])
+    pub fn quantize_stored_embeddings(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.storage.quantize_stored_embeddings()
+    }
+
+    /// Store new embeddings as int8 (see [`Storage::set_quantize_embeddings`])
+    pub fn set_quantize_embeddings(&mut self, on: bool) {
+        self.storage.set_quantize_embeddings(on);
+    }
+
+    /// Delete every procedural memory; returns how many were removed
+    pub fn clear(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.storage.clear()
+    }
+
+    /// Number of stored procedural memories
+    pub fn count(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.storage.count()
+    }
+
+    /// Apply forgetting (procedural memories decay slowest via the curve's per-type rate)
+    pub fn apply_forgetting(&mut self, curve: &ForgettingCurve) -> Result<(), Box<dyn std::error::Error>> {
+        let all = self.storage.get_all()?;
+        for mut item in all {
+            let decay = curve.calculate_decay(&item);
+            item.decay(decay);
+
+            if item.is_forgotten() {
+                self.storage.delete(&item.id)?;
+            } else {
+                self.storage.update(&item)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Search for relevant patterns
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
         self.storage.search(query, limit)
@@ -94,6 +195,41 @@ impl ProceduralMemory {
         Ok(patterns)
     }
 
+    /// Find code templates that recur across stored procedural memories
+    ///
+    /// Snippets are grouped by [`code_shape`], so the same structure with
+    /// different names or literals lands in one group.
+    pub fn extract_patterns(&self) -> Result<Vec<crate::predict::Pattern>, Box<dyn std::error::Error>> {
+        self.extract_patterns_min(DEFAULT_MIN_OCCURRENCES)
+    }
+
+    /// [`extract_patterns`](Self::extract_patterns) with a custom recurrence threshold
+    pub fn extract_patterns_min(&self, min_occurrences: usize) -> Result<Vec<crate::predict::Pattern>, Box<dyn std::error::Error>> {
+        let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+        for item in self.storage.search("", usize::MAX)? {
+            // Learned trigger → action rows are JSON, not code
+            if serde_json::from_str::<Pattern>(&item.content).is_ok() {
+                continue;
+            }
+            if let Some(shape) = code_shape(&item.content) {
+                groups.entry(shape).or_default().push(item.content);
+            }
+        }
+
+        let mut patterns: Vec<crate::predict::Pattern> = groups
+            .into_iter()
+            .filter(|(_, snippets)| snippets.len() >= min_occurrences.max(1))
+            .map(|(shape, snippets)| crate::predict::Pattern {
+                name: format!("코드 템플릿: {}", shape),
+                description: format!("같은 구조가 {}번 반복", snippets.len()),
+                frequency: snippets.len(),
+                examples: snippets.into_iter().take(3).collect(),
+            })
+            .collect();
+        patterns.sort_by(|a, b| b.frequency.cmp(&a.frequency).then_with(|| a.name.cmp(&b.name)));
+        Ok(patterns)
+    }
+
     /// Record feedback on a pattern
     pub fn feedback(&mut self, trigger: &str, success: bool) -> Result<(), Box<dyn std::error::Error>> {
         let items = self.storage.search(trigger, 1)?;
@@ -104,7 +240,7 @@ impl ProceduralMemory {
                 } else {
                     pattern.failure();
                 }
-                item.content = serde_json::to_string(&pattern)?;
+                item.set_content(&serde_json::to_string(&pattern)?);
                 item.access();
                 self.storage.update(&item)?;
             }
@@ -112,3 +248,120 @@ impl ProceduralMemory {
         Ok(())
     }
 }
+
+/// Structural shape of a code snippet
+///
+/// Identifiers become `ID`, numbers `NUM` and string literals `STR`;
+/// keywords and punctuation are kept. Returns None for prose (no code
+/// punctuation) and for snippets too short to be a meaningful template.
+pub fn code_shape(content: &str) -> Option<String> {
+    if !content.contains(|c: char| "(){}[];=".contains(c)) {
+        return None;
+    }
+
+    let mut tokens: Vec<String> = Vec::new();
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == '"' || c == '\'' || c == '`' {
+            let mut escaped = false;
+            for next in chars.by_ref() {
+                if next == c && !escaped {
+                    break;
+                }
+                escaped = next == '\\' && !escaped;
+            }
+            tokens.push("STR".to_string());
+        } else if c.is_ascii_digit() {
+            while chars.peek().map_or(false, |n| n.is_ascii_alphanumeric() || *n == '.' || *n == '_') {
+                chars.next();
+            }
+            tokens.push("NUM".to_string());
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut word = c.to_string();
+            while let Some(&n) = chars.peek() {
+                if !(n.is_alphanumeric() || n == '_') {
+                    break;
+                }
+                word.push(n);
+                chars.next();
+            }
+            if KEYWORDS.contains(&word.as_str()) {
+                tokens.push(word);
+            } else {
+                tokens.push("ID".to_string());
+            }
+        } else {
+            tokens.push(c.to_string());
+        }
+    }
+
+    if tokens.len() < MIN_SNIPPET_TOKENS {
+        return None;
+    }
+    Some(tokens.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_code_shape_ignores_names_and_literals() {
+        assert_eq!(
+            code_shape("let total = compute(items, 10);"),
+            code_shape("let  sum = add(xs, 42) ;")
+        );
+        assert_eq!(code_shape("print(\"a\", x);"), code_shape("print('b\\'c', y);"));
+        assert!(code_shape("print(\"a\", x);").unwrap().contains("STR"));
+        assert_eq!(code_shape("Use Result for errors"), None);
+        assert_eq!(code_shape("x = 1"), None);
+    }
+
+    #[test]
+    fn test_extract_patterns_finds_recurring_template() {
+        let dir = tempdir().unwrap();
+        let mut procedural = ProceduralMemory::new(dir.path().to_str().unwrap()).unwrap();
+
+        for snippet in [
+            "let file = File::open(path)?;",
+            "let config = Config::open(config_path)?;",
+            "let db  = Database::open(db_path)?;",
+            "Always run clippy before pushing",
+        ] {
+            procedural.store(MemoryItem::new(snippet, None)).unwrap();
+        }
+        procedural.learn_pattern(Pattern::new("compile error", "read the message")).unwrap();
+
+        let patterns = procedural.extract_patterns().unwrap();
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].frequency, 3);
+        assert!(patterns[0].name.contains("let ID = ID : : ID ( "));
+    }
+
+    #[test]
+    fn test_apply_forgetting_decays_procedural_memories() {
+        let dir = tempdir().unwrap();
+        let mut procedural = ProceduralMemory::new(dir.path().to_str().unwrap()).unwrap();
+
+        let aged = |content: &str, days: i64| {
+            let mut item = MemoryItem::new(content, None);
+            item.strength = 0.8;
+            item.last_accessed = chrono::Utc::now() - chrono::Duration::days(days);
+            item
+        };
+        let habit = aged("Always run clippy before pushing", 5);
+        let stale = aged("Restart the build server by hand", 3650);
+        let (habit_id, stale_id) = (habit.id, stale.id);
+        procedural.store(habit).unwrap();
+        procedural.store(stale).unwrap();
+
+        procedural.apply_forgetting(&ForgettingCurve::per_type()).unwrap();
+        let strength = procedural.get(&habit_id).unwrap().unwrap().strength;
+        assert!(strength < 0.8 && strength > 0.1, "{}", strength);
+        assert!(procedural.get(&stale_id).unwrap().is_none());
+    }
+}
@@ -3,12 +3,21 @@
 //! Personal memory system for Sam (디지털 여우 🦊)
 //! Stores conversations, learnings, and context for continuity.
 
-use crate::{Brain, MemoryItem, Embedder, HnswIndex};
+use crate::{Brain, MemoryItem, MemoryType, Embedder, HnswIndex, cosine_similarity};
+use crate::forgetting::ForgettingCurve;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// Current `SamBrain::export` file format (2: memories carry `priority`,
+/// 3: memories carry the stored item)
+pub const SAM_EXPORT_VERSION: u32 = 3;
+
+/// Share of the [`SamBrain::recall`] score taken by memory priority
+pub const SAM_PRIORITY_WEIGHT: f32 = 0.3;
+
 /// Sam's memory categories
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SamMemoryType {
@@ -28,6 +37,60 @@ pub enum SamMemoryType {
     Task,
 }
 
+impl SamMemoryType {
+    /// Every category, in display order
+    pub const ALL: [SamMemoryType; 7] = [
+        SamMemoryType::Conversation,
+        SamMemoryType::Learning,
+        SamMemoryType::Project,
+        SamMemoryType::Decision,
+        SamMemoryType::Lesson,
+        SamMemoryType::Preference,
+        SamMemoryType::Task,
+    ];
+
+    /// Tag marking memories of this category (`sam:conversation`, ...)
+    pub fn tag(&self) -> String {
+        format!("sam:{:?}", self).to_lowercase()
+    }
+
+    /// Category marked by a `sam:*` tag
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.tag() == tag)
+    }
+
+    /// Daily decay rate used by [`SamBrain::sleep`]
+    ///
+    /// Conversation fragments fade fastest; preferences and lessons
+    /// are meant to stick.
+    pub fn decay_rate(&self) -> f32 {
+        match self {
+            SamMemoryType::Conversation => 0.2,
+            SamMemoryType::Task => 0.1,
+            SamMemoryType::Project => 0.05,
+            SamMemoryType::Learning => 0.03,
+            SamMemoryType::Decision => 0.03,
+            SamMemoryType::Lesson => 0.01,
+            SamMemoryType::Preference => 0.01,
+        }
+    }
+
+    /// Default recall priority (0.0-1.0) used by [`SamBrain::recall`]
+    ///
+    /// Preferences and lessons outrank one-off conversation fragments.
+    pub fn default_priority(&self) -> f32 {
+        match self {
+            SamMemoryType::Preference => 1.0,
+            SamMemoryType::Lesson => 0.9,
+            SamMemoryType::Decision => 0.8,
+            SamMemoryType::Learning => 0.7,
+            SamMemoryType::Project => 0.6,
+            SamMemoryType::Task => 0.5,
+            SamMemoryType::Conversation => 0.3,
+        }
+    }
+}
+
 impl std::fmt::Display for SamMemoryType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -44,6 +107,7 @@ impl std::fmt::Display for SamMemoryType {
 
 /// A memory item specific to Sam
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "SamMemoryFields")]
 pub struct SamMemory {
     pub id: Uuid,
     pub content: String,
@@ -51,19 +115,54 @@ pub struct SamMemory {
     pub source: String, // "chat", "heartbeat", "cron", etc.
     pub channel: Option<String>, // "imessage", "discord", etc.
     pub importance: u8, // 1-5
+    /// Recall weight, 0.0-1.0 (defaults to [`SamMemoryType::default_priority`])
+    pub priority: f32,
     pub created_at: DateTime<Utc>,
     pub tags: Vec<String>,
 }
 
+/// Serialized [`SamMemory`]; files from before priorities get the
+/// category's default priority
+#[derive(Deserialize)]
+struct SamMemoryFields {
+    id: Uuid,
+    content: String,
+    memory_type: SamMemoryType,
+    source: String,
+    channel: Option<String>,
+    importance: u8,
+    #[serde(default)]
+    priority: Option<f32>,
+    created_at: DateTime<Utc>,
+    tags: Vec<String>,
+}
+
+impl From<SamMemoryFields> for SamMemory {
+    fn from(fields: SamMemoryFields) -> Self {
+        Self {
+            priority: fields.priority.unwrap_or_else(|| fields.memory_type.default_priority()),
+            id: fields.id,
+            content: fields.content,
+            memory_type: fields.memory_type,
+            source: fields.source,
+            channel: fields.channel,
+            importance: fields.importance,
+            created_at: fields.created_at,
+            tags: fields.tags,
+        }
+    }
+}
+
 impl SamMemory {
     pub fn new(content: &str, memory_type: SamMemoryType) -> Self {
         Self {
             id: Uuid::new_v4(),
             content: content.to_string(),
-            memory_type,
+            memory_type: memory_type.clone(),
             source: "manual".to_string(),
             channel: None,
             importance: 3,
+            priority: memory_type.default_priority(),
             created_at: Utc::now(),
             tags: Vec::new(),
         }
@@ -117,6 +216,12 @@ impl SamMemory {
         self
     }
 
+    /// Override the category's default priority
+    pub fn with_priority(mut self, priority: f32) -> Self {
+        self.priority = priority.clamp(0.0, 1.0);
+        self
+    }
+
     pub fn with_tags(mut self, tags: Vec<String>) -> Self {
         self.tags = tags;
         self
@@ -126,6 +231,63 @@ impl SamMemory {
         self.source = source.to_string();
         self
     }
+
+    /// Rebuild a Sam memory from its stored item (inverse of `SamBrain::remember`)
+    ///
+    /// None if the item has no `sam:*` tag. Source and channel are not
+    /// stored, so they come back as the defaults.
+    pub fn from_item(item: &MemoryItem) -> Option<Self> {
+        let memory_type = item.tags.iter().find_map(|t| SamMemoryType::from_tag(t))?;
+        let importance = item
+            .tags
+            .iter()
+            .find_map(|t| t.strip_prefix("importance:")?.parse().ok())
+            .unwrap_or(3);
+        let priority = item
+            .tags
+            .iter()
+            .find_map(|t| t.strip_prefix("priority:")?.parse::<f32>().ok())
+            .filter(|p| p.is_finite())
+            .map_or(memory_type.default_priority(), |p| p.clamp(0.0, 1.0));
+        let tags = item
+            .tags
+            .iter()
+            .filter(|t| {
+                SamMemoryType::from_tag(t).is_none()
+                    && !t.starts_with("importance:")
+                    && !t.starts_with("priority:")
+            })
+            .cloned()
+            .collect();
+        Some(Self {
+            id: item.id,
+            content: item.content.clone(),
+            importance,
+            priority,
+            created_at: item.created_at,
+            tags,
+            ..Self::new(&item.content, memory_type)
+        })
+    }
+}
+
+/// File written by `SamBrain::export`
+#[derive(Debug, Serialize, Deserialize)]
+struct SamExport {
+    version: u32,
+    exported_at: DateTime<Utc>,
+    memories: Vec<SamExportEntry>,
+}
+
+/// One exported memory: Sam's view of it plus the stored item
+#[derive(Debug, Serialize, Deserialize)]
+struct SamExportEntry {
+    #[serde(flatten)]
+    memory: SamMemory,
+    /// Strength, access statistics, associations, … (without the
+    /// embedding, which is recomputed on import); absent before version 3
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    item: Option<MemoryItem>,
 }
 
 /// Sam's Brain - wrapper around memory-brain for Sam-specific operations
@@ -153,19 +315,23 @@ impl SamBrain {
         let id = memory.id;
         
         // Convert to MemoryItem
-        let embedding = self.embedder.embed(&memory.content);
+        let embedding = self.embedder.try_embed_document(&memory.content).ok();
         let mut item = MemoryItem::new(&memory.content, Some(&format!("{}", memory.memory_type)));
         item.id = memory.id;
+        item.created_at = memory.created_at;
         item.tags = memory.tags.clone();
-        item.tags.push(format!("sam:{:?}", memory.memory_type).to_lowercase());
+        item.tags.push(memory.memory_type.tag());
         item.tags.push(format!("importance:{}", memory.importance));
-        item.embedding = Some(embedding.clone());
+        item.tags.push(format!("priority:{}", memory.priority));
+        item.embedding = embedding.clone();
         
         // Store in brain
         self.brain.semantic.store(item)?;
         
         // Add to HNSW
-        let _ = self.hnsw.add(id, embedding);
+        if let Some(embedding) = embedding {
+            let _ = self.hnsw.add(id, embedding);
+        }
         
         Ok(id)
     }
@@ -194,21 +360,46 @@ impl SamBrain {
         self.remember(memory)
     }
 
-    /// Recall memories related to a query
+    /// Recall memories related to a query, weighted by priority
+    ///
+    /// Candidates from the brain's recall are re-ranked by query similarity
+    /// blended with [`SamMemory::priority`] (see [`SAM_PRIORITY_WEIGHT`]).
+    /// Only the returned memories are marked accessed.
     pub fn recall(&mut self, query: &str, limit: usize) -> Vec<MemoryItem> {
-        self.brain.recall(query, limit)
+        let query_embedding = self.embedder.embed_query(query);
+        let mut scored: Vec<(f32, MemoryItem)> = self
+            .brain
+            .recall_with_access(query, limit.saturating_mul(3), false)
+            .into_iter()
+            .map(|item| {
+                let similarity = item
+                    .embedding
+                    .as_ref()
+                    .map(|emb| cosine_similarity(&query_embedding, emb))
+                    .filter(|s| s.is_finite())
+                    .unwrap_or(0.0);
+                // Memories stored outside Sam get a middling priority
+                let priority = SamMemory::from_item(&item).map_or(0.5, |m| m.priority);
+                ((1.0 - SAM_PRIORITY_WEIGHT) * similarity + SAM_PRIORITY_WEIGHT * priority, item)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        let mut results: Vec<MemoryItem> = scored.into_iter().take(limit).map(|(_, item)| item).collect();
+        if self.brain.records_access() {
+            self.brain.record_accesses(&mut results);
+        }
+        results
     }
 
     /// Fast recall using HNSW
     pub fn fast_recall(&self, query: &str, limit: usize) -> Vec<(Uuid, f32)> {
-        let query_embedding = self.embedder.embed(query);
+        let query_embedding = self.embedder.embed_query(query);
         self.hnsw.search(&query_embedding, limit)
     }
 
     /// Get memories by type
     pub fn recall_by_type(&self, memory_type: SamMemoryType, _limit: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
-        let tag = format!("sam:{:?}", memory_type).to_lowercase();
-        self.brain.semantic.get_by_tag(&tag)
+        self.brain.semantic.get_by_tag(&memory_type.tag())
     }
 
     /// Get all preferences
@@ -221,26 +412,177 @@ impl SamBrain {
         self.recall_by_type(SamMemoryType::Lesson, 100)
     }
 
+    /// Write every Sam memory to a portable JSON file
+    ///
+    /// Returns how many memories were written.
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut memories = Vec::new();
+        for memory_type in SamMemoryType::ALL {
+            for mut item in self.recall_by_type(memory_type, usize::MAX)? {
+                if let Some(memory) = SamMemory::from_item(&item) {
+                    item.embedding = None;
+                    memories.push(SamExportEntry { memory, item: Some(item) });
+                }
+            }
+        }
+        memories.sort_by_key(|entry| entry.memory.created_at);
+
+        let export = SamExport {
+            version: SAM_EXPORT_VERSION,
+            exported_at: Utc::now(),
+            memories,
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&export)?)?;
+        Ok(export.memories.len())
+    }
+
+    /// Remember every memory of a file written by [`SamBrain::export`]
+    ///
+    /// Stored items (version 3 files) are restored verbatim, keeping
+    /// strength and access statistics; older files keep ids and creation
+    /// times. Memories whose id already exists are skipped, so importing
+    /// twice is harmless. Returns how many memories were added.
+    pub fn import<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, Box<dyn std::error::Error>> {
+        let export: SamExport = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        if export.version > SAM_EXPORT_VERSION {
+            return Err(format!(
+                "Sam export version {} is newer than supported version {}",
+                export.version, SAM_EXPORT_VERSION
+            ).into());
+        }
+
+        let mut imported = 0;
+        // Version 1 files predate priorities; they deserialize to the default
+        for entry in export.memories {
+            if self.brain.semantic.get(&entry.memory.id)?.is_some() {
+                continue;
+            }
+            match entry.item {
+                Some(mut item) => {
+                    // `update` writes by id, so the item isn't merged into a
+                    // similar fact and keeps its strength and access counts
+                    item.embedding = self.embedder.try_embed_document(&item.content).ok();
+                    self.brain.semantic.update(&item)?;
+                    if let Some(embedding) = item.embedding {
+                        let _ = self.hnsw.add(item.id, embedding);
+                    }
+                }
+                None => {
+                    self.remember(entry.memory)?;
+                }
+            }
+            imported += 1;
+        }
+        Ok(imported)
+    }
+
+    /// Apply the forgetting curve to every Sam memory
+    ///
+    /// Each category decays at its own [`SamMemoryType::decay_rate`];
+    /// memories that fall below the forgotten threshold are deleted.
+    pub fn sleep(&mut self) -> Result<SamSleepReport, Box<dyn std::error::Error>> {
+        let mut report = SamSleepReport::default();
+
+        for memory_type in SamMemoryType::ALL {
+            // Sam memories live in the semantic store
+            let curve = ForgettingCurve::new().with_decay(MemoryType::Semantic, memory_type.decay_rate());
+            for mut item in self.recall_by_type(memory_type, usize::MAX)? {
+                item.decay(curve.calculate_decay(&item));
+                if item.is_forgotten() {
+                    self.brain.forget(item.id)?;
+                    self.hnsw.remove(&item.id);
+                    report.forgotten += 1;
+                } else {
+                    self.brain.semantic.update(&item)?;
+                    report.decayed += 1;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
     /// Get stats
     pub fn stats(&self) -> SamBrainStats {
         let hnsw_stats = self.hnsw.stats();
+        let count = |memory_type: SamMemoryType| {
+            self.recall_by_type(memory_type, usize::MAX).map(|items| items.len()).unwrap_or(0)
+        };
+        let avg_priority = SamMemoryType::ALL
+            .into_iter()
+            .filter_map(|memory_type| {
+                let priorities: Vec<f32> = self
+                    .recall_by_type(memory_type.clone(), usize::MAX)
+                    .ok()?
+                    .iter()
+                    .filter_map(SamMemory::from_item)
+                    .map(|m| m.priority)
+                    .collect();
+                if priorities.is_empty() {
+                    return None;
+                }
+                let avg = priorities.iter().sum::<f32>() / priorities.len() as f32;
+                Some((memory_type, avg))
+            })
+            .collect();
         SamBrainStats {
             total_memories: hnsw_stats.count,
             embedding_dim: hnsw_stats.dimension,
+            conversations: count(SamMemoryType::Conversation),
+            learnings: count(SamMemoryType::Learning),
+            projects: count(SamMemoryType::Project),
+            decisions: count(SamMemoryType::Decision),
+            lessons: count(SamMemoryType::Lesson),
+            preferences: count(SamMemoryType::Preference),
+            tasks: count(SamMemoryType::Task),
+            avg_priority,
         }
     }
 }
 
+/// Result of [`SamBrain::sleep`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SamSleepReport {
+    /// Memories whose strength was reduced
+    pub decayed: usize,
+    /// Memories deleted for being too weak
+    pub forgotten: usize,
+}
+
 #[derive(Debug)]
 pub struct SamBrainStats {
     pub total_memories: usize,
     pub embedding_dim: usize,
+    pub conversations: usize,
+    pub learnings: usize,
+    pub projects: usize,
+    pub decisions: usize,
+    pub lessons: usize,
+    pub preferences: usize,
+    pub tasks: usize,
+    /// Mean priority per category, for categories with memories
+    pub avg_priority: Vec<(SamMemoryType, f32)>,
 }
 
 impl std::fmt::Display for SamBrainStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "🧠 Sam's Brain: {} memories ({}d embeddings)", 
-            self.total_memories, self.embedding_dim)
+        writeln!(f, "🧠 Sam's Brain: {} memories ({}d embeddings)", 
+            self.total_memories, self.embedding_dim)?;
+        write!(f, "   {} {}  {} {}  {} {}  {} {}  {} {}  {} {}  {} {}",
+            SamMemoryType::Conversation, self.conversations,
+            SamMemoryType::Learning, self.learnings,
+            SamMemoryType::Project, self.projects,
+            SamMemoryType::Decision, self.decisions,
+            SamMemoryType::Lesson, self.lessons,
+            SamMemoryType::Preference, self.preferences,
+            SamMemoryType::Task, self.tasks)?;
+        if !self.avg_priority.is_empty() {
+            write!(f, "\n   priority:")?;
+            for (memory_type, avg) in &self.avg_priority {
+                write!(f, "  {} {:.2}", memory_type, avg)?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -270,5 +612,128 @@ mod tests {
         // Recall
         let results = brain.recall("Paul", 5);
         assert!(!results.is_empty());
+
+        let stats = brain.stats();
+        assert_eq!((stats.learnings, stats.preferences, stats.lessons), (1, 1, 1));
+        assert_eq!(stats.conversations, 0);
+    }
+
+    #[test]
+    fn test_export_import_roundtrip_keeps_types() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sam.json");
+
+        let mut brain = SamBrain::new(dir.path().join("old").to_str().unwrap()).unwrap();
+        brain.remember_conversation("Talked about the trip to Busan", "discord").unwrap();
+        brain.remember_preference("Paul likes dark mode").unwrap();
+        let project = brain.remember(SamMemory::project("memory-brain", "Add sam export")).unwrap();
+
+        // Bookkeeping that `remember` would reset
+        let mut original = brain.brain.semantic.get(&project).unwrap().unwrap();
+        original.strength = 0.4;
+        original.access_count = 7;
+        original.last_accessed = Utc::now() - chrono::Duration::days(3);
+        brain.brain.semantic.update(&original).unwrap();
+        assert_eq!(brain.export(&path).unwrap(), 3);
+
+        let mut fresh = SamBrain::new(dir.path().join("new").to_str().unwrap()).unwrap();
+        assert_eq!(fresh.import(&path).unwrap(), 3);
+        let stats = fresh.stats();
+        assert_eq!((stats.conversations, stats.preferences, stats.projects), (1, 1, 1));
+
+        let item = fresh.brain.semantic.get(&project).unwrap().unwrap();
+        let restored = SamMemory::from_item(&item).unwrap();
+        assert_eq!(restored.memory_type, SamMemoryType::Project);
+        assert_eq!(restored.tags, vec!["memory-brain".to_string()]);
+        assert!(item.tags.contains(&"sam:project".to_string()));
+        assert_eq!(item.strength, original.strength);
+        assert_eq!(item.access_count, original.access_count);
+        assert_eq!(item.last_accessed.timestamp_millis(), original.last_accessed.timestamp_millis());
+        assert!(item.embedding.is_some());
+
+        // Importing again adds nothing
+        assert_eq!(fresh.import(&path).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_sleep_decays_conversations_faster_than_preferences() {
+        let dir = tempdir().unwrap();
+        let mut brain = SamBrain::new(dir.path().to_str().unwrap()).unwrap();
+
+        let conversation = brain.remember_conversation("Chatted about the weather", "imessage").unwrap();
+        let preference = brain.remember_preference("Paul likes dark mode").unwrap();
+
+        // Age both memories equally
+        for id in [conversation, preference] {
+            let mut item = brain.brain.semantic.get(&id).unwrap().unwrap();
+            item.last_accessed = Utc::now() - chrono::Duration::days(5);
+            brain.brain.semantic.update(&item).unwrap();
+        }
+
+        let report = brain.sleep().unwrap();
+        assert_eq!(report, SamSleepReport { decayed: 2, forgotten: 0 });
+
+        let strength = |id| brain.brain.semantic.get(&id).unwrap().unwrap().strength;
+        let (conversation, preference) = (strength(conversation), strength(preference));
+        assert!(conversation < preference, "{} vs {}", conversation, preference);
+        assert!(preference < 1.0);
+    }
+
+    #[test]
+    fn test_recall_records_access_only_for_returned_memories() {
+        let dir = tempdir().unwrap();
+        let mut brain = SamBrain::new(dir.path().to_str().unwrap()).unwrap();
+
+        let rust = brain.remember_preference("Paul likes Rust").unwrap();
+        let tea = brain.remember_conversation("Paul drinks green tea", "imessage").unwrap();
+
+        let results = brain.recall("Paul", 1);
+        assert_eq!(results.len(), 1);
+        let (returned, skipped) = if results[0].id == rust { (rust, tea) } else { (tea, rust) };
+        // New memories start at one access
+        assert_eq!(brain.brain.semantic.get(&returned).unwrap().unwrap().access_count, 2);
+        assert_eq!(brain.brain.semantic.get(&skipped).unwrap().unwrap().access_count, 1);
+    }
+
+    #[test]
+    fn test_recall_ranks_preference_above_equally_matching_conversation() {
+        let dir = tempdir().unwrap();
+        let mut brain = SamBrain::new(dir.path().to_str().unwrap()).unwrap();
+
+        let conversation = brain.remember_conversation("Paul drinks green tea every morning", "imessage").unwrap();
+        let preference = brain.remember_preference("Every morning Paul drinks green tea").unwrap();
+
+        let results = brain.recall("green tea", 5);
+        let rank = |id| results.iter().position(|item| item.id == id).unwrap();
+        assert!(rank(preference) < rank(conversation));
+
+        // Priority survives storage, and an override sticks
+        let task = brain.remember(SamMemory::new("Renew the passport", SamMemoryType::Task).with_priority(0.9)).unwrap();
+        let item = brain.brain.semantic.get(&task).unwrap().unwrap();
+        assert_eq!(SamMemory::from_item(&item).unwrap().priority, 0.9);
+
+        // Memories saved before priorities existed take their category's default
+        let json = serde_json::json!({
+            "id": Uuid::new_v4(),
+            "content": "Renew the passport",
+            "memory_type": "Task",
+            "source": "manual",
+            "channel": null,
+            "importance": 3,
+            "created_at": Utc::now(),
+            "tags": [],
+        });
+        let old: SamMemory = serde_json::from_value(json).unwrap();
+        assert_eq!(old.priority, SamMemoryType::Task.default_priority());
+
+        let stats = brain.stats();
+        assert_eq!(
+            stats.avg_priority,
+            vec![
+                (SamMemoryType::Conversation, 0.3),
+                (SamMemoryType::Preference, 1.0),
+                (SamMemoryType::Task, 0.9),
+            ]
+        );
     }
 }
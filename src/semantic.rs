@@ -8,20 +8,56 @@
 
 use crate::types::{MemoryItem, MemoryType};
 use crate::forgetting::ForgettingCurve;
+use crate::inverted_index::InvertedIndex;
 use crate::storage::Storage;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Above this many candidates one full scan beats per-id lookups
+const MAX_KEY_LOOKUPS: usize = 200;
+
+/// Text indexed for [`SemanticMemory::search_like`] (content and context)
+fn indexed_text(item: &MemoryItem) -> String {
+    match item.context {
+        Some(ref context) => format!("{} {}", item.content, context),
+        None => item.content.clone(),
+    }
+}
 
 pub struct SemanticMemory {
     storage: Storage,
+    /// term → id map over stored content, so keyword searches fetch by id
+    terms: InvertedIndex,
+    /// `terms` has been built from storage (lazily, on first search)
+    terms_loaded: AtomicBool,
 }
 
 impl SemanticMemory {
     pub fn new(db_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let storage = Storage::new(db_path, "semantic")?;
-        Ok(Self { storage })
+        Self::with_storage(Storage::new(db_path, "semantic")?)
+    }
+
+    /// Open in `keyspace` with custom CoreDB tuning (see [`Storage::open`])
+    pub fn open(db_path: &str, keyspace: &str, tuning: &coredb::DatabaseConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::with_storage(Storage::open(db_path, keyspace, "semantic", tuning)?)
+    }
+
+    fn with_storage(storage: Storage) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            storage,
+            terms: InvertedIndex::new(),
+            terms_loaded: AtomicBool::new(false),
+        })
     }
 
     /// Store a semantic fact/concept
-    pub fn store(&mut self, mut item: MemoryItem) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn store(&mut self, item: MemoryItem) -> Result<(), Box<dyn std::error::Error>> {
+        self.store_merged(item).map(|_| ())
+    }
+
+    /// Like [`SemanticMemory::store`], returning the id the fact is stored
+    /// under: the item's own, or that of the similar fact it strengthened
+    pub fn store_merged(&mut self, mut item: MemoryItem) -> Result<uuid::Uuid, Box<dyn std::error::Error>> {
         item.memory_type = MemoryType::Semantic;
         
         // Check for duplicate/similar facts and merge
@@ -30,20 +66,157 @@ impl SemanticMemory {
             let mut updated = existing;
             updated.access();
             self.storage.update(&updated)?;
+            Ok(updated.id)
         } else {
             self.storage.save(&item)?;
+            self.terms.add(item.id, &indexed_text(&item));
+            Ok(item.id)
         }
+    }
+
+    /// Get a memory by id
+    pub fn get(&self, id: &uuid::Uuid) -> Result<Option<MemoryItem>, Box<dyn std::error::Error>> {
+        self.storage.get_by_id(id)
+    }
+
+    /// Overwrite a stored memory (matched by id)
+    pub fn update(&self, item: &MemoryItem) -> Result<(), Box<dyn std::error::Error>> {
+        self.storage.update(item)?;
+        self.terms.update(item.id, &indexed_text(item));
         Ok(())
     }
 
+    /// Link `from` → `to` (no-op if `from` isn't stored here)
+    pub fn add_association(&mut self, from: uuid::Uuid, to: uuid::Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        self.storage.add_association(from, to)
+    }
+
+    /// Delete a memory by id (false if it isn't stored here)
+    pub fn delete(&mut self, id: &uuid::Uuid) -> Result<bool, Box<dyn std::error::Error>> {
+        if self.storage.get_by_id(id)?.is_none() {
+            return Ok(false);
+        }
+        self.storage.delete(id)?;
+        self.terms.remove(id);
+        Ok(true)
+    }
+
+    /// Bytes of stored embeddings (see [`Storage::embedding_bytes`])
+    pub fn embedding_bytes(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.storage.embedding_bytes()
+    }
+
+    /// Rewrite stored embeddings as int8 (see [`Storage::quantize_stored_embeddings`])
+    pub fn quantize_stored_embeddings(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.storage.quantize_stored_embeddings()
+    }
+
+    /// Store new embeddings as int8 (see [`Storage::set_quantize_embeddings`])
+    pub fn set_quantize_embeddings(&mut self, on: bool) {
+        self.storage.set_quantize_embeddings(on);
+    }
+
+    /// Delete every semantic memory; returns how many were removed
+    pub fn clear(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        let removed = self.storage.clear()?;
+        self.terms.clear();
+        self.terms_loaded.store(true, Ordering::Relaxed);
+        Ok(removed)
+    }
+
+    /// Number of stored semantic memories
+    pub fn count(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.storage.count()
+    }
+
     /// Search semantic memories
+    ///
+    /// Keyword queries go through [`Self::search_like`]; an empty query
+    /// lists everything.
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
-        self.storage.search(query, limit)
+        self.search_like(query, limit)
+    }
+
+    /// Substring search that only reads candidate rows
+    ///
+    /// CoreDB has no `LIKE`, so an in-memory term → id map narrows the
+    /// candidates (memories with a word containing each query word) and
+    /// only those are fetched by key. Results match [`Storage::search`]:
+    /// case-insensitive substring on content or context, strongest first.
+    ///
+    /// Query words are the index's own tokens (stop words kept, Korean
+    /// particles stripped), so "rust is" or "러스트를" still narrow to
+    /// candidates. Queries without any indexable word (empty, single Latin
+    /// characters, punctuation) or with too many candidates fall back to a
+    /// full scan.
+    pub fn search_like(&self, term: &str, limit: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+        let words: Vec<String> = crate::text::index_tokens_with_offsets(term)
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect();
+        if words.is_empty() {
+            return self.storage.search(term, limit);
+        }
+        self.ensure_terms_loaded()?;
+
+        let keywords = self.terms.keywords();
+        let mut candidates: Option<HashSet<uuid::Uuid>> = None;
+        for word in &words {
+            let ids: HashSet<uuid::Uuid> = keywords
+                .iter()
+                .filter(|kw| kw.contains(word.as_str()))
+                .flat_map(|kw| self.terms.keyword_docs(kw))
+                .collect();
+            candidates = Some(match candidates {
+                Some(prev) => prev.intersection(&ids).copied().collect(),
+                None => ids,
+            });
+        }
+
+        let candidates = candidates.unwrap_or_default();
+        if candidates.len() > MAX_KEY_LOOKUPS {
+            return self.storage.search(term, limit);
+        }
+
+        let needle = term.to_lowercase();
+        let mut items = Vec::new();
+        for id in candidates {
+            if let Some(item) = self.storage.get_by_id(&id)? {
+                let matches = item.content.to_lowercase().contains(&needle)
+                    || item.context.as_ref().map_or(false, |c| c.to_lowercase().contains(&needle));
+                if matches {
+                    items.push(item);
+                }
+            }
+        }
+
+        items.sort_by(|a, b| b.strength.partial_cmp(&a.strength).unwrap_or(std::cmp::Ordering::Equal));
+        items.truncate(limit);
+        Ok(items)
+    }
+
+    /// Full-table scans issued by the underlying storage
+    pub fn full_scans(&self) -> u64 {
+        self.storage.full_scans()
+    }
+
+    /// Build the term index from storage once
+    fn ensure_terms_loaded(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.terms_loaded.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let docs: Vec<(uuid::Uuid, String)> = self.storage.get_all()?
+            .into_iter()
+            .map(|item| (item.id, indexed_text(&item)))
+            .collect();
+        self.terms.add_batch(&docs);
+        self.terms_loaded.store(true, Ordering::Relaxed);
+        Ok(())
     }
 
     /// Find similar facts (to avoid duplicates)
     fn find_similar(&self, content: &str) -> Result<Option<MemoryItem>, Box<dyn std::error::Error>> {
-        let results = self.storage.search(content, 1)?;
+        let results = self.search_like(content, 1)?;
         // If high similarity match exists, return it
         if let Some(item) = results.into_iter().next() {
             if content.to_lowercase().contains(&item.content.to_lowercase()) 
@@ -54,11 +227,35 @@ impl SemanticMemory {
         Ok(None)
     }
 
+    /// Embedding dimension recorded for this database (keyspace metadata)
+    pub fn embedding_dim(&self) -> Result<Option<usize>, Box<dyn std::error::Error>> {
+        self.storage.embedding_dim()
+    }
+
+    /// Overwrite the recorded embedding dimension
+    pub fn set_embedding_dim(&self, dim: usize) -> Result<(), Box<dyn std::error::Error>> {
+        self.storage.set_embedding_dim(dim)
+    }
+
     /// Get facts by tag
     pub fn get_by_tag(&self, tag: &str) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
         self.storage.get_by_tag(tag)
     }
 
+    /// Get recent semantic memories, skipping the newest `offset`
+    pub fn get_recent(&self, limit: usize, offset: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+        self.storage.get_recent(limit, offset)
+    }
+
+    /// Get memories created within a time range
+    pub fn get_by_time_range(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+        self.storage.get_by_time_range(start, end)
+    }
+
     /// Execute arbitrary CQL query and return HTML results
     pub fn execute_cql_html(&self, query: &str) -> Result<String, String> {
         self.storage.execute_cql_html(query)
@@ -68,12 +265,14 @@ impl SemanticMemory {
     pub fn apply_forgetting(&mut self, curve: &ForgettingCurve) -> Result<(), Box<dyn std::error::Error>> {
         let all = self.storage.get_all()?;
         for mut item in all {
-            // Semantic memories decay at half the rate of episodic
+            // Slower than episodic via the curve's per-type rate, and
+            // never more than half the strength per pass
             let decay = curve.calculate_decay(&item) * 0.5 + 0.5;
             item.decay(decay);
             
             if item.is_forgotten() {
                 self.storage.delete(&item.id)?;
+                self.terms.remove(&item.id);
             } else {
                 self.storage.update(&item)?;
             }
@@ -81,3 +280,75 @@ impl SemanticMemory {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forgetting_keeps_at_least_half_the_strength() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut semantic = SemanticMemory::new(dir.path().to_str().unwrap()).unwrap();
+
+        let mut item = MemoryItem::new("Rust uses ownership for memory safety", None);
+        item.strength = 0.8;
+        item.last_accessed = chrono::Utc::now() - chrono::Duration::days(365);
+        let id = item.id;
+        semantic.store(item).unwrap();
+
+        semantic.apply_forgetting(&ForgettingCurve::per_type()).unwrap();
+        let strength = semantic.get(&id).unwrap().unwrap().strength;
+        assert!((0.4..0.8).contains(&strength), "{}", strength);
+    }
+
+    #[test]
+    fn test_keyword_search_avoids_full_scans() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut semantic = SemanticMemory::new(dir.path().to_str().unwrap()).unwrap();
+
+        for i in 0..200 {
+            semantic.store(MemoryItem::new(&format!("Filler fact number {}", i), None)).unwrap();
+        }
+        semantic.store(MemoryItem::new("Rust uses ownership for memory safety", None)).unwrap();
+        semantic.store(MemoryItem::new("Ownership rules are checked at compile time", None)).unwrap();
+
+        // Plain scan-and-filter: one full scan per query
+        let before = semantic.full_scans();
+        for _ in 0..10 {
+            assert_eq!(semantic.storage.search("ownership", 10).unwrap().len(), 2);
+        }
+        let scan_path = semantic.full_scans() - before;
+
+        // Term index: candidates are fetched by id
+        let before = semantic.full_scans();
+        for _ in 0..10 {
+            let found = semantic.search_like("ownership", 10).unwrap();
+            assert_eq!(found.len(), 2);
+            assert!(found.iter().all(|m| m.content.to_lowercase().contains("ownership")));
+        }
+        let index_path = semantic.full_scans() - before;
+
+        assert_eq!(scan_path, 10);
+        assert_eq!(index_path, 0);
+
+        // Substring inside a word still matches, like Storage::search
+        assert_eq!(semantic.search_like("wnersh", 10).unwrap().len(), 2);
+        assert!(semantic.search_like("borrow checker", 10).unwrap().is_empty());
+        assert_eq!(semantic.full_scans() - before, 0);
+    }
+
+    #[test]
+    fn test_stop_word_and_particle_queries_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut semantic = SemanticMemory::new(dir.path().to_str().unwrap()).unwrap();
+
+        semantic.store(MemoryItem::new("Rust is fast and safe", None)).unwrap();
+        semantic.store(MemoryItem::new("러스트를 배우는 중이다", None)).unwrap();
+
+        let before = semantic.full_scans();
+        assert_eq!(semantic.search_like("rust is", 10).unwrap().len(), 1);
+        assert_eq!(semantic.search_like("러스트를", 10).unwrap().len(), 1);
+        assert!(semantic.search_like("rust was", 10).unwrap().is_empty());
+        assert_eq!(semantic.full_scans() - before, 0);
+    }
+}
@@ -5,29 +5,203 @@
 //! ## Endpoints
 //! - POST /store - Store a memory
 //! - POST /recall - Search memories
+//! - GET /recall/stream - Search memories, streamed as server-sent events
 //! - POST /batch - Batch store memories
 //! - GET /stats - Get statistics
+//! - PATCH /memory/:id - Edit a memory's content, tags or strength
 //! - DELETE /memory/:id - Delete a memory
+//! - GET /audit?from=&to= - Audit log counts per day and command
+//! - GET /metrics - Prometheus metrics (outside `/api`, same auth)
+//!
+//! ## Auth
+//! When API keys are configured (`MEMORY_BRAIN_API_KEYS=key1,key2`), every
+//! `/api` endpoint except `/health` needs `Authorization: Bearer <key>` (401
+//! otherwise). Write endpoints are rate limited per key with a token bucket
+//! (`MEMORY_BRAIN_RATE_LIMIT` writes per minute, 429 when exhausted).
+//! The web UI's memory data, image and write routes are keyed the same way;
+//! browsers sign in at `/login`, which keeps the key in an HttpOnly cookie.
 
 use axum::{
-    extract::{Path, State},
-    http::{StatusCode, Method},
-    response::Json,
+    extract::{MatchedPath, Path, Query, Request, State},
+    http::{header, StatusCode, Method},
+    middleware::{self, Next},
+    response::sse::{Event, KeepAlive, Sse},
+    response::{IntoResponse, Json, Response},
     routing::{get, post, delete},
     Router,
 };
 use tower_http::cors::{CorsLayer, Any};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::{mpsc, RwLock};
+use tokio_stream::wrappers::ReceiverStream;
 
-use crate::{Brain, MemoryItem, MemoryType, GloVeEmbedder, HnswIndex, Embedder};
+use crate::cli_json::JsonMemory;
+use crate::clip_onnx::ClipServerProvider;
+use crate::visual::ClipProvider;
+use crate::visual_storage::VisualStorage;
+use crate::{Brain, Config, MemoryEdit, MemoryItem, MemoryType, HnswIndex, Embedder};
 
 /// Server state
 pub struct AppState {
     pub brain: RwLock<Brain>,
     pub hnsw: HnswIndex,
     pub embedder: Arc<dyn Embedder>,
+    /// Accepted bearer tokens (empty: no auth)
+    pub api_keys: HashSet<String>,
+    /// Per-key limiter for write routes
+    pub rate_limiter: RateLimiter,
+    /// Request latency histograms for `/metrics`
+    pub metrics: crate::metrics::RequestMetrics,
+    /// CoreDB visual memories searched by the web UI alongside the photos index
+    pub visual: Option<Arc<VisualStorage>>,
+}
+
+impl AppState {
+    /// Open state: no API keys, default write rate limit
+    pub fn new(brain: Brain, hnsw: HnswIndex, embedder: Arc<dyn Embedder>) -> Self {
+        Self {
+            brain: RwLock::new(brain),
+            hnsw,
+            embedder,
+            api_keys: HashSet::new(),
+            rate_limiter: RateLimiter::default(),
+            metrics: crate::metrics::RequestMetrics::new(),
+            visual: None,
+        }
+    }
+
+    /// Require one of `keys` as a bearer token
+    pub fn with_api_keys(mut self, keys: HashSet<String>) -> Self {
+        self.api_keys = keys;
+        self
+    }
+
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = limiter;
+        self
+    }
+
+    /// Include `visual`'s memories in the web UI's image search
+    pub fn with_visual(mut self, visual: Arc<VisualStorage>) -> Self {
+        self.visual = Some(visual);
+        self
+    }
+}
+
+/// Default writes per minute per API key
+pub const DEFAULT_WRITES_PER_MINUTE: u32 = 60;
+
+/// Token bucket per API key: `capacity` requests at once, refilled at
+/// `capacity` per minute
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<HashMap<String, (f64, Instant)>>,
+}
+
+impl RateLimiter {
+    pub fn per_minute(capacity: u32) -> Self {
+        Self {
+            capacity: capacity as f64,
+            refill_per_sec: capacity as f64 / 60.0,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take one token for `key`; false if its bucket is empty
+    pub fn try_acquire(&self, key: &str) -> bool {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let (tokens, last) = buckets.entry(key.to_string()).or_insert((self.capacity, now));
+        *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * self.refill_per_sec).min(self.capacity);
+        *last = now;
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::per_minute(DEFAULT_WRITES_PER_MINUTE)
+    }
+}
+
+/// Keys from `MEMORY_BRAIN_API_KEYS` (comma separated, blanks ignored)
+pub fn api_keys_from_env() -> HashSet<String> {
+    std::env::var("MEMORY_BRAIN_API_KEYS")
+        .map(|v| v.split(',').map(str::trim).filter(|k| !k.is_empty()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Cookie the web UI's `/login` stores the API key in
+pub const API_KEY_COOKIE: &str = "memory_brain_key";
+
+/// API key a request authenticated with
+#[derive(Clone)]
+struct ApiKey(String);
+
+/// Key sent as a bearer token, else in the [`API_KEY_COOKIE`] cookie
+fn request_key(req: &Request) -> Option<String> {
+    let headers = req.headers();
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if let Some(token) = bearer {
+        return Some(token.trim().to_string());
+    }
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == API_KEY_COOKIE)
+        .and_then(|(_, value)| urlencoding::decode(value).ok())
+        .map(|value| value.into_owned())
+}
+
+/// Reject requests without a configured key (no-op without keys)
+async fn require_api_key(State(state): State<Arc<AppState>>, mut req: Request, next: Next) -> Response {
+    if state.api_keys.is_empty() {
+        return next.run(req).await;
+    }
+    match request_key(&req) {
+        Some(token) if state.api_keys.contains(&token) => {
+            req.extensions_mut().insert(ApiKey(token));
+            next.run(req).await
+        }
+        _ => (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")]).into_response(),
+    }
+}
+
+/// Per-key token bucket on write routes (runs after `require_api_key`)
+async fn rate_limit(State(state): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    if let Some(ApiKey(key)) = req.extensions().get::<ApiKey>() {
+        if !state.rate_limiter.try_acquire(key) {
+            return StatusCode::TOO_MANY_REQUESTS.into_response();
+        }
+    }
+    next.run(req).await
+}
+
+/// Record request latency per matched route (wraps auth and rate limiting)
+async fn track_latency(State(state): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = req.method().clone();
+    let route = req.extensions().get::<MatchedPath>().cloned();
+    let response = next.run(req).await;
+    let route = route.as_ref().map_or("unmatched", |r| r.as_str());
+    state.metrics.observe(route, method.as_str(), started.elapsed().as_secs_f64());
+    response
 }
 
 /// Store request
@@ -47,6 +221,29 @@ pub struct StoreRequest {
 pub struct StoreResponse {
     id: String,
     success: bool,
+    /// Store the memory went to (given or classified)
+    memory_type: String,
+}
+
+/// Memory type from a request field (`episodic`, `semantic`, `procedural`
+/// or their short forms); None for anything else
+pub(crate) fn parse_memory_type(name: &str) -> Option<MemoryType> {
+    match name.trim().to_lowercase().as_str() {
+        "episodic" | "epi" | "e" => Some(MemoryType::Episodic),
+        "semantic" | "sem" | "s" => Some(MemoryType::Semantic),
+        "procedural" | "proc" | "p" => Some(MemoryType::Procedural),
+        _ => None,
+    }
+}
+
+/// Lowercase name of a memory type, as accepted by [`parse_memory_type`]
+fn memory_type_name(memory_type: &MemoryType) -> &'static str {
+    match memory_type {
+        MemoryType::Working => "working",
+        MemoryType::Episodic => "episodic",
+        MemoryType::Semantic => "semantic",
+        MemoryType::Procedural => "procedural",
+    }
 }
 
 /// Recall request
@@ -57,10 +254,40 @@ pub struct RecallRequest {
     limit: usize,
     #[serde(default)]
     use_hnsw: bool,
+    /// MMR diversity in [0, 1] (0 = pure relevance)
+    #[serde(default)]
+    diversity: Option<f32>,
 }
 
 fn default_limit() -> usize { 5 }
 
+/// Audit range query (`GET /audit?from=YYYY-MM-DD&to=YYYY-MM-DD`); defaults
+/// to the last 7 days
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    #[serde(default)]
+    from: Option<String>,
+    #[serde(default)]
+    to: Option<String>,
+}
+
+/// Streaming recall query (`GET /recall/stream?query=...&limit=...`)
+#[derive(Debug, Deserialize)]
+pub struct RecallStreamQuery {
+    query: String,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+/// One streamed recall result
+#[derive(Debug, Serialize)]
+pub struct StreamedMemory {
+    #[serde(flatten)]
+    memory: JsonMemory,
+    /// `keyword` (inverted index hit) or `semantic` (embedding match)
+    source: &'static str,
+}
+
 /// Memory response
 #[derive(Debug, Serialize)]
 pub struct MemoryResponse {
@@ -73,6 +300,34 @@ pub struct MemoryResponse {
     created_at: String,
 }
 
+impl From<MemoryItem> for MemoryResponse {
+    fn from(m: MemoryItem) -> Self {
+        Self {
+            id: m.id.to_string(),
+            content: m.content,
+            context: m.context,
+            tags: m.tags,
+            strength: m.strength,
+            similarity: None,
+            created_at: m.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Edit request (`PATCH /memory/:id`); omitted fields are left unchanged
+#[derive(Debug, Deserialize)]
+pub struct PatchMemoryRequest {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+    #[serde(default)]
+    strength: Option<f32>,
+    /// Move to another long-term store (episodic | semantic | procedural)
+    #[serde(default)]
+    memory_type: Option<String>,
+}
+
 /// Batch store request
 #[derive(Debug, Deserialize)]
 pub struct BatchStoreRequest {
@@ -92,6 +347,7 @@ pub struct StatsResponse {
     working_memory: usize,
     semantic_memory: usize,
     episodic_memory: usize,
+    procedural_memory: usize,
     hnsw_indexed: usize,
     embedding_dim: usize,
 }
@@ -101,62 +357,97 @@ pub fn create_router(state: Arc<AppState>) -> Router {
     // CORS configuration - allow all origins for API access
     let cors = CorsLayer::new()
         .allow_origin(Any)
-        .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
+        .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE, Method::OPTIONS])
         .allow_headers(Any);
 
-    // API routes
-    let api = Router::new()
+    // Write routes are rate limited per API key
+    let writes = Router::new()
         .route("/store", post(store_handler))
-        .route("/recall", post(recall_handler))
         .route("/batch", post(batch_handler))
+        .route("/memory/:id", delete(delete_handler).patch(patch_handler))
+        .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit));
+
+    // API routes (all but /health need a key when keys are configured)
+    let api = Router::new()
+        .route("/recall", post(recall_handler))
+        .route("/recall/stream", get(recall_stream_handler))
         .route("/stats", get(stats_handler))
-        .route("/memory/:id", delete(delete_handler))
+        .route("/audit", get(audit_handler))
+        .merge(writes)
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_api_key))
+        .route_layer(middleware::from_fn_with_state(state.clone(), track_latency))
         .route("/health", get(health_handler));
+
+    // Prometheus scrape endpoint (keyed like the API)
+    let metrics = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_api_key));
     
-    // Web UI routes
+    // Web UI routes; the ones returning memories or image files are keyed,
+    // the mutating ones and the CQL console also rate limited like the API writes
     let web = crate::web_ui::create_web_router();
+    let web_reads = crate::web_ui::create_web_read_router()
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_api_key));
+    let web_writes = crate::web_ui::create_web_write_router()
+        .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_api_key));
     
     Router::new()
         .nest("/api", api)
+        .merge(metrics)
         .merge(web)
+        .merge(web_reads)
+        .merge(web_writes)
         .layer(cors)
         .with_state(state)
 }
 
 /// Store a memory
+///
+/// `memory_type` picks the store; without it the consolidator classifies
+/// the content. An unknown type is a 400.
 async fn store_handler(
     State(state): State<Arc<AppState>>,
     Json(req): Json<StoreRequest>,
 ) -> Result<Json<StoreResponse>, StatusCode> {
-    let mut brain = state.brain.write().await;
-    
-    // Generate embedding
-    let embedding = state.embedder.embed(&req.content);
-    
-    // Create memory item
+    let item = store_request(&state, req).await.map_err(|e| match e {
+        StoreError::BadType => StatusCode::BAD_REQUEST,
+        StoreError::Failed => StatusCode::INTERNAL_SERVER_ERROR,
+    })?;
+    Ok(Json(StoreResponse {
+        id: item.id.to_string(),
+        success: true,
+        memory_type: memory_type_name(&item.memory_type).to_string(),
+    }))
+}
+
+/// Why [`store_request`] did not store
+enum StoreError {
+    BadType,
+    Failed,
+}
+
+/// Embed, route by type and index one store request (brain and server HNSW)
+async fn store_request(state: &AppState, req: StoreRequest) -> Result<MemoryItem, StoreError> {
+    let memory_type = match req.memory_type.as_deref().filter(|t| !t.trim().is_empty()) {
+        Some(name) => Some(parse_memory_type(name).ok_or(StoreError::BadType)?),
+        None => None,
+    };
+
     let mut item = MemoryItem::new(&req.content, req.context.as_deref());
     item.tags = req.tags;
-    item.embedding = Some(embedding.clone());
-    
-    if let Some(ref mt) = req.memory_type {
-        item.memory_type = match mt.to_lowercase().as_str() {
-            "episodic" => MemoryType::Episodic,
-            "procedural" => MemoryType::Procedural,
-            _ => MemoryType::Semantic,
-        };
-    }
-    
-    let id = item.id.to_string();
-    
-    // Store in brain
-    match brain.semantic.store(item.clone()) {
-        Ok(_) => {
-            // Also add to HNSW index
-            let _ = state.hnsw.add(item.id, embedding);
-            Ok(Json(StoreResponse { id, success: true }))
-        }
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    item.embedding = state.embedder.try_embed_document(&req.content).ok();
+
+    let item = state
+        .brain
+        .write()
+        .await
+        .store_typed(item, memory_type)
+        .map_err(|_| StoreError::Failed)?;
+    if let Some(ref embedding) = item.embedding {
+        let _ = state.hnsw.add(item.id, embedding.clone());
     }
+    Ok(item)
 }
 
 /// Recall memories
@@ -164,11 +455,12 @@ async fn recall_handler(
     State(state): State<Arc<AppState>>,
     Json(req): Json<RecallRequest>,
 ) -> Result<Json<Vec<MemoryResponse>>, StatusCode> {
-    let mut brain = state.brain.write().await;
+    // Recall is `&self`, so concurrent queries share the read lock
+    let brain = state.brain.read().await;
     
     let results = if req.use_hnsw {
         // Use HNSW for fast search
-        let query_embedding = state.embedder.embed(&req.query);
+        let query_embedding = state.embedder.embed_query(&req.query);
         let hnsw_results = state.hnsw.search(&query_embedding, req.limit);
         
         // Convert to MemoryResponse (need to fetch full items)
@@ -186,46 +478,102 @@ async fn recall_handler(
             })
             .collect()
     } else {
-        // Use brain's recall
-        let memories = brain.recall(&req.query, req.limit);
+        // Use brain's recall (MMR re-ranked when diversity is requested)
+        let memories = match req.diversity {
+            Some(diversity) if diversity > 0.0 => {
+                brain.recall_diverse(&req.query, req.limit, 1.0 - diversity.min(1.0))
+            }
+            _ => brain.recall(&req.query, req.limit),
+        };
         
-        memories
-            .into_iter()
-            .map(|m| MemoryResponse {
-                id: m.id.to_string(),
-                content: m.content,
-                context: m.context,
-                tags: m.tags,
-                strength: m.strength,
-                similarity: None,
-                created_at: m.created_at.to_rfc3339(),
-            })
-            .collect()
+        memories.into_iter().map(MemoryResponse::from).collect()
     };
     
     Ok(Json(results))
 }
 
+/// Stream recall results as server-sent events
+///
+/// Keyword-index hits are sent first, then embedding-ranked matches, one
+/// `data:` event per memory (at most `limit`), followed by a `done` event
+/// carrying the count. Stops early if the client disconnects.
+async fn recall_stream_handler(
+    State(state): State<Arc<AppState>>,
+    Query(req): Query<RecallStreamQuery>,
+) -> Sse<ReceiverStream<Result<Event, Infallible>>> {
+    let (tx, rx) = mpsc::channel(16);
+
+    tokio::spawn(async move {
+        let mut sent = HashSet::new();
+
+        // 1. Keyword hits (cheap index lookup)
+        let keyword_hits: Vec<MemoryItem> = {
+            let brain = state.brain.read().await;
+            brain
+                .keyword_index
+                .search_ranked(&req.query, req.limit)
+                .into_iter()
+                .filter_map(|(id, _)| brain.get_by_id(&id).ok().flatten())
+                .collect()
+        };
+        for item in keyword_hits {
+            if sent.len() >= req.limit || !sent.insert(item.id) {
+                continue;
+            }
+            if !send_memory(&tx, &item, "keyword", None).await {
+                return; // client went away
+            }
+        }
+
+        // 2. Embedding-ranked matches not already sent
+        if sent.len() < req.limit {
+            let semantic = {
+                let brain = state.brain.read().await;
+                brain.semantic_search_hnsw(&req.query, req.limit + sent.len())
+            };
+            for (item, similarity) in semantic {
+                if sent.len() >= req.limit || !sent.insert(item.id) {
+                    continue;
+                }
+                if !send_memory(&tx, &item, "semantic", Some(similarity)).await {
+                    return;
+                }
+            }
+        }
+
+        let done = Event::default().event("done").data(sent.len().to_string());
+        let _ = tx.send(Ok(done)).await;
+    });
+
+    Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default())
+}
+
+/// Send one memory event; false once the client has disconnected
+async fn send_memory(
+    tx: &mpsc::Sender<Result<Event, Infallible>>,
+    item: &MemoryItem,
+    source: &'static str,
+    similarity: Option<f32>,
+) -> bool {
+    let mut memory = JsonMemory::new(item);
+    memory.similarity = similarity;
+    match Event::default().json_data(StreamedMemory { memory, source }) {
+        Ok(event) => tx.send(Ok(event)).await.is_ok(),
+        Err(_) => true, // unserializable item: skip it
+    }
+}
+
 /// Batch store
 async fn batch_handler(
     State(state): State<Arc<AppState>>,
     Json(req): Json<BatchStoreRequest>,
 ) -> Result<Json<BatchResponse>, StatusCode> {
-    let mut brain = state.brain.write().await;
     let mut stored = 0;
     let mut errors = 0;
     
     for mem_req in req.memories {
-        let embedding = state.embedder.embed(&mem_req.content);
-        let mut item = MemoryItem::new(&mem_req.content, mem_req.context.as_deref());
-        item.tags = mem_req.tags;
-        item.embedding = Some(embedding.clone());
-        
-        match brain.semantic.store(item.clone()) {
-            Ok(_) => {
-                let _ = state.hnsw.add(item.id, embedding);
-                stored += 1;
-            }
+        match store_request(&state, mem_req).await {
+            Ok(_) => stored += 1,
             Err(_) => errors += 1,
         }
     }
@@ -237,34 +585,142 @@ async fn batch_handler(
 async fn stats_handler(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<StatsResponse>, StatusCode> {
-    let brain = state.brain.read().await;
+    let stats = state.brain.read().await.stats();
     let hnsw_stats = state.hnsw.stats();
     
-    // Get counts by searching with empty query
-    let semantic_count = brain.semantic.search("", 10000).map(|v| v.len()).unwrap_or(0);
-    let episodic_count = brain.episodic.search("", 10000).map(|v| v.len()).unwrap_or(0);
-    
     Ok(Json(StatsResponse {
-        working_memory: brain.working.len(),
-        semantic_memory: semantic_count,
-        episodic_memory: episodic_count,
+        working_memory: stats.working,
+        semantic_memory: stats.semantic,
+        episodic_memory: stats.episodic,
+        procedural_memory: stats.procedural,
         hnsw_indexed: hnsw_stats.count,
         embedding_dim: hnsw_stats.dimension,
     }))
 }
 
-/// Delete a memory
+/// Prometheus text exposition: memory counts, audit counts, cache and
+/// index sizes, request latencies
+async fn metrics_handler(State(state): State<Arc<AppState>>) -> Response {
+    use crate::metrics::{write_header, write_sample};
+
+    let mut out = String::with_capacity(4096);
+    {
+        let brain = state.brain.read().await;
+        let name = "memory_brain_memories";
+        write_header(&mut out, name, "gauge", "Memories stored, by type");
+        write_sample(&mut out, name, &[("type", "working")], brain.working.len() as f64);
+        write_sample(&mut out, name, &[("type", "episodic")], brain.episodic.count().unwrap_or(0) as f64);
+        write_sample(&mut out, name, &[("type", "semantic")], brain.semantic.count().unwrap_or(0) as f64);
+        write_sample(&mut out, name, &[("type", "procedural")], brain.procedural.count().unwrap_or(0) as f64);
+
+        let name = "memory_brain_keyword_index_terms";
+        write_header(&mut out, name, "gauge", "Distinct terms in the keyword index");
+        write_sample(&mut out, name, &[], brain.keyword_index.stats().unique_keywords as f64);
+    }
+
+    let name = "memory_brain_hnsw_vectors";
+    write_header(&mut out, name, "gauge", "Vectors in the HNSW index");
+    write_sample(&mut out, name, &[], state.hnsw.stats().count as f64);
+
+    let (stores, recalls, searches) = crate::audit::get_daily_stats();
+    let name = "memory_brain_operations_today";
+    write_header(&mut out, name, "gauge", "Audit-logged operations since local midnight");
+    write_sample(&mut out, name, &[("op", "store")], stores as f64);
+    write_sample(&mut out, name, &[("op", "recall")], recalls as f64);
+    write_sample(&mut out, name, &[("op", "search")], searches as f64);
+
+    if let Some(cache) = state.embedder.cache_stats() {
+        let name = "memory_brain_embedding_cache_hit_ratio";
+        write_header(&mut out, name, "gauge", "Embedding cache hit ratio");
+        write_sample(&mut out, name, &[], cache.hit_rate);
+        let name = "memory_brain_embedding_cache_entries";
+        write_header(&mut out, name, "gauge", "Cached embeddings");
+        write_sample(&mut out, name, &[], cache.size as f64);
+    }
+
+    state.metrics.render(&mut out);
+    ([(header::CONTENT_TYPE, crate::metrics::CONTENT_TYPE)], out).into_response()
+}
+
+/// Audit counts for a date range (400 on a bad or reversed range)
+async fn audit_handler(
+    Query(req): Query<AuditQuery>,
+) -> Result<Json<crate::audit::AuditStats>, StatusCode> {
+    let parse = |s: &str| {
+        chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| StatusCode::BAD_REQUEST)
+    };
+    let to = match req.to {
+        Some(ref s) => parse(s)?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let from = match req.from {
+        Some(ref s) => parse(s)?,
+        None => to - chrono::Duration::days(6),
+    };
+    if from > to {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    Ok(Json(crate::audit::get_stats_range(from, to)))
+}
+
+/// Resolve a full or prefix id (404 if missing, 400 if ambiguous)
+fn resolve_memory(brain: &Brain, id: &str) -> Result<MemoryItem, StatusCode> {
+    match brain.get_by_id_prefix(id) {
+        Ok(Some(item)) => Ok(item),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::BAD_REQUEST),
+    }
+}
+
+/// Delete a memory, returning the deleted item
 async fn delete_handler(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-) -> StatusCode {
-    if let Ok(uuid) = uuid::Uuid::parse_str(&id) {
-        state.hnsw.remove(&uuid);
-        // TODO: also delete from brain storage
-        StatusCode::OK
-    } else {
-        StatusCode::BAD_REQUEST
+) -> Result<Json<MemoryResponse>, StatusCode> {
+    let mut brain = state.brain.write().await;
+    let item = resolve_memory(&brain, &id)?;
+
+    brain
+        .delete(&item.id.to_string())
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    state.hnsw.remove(&item.id);
+
+    Ok(Json(item.into()))
+}
+
+/// Edit a memory, returning the updated item
+async fn patch_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<PatchMemoryRequest>,
+) -> Result<Json<MemoryResponse>, StatusCode> {
+    let mut brain = state.brain.write().await;
+    let item = resolve_memory(&brain, &id)?;
+
+    let memory_type = match req.memory_type.as_deref() {
+        Some(name) => Some(parse_memory_type(name).ok_or(StatusCode::BAD_REQUEST)?),
+        None => None,
+    };
+    let changes = MemoryEdit {
+        content: req.content,
+        tags: req.tags,
+        strength: req.strength,
+        memory_type,
+    };
+    let updated = brain
+        .edit(&item.id.to_string(), &changes)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Keep the server's HNSW index in step with re-embedded content
+    if updated.content != item.content {
+        state.hnsw.remove(&updated.id);
+        if let Some(ref emb) = updated.embedding {
+            let _ = state.hnsw.add(updated.id, emb.clone());
+        }
     }
+
+    Ok(Json(updated.into()))
 }
 
 /// Health check
@@ -272,33 +728,86 @@ async fn health_handler() -> &'static str {
     "OK"
 }
 
-/// Start the server
-pub async fn start_server(host: &str, port: u16, db_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize brain
-    let embedder: Arc<dyn Embedder> = Arc::new(GloVeEmbedder::test_embedder());
+/// Visual storage at `config.visual.db_path` with its cache loaded
+///
+/// Needs the CLIP server: hash embeddings would store and search vectors
+/// unrelated to the images, so without it visual memories stay disabled.
+async fn open_visual_storage(config: &Config) -> Result<Arc<VisualStorage>, Box<dyn std::error::Error>> {
+    let url = &config.visual.clip_server_url;
+    let provider = ClipServerProvider::new(url)
+        .map_err(|e| format!("CLIP server not available at {}: {}", url, e))?;
+    let clip: Arc<dyn ClipProvider> = Arc::new(provider);
+
+    let db_path = config.visual.db_path.to_string_lossy();
+    let db = coredb::CoreDB::new(crate::default_coredb_config(&db_path))
+        .await
+        .map_err(|e| e.to_string())?;
+    let storage = VisualStorage::new(Arc::new(RwLock::new(db)), clip, "visual_brain").await?;
+    let loaded = storage.load_cache().await?;
+    println!("🖼️ Visual memories: {}", loaded);
+    Ok(Arc::new(storage))
+}
+
+/// Start the server on `config.server.host:config.server.port`
+pub async fn start_server(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize brain with the configured embedder, like the CLI
+    let configured = crate::embedder_from_config(config, false);
+    let embedder = configured.embedder;
     let dimension = embedder.dimension();
     
-    let mut brain = Brain::with_embedder(db_path, embedder.clone())?;
+    let mut brain = Brain::with_config(config, embedder.clone())?;
+    if let Err(e) = brain.check_embedding_dim() {
+        eprintln!("⚠️ {}", e);
+        eprintln!("   Re-embed with the current embedder: memory-brain reindex --reembed");
+    }
     
     // Rebuild indexes for search (critical for recall to work!)
-    let stats = brain.rebuild_indexes()?;
+    let stats = brain.load_or_rebuild_indexes()?;
     println!("🔍 Index loaded: {} memories, {} keywords", 
         stats.episodic_count + stats.semantic_count + stats.procedural_count,
         stats.index_stats.unique_keywords);
     
-    let state = Arc::new(AppState {
-        brain: RwLock::new(brain),
-        hnsw: HnswIndex::new(dimension),
-        embedder,
-    });
+    let api_keys = api_keys_from_env();
+    let writes_per_minute = std::env::var("MEMORY_BRAIN_RATE_LIMIT")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|&n: &u32| n > 0)
+        .unwrap_or(DEFAULT_WRITES_PER_MINUTE);
+    if api_keys.is_empty() {
+        println!("⚠️  No MEMORY_BRAIN_API_KEYS set: API is open to anyone who can reach it");
+    } else {
+        println!("🔑 API keys: {} (writes limited to {}/min per key)", api_keys.len(), writes_per_minute);
+    }
+
+    let mut state = AppState::new(brain, HnswIndex::new(dimension), embedder)
+        .with_api_keys(api_keys)
+        .with_rate_limiter(RateLimiter::per_minute(writes_per_minute));
+    match open_visual_storage(config).await {
+        Ok(visual) => state = state.with_visual(visual),
+        Err(e) => println!("⚠️  Visual memories unavailable in the web UI: {}", e),
+    }
+    let state = Arc::new(state);
     
     let app = create_router(state);
     
-    let addr = format!("{}:{}", host, port);
+    let addr = format!("{}:{}", config.server.host, config.server.port);
     println!("🧠 Memory Brain Server starting on http://{}", addr);
     
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    
+    // Keep what this run embedded, like the CLI does on exit
+    if let Some(cache) = configured.cache {
+        if cache.stats().misses > 0 {
+            if let Err(e) = cache.persist_to_disk(&config.embedding.cache_path) {
+                eprintln!("⚠️ Embedding cache not saved: {}", e);
+            }
+        }
+    }
     
     Ok(())
 }
@@ -306,6 +815,7 @@ pub async fn start_server(host: &str, port: u16, db_path: &str) -> Result<(), Bo
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::GloVeEmbedder;
     use axum::body::Body;
     use axum::http::Request;
     use tower::ServiceExt;
@@ -317,11 +827,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let brain = Brain::with_embedder(dir.path().join("test.db").to_str().unwrap(), embedder.clone()).unwrap();
         
-        let state = Arc::new(AppState {
-            brain: RwLock::new(brain),
-            hnsw: HnswIndex::new(dim),
-            embedder,
-        });
+        let state = Arc::new(AppState::new(brain, HnswIndex::new(dim), embedder));
         
         let app = create_router(state);
         
@@ -332,4 +838,264 @@ mod tests {
         
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    fn test_app_state() -> (AppState, tempfile::TempDir) {
+        let embedder: Arc<dyn Embedder> = Arc::new(GloVeEmbedder::test_embedder());
+        let dim = embedder.dimension();
+        let dir = tempfile::tempdir().unwrap();
+        let brain = Brain::with_embedder(dir.path().join("test.db").to_str().unwrap(), embedder.clone()).unwrap();
+
+        (AppState::new(brain, HnswIndex::new(dim), embedder), dir)
+    }
+
+    fn test_state() -> (Arc<AppState>, tempfile::TempDir) {
+        let (state, dir) = test_app_state();
+        (Arc::new(state), dir)
+    }
+
+    async fn send_json(state: &Arc<AppState>, method: &str, uri: &str, body: Option<serde_json::Value>) -> (StatusCode, serde_json::Value) {
+        send_with_key(state, method, uri, None, body).await
+    }
+
+    async fn send_with_key(state: &Arc<AppState>, method: &str, uri: &str, key: Option<&str>, body: Option<serde_json::Value>) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(key) = key {
+            request = request.header("authorization", format!("Bearer {}", key));
+        }
+        let request = request
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap();
+        let response = create_router(state.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_store_patch_delete_memory() {
+        let (state, _dir) = test_state();
+
+        let (status, stored) = send_json(&state, "POST", "/api/store",
+            Some(serde_json::json!({"content": "Python is a language", "tags": ["lang"]}))).await;
+        assert_eq!(status, StatusCode::OK);
+        let id = stored["id"].as_str().unwrap().to_string();
+
+        // Patch by prefix: new content is re-embedded and re-indexed
+        let (status, patched) = send_json(&state, "PATCH", &format!("/api/memory/{}", &id[..8]),
+            Some(serde_json::json!({"content": "Rust is about ownership", "strength": 0.9}))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(patched["id"], id.as_str());
+        assert_eq!(patched["content"], "Rust is about ownership");
+        assert_eq!(patched["tags"], serde_json::json!(["lang"]));
+        assert!((patched["strength"].as_f64().unwrap() - 0.9).abs() < 1e-6);
+        {
+            let brain = state.brain.read().await;
+            let uuid = uuid::Uuid::parse_str(&id).unwrap();
+            assert!(brain.keyword_index.search_or("ownership").contains(&uuid));
+            assert!(!brain.keyword_index.search_or("python").contains(&uuid));
+            assert_eq!(brain.get_by_id(&uuid).unwrap().unwrap().content, "Rust is about ownership");
+        }
+
+        let (status, deleted) = send_json(&state, "DELETE", &format!("/api/memory/{}", id), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(deleted["content"], "Rust is about ownership");
+
+        let (status, _) = send_json(&state, "DELETE", &format!("/api/memory/{}", id), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_api_key_auth_and_rate_limit() {
+        let store = || Some(serde_json::json!({"content": "Rust is about ownership"}));
+
+        // No keys configured: open
+        let (open, _dir) = test_state();
+        let (status, _) = send_json(&open, "POST", "/api/store", store()).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (state, _dir2) = test_app_state();
+        let state = Arc::new(
+            state
+                .with_api_keys(HashSet::from(["secret".to_string()]))
+                .with_rate_limiter(RateLimiter::per_minute(2)),
+        );
+
+        let (status, _) = send_json(&state, "POST", "/api/store", store()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send_with_key(&state, "GET", "/api/stats", Some("wrong"), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send_json(&state, "GET", "/api/health", None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = send_with_key(&state, "GET", "/api/stats", Some("secret"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        for _ in 0..2 {
+            let (status, _) = send_with_key(&state, "POST", "/api/store", Some("secret"), store()).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let (status, _) = send_with_key(&state, "POST", "/api/store", Some("secret"), store()).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+        // Reads aren't rate limited
+        let (status, _) = send_with_key(&state, "GET", "/api/stats", Some("secret"), None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_memory_endpoints_404_on_unknown_id() {
+        let (state, _dir) = test_state();
+        let bogus = uuid::Uuid::new_v4().to_string();
+
+        let (status, _) = send_json(&state, "PATCH", &format!("/api/memory/{}", bogus),
+            Some(serde_json::json!({"strength": 0.1}))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = send_json(&state, "DELETE", "/api/memory/deadbeef", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_store_routes_by_memory_type() {
+        let (state, _dir) = test_state();
+
+        for (content, memory_type) in [
+            ("Shipped the release on Friday evening", "episodic"),
+            ("Pattern: run clippy before every push", "procedural"),
+            ("Tokio is an async runtime", "semantic"),
+        ] {
+            let (status, stored) = send_json(&state, "POST", "/api/store",
+                Some(serde_json::json!({"content": content, "memory_type": memory_type}))).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(stored["memory_type"], memory_type);
+
+            // Indexed regardless of the target store
+            let id = uuid::Uuid::parse_str(stored["id"].as_str().unwrap()).unwrap();
+            let brain = state.brain.read().await;
+            let word = content.split(' ').last().unwrap().to_lowercase();
+            assert!(brain.keyword_index.search_or(&word).contains(&id), "{} not indexed", word);
+            assert!(state.hnsw.stats().count >= 1);
+        }
+
+        let (_, stats) = send_json(&state, "GET", "/api/stats", None).await;
+        assert_eq!(stats["episodic_memory"], 1);
+        assert_eq!(stats["procedural_memory"], 1);
+        assert_eq!(stats["semantic_memory"], 1);
+        assert_eq!(stats["hnsw_indexed"], 3);
+
+        // Without a type the consolidator decides; unknown types are rejected
+        let (status, stored) = send_json(&state, "POST", "/api/store",
+            Some(serde_json::json!({"content": "Rust uses ownership"}))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(stored["memory_type"].is_string());
+        let (_, stats) = send_json(&state, "GET", "/api/stats", None).await;
+        let total: u64 = ["episodic_memory", "procedural_memory", "semantic_memory"]
+            .iter()
+            .map(|k| stats[*k].as_u64().unwrap())
+            .sum();
+        assert_eq!(total, 4);
+
+        let (status, _) = send_json(&state, "POST", "/api/store",
+            Some(serde_json::json!({"content": "x", "memory_type": "dream"}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_metrics_prometheus_format() {
+        let (state, _dir) = test_state();
+        let (status, _) = send_json(&state, "POST", "/api/store",
+            Some(serde_json::json!({"content": "Metrics are scraped every 15 seconds"}))).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let response = create_router(state.clone())
+            .oneshot(Request::builder().uri("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[header::CONTENT_TYPE].to_str().unwrap().starts_with("text/plain; version=0.0.4"));
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+
+        // Every line is a HELP/TYPE comment or `name{labels} value`
+        let name_re = regex::Regex::new(r#"^[a-zA-Z_:][a-zA-Z0-9_:]*(\{([a-zA-Z_][a-zA-Z0-9_]*="([^"\\]|\\.)*",?)*\})? \S+$"#).unwrap();
+        let mut typed = HashSet::new();
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix("# TYPE ") {
+                let mut parts = rest.split(' ');
+                typed.insert(parts.next().unwrap().to_string());
+                assert!(matches!(parts.next(), Some("gauge" | "counter" | "histogram")), "{}", line);
+            } else if !line.starts_with("# HELP ") {
+                assert!(name_re.is_match(line), "invalid sample line: {}", line);
+                let value = line.rsplit(' ').next().unwrap();
+                assert!(value == "+Inf" || value.parse::<f64>().is_ok(), "{}", line);
+                let family = line.split(['{', ' ']).next().unwrap();
+                let family = ["_bucket", "_sum", "_count"]
+                    .iter()
+                    .find_map(|s| family.strip_suffix(s).filter(|f| typed.contains(*f)))
+                    .unwrap_or(family);
+                assert!(typed.contains(family), "sample before its TYPE: {}", line);
+            }
+        }
+
+        assert!(text.contains("memory_brain_memories{type=\"semantic\"} 1\n"));
+        assert!(text.contains("memory_brain_request_duration_seconds_count{route=\"/api/store\",method=\"POST\"} 1\n"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_audit_range_validation() {
+        let (state, _dir) = test_state();
+
+        let (status, stats) = send_json(&state, "GET", "/api/audit?from=2024-03-01&to=2024-03-02", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(stats["from"], "2024-03-01");
+        assert!(stats["by_command"].is_object());
+
+        let (status, _) = send_json(&state, "GET", "/api/audit?from=2024-03-05&to=2024-03-01", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send_json(&state, "GET", "/api/audit?from=yesterday", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_recall_stream_keyword_before_semantic() {
+        let embedder: Arc<dyn Embedder> = Arc::new(GloVeEmbedder::test_embedder());
+        let dim = embedder.dimension();
+        let dir = tempfile::tempdir().unwrap();
+        let mut brain = Brain::with_embedder(dir.path().join("test.db").to_str().unwrap(), embedder.clone()).unwrap();
+        brain.process("Python is a programming language", None).unwrap();
+        brain.process("Rust is about ownership", None).unwrap();
+
+        let state = Arc::new(AppState::new(brain, HnswIndex::new(dim), embedder));
+        let app = create_router(state);
+
+        let response = app
+            .oneshot(Request::builder().uri("/api/recall/stream?query=rust&limit=5").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        let mut sources = Vec::new();
+        let mut done = false;
+        for block in body.split("\n\n") {
+            let data = block.lines().find_map(|l| l.strip_prefix("data:")).map(str::trim);
+            if block.lines().any(|l| l.trim() == "event: done") {
+                done = true;
+            } else if let Some(data) = data {
+                let value: serde_json::Value = serde_json::from_str(data).unwrap();
+                sources.push((value["source"].as_str().unwrap().to_string(), value["content"].as_str().unwrap().to_string()));
+            }
+        }
+
+        assert!(done);
+        assert_eq!(sources[0].0, "keyword");
+        assert!(sources[0].1.contains("Rust"));
+        let first_semantic = sources.iter().position(|(s, _)| s == "semantic").unwrap();
+        assert!(sources[first_semantic..].iter().all(|(s, _)| s == "semantic"));
+        assert!(sources.iter().any(|(_, c)| c.contains("Python")));
+    }
 }
@@ -12,12 +12,24 @@ use std::arch::x86_64::*;
 /// SIMD-optimized cosine similarity
 /// 
 /// Uses NEON on ARM64 (Apple Silicon), AVX on x86_64, scalar fallback otherwise.
+/// Returns 0.0 for mismatched lengths, zero-norm vectors and non-finite
+/// results (NaN/inf components, overflow), so callers can sort safely.
 #[inline]
 pub fn cosine_similarity_simd(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() || a.is_empty() {
         return 0.0;
     }
 
+    let sim = cosine_similarity_dispatch(a, b);
+    if sim.is_finite() {
+        sim.clamp(-1.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+#[inline]
+fn cosine_similarity_dispatch(a: &[f32], b: &[f32]) -> f32 {
     #[cfg(target_arch = "aarch64")]
     {
         unsafe { cosine_similarity_neon(a, b) }
@@ -279,17 +291,82 @@ pub fn batch_cosine_similarity(query: &[f32], vectors: &[Vec<f32>]) -> Vec<f32>
 }
 
 /// Find top-k most similar vectors
+///
+/// Returns `(index, similarity)` best first; ties keep the lower index.
 pub fn top_k_similar(query: &[f32], vectors: &[Vec<f32>], k: usize) -> Vec<(usize, f32)> {
-    let mut similarities: Vec<(usize, f32)> = vectors
-        .iter()
-        .enumerate()
-        .map(|(i, v)| (i, cosine_similarity_simd(query, v)))
-        .collect();
-    
-    // Partial sort for top-k
-    similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-    similarities.truncate(k);
-    similarities
+    top_k_similar_above(query, vectors, k, None)
+}
+
+/// [`top_k_similar`] keeping only similarities of at least `min_similarity`
+pub fn top_k_similar_above(
+    query: &[f32],
+    vectors: &[Vec<f32>],
+    k: usize,
+    min_similarity: Option<f32>,
+) -> Vec<(usize, f32)> {
+    let scores = vectors.iter().map(|v| cosine_similarity_simd(query, v));
+    select_top_k(scores, k, min_similarity).0
+}
+
+/// Heap entry that orders the worst candidate (lowest score, then highest
+/// index) as the greatest, so `BinaryHeap` acts as a bounded min-heap
+#[derive(Debug, PartialEq)]
+struct WorstFirst {
+    score: f32,
+    index: usize,
+}
+
+impl Eq for WorstFirst {}
+
+impl PartialOrd for WorstFirst {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for WorstFirst {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.score.total_cmp(&self.score).then_with(|| self.index.cmp(&other.index))
+    }
+}
+
+/// Top `k` of `scores` with a heap of size `k`
+///
+/// A candidate is skipped unless it beats both `min_similarity` and, once
+/// the heap is full, the current k-th best. Also returns how many
+/// candidates were pushed onto the heap.
+fn select_top_k(
+    scores: impl Iterator<Item = f32>,
+    k: usize,
+    min_similarity: Option<f32>,
+) -> (Vec<(usize, f32)>, usize) {
+    if k == 0 {
+        return (Vec::new(), 0);
+    }
+    let mut heap: std::collections::BinaryHeap<WorstFirst> = std::collections::BinaryHeap::with_capacity(k);
+    let mut threshold = min_similarity.unwrap_or(f32::NEG_INFINITY);
+    let mut pushes = 0;
+
+    for (index, score) in scores.enumerate() {
+        // Equal to the k-th best loses: the earlier index wins ties
+        let full = heap.len() == k;
+        if score < threshold || (full && score <= threshold) {
+            continue;
+        }
+        if full {
+            heap.pop();
+        }
+        heap.push(WorstFirst { score, index });
+        pushes += 1;
+        if heap.len() == k {
+            let kth = heap.peek().map_or(threshold, |worst| worst.score);
+            threshold = threshold.max(kth);
+        }
+    }
+
+    // Ascending order of `WorstFirst` is best first
+    let top = heap.into_sorted_vec().into_iter().map(|e| (e.index, e.score)).collect();
+    (top, pushes)
 }
 
 #[cfg(test)]
@@ -378,4 +455,42 @@ mod tests {
         assert_eq!(top.len(), 2);
         assert_eq!(top[0].0, 1); // index 1 should be first (highest similarity)
     }
+
+    #[test]
+    fn test_top_k_heap_matches_full_sort() {
+        // Deterministic pseudo-random corpus, with duplicates for ties
+        let mut seed = 42u64;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+        };
+        let query: Vec<f32> = (0..64).map(|_| next()).collect();
+        let mut vectors: Vec<Vec<f32>> = (0..2000).map(|_| (0..64).map(|_| next()).collect()).collect();
+        vectors.extend(vectors[..50].to_vec());
+
+        let naive = |k: usize, min: f32| -> Vec<(usize, f32)> {
+            let mut all: Vec<(usize, f32)> = batch_cosine_similarity(&query, &vectors)
+                .into_iter()
+                .enumerate()
+                .filter(|(_, s)| *s >= min)
+                .collect();
+            all.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            all.truncate(k);
+            all
+        };
+
+        for k in [0, 1, 10, 100, 5000] {
+            assert_eq!(top_k_similar(&query, &vectors, k), naive(k, f32::NEG_INFINITY), "k={}", k);
+            assert_eq!(top_k_similar_above(&query, &vectors, k, Some(0.2)), naive(k, 0.2), "k={}", k);
+        }
+
+        // The cutoff keeps weak candidates off the heap
+        let scores = || batch_cosine_similarity(&query, &vectors).into_iter();
+        let (_, pushes_all) = select_top_k(scores(), 100, None);
+        let (top, pushes_cut) = select_top_k(scores(), 100, Some(0.2));
+        assert!(pushes_cut < pushes_all, "{} vs {}", pushes_cut, pushes_all);
+        assert!(top.iter().all(|(_, s)| *s >= 0.2));
+        // And the k-th-best bound skips most of the corpus
+        assert!(pushes_all < vectors.len() / 2, "{}", pushes_all);
+    }
 }
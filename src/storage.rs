@@ -1,20 +1,48 @@
 //! Storage - CoreDB backend for persistent memory (sync wrapper)
 
+use crate::compression::QuantizedEmbedding;
 use crate::types::{MemoryItem, MemoryType, Emotion};
 use chrono::{DateTime, Utc};
 use coredb::{CoreDB, DatabaseConfig};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::runtime::{Runtime, Handle};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+/// Keyspace holding the memory tables unless a brain is built with another
+pub const DEFAULT_KEYSPACE: &str = "memory_brain";
+
+/// Metadata key holding the dimension of stored embeddings
+pub const EMBEDDING_DIM_KEY: &str = "embedding_dim";
+
+/// Prefix of int8-quantized embeddings in the `embedding` column
+///
+/// Layout: `q8:<scale>:<zero_point>:<base64 i8 values>`. Anything else is
+/// read as the original JSON float array.
+pub const QUANTIZED_PREFIX: &str = "q8:";
+
+/// Escape a value for use inside a single-quoted CQL string literal
+///
+/// Doubles single quotes, the only escape CQL string literals have;
+/// everything else (backslashes, newlines, …) is stored as written.
+pub fn escape_cql_string(s: &str) -> String {
+    s.replace('\'', "''")
+}
+
 pub struct Storage {
     db: Arc<RwLock<CoreDB>>,
     /// Some if we own the runtime, None if reusing existing
     owned_runtime: Option<Runtime>,
     keyspace: String,
     table: String,
+    /// The `embedding_dim` marker is known to exist (skip the lookup on save)
+    dim_recorded: AtomicBool,
+    /// Write embeddings as base64 int8 instead of JSON floats
+    quantize_embeddings: bool,
+    /// `SELECT *` queries issued against the whole table
+    full_scans: AtomicU64,
 }
 
 impl Storage {
@@ -31,6 +59,19 @@ impl Storage {
     }
 
     pub fn new(db_path: &str, table_name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::open(db_path, DEFAULT_KEYSPACE, table_name, &default_coredb_config(db_path))
+    }
+
+    /// Open `keyspace.table_name` with custom CoreDB tuning
+    ///
+    /// The data and commitlog directories always come from `db_path`;
+    /// only the tuning fields of `tuning` are used.
+    pub fn open(
+        db_path: &str,
+        keyspace: &str,
+        table_name: &str,
+        tuning: &DatabaseConfig,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         // Check if we're already in a tokio runtime
         let in_runtime = Handle::try_current().is_ok();
         let owned_runtime = if in_runtime { None } else { Some(Runtime::new()?) };
@@ -38,12 +79,7 @@ impl Storage {
         let config = DatabaseConfig {
             data_directory: PathBuf::from(db_path).join("data"),
             commitlog_directory: PathBuf::from(db_path).join("commitlog"),
-            memtable_flush_threshold_mb: 16,
-            compaction_throughput_mb_per_sec: 16,
-            concurrent_reads: 32,
-            concurrent_writes: 32,
-            block_cache_size_mb: 64,        // 64MB cache
-            block_cache_max_entries: 5_000,
+            ..*tuning
         };
 
         let db = if in_runtime {
@@ -55,13 +91,14 @@ impl Storage {
             // Not in async context - use our runtime
             owned_runtime.as_ref().unwrap().block_on(CoreDB::new(config))?
         };
-        let keyspace = "memory_brain".to_string();
-        
         let storage = Self {
             db: Arc::new(RwLock::new(db)),
             owned_runtime,
-            keyspace: keyspace.clone(),
+            keyspace: keyspace.to_string(),
             table: table_name.to_string(),
+            dim_recorded: AtomicBool::new(false),
+            quantize_embeddings: false,
+            full_scans: AtomicU64::new(0),
         };
 
         storage.init_tables()?;
@@ -92,49 +129,184 @@ impl Storage {
                     access_count INT,
                     strength TEXT,
                     embedding TEXT,
-                    tags TEXT
+                    tags TEXT,
+                    associations TEXT,
+                    expires_at BIGINT,
+                    content_hash BIGINT
                 )",
                 self.keyspace, self.table
             );
             let _ = db.execute_cql(&table_query).await; // Ignore if exists
 
+            // Tables created before associations were persisted
+            let alter_query = format!(
+                "ALTER TABLE {}.{} ADD associations TEXT",
+                self.keyspace, self.table
+            );
+            let _ = db.execute_cql(&alter_query).await; // Ignore if column exists
+
+            // Tables created before memories could expire
+            let alter_query = format!(
+                "ALTER TABLE {}.{} ADD expires_at BIGINT",
+                self.keyspace, self.table
+            );
+            let _ = db.execute_cql(&alter_query).await; // Ignore if column exists
+
+            // Tables created before content hashes were persisted
+            let alter_query = format!(
+                "ALTER TABLE {}.{} ADD content_hash BIGINT",
+                self.keyspace, self.table
+            );
+            let _ = db.execute_cql(&alter_query).await; // Ignore if column exists
+
+            // Keyspace-wide key/value metadata (shared by all memory tables)
+            let meta_query = format!(
+                "CREATE TABLE {}.metadata (key TEXT PRIMARY KEY, value TEXT)",
+                self.keyspace
+            );
+            let _ = db.execute_cql(&meta_query).await; // Ignore if exists
+
+            Ok(())
+        })
+    }
+
+    /// Store embeddings written from now on as int8 (about 5x smaller)
+    ///
+    /// Rows written either way remain readable.
+    pub fn set_quantize_embeddings(&mut self, on: bool) {
+        self.quantize_embeddings = on;
+    }
+
+    pub fn quantizes_embeddings(&self) -> bool {
+        self.quantize_embeddings
+    }
+
+    /// Number of full-table scans issued so far
+    pub fn full_scans(&self) -> u64 {
+        self.full_scans.load(Ordering::Relaxed)
+    }
+
+    /// `SELECT *` over the whole table (counted in [`Self::full_scans`])
+    fn full_scan_cql(&self) -> String {
+        self.full_scans.fetch_add(1, Ordering::Relaxed);
+        format!("SELECT * FROM {}.{}", self.keyspace, self.table)
+    }
+
+    /// Read a keyspace metadata value
+    pub fn get_meta(&self, key: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        self.block_on(async {
+            let db = self.db.read().await;
+            let cql = format!(
+                "SELECT * FROM {}.metadata WHERE key = '{}'",
+                self.keyspace, escape_cql_string(key)
+            );
+            let value = match db.execute_cql(&cql).await? {
+                coredb::QueryResult::Rows(rows) => rows.iter().find_map(|row| {
+                    let matches = matches!(row.columns.get("key"), Some(coredb::CassandraValue::Text(k)) if k == key);
+                    match row.columns.get("value") {
+                        Some(coredb::CassandraValue::Text(v)) if matches => Some(v.clone()),
+                        _ => None,
+                    }
+                }),
+                _ => None,
+            };
+            Ok(value)
+        })
+    }
+
+    /// Write a keyspace metadata value
+    pub fn set_meta(&self, key: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.block_on(async {
+            let db = self.db.read().await;
+            let query = format!(
+                "INSERT INTO {}.metadata (key, value) VALUES ('{}', '{}')",
+                self.keyspace, escape_cql_string(key), escape_cql_string(value)
+            );
+            db.execute_cql(&query).await?;
+            db.flush_all().await?;
             Ok(())
         })
     }
 
+    /// Dimension of the embeddings in this database, if any were stored
+    pub fn embedding_dim(&self) -> Result<Option<usize>, Box<dyn std::error::Error>> {
+        Ok(self.get_meta(EMBEDDING_DIM_KEY)?.and_then(|v| v.trim().parse().ok()))
+    }
+
+    /// Overwrite the `embedding_dim` marker (after re-embedding everything)
+    pub fn set_embedding_dim(&self, dim: usize) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_meta(EMBEDDING_DIM_KEY, &dim.to_string())?;
+        self.dim_recorded.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Write the `embedding_dim` marker on the first stored embedding
+    fn record_embedding_dim(&self, dim: usize) -> Result<(), Box<dyn std::error::Error>> {
+        if dim == 0 || self.dim_recorded.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        if self.embedding_dim()?.is_none() {
+            self.set_meta(EMBEDDING_DIM_KEY, &dim.to_string())?;
+        }
+        self.dim_recorded.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
     /// Save a memory item
     pub fn save(&self, item: &MemoryItem) -> Result<(), Box<dyn std::error::Error>> {
+        self.save_with(item, self.quantize_embeddings)
+    }
+
+    /// Save with an explicit embedding format
+    fn save_with(&self, item: &MemoryItem, quantize: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(ref emb) = item.embedding {
+            self.record_embedding_dim(emb.len())?;
+        }
+
         self.block_on(async {
             let db = self.db.read().await;
 
             let embedding_json = item.embedding.as_ref()
-                .map(|e| serde_json::to_string(e).unwrap_or_default())
+                .map(|e| encode_embedding(e, quantize))
                 .unwrap_or_default();
             
             let tags_json = serde_json::to_string(&item.tags)?;
+            let associations_json = serde_json::to_string(&item.associations)?;
             let context = item.context.clone().unwrap_or_default();
 
-            // Escape single quotes for CQL
-            let content = item.content.replace('\'', "''");
-            let context = context.replace('\'', "''");
-            let embedding_json = embedding_json.replace('\'', "''");
-            let tags_json = tags_json.replace('\'', "''");
+            // Every string literal goes through the CQL escaper
+            let content = escape_cql_string(&item.content);
+            let context = escape_cql_string(&context);
+            let embedding_json = escape_cql_string(&embedding_json);
+            let tags_json = escape_cql_string(&tags_json);
+            let associations_json = escape_cql_string(&associations_json);
+            let memory_type = escape_cql_string(&format!("{:?}", item.memory_type));
+            let emotion = escape_cql_string(&format!("{:?}", item.emotion));
+            // Column left out (null) for memories that never expire
+            let (expires_column, expires_value) = match item.expires_at {
+                Some(at) => (", expires_at", format!(", {}", at.timestamp_millis())),
+                None => ("", String::new()),
+            };
 
             let query = format!(
-                "INSERT INTO {}.{} (id, content, context, memory_type, emotion, created_at, last_accessed, access_count, strength, embedding, tags) \
-                 VALUES ('{}', '{}', '{}', '{}', '{}', {}, {}, {}, '{}', '{}', '{}')",
+                "INSERT INTO {}.{} (id, content, context, memory_type, emotion, created_at, last_accessed, access_count, strength, embedding, tags, associations, content_hash{}) \
+                 VALUES ('{}', '{}', '{}', '{}', '{}', {}, {}, {}, '{}', '{}', '{}', '{}', {}{})",
                 self.keyspace, self.table,
+                expires_column,
                 item.id,
                 content,
                 context,
-                format!("{:?}", item.memory_type),
-                format!("{:?}", item.emotion),
+                memory_type,
+                emotion,
                 item.created_at.timestamp_millis(),
                 item.last_accessed.timestamp_millis(),
                 item.access_count,
                 item.strength,
                 embedding_json,
-                tags_json
+                tags_json,
+                associations_json,
+                item.dedup_key() as i64,
+                expires_value
             );
 
             db.execute_cql(&query).await?;
@@ -151,6 +323,54 @@ impl Storage {
         self.save(item)
     }
 
+    /// Raw `embedding` column of every row that has one
+    fn embedding_columns(&self) -> Result<Vec<(Uuid, String)>, Box<dyn std::error::Error>> {
+        self.block_on(async {
+            let db = self.db.read().await;
+            let cql = format!("SELECT id, embedding FROM {}.{}", self.keyspace, self.table);
+            let mut columns = Vec::new();
+            if let coredb::QueryResult::Rows(rows) = db.execute_cql(&cql).await? {
+                for row in rows {
+                    let id = match row.columns.get("id") {
+                        Some(coredb::CassandraValue::Text(s)) => Uuid::parse_str(s).ok(),
+                        _ => None,
+                    };
+                    let embedding = match row.columns.get("embedding") {
+                        Some(coredb::CassandraValue::Text(s)) if !s.is_empty() => Some(s.clone()),
+                        _ => None,
+                    };
+                    if let (Some(id), Some(embedding)) = (id, embedding) {
+                        columns.push((id, embedding));
+                    }
+                }
+            }
+            Ok(columns)
+        })
+    }
+
+    /// Bytes taken by the `embedding` column across all rows
+    pub fn embedding_bytes(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        Ok(self.embedding_columns()?.iter().map(|(_, embedding)| embedding.len()).sum())
+    }
+
+    /// Rewrite JSON-float embeddings as int8; returns how many rows changed
+    ///
+    /// Rows that are already quantized are left alone, so repeated runs don't
+    /// compound the precision loss.
+    pub fn quantize_stored_embeddings(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut rewritten = 0;
+        for (id, embedding) in self.embedding_columns()? {
+            if embedding.starts_with(QUANTIZED_PREFIX) {
+                continue;
+            }
+            if let Some(item) = self.get_by_id(&id)? {
+                self.save_with(&item, true)?;
+                rewritten += 1;
+            }
+        }
+        Ok(rewritten)
+    }
+
     /// Delete a memory item
     pub fn delete(&self, id: &Uuid) -> Result<(), Box<dyn std::error::Error>> {
         self.block_on(async {
@@ -164,13 +384,33 @@ impl Storage {
         })
     }
 
+    /// Get a memory item by id (key lookup)
+    pub fn get_by_id(&self, id: &Uuid) -> Result<Option<MemoryItem>, Box<dyn std::error::Error>> {
+        self.block_on(async {
+            let db = self.db.read().await;
+            let cql = format!(
+                "SELECT * FROM {}.{} WHERE id = '{}'",
+                self.keyspace, self.table, id
+            );
+            let result = match db.execute_cql(&cql).await {
+                Ok(result) => result,
+                // Fallback: full scan if the key lookup isn't supported
+                Err(_) => {
+                    let cql = self.full_scan_cql();
+                    db.execute_cql(&cql).await?
+                }
+            };
+            Ok(self.parse_query_result(result)?.into_iter().find(|item| item.id == *id))
+        })
+    }
+
     /// Search memories by content
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
         self.block_on(async {
             let db = self.db.read().await;
 
             // CoreDB: fetch all and filter (TODO: add LIKE support to CoreDB)
-            let cql = format!("SELECT * FROM {}.{}", self.keyspace, self.table);
+            let cql = self.full_scan_cql();
             let result = db.execute_cql(&cql).await?;
             
             let mut items = self.parse_query_result(result)?;
@@ -194,28 +434,71 @@ impl Storage {
         })
     }
 
+    /// Delete every memory in this table; returns how many were removed
+    pub fn clear(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let items = self.get_all()?;
+        for item in &items {
+            self.delete(&item.id)?;
+        }
+        Ok(items.len())
+    }
+
     /// Get all memories
     pub fn get_all(&self) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
         self.block_on(async {
             let db = self.db.read().await;
-            let cql = format!("SELECT * FROM {}.{}", self.keyspace, self.table);
+            let cql = self.full_scan_cql();
             let result = db.execute_cql(&cql).await?;
             self.parse_query_result(result)
         })
     }
 
+    /// Count memories without deserializing them
+    ///
+    /// Tries `SELECT COUNT(*)` first and falls back to an id-only projection.
+    pub fn count(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        self.block_on(async {
+            let db = self.db.read().await;
+
+            let cql = format!("SELECT COUNT(*) FROM {}.{}", self.keyspace, self.table);
+            if let Ok(coredb::QueryResult::Rows(rows)) = db.execute_cql(&cql).await {
+                if let [row] = rows.as_slice() {
+                    let count = row.columns.iter()
+                        .filter(|(name, _)| name.to_lowercase().contains("count"))
+                        .find_map(|(_, v)| match v {
+                            coredb::CassandraValue::BigInt(n) => Some(*n as usize),
+                            coredb::CassandraValue::Int(n) => Some(*n as usize),
+                            _ => None,
+                        });
+                    if let (Some(count), 1) = (count, row.columns.len()) {
+                        return Ok(count);
+                    }
+                }
+            }
+
+            // Fallback: id-only projection, skips content/embedding parsing
+            let cql = format!("SELECT id FROM {}.{}", self.keyspace, self.table);
+            match db.execute_cql(&cql).await? {
+                coredb::QueryResult::Rows(rows) => Ok(rows.len()),
+                _ => Ok(0),
+            }
+        })
+    }
+
     /// Get recent memories
-    pub fn get_recent(&self, limit: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+    ///
+    /// Newest first, skipping the first `offset`. Ties on `created_at` are
+    /// broken by id so consecutive pages never overlap or skip rows.
+    pub fn get_recent(&self, limit: usize, offset: usize) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
         self.block_on(async {
             let db = self.db.read().await;
-            let cql = format!("SELECT * FROM {}.{}", self.keyspace, self.table);
+            let cql = self.full_scan_cql();
             let result = db.execute_cql(&cql).await?;
             let mut items = self.parse_query_result(result)?;
             
-            // Sort by created_at DESC
-            items.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-            items.truncate(limit);
-            Ok(items)
+            // Sort by (created_at, id) DESC
+            items.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.id.cmp(&a.id)));
+            Ok(items.into_iter().skip(offset).take(limit).collect())
         })
     }
 
@@ -227,7 +510,7 @@ impl Storage {
     ) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
         self.block_on(async {
             let db = self.db.read().await;
-            let cql = format!("SELECT * FROM {}.{}", self.keyspace, self.table);
+            let cql = self.full_scan_cql();
             let result = db.execute_cql(&cql).await?;
             let mut items = self.parse_query_result(result)?;
             
@@ -242,7 +525,7 @@ impl Storage {
     pub fn get_by_tag(&self, tag: &str) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
         self.block_on(async {
             let db = self.db.read().await;
-            let cql = format!("SELECT * FROM {}.{}", self.keyspace, self.table);
+            let cql = self.full_scan_cql();
             let result = db.execute_cql(&cql).await?;
             let mut items = self.parse_query_result(result)?;
             
@@ -333,15 +616,31 @@ impl Storage {
         }
     }
 
-    pub fn add_association(&self, _from_id: Uuid, _to_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
-        // TODO: Implement associations table in CoreDB
+    /// Add association between memories (stored on `from_id`'s row)
+    ///
+    /// No-op if `from_id` isn't in this table.
+    pub fn add_association(&self, from_id: Uuid, to_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(mut item) = self.get_by_id(&from_id)? {
+            if !item.associations.contains(&to_id) {
+                item.associate(to_id);
+                self.update(&item)?;
+            }
+        }
         Ok(())
     }
 
-    /// Get associated memories
-    pub fn get_associated(&self, _id: Uuid) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
-        // TODO: Implement associations in CoreDB
-        Ok(Vec::new())
+    /// Get associated memories (only those stored in this table)
+    pub fn get_associated(&self, id: Uuid) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
+        let Some(item) = self.get_by_id(&id)? else {
+            return Ok(Vec::new());
+        };
+        let mut linked = Vec::new();
+        for assoc_id in &item.associations {
+            if let Some(other) = self.get_by_id(assoc_id)? {
+                linked.push(other);
+            }
+        }
+        Ok(linked)
     }
 
     fn parse_query_result(&self, result: coredb::QueryResult) -> Result<Vec<MemoryItem>, Box<dyn std::error::Error>> {
@@ -457,7 +756,7 @@ impl Storage {
 
         let embedding = columns.get("embedding").and_then(|v| {
             if let coredb::CassandraValue::Text(s) = v {
-                serde_json::from_str(s).ok()
+                decode_embedding(s)
             } else {
                 None
             }
@@ -471,6 +770,27 @@ impl Storage {
             }
         }).unwrap_or_default();
 
+        let associations = columns.get("associations").and_then(|v| {
+            if let coredb::CassandraValue::Text(s) = v {
+                serde_json::from_str(s).ok()
+            } else {
+                None
+            }
+        }).unwrap_or_default();
+
+        let expires_at = columns.get("expires_at").and_then(|v| match v {
+            coredb::CassandraValue::BigInt(ts) => {
+                DateTime::from_timestamp_millis(*ts).map(|dt| dt.with_timezone(&Utc))
+            }
+            _ => None,
+        });
+
+        // Rows written before the column existed hash on load
+        let content_hash = match columns.get("content_hash") {
+            Some(coredb::CassandraValue::BigInt(h)) if *h != 0 => *h as u64,
+            _ => crate::types::content_hash(&content),
+        };
+
         Some(MemoryItem {
             id,
             content,
@@ -482,8 +802,164 @@ impl Storage {
             access_count,
             strength,
             embedding,
-            associations: Vec::new(),
+            associations,
             tags,
+            expires_at,
+            content_hash,
         })
     }
 }
+
+/// CoreDB settings used by [`Storage::new`]
+///
+/// 16MB memtable flushes, 32 concurrent reads/writes and a 64MB block cache.
+pub fn default_coredb_config(db_path: &str) -> DatabaseConfig {
+    DatabaseConfig {
+        data_directory: PathBuf::from(db_path).join("data"),
+        commitlog_directory: PathBuf::from(db_path).join("commitlog"),
+        memtable_flush_threshold_mb: 16,
+        compaction_throughput_mb_per_sec: 16,
+        concurrent_reads: 32,
+        concurrent_writes: 32,
+        block_cache_size_mb: 64,        // 64MB cache
+        block_cache_max_entries: 5_000,
+    }
+}
+
+/// Serialize an embedding for the `embedding` column
+pub fn encode_embedding(embedding: &[f32], quantize: bool) -> String {
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+    if !quantize {
+        return serde_json::to_string(embedding).unwrap_or_default();
+    }
+    let q = QuantizedEmbedding::from_f32(embedding);
+    let bytes: Vec<u8> = q.values.iter().map(|&v| v as u8).collect();
+    format!("{}{}:{}:{}", QUANTIZED_PREFIX, q.scale, q.zero_point, STANDARD.encode(bytes))
+}
+
+/// Parse an `embedding` column in either the quantized or JSON format
+pub fn decode_embedding(text: &str) -> Option<Vec<f32>> {
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+    let Some(rest) = text.strip_prefix(QUANTIZED_PREFIX) else {
+        return serde_json::from_str(text).ok();
+    };
+    let mut parts = rest.splitn(3, ':');
+    let scale = parts.next()?.parse::<f32>().ok()?;
+    let zero_point = parts.next()?.parse::<f32>().ok()?;
+    let values = STANDARD.decode(parts.next()?).ok()?.into_iter().map(|b| b as i8).collect();
+    Some(QuantizedEmbedding { values, scale, zero_point }.to_f32())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_embedding() -> Vec<f32> {
+        (0..256).map(|i| ((i as f32) * 0.37).sin() * 0.8).collect()
+    }
+
+    #[test]
+    fn test_quantized_embedding_roundtrip() {
+        let original = sample_embedding();
+        let json = encode_embedding(&original, false);
+        let quantized = encode_embedding(&original, true);
+
+        assert!(quantized.starts_with(QUANTIZED_PREFIX));
+        assert!(quantized.len() * 3 < json.len(), "{} vs {} bytes", quantized.len(), json.len());
+
+        let restored = decode_embedding(&quantized).unwrap();
+        assert_eq!(restored.len(), original.len());
+        let similarity = crate::simd_ops::cosine_similarity_simd(&original, &restored);
+        assert!(similarity > 0.999, "similarity {}", similarity);
+
+        // Old rows still decode exactly
+        assert_eq!(decode_embedding(&json).unwrap(), original);
+    }
+
+    #[test]
+    fn test_escape_cql_string() {
+        assert_eq!(escape_cql_string("it's"), "it''s");
+        assert_eq!(escape_cql_string("''"), "''''");
+        assert_eq!(escape_cql_string(r"C:\tmp\"), r"C:\tmp\");
+        assert_eq!(escape_cql_string("line 1\nline 2\t!"), "line 1\nline 2\t!");
+    }
+
+    #[test]
+    fn test_save_load_keeps_content_verbatim() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_str().unwrap(), "verbatim").unwrap();
+
+        let content = "path C:\\Users\\sam's\ndon't \\n unescape\r\n\tend\\";
+        let item = MemoryItem::new(content, Some("line 1\nit's \\ line 2"));
+        storage.save(&item).unwrap();
+
+        let loaded = storage.get_by_id(&item.id).unwrap().unwrap();
+        assert_eq!(loaded.content, content);
+        assert_eq!(loaded.context, item.context);
+    }
+
+    #[test]
+    fn test_hostile_content_roundtrips() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_str().unwrap(), "escaping").unwrap();
+
+        let content = r#"O'Brien said "hi"; DROP TABLE memories; -- '' \ end\"#;
+        let mut item = MemoryItem::new(content, Some("it's a trap'); --"));
+        item.tags = vec!["o'neil".to_string(), r"back\slash".to_string()];
+        storage.save(&item).unwrap();
+
+        let found = storage.search("O'Brien", 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].content, content);
+        assert_eq!(found[0].context.as_deref(), Some("it's a trap'); --"));
+        assert_eq!(found[0].tags, item.tags);
+
+        // The table survived and still accepts writes
+        storage.save(&MemoryItem::new("after", None)).unwrap();
+        assert_eq!(storage.search("", 10).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_storage_reads_both_embedding_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = Storage::new(dir.path().to_str().unwrap(), "quantized").unwrap();
+
+        let mut legacy = MemoryItem::new("Stored as JSON floats", None);
+        legacy.embedding = Some(sample_embedding());
+        storage.save(&legacy).unwrap();
+
+        storage.set_quantize_embeddings(true);
+        let mut compact = MemoryItem::new("Stored as int8", None);
+        compact.embedding = Some(sample_embedding());
+        storage.save(&compact).unwrap();
+
+        let legacy = storage.get_by_id(&legacy.id).unwrap().unwrap();
+        assert_eq!(legacy.embedding.unwrap(), sample_embedding());
+
+        let compact = storage.get_by_id(&compact.id).unwrap().unwrap().embedding.unwrap();
+        let similarity = crate::simd_ops::cosine_similarity_simd(&sample_embedding(), &compact);
+        assert!((1.0 - similarity).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_quantize_stored_embeddings_shrinks_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_str().unwrap(), "compress").unwrap();
+        for i in 0..3 {
+            let mut item = MemoryItem::new(&format!("Memory {}", i), None);
+            item.embedding = Some(sample_embedding());
+            storage.save(&item).unwrap();
+        }
+
+        let before = storage.embedding_bytes().unwrap();
+        assert_eq!(storage.quantize_stored_embeddings().unwrap(), 3);
+        let after = storage.embedding_bytes().unwrap();
+        assert!(after * 3 < before, "{} vs {} bytes", after, before);
+
+        // Nothing left to rewrite
+        assert_eq!(storage.quantize_stored_embeddings().unwrap(), 0);
+        assert_eq!(storage.embedding_bytes().unwrap(), after);
+    }
+}
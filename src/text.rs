@@ -0,0 +1,246 @@
+//! Language-Aware Tokenization
+//!
+//! Splits text into search tokens for the keyword index, bloom filter and
+//! recall. Latin words are lowercased; Korean words lose trailing particles
+//! (조사) so "러스트를" and "러스트" share a token; Chinese/Japanese runs have
+//! no spaces, so they fall back to overlapping character bigrams.
+
+use std::ops::Range;
+
+/// Bumped whenever `tokenize` output or the indexed tokens change
+/// (persisted indexes rebuild)
+pub const TOKENIZER_VERSION: u32 = 2;
+
+/// Which stop-word lists apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    English,
+    Korean,
+    /// Both lists (mixed Korean/English text)
+    #[default]
+    Auto,
+}
+
+impl Lang {
+    /// Korean if the text contains Hangul, else English
+    pub fn detect(text: &str) -> Self {
+        if text.chars().any(is_hangul) {
+            Lang::Korean
+        } else {
+            Lang::English
+        }
+    }
+}
+
+const ENGLISH_STOP_WORDS: &[&str] = &[
+    "the", "a", "an", "is", "are", "was", "were", "be", "been", "being",
+    "have", "has", "had", "do", "does", "did", "will", "would", "could",
+    "should", "may", "might", "must", "shall", "can", "need", "what",
+    "when", "where", "which", "who", "whom", "this", "that", "these",
+    "those", "with", "from", "about", "into", "through", "during",
+    "before", "after", "above", "below", "between", "under", "again",
+    "like", "know", "think", "want", "tell", "your", "you", "for",
+    "to", "of", "in", "on", "at", "by", "as", "and", "or", "but", "if",
+    "it", "its", "so", "than", "then", "too", "very", "just",
+];
+
+const KOREAN_STOP_WORDS: &[&str] = &[
+    "이", "그", "저", "것", "수", "등", "및", "더", "또", "좀", "잘",
+    "나", "너", "저희", "우리", "나는", "저는", "내가", "제가",
+    "이것", "그것", "저것", "여기", "거기", "저기",
+    "그리고", "그러나", "하지만", "그래서", "그런데", "또는", "혹은", "즉",
+    "있다", "없다", "하다", "이다", "되다", "있는", "하는", "같은",
+    "때문에", "위해", "대한", "통해", "정말", "너무", "아주", "매우",
+];
+
+/// Particles stripped from the end of a Korean word, longest first
+///
+/// 이/가 are left alone: too many nouns end in 이 (고양이, 아이).
+const KOREAN_PARTICLES: &[&str] = &[
+    "에서는", "에서도", "으로는", "에게는", "한테서", "에게서",
+    "에서", "에게", "한테", "으로", "까지", "부터", "처럼", "보다", "이랑", "하고",
+    "은", "는", "을", "를", "에", "의", "도", "로", "와", "과", "만",
+];
+
+/// Whether `word` (lowercase) is a stop word for `lang`
+pub fn is_stop_word(word: &str, lang: Lang) -> bool {
+    let english = matches!(lang, Lang::English | Lang::Auto) && ENGLISH_STOP_WORDS.contains(&word);
+    let korean = matches!(lang, Lang::Korean | Lang::Auto) && KOREAN_STOP_WORDS.contains(&word);
+    english || korean
+}
+
+/// Tokens in order, without stop words
+///
+/// Latin/digit tokens need at least 2 characters; Korean and CJK tokens
+/// are kept from 1 character.
+pub fn tokenize(text: &str, lang: Lang) -> Vec<String> {
+    tokenize_with_offsets(text, lang).into_iter().map(|(token, _)| token).collect()
+}
+
+/// [`tokenize`] with the byte range of `text` each token came from
+///
+/// Latin ranges cover the original (not lowercased) run, Korean ranges
+/// only the stem, CJK ranges the bigram's two characters.
+pub fn tokenize_with_offsets(text: &str, lang: Lang) -> Vec<(String, Range<usize>)> {
+    let mut tokens = index_tokens_with_offsets(text);
+    tokens.retain(|(t, _)| !is_stop_word(t, lang));
+    tokens
+}
+
+/// Every token in order, stop words included, with its byte range
+///
+/// What indexes record, so token positions stay adjacent to the text;
+/// stop words are dropped from queries instead (see [`tokenize`]).
+pub fn index_tokens_with_offsets(text: &str) -> Vec<(String, Range<usize>)> {
+    let mut tokens = Vec::new();
+
+    for word in text.split_whitespace() {
+        // `word` is a subslice of `text`
+        let word_start = word.as_ptr() as usize - text.as_ptr() as usize;
+        for (i, (script, run_start, run)) in script_runs(word).into_iter().enumerate() {
+            let first = i == 0;
+            let start = word_start + run_start;
+            match script {
+                Script::Latin => {
+                    let lower = run.to_lowercase();
+                    if lower.chars().count() >= 2 {
+                        tokens.push((lower, start..start + run.len()));
+                    }
+                }
+                // A particle glued to a non-Korean word ("Rust로") carries no meaning
+                Script::Hangul if !first && KOREAN_PARTICLES.contains(&run) => {}
+                Script::Hangul => {
+                    let stem = strip_particle(run);
+                    if !stem.is_empty() {
+                        tokens.push((stem.to_string(), start..start + stem.len()));
+                    }
+                }
+                Script::Cjk => tokens.extend(
+                    bigrams(run)
+                        .into_iter()
+                        .map(|(gram, range)| (gram, start + range.start..start + range.end)),
+                ),
+            }
+        }
+    }
+
+    tokens
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Script {
+    /// Letters/digits of alphabetic scripts, plus `_`
+    Latin,
+    Hangul,
+    /// Han ideographs and kana
+    Cjk,
+}
+
+pub(crate) fn is_hangul(c: char) -> bool {
+    matches!(c, '\u{AC00}'..='\u{D7A3}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}')
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}'
+        | '\u{3040}'..='\u{309F}' | '\u{30A0}'..='\u{30FF}')
+}
+
+fn script_of(c: char) -> Option<Script> {
+    if is_hangul(c) {
+        Some(Script::Hangul)
+    } else if is_cjk(c) {
+        Some(Script::Cjk)
+    } else if c.is_alphanumeric() || c == '_' {
+        Some(Script::Latin)
+    } else {
+        None
+    }
+}
+
+/// Maximal same-script runs of a word with their byte offsets in it;
+/// punctuation separates runs
+fn script_runs(word: &str) -> Vec<(Script, usize, &str)> {
+    let mut runs = Vec::new();
+    let mut current: Option<(Script, usize)> = None;
+    for (i, c) in word.char_indices() {
+        let script = script_of(c);
+        if let Some((prev, start)) = current {
+            if script == Some(prev) {
+                continue;
+            }
+            runs.push((prev, start, &word[start..i]));
+        }
+        current = script.map(|s| (s, i));
+    }
+    if let Some((prev, start)) = current {
+        runs.push((prev, start, &word[start..]));
+    }
+    runs
+}
+
+/// `word` without a trailing particle, keeping at least one syllable
+/// (two for single-syllable particles, so 사과 and 회의 stay whole)
+fn strip_particle(word: &str) -> &str {
+    let syllables = word.chars().count();
+    for particle in KOREAN_PARTICLES {
+        if let Some(stem) = word.strip_suffix(particle) {
+            let stem_len = syllables - particle.chars().count();
+            let min_stem = if particle.chars().count() == 1 { 2 } else { 1 };
+            if stem_len >= min_stem {
+                return stem;
+            }
+        }
+    }
+    word
+}
+
+/// Overlapping character bigrams with their byte ranges in `run`
+/// (a single character stays as is)
+fn bigrams(run: &str) -> Vec<(String, Range<usize>)> {
+    let chars: Vec<(usize, char)> = run.char_indices().collect();
+    if chars.len() < 2 {
+        return vec![(run.to_string(), 0..run.len())];
+    }
+    chars
+        .windows(2)
+        .map(|w| {
+            let range = w[0].0..w[1].0 + w[1].1.len_utf8();
+            (run[range.clone()].to_string(), range)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_korean_sentence_units() {
+        let tokens = tokenize("오늘 회의에서 러스트 프로젝트를 논의했다", Lang::Korean);
+        assert_eq!(tokens, vec!["오늘", "회의", "러스트", "프로젝트", "논의했다"]);
+
+        // Particles on short nouns and non-Korean words
+        assert_eq!(tokenize("사과를 먹었다", Lang::Auto), vec!["사과", "먹었다"]);
+        assert_eq!(tokenize("Rust로 메모리 안전성을 보장한다", Lang::Auto), vec!["rust", "메모리", "안전성", "보장한다"]);
+        assert_eq!(tokenize("그리고 나는 회의", Lang::Korean), vec!["회의"]);
+    }
+
+    #[test]
+    fn test_english_stop_words_dropped() {
+        let tokens = tokenize("What is the Rust borrow-checker for?", Lang::English);
+        assert_eq!(tokens, vec!["rust", "borrow", "checker"]);
+        assert_eq!(tokenize("snake_case x", Lang::Auto), vec!["snake_case"]);
+
+        // Korean list doesn't apply to English-only
+        assert!(tokenize("그리고", Lang::English).contains(&"그리고".to_string()));
+    }
+
+    #[test]
+    fn test_cjk_bigrams() {
+        assert_eq!(tokenize("東京大学", Lang::Auto), vec!["東京", "京大", "大学"]);
+        assert_eq!(tokenize("猫", Lang::Auto), vec!["猫"]);
+        assert_eq!(Lang::detect("메모리 test"), Lang::Korean);
+        assert_eq!(Lang::detect("memory"), Lang::English);
+    }
+}
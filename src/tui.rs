@@ -1,8 +1,11 @@
 //! Interactive TUI Dashboard for memory-brain
 //! 
 //! Navigate memories, view stats, and search interactively! 🧠
+//! On the Memories tab, Enter opens a memory's details, `t` edits its tags
+//! and `d` deletes it (after confirmation).
 
 use std::io::{self, stdout};
+use std::ops::Range;
 use std::time::Duration;
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
@@ -14,7 +17,32 @@ use ratatui::{
     widgets::*,
 };
 use chrono::Datelike;
+use uuid::Uuid;
 use crate::audit;
+use crate::{Brain, MemoryEdit, MemoryItem};
+
+/// Index of the Memories tab
+const MEMORIES_TAB: usize = 1;
+
+/// What the Memories tab is showing
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+    /// Memory list with a preview pane
+    List,
+    /// Full details of the selected memory
+    Detail,
+    /// Waiting for y/n before deleting the selected memory
+    ConfirmDelete,
+    /// Editing the selected memory's tags (comma-separated)
+    EditTags(String),
+}
+
+/// Change requested by a key press, applied to the `Brain` by the caller
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Delete(Uuid),
+    SetTags(Uuid, Vec<String>),
+}
 
 /// App state
 pub struct App {
@@ -26,15 +54,18 @@ pub struct App {
     should_quit: bool,
     /// Selected memory index
     selected_memory: usize,
-    /// Memory list (content previews)
-    memories: Vec<(String, String, String)>, // (id, content, tags)
+    /// Loaded memories, newest first
+    memories: Vec<MemoryItem>,
     /// Stats
     stats: (usize, usize, usize), // stores, recalls, searches
     /// Weekly stats
     weekly_stats: Vec<(String, usize, usize, usize)>,
-    /// Scroll offset for memory list
-    #[allow(dead_code)]
+    /// First memory row drawn (the list only renders visible rows)
     scroll_offset: usize,
+    /// Memories tab mode
+    mode: Mode,
+    /// Result of the last action, shown in the footer
+    status: Option<String>,
 }
 
 impl App {
@@ -51,11 +82,21 @@ impl App {
             stats,
             weekly_stats,
             scroll_offset: 0,
+            mode: Mode::List,
+            status: None,
         }
     }
     
-    pub fn load_memories(&mut self, memories: Vec<(String, String, String)>) {
+    pub fn load_memories(&mut self, memories: Vec<MemoryItem>) {
         self.memories = memories;
+        self.selected_memory = 0;
+        self.scroll_offset = 0;
+        self.mode = Mode::List;
+    }
+
+    /// Currently selected memory
+    pub fn selected(&self) -> Option<&MemoryItem> {
+        self.memories.get(self.selected_memory)
     }
     
     fn next_tab(&mut self) {
@@ -85,10 +126,142 @@ impl App {
             }
         }
     }
+
+    /// Handle one key press; returns a change for the caller to apply
+    ///
+    /// Kept free of terminal I/O so the transitions can be tested.
+    pub fn handle_key(&mut self, code: KeyCode) -> Option<Action> {
+        if self.tab == MEMORIES_TAB && self.mode != Mode::List {
+            return self.handle_memory_key(code);
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Tab | KeyCode::Right => self.next_tab(),
+            KeyCode::BackTab | KeyCode::Left => self.prev_tab(),
+            KeyCode::Down | KeyCode::Char('j') => self.next_memory(),
+            KeyCode::Up | KeyCode::Char('k') => self.prev_memory(),
+            KeyCode::Char('1') => self.tab = 0,
+            KeyCode::Char('2') => self.tab = 1,
+            KeyCode::Char('3') => self.tab = 2,
+            KeyCode::Char('4') => self.tab = 3,
+            KeyCode::Enter if self.tab == MEMORIES_TAB && self.selected().is_some() => {
+                self.mode = Mode::Detail;
+            }
+            KeyCode::Char('d') if self.tab == MEMORIES_TAB && self.selected().is_some() => {
+                self.mode = Mode::ConfirmDelete;
+            }
+            KeyCode::Char('t') if self.tab == MEMORIES_TAB => self.start_tag_edit(),
+            _ => {}
+        }
+        None
+    }
+
+    /// Keys in the detail, confirm and tag-edit modes
+    fn handle_memory_key(&mut self, code: KeyCode) -> Option<Action> {
+        match (&mut self.mode, code) {
+            (Mode::Detail, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) => self.mode = Mode::List,
+            (Mode::Detail, KeyCode::Char('d')) => self.mode = Mode::ConfirmDelete,
+            (Mode::Detail, KeyCode::Char('t')) => self.start_tag_edit(),
+            (Mode::ConfirmDelete, KeyCode::Char('y') | KeyCode::Char('Y')) => {
+                self.mode = Mode::List;
+                return self.selected().map(|m| Action::Delete(m.id));
+            }
+            (Mode::ConfirmDelete, _) => self.mode = Mode::List,
+            (Mode::EditTags(buffer), KeyCode::Char(c)) => buffer.push(c),
+            (Mode::EditTags(buffer), KeyCode::Backspace) => {
+                buffer.pop();
+            }
+            (Mode::EditTags(buffer), KeyCode::Enter) => {
+                let tags = parse_tags(buffer);
+                self.mode = Mode::Detail;
+                return self.selected().map(|m| Action::SetTags(m.id, tags));
+            }
+            (Mode::EditTags(_), KeyCode::Esc) => self.mode = Mode::Detail,
+            _ => {}
+        }
+        None
+    }
+
+    fn start_tag_edit(&mut self) {
+        if let Some(memory) = self.selected() {
+            self.mode = Mode::EditTags(memory.tags.join(", "));
+        }
+    }
+
+    /// Reflect an applied action in the loaded list
+    pub fn action_applied(&mut self, action: &Action) {
+        match action {
+            Action::Delete(id) => {
+                self.memories.retain(|m| m.id != *id);
+                if self.selected_memory >= self.memories.len() {
+                    self.selected_memory = self.memories.len().saturating_sub(1);
+                }
+                self.mode = Mode::List;
+                self.status = Some(format!("🗑️  Deleted {}", &id.to_string()[..8]));
+            }
+            Action::SetTags(id, tags) => {
+                if let Some(memory) = self.memories.iter_mut().find(|m| m.id == *id) {
+                    memory.tags = tags.clone();
+                }
+                self.status = Some(format!("🏷️  Tags updated for {}", &id.to_string()[..8]));
+            }
+        }
+    }
+
+    /// Rows of the memory list to draw for a list `height` rows tall
+    ///
+    /// Scrolls just enough to keep the selection visible, so only the
+    /// visible slice of a large list is ever turned into widgets.
+    pub fn visible_window(&mut self, height: usize) -> Range<usize> {
+        let height = height.max(1);
+        if self.selected_memory < self.scroll_offset {
+            self.scroll_offset = self.selected_memory;
+        } else if self.selected_memory >= self.scroll_offset + height {
+            self.scroll_offset = self.selected_memory + 1 - height;
+        }
+        self.scroll_offset = self.scroll_offset.min(self.memories.len().saturating_sub(height));
+        self.scroll_offset..(self.scroll_offset + height).min(self.memories.len())
+    }
+}
+
+/// Comma-separated tags, trimmed, empties dropped
+fn parse_tags(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Apply an action to the brain, returning a message on failure
+fn apply_action(brain: &mut Brain, action: &Action) -> Result<(), String> {
+    match action {
+        Action::Delete(id) => brain.forget(*id).map(|_| ()).map_err(|e| e.to_string()),
+        Action::SetTags(id, tags) => {
+            let changes = MemoryEdit {
+                content: None,
+                tags: Some(tags.clone()),
+                strength: None,
+                memory_type: None,
+            };
+            brain.edit(&id.to_string(), &changes).map(|_| ()).map_err(|e| e.to_string())
+        }
+    }
 }
 
-/// Run the TUI
-pub fn run_tui(memories: Vec<(String, String, String)>) -> io::Result<()> {
+/// Run the TUI over every long-term memory in `brain`
+pub fn run_tui(brain: &mut Brain) -> io::Result<()> {
+    let mut memories = Vec::new();
+    for items in [
+        brain.episodic.search("", usize::MAX),
+        brain.semantic.search("", usize::MAX),
+        brain.procedural.search("", usize::MAX),
+    ] {
+        memories.extend(items.unwrap_or_default());
+    }
+    memories.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
     // Setup terminal
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
@@ -101,23 +274,17 @@ pub fn run_tui(memories: Vec<(String, String, String)>) -> io::Result<()> {
     // Main loop
     loop {
         // Draw
-        terminal.draw(|frame| ui(frame, &app))?;
+        terminal.draw(|frame| ui(frame, &mut app))?;
         
         // Handle events
         if event::poll(Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => app.should_quit = true,
-                        KeyCode::Tab | KeyCode::Right => app.next_tab(),
-                        KeyCode::BackTab | KeyCode::Left => app.prev_tab(),
-                        KeyCode::Down | KeyCode::Char('j') => app.next_memory(),
-                        KeyCode::Up | KeyCode::Char('k') => app.prev_memory(),
-                        KeyCode::Char('1') => app.tab = 0,
-                        KeyCode::Char('2') => app.tab = 1,
-                        KeyCode::Char('3') => app.tab = 2,
-                        KeyCode::Char('4') => app.tab = 3,
-                        _ => {}
+                    if let Some(action) = app.handle_key(key.code) {
+                        match apply_action(brain, &action) {
+                            Ok(()) => app.action_applied(&action),
+                            Err(e) => app.status = Some(format!("❌ {}", e)),
+                        }
                     }
                 }
             }
@@ -136,7 +303,7 @@ pub fn run_tui(memories: Vec<(String, String, String)>) -> io::Result<()> {
 }
 
 /// Draw the UI
-fn ui(frame: &mut Frame, app: &App) {
+fn ui(frame: &mut Frame, app: &mut App) {
     let area = frame.area();
     
     // Main layout
@@ -188,7 +355,16 @@ fn ui(frame: &mut Frame, app: &App) {
     }
     
     // Footer
-    let footer = Paragraph::new(" ←/→ or Tab: Switch tabs | ↑/↓ or j/k: Navigate | q: Quit ")
+    let hint = match (&app.mode, app.tab) {
+        (Mode::Detail, MEMORIES_TAB) => " Esc: Back | t: Edit tags | d: Delete ".to_string(),
+        (Mode::ConfirmDelete, MEMORIES_TAB) => " Delete this memory? y: Yes | any other key: Cancel ".to_string(),
+        (Mode::EditTags(_), MEMORIES_TAB) => " Type tags (comma-separated) | Enter: Save | Esc: Cancel ".to_string(),
+        _ => match app.status {
+            Some(ref status) => format!(" {} ", status),
+            None => " ←/→ or Tab: Switch tabs | ↑/↓ or j/k: Navigate | Enter: Details | q: Quit ".to_string(),
+        },
+    };
+    let footer = Paragraph::new(hint)
         .style(Style::default().fg(Color::DarkGray))
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::DarkGray)));
@@ -244,6 +420,8 @@ fn draw_dashboard(frame: &mut Frame, area: Rect, app: &App) {
         Line::from("  1-4: Jump to tab"),
         Line::from("  Tab: Next tab"),
         Line::from("  j/k: Navigate list"),
+        Line::from("  Enter: Memory details"),
+        Line::from("  t / d: Edit tags / Delete"),
         Line::from("  q: Quit"),
     ];
     
@@ -256,24 +434,20 @@ fn draw_dashboard(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 /// Draw memories list tab
-fn draw_memories(frame: &mut Frame, area: Rect, app: &App) {
+fn draw_memories(frame: &mut Frame, area: Rect, app: &mut App) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
         .split(area);
     
-    // Left: Memory list
-    let items: Vec<ListItem> = app.memories.iter()
-        .enumerate()
-        .map(|(i, (id, content, _))| {
-            let preview: String = content.chars().take(30).collect();
-            let display = format!("{}: {}...", &id[..8.min(id.len())], preview);
-            let style = if i == app.selected_memory {
-                Style::default().fg(Color::Yellow).bold()
-            } else {
-                Style::default().fg(Color::White)
-            };
-            ListItem::new(display).style(style)
+    // Left: Memory list (only the visible rows are built)
+    let window = app.visible_window(chunks[0].height.saturating_sub(2) as usize);
+    let offset = window.start;
+    let items: Vec<ListItem> = app.memories[window]
+        .iter()
+        .map(|m| {
+            let preview: String = m.content.chars().take(30).collect();
+            ListItem::new(format!("{}: {}...", &m.id.to_string()[..8], preview))
         })
         .collect();
     
@@ -282,42 +456,94 @@ fn draw_memories(frame: &mut Frame, area: Rect, app: &App) {
             .borders(Borders::ALL)
             .title(format!(" Memories ({}) ", app.memories.len()))
             .border_style(Style::default().fg(Color::Cyan)))
-        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
         .highlight_symbol("▶ ");
     
     let mut state = ListState::default();
-    state.select(Some(app.selected_memory));
+    if !app.memories.is_empty() {
+        state.select(Some(app.selected_memory - offset));
+    }
     frame.render_stateful_widget(list, chunks[0], &mut state);
     
-    // Right: Selected memory details
-    let detail_content = if let Some((id, content, tags)) = app.memories.get(app.selected_memory) {
-        vec![
+    // Right: preview, or full details once opened with Enter
+    let detail_content = match app.selected() {
+        Some(memory) if app.mode == Mode::List => vec![
             Line::from(vec![
                 Span::styled("ID: ", Style::default().fg(Color::DarkGray)),
-                Span::styled(id, Style::default().fg(Color::Cyan)),
+                Span::styled(memory.id.to_string(), Style::default().fg(Color::Cyan)),
             ]),
             Line::from(""),
             Line::from(Span::styled("Content:", Style::default().fg(Color::Yellow))),
-            Line::from(content.as_str()),
+            Line::from(memory.content.as_str()),
             Line::from(""),
             Line::from(vec![
                 Span::styled("Tags: ", Style::default().fg(Color::DarkGray)),
-                Span::styled(tags, Style::default().fg(Color::Green)),
+                Span::styled(memory.tags.join(", "), Style::default().fg(Color::Green)),
             ]),
-        ]
-    } else {
-        vec![Line::from("No memory selected")]
+        ],
+        Some(memory) => detail_lines(app, memory),
+        None => vec![Line::from("No memory selected")],
     };
     
+    let title = match app.mode {
+        Mode::List => " Details ",
+        Mode::Detail => " Details [Esc: back] ",
+        Mode::ConfirmDelete => " Delete? [y/n] ",
+        Mode::EditTags(_) => " Edit Tags ",
+    };
     let detail = Paragraph::new(detail_content)
         .wrap(Wrap { trim: true })
         .block(Block::default()
             .borders(Borders::ALL)
-            .title(" Details ")
-            .border_style(Style::default().fg(Color::Yellow)));
+            .title(title)
+            .border_style(Style::default().fg(if app.mode == Mode::ConfirmDelete { Color::Red } else { Color::Yellow })));
     frame.render_widget(detail, chunks[1]);
 }
 
+/// Full detail view of `memory`
+fn detail_lines<'a>(app: &'a App, memory: &'a MemoryItem) -> Vec<Line<'a>> {
+    let label = |text: &'static str| Span::styled(text, Style::default().fg(Color::DarkGray));
+    let mut lines = vec![
+        Line::from(vec![label("ID:        "), Span::styled(memory.id.to_string(), Style::default().fg(Color::Cyan))]),
+        Line::from(vec![label("Type:      "), Span::raw(format!("{:?}", memory.memory_type))]),
+        Line::from(vec![label("Strength:  "), Span::raw(format!("{:.2}", memory.strength))]),
+        Line::from(vec![label("Emotion:   "), Span::raw(format!("{:?}", memory.emotion))]),
+        Line::from(vec![label("Accessed:  "), Span::raw(format!("{} times", memory.access_count))]),
+        Line::from(vec![label("Created:   "), Span::raw(memory.created_at.format("%Y-%m-%d %H:%M").to_string())]),
+    ];
+
+    lines.push(match app.mode {
+        Mode::EditTags(ref buffer) => Line::from(vec![
+            label("Tags:      "),
+            Span::styled(format!("{}▏", buffer), Style::default().fg(Color::Yellow).bold()),
+        ]),
+        _ => Line::from(vec![label("Tags:      "), Span::styled(memory.tags.join(", "), Style::default().fg(Color::Green))]),
+    });
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Content:", Style::default().fg(Color::Yellow))));
+    lines.push(Line::from(memory.content.as_str()));
+    if let Some(ref context) = memory.context {
+        lines.push(Line::from(vec![label("Context: "), Span::raw(context.as_str())]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!("Associations ({}):", memory.associations.len()),
+        Style::default().fg(Color::Yellow),
+    )));
+    for id in &memory.associations {
+        let preview = app
+            .memories
+            .iter()
+            .find(|m| m.id == *id)
+            .map(|m| m.content.chars().take(40).collect::<String>())
+            .unwrap_or_else(|| "(not loaded)".to_string());
+        lines.push(Line::from(format!("  {} {}", &id.to_string()[..8], preview)));
+    }
+    lines
+}
+
 /// Draw trends tab
 fn draw_trends(frame: &mut Frame, area: Rect, app: &App) {
     // Create bar chart data
@@ -398,4 +624,87 @@ mod tests {
         app.prev_tab();
         assert_eq!(app.tab, 0);
     }
+
+    fn app_with(contents: &[&str]) -> App {
+        let mut app = App::new();
+        app.load_memories(contents.iter().map(|c| MemoryItem::new(c, None)).collect());
+        app.tab = MEMORIES_TAB;
+        app
+    }
+
+    #[test]
+    fn test_selection_detail_and_tag_edit() {
+        let mut app = app_with(&["first", "second"]);
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.handle_key(KeyCode::Enter), None);
+        assert_eq!(app.mode, Mode::Detail);
+        let id = app.selected().unwrap().id;
+        assert_eq!(app.selected().unwrap().content, "second");
+
+        // 'q' leaves the detail view rather than quitting
+        app.handle_key(KeyCode::Char('q'));
+        assert_eq!(app.mode, Mode::List);
+        assert!(!app.should_quit);
+
+        app.handle_key(KeyCode::Enter);
+        app.handle_key(KeyCode::Char('t'));
+        assert_eq!(app.mode, Mode::EditTags(String::new()));
+        for c in "rust, ,db".chars() {
+            assert_eq!(app.handle_key(KeyCode::Char(c)), None);
+        }
+        app.handle_key(KeyCode::Backspace);
+        let action = app.handle_key(KeyCode::Enter).unwrap();
+        assert_eq!(action, Action::SetTags(id, vec!["rust".to_string(), "d".to_string()]));
+        assert_eq!(app.mode, Mode::Detail);
+
+        app.action_applied(&action);
+        assert_eq!(app.selected().unwrap().tags, vec!["rust", "d"]);
+
+        // Esc cancels an edit without an action
+        app.handle_key(KeyCode::Char('t'));
+        app.handle_key(KeyCode::Char('x'));
+        assert_eq!(app.handle_key(KeyCode::Esc), None);
+        assert_eq!(app.selected().unwrap().tags, vec!["rust", "d"]);
+    }
+
+    #[test]
+    fn test_delete_requires_confirmation() {
+        let mut app = app_with(&["first", "second"]);
+        app.handle_key(KeyCode::Down);
+        let id = app.selected().unwrap().id;
+
+        app.handle_key(KeyCode::Char('d'));
+        assert_eq!(app.mode, Mode::ConfirmDelete);
+        assert_eq!(app.handle_key(KeyCode::Char('n')), None);
+        assert_eq!(app.mode, Mode::List);
+
+        app.handle_key(KeyCode::Char('d'));
+        let action = app.handle_key(KeyCode::Char('y')).unwrap();
+        assert_eq!(action, Action::Delete(id));
+        app.action_applied(&action);
+        assert_eq!(app.memories.len(), 1);
+        assert_eq!(app.selected_memory, 0);
+        assert!(app.status.is_some());
+    }
+
+    #[test]
+    fn test_visible_window_follows_selection() {
+        let contents: Vec<String> = (0..1000).map(|i| format!("memory {}", i)).collect();
+        let refs: Vec<&str> = contents.iter().map(String::as_str).collect();
+        let mut app = app_with(&refs);
+
+        assert_eq!(app.visible_window(10), 0..10);
+        for _ in 0..15 {
+            app.next_memory();
+        }
+        assert_eq!(app.visible_window(10), 6..16);
+        app.prev_memory();
+        assert_eq!(app.visible_window(10), 6..16);
+
+        // Wrapping to the top scrolls back
+        app.selected_memory = 999;
+        assert_eq!(app.visible_window(10), 990..1000);
+        app.next_memory();
+        assert_eq!(app.visible_window(10), 0..10);
+    }
 }
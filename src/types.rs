@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Type of memory storage
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum MemoryType {
     Working,    // Short-term, volatile
     Episodic,   // "When did what" - events
@@ -12,7 +12,7 @@ pub enum MemoryType {
 }
 
 /// Emotional valence affects memory strength
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Emotion {
     Neutral,
     Positive,
@@ -20,6 +20,110 @@ pub enum Emotion {
     Surprise,
 }
 
+/// Relevance added per unit of emotional intensity when ranking recall
+pub const EMOTION_RECALL_BOOST: f32 = 0.15;
+
+impl Emotion {
+    /// Parse `positive|negative|surprise|neutral` (case-insensitive)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "neutral" | "none" => Some(Emotion::Neutral),
+            "positive" | "pos" | "+" => Some(Emotion::Positive),
+            "negative" | "neg" | "-" => Some(Emotion::Negative),
+            "surprise" | "surprised" | "!" => Some(Emotion::Surprise),
+            _ => None,
+        }
+    }
+
+    /// Emotional intensity (0.0 - 1.0); negative memories stick hardest
+    pub fn intensity(&self) -> f32 {
+        match self {
+            Emotion::Neutral => 0.0,
+            Emotion::Positive => 0.6,
+            Emotion::Surprise => 0.7,
+            Emotion::Negative => 0.8,
+        }
+    }
+}
+
+/// English words with their inflected forms (matched as whole words) and
+/// Korean stems (matched at the start of a word, where endings and
+/// particles attach)
+const POSITIVE_WORDS: &[&str] = &[
+    "love", "loved", "loves", "loving", "happy", "happier", "happiest", "happily", "happiness",
+    "glad", "great", "awesome", "excited", "exciting", "excitement", "delighted", "delightful",
+    "proud", "wonderful", "fantastic", "enjoy", "enjoyed", "enjoying", "enjoys",
+    "thank", "thanks", "thankful", "grateful", "celebrate", "celebrated", "celebrating", "celebration",
+    "좋", "행복", "기쁘", "기뻐", "최고", "감사", "사랑", "신나", "뿌듯",
+];
+
+const NEGATIVE_WORDS: &[&str] = &[
+    "hate", "hated", "hates", "angry", "angrier", "anger", "sad", "sadder", "sadly", "sadness",
+    "upset", "terrible", "terribly", "awful", "horrible", "horribly",
+    "frustrated", "frustrating", "frustration", "annoyed", "annoying", "annoys",
+    "worried", "worry", "worrying", "worries", "afraid", "scared", "scary",
+    "disappointed", "disappointing", "disappointment", "miserable", "furious",
+    "싫", "화나", "화났", "슬프", "슬퍼", "짜증", "우울", "걱정", "실망", "무서",
+];
+
+const SURPRISE_WORDS: &[&str] = &[
+    "wow", "surprise", "surprised", "surprising", "surprisingly", "unexpected", "unexpectedly",
+    "shock", "shocked", "shocking", "astonished", "astonishing", "unbelievable", "whoa",
+    "놀라", "놀랐", "헐", "대박", "깜짝",
+];
+
+/// Whether `word` is in `lexicon`: English entries must match the whole
+/// word, Korean stems only its start
+fn lexicon_hit(lexicon: &[&str], word: &str) -> bool {
+    lexicon.iter().any(|&entry| {
+        let korean = entry.chars().next().is_some_and(crate::text::is_hangul);
+        if korean {
+            word.starts_with(entry)
+        } else {
+            word == entry
+        }
+    })
+}
+
+/// Guess the emotion of a text from a small English/Korean lexicon
+///
+/// Each word is looked up in the lexicons (see [`lexicon_hit`]) and the
+/// category with the most hits wins; ties and texts without hits are
+/// neutral. `?!`/`!?` counts as surprise.
+pub fn detect_emotion(content: &str) -> Emotion {
+    let lower = content.to_lowercase();
+    let (mut positive, mut negative, mut surprise) = (0, 0, 0);
+    if lower.contains("?!") || lower.contains("!?") {
+        surprise += 1;
+    }
+
+    for word in lower.split_whitespace() {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        let hits = |lexicon: &[&str]| lexicon_hit(lexicon, word);
+        if hits(POSITIVE_WORDS) {
+            positive += 1;
+        }
+        if hits(NEGATIVE_WORDS) {
+            negative += 1;
+        }
+        if hits(SURPRISE_WORDS) {
+            surprise += 1;
+        }
+    }
+
+    let best = positive.max(negative).max(surprise);
+    let winners = [positive, negative, surprise].iter().filter(|&&n| n == best).count();
+    if best == 0 || winners > 1 {
+        Emotion::Neutral
+    } else if best == negative {
+        Emotion::Negative
+    } else if best == surprise {
+        Emotion::Surprise
+    } else {
+        Emotion::Positive
+    }
+}
+
 /// A single memory item
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryItem {
@@ -45,6 +149,31 @@ pub struct MemoryItem {
     
     // Tags for categorization
     pub tags: Vec<String>,
+
+    // When the memory expires (purged by `Brain::sleep`); None = never
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+
+    // `content_hash(content)`, used for dedup; 0 in archives that predate it
+    #[serde(default)]
+    pub content_hash: u64,
+}
+
+/// Content with runs of whitespace collapsed, used for exact-match dedup
+pub fn normalize_content(content: &str) -> String {
+    content.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Stable 64-bit FNV-1a hash of the normalized content
+///
+/// Persisted with each memory, so it must not change between releases
+/// (unlike `DefaultHasher`).
+pub fn content_hash(content: &str) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    normalize_content(content)
+        .bytes()
+        .fold(OFFSET, |h, b| (h ^ b as u64).wrapping_mul(PRIME))
 }
 
 impl MemoryItem {
@@ -63,19 +192,49 @@ impl MemoryItem {
             embedding: None,
             associations: Vec::new(),
             tags: Vec::new(),
+            expires_at: None,
+            content_hash: content_hash(content),
+        }
+    }
+
+    /// Replace the content, keeping `content_hash` in sync
+    pub fn set_content(&mut self, content: &str) {
+        self.content = content.to_string();
+        self.content_hash = content_hash(content);
+    }
+
+    /// Stored hash, or computed if the item predates `content_hash`
+    pub fn dedup_key(&self) -> u64 {
+        if self.content_hash == 0 {
+            content_hash(&self.content)
+        } else {
+            self.content_hash
         }
     }
 
-    /// Calculate relevance score based on strength, recency, and access frequency
+    /// Expire `ttl` after now
+    pub fn with_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.expires_at = Some(Utc::now() + ttl);
+        self
+    }
+
+    /// Whether the expiry time has passed at `now`
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.map_or(false, |at| at <= now)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Utc::now())
+    }
+
+    /// Calculate relevance score based on strength, recency, access frequency
+    /// and emotional intensity (charged memories surface more readily)
     pub fn relevance_score(&self) -> f32 {
-        let recency = self.recency_factor();
-        let frequency = (self.access_count as f32).ln() / 10.0;
-        
-        self.strength * 0.5 + recency * 0.3 + frequency * 0.2
+        RecallWeights { similarity: 0.0, strength: 0.5, recency: 0.3 }.score(self, 0.0)
     }
 
     /// Recency factor (1.0 for just accessed, decays over time)
-    fn recency_factor(&self) -> f32 {
+    pub fn recency_factor(&self) -> f32 {
         let hours_since = (Utc::now() - self.last_accessed).num_hours() as f32;
         (-hours_since / 168.0).exp() // Half-life of ~1 week
     }
@@ -127,6 +286,96 @@ impl MemoryItem {
             self.associations.push(other_id);
         }
     }
+
+    /// Fold a duplicate into this memory, keeping this memory's id
+    ///
+    /// Tags and associations are unioned (links between the two are
+    /// dropped), access counts summed, and the higher strength, latest
+    /// access and earliest creation time kept. The longer content wins,
+    /// together with its embedding; on a tie this memory's content stays.
+    pub fn merge_into(&mut self, other: MemoryItem) {
+        for tag in other.tags {
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+        for assoc in other.associations {
+            if assoc != self.id && assoc != other.id {
+                self.associate(assoc);
+            }
+        }
+        self.associations.retain(|a| *a != other.id);
+
+        self.access_count = self.access_count.saturating_add(other.access_count);
+        self.strength = self.strength.max(other.strength);
+        self.last_accessed = self.last_accessed.max(other.last_accessed);
+        self.created_at = self.created_at.min(other.created_at);
+
+        if other.content.chars().count() > self.content.chars().count() {
+            self.set_content(&other.content);
+            if other.embedding.is_some() {
+                self.embedding = other.embedding;
+            }
+        }
+    }
+}
+
+/// Weights of the `recall` ranking score
+///
+/// `score = similarity * sim(query, item) + strength * item.strength
+///        + recency * item.recency_factor()`, plus the access-frequency and
+/// emotion terms of [`MemoryItem::relevance_score`]. The defaults reproduce
+/// the old ranking: a 0.5/0.5 strength/similarity blend weighted 0.5, next
+/// to recency at 0.3.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RecallWeights {
+    pub similarity: f32,
+    pub strength: f32,
+    pub recency: f32,
+}
+
+impl Default for RecallWeights {
+    fn default() -> Self {
+        Self { similarity: 0.25, strength: 0.25, recency: 0.3 }
+    }
+}
+
+impl RecallWeights {
+    /// Parse `sim=0.7,strength=0.2,recency=0.1` (omitted weights keep their default)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut weights = Self::default();
+        for part in spec.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Expected name=weight, got '{}'", part))?;
+            let value: f32 = value
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid weight '{}'", value.trim()))?;
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("Weight must be >= 0, got {}", value));
+            }
+            match name.trim().to_lowercase().as_str() {
+                "sim" | "similarity" => weights.similarity = value,
+                "strength" => weights.strength = value,
+                "recency" => weights.recency = value,
+                other => return Err(format!("Unknown weight '{}' (sim|strength|recency)", other)),
+            }
+        }
+        Ok(weights)
+    }
+
+    /// Ranking score of `item` given its query similarity
+    pub fn score(&self, item: &MemoryItem, similarity: f32) -> f32 {
+        let frequency = (item.access_count as f32).ln() / 10.0;
+        let emotion = item.emotion.intensity() * EMOTION_RECALL_BOOST;
+
+        self.similarity * similarity
+            + self.strength * item.strength
+            + self.recency * item.recency_factor()
+            + frequency * 0.2
+            + emotion
+    }
 }
 
 /// Query for memory search
@@ -155,6 +404,16 @@ impl MemoryQuery {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_recall_weights_parse() {
+        let weights = RecallWeights::parse("sim=0.7, strength=0.2,recency=0.1").unwrap();
+        assert_eq!(weights, RecallWeights { similarity: 0.7, strength: 0.2, recency: 0.1 });
+        assert_eq!(RecallWeights::parse("recency=0").unwrap().similarity, RecallWeights::default().similarity);
+        assert!(RecallWeights::parse("novelty=1").is_err());
+        assert!(RecallWeights::parse("sim=-1").is_err());
+        assert!(RecallWeights::parse("sim").is_err());
+    }
+
     #[test]
     fn test_memory_item_creation() {
         let item = MemoryItem::new("test content", Some("context"));
@@ -165,6 +424,20 @@ mod tests {
         assert_eq!(item.strength, 1.0);
     }
 
+    #[test]
+    fn test_content_hash_ignores_whitespace() {
+        let a = MemoryItem::new("Rust uses  ownership\n", None);
+        let b = MemoryItem::new("Rust uses ownership", None);
+        assert_eq!(a.content_hash, b.content_hash);
+        assert_ne!(a.content_hash, MemoryItem::new("Rust uses borrowing", None).content_hash);
+
+        let mut c = b.clone();
+        c.set_content("Rust uses borrowing");
+        assert_ne!(c.content_hash, b.content_hash);
+        c.content_hash = 0;
+        assert_eq!(c.dedup_key(), content_hash("Rust uses borrowing"));
+    }
+
     #[test]
     fn test_memory_item_access() {
         let mut item = MemoryItem::new("test", None);
@@ -232,6 +505,75 @@ mod tests {
         assert_eq!(item1.associations.len(), 1);
     }
 
+    #[test]
+    fn test_merge_into_unions_tags_and_associations() {
+        let linked = Uuid::new_v4();
+        let shared = Uuid::new_v4();
+        let mut item = MemoryItem::new("deploy notes", None).with_tags(vec!["ops".into(), "deploy".into()]);
+        let mut other = MemoryItem::new("deploy notes", None).with_tags(vec!["deploy".into(), "friday".into()]);
+        item.associate(shared);
+        item.associate(other.id);
+        other.associate(shared);
+        other.associate(linked);
+        other.associate(item.id);
+
+        item.merge_into(other);
+
+        assert_eq!(item.tags, vec!["ops", "deploy", "friday"]);
+        // No self-links, no link to the absorbed duplicate, no repeats
+        assert_eq!(item.associations, vec![shared, linked]);
+    }
+
+    #[test]
+    fn test_merge_into_counts_strength_and_timestamps() {
+        let now = Utc::now();
+        let mut item = MemoryItem::new("standup at 10", None);
+        item.access_count = 2;
+        item.strength = 0.3;
+        item.created_at = now - chrono::Duration::days(1);
+        item.last_accessed = now - chrono::Duration::hours(5);
+        let mut other = MemoryItem::new("standup at 10", None);
+        other.access_count = 3;
+        other.strength = 0.8;
+        other.created_at = now - chrono::Duration::days(10);
+        other.last_accessed = now - chrono::Duration::hours(1);
+        let (older_created, newer_accessed) = (other.created_at, other.last_accessed);
+
+        item.merge_into(other);
+
+        assert_eq!(item.access_count, 5);
+        assert_eq!(item.strength, 0.8);
+        assert_eq!(item.created_at, older_created);
+        assert_eq!(item.last_accessed, newer_accessed);
+
+        // The min/max hold the other way round too
+        let mut recent = MemoryItem::new("standup at 10", None);
+        recent.strength = 0.9;
+        recent.merge_into(item.clone());
+        assert_eq!(recent.created_at, older_created);
+        assert_eq!(recent.strength, 0.9);
+    }
+
+    #[test]
+    fn test_merge_into_keeps_longer_content() {
+        let mut item = MemoryItem::new("Port 5433", None);
+        item.embedding = Some(vec![1.0, 0.0]);
+        let id = item.id;
+        let mut other = MemoryItem::new("Staging database runs on port 5433", None);
+        other.embedding = Some(vec![0.0, 1.0]);
+
+        item.merge_into(other);
+        assert_eq!(item.id, id);
+        assert_eq!(item.content, "Staging database runs on port 5433");
+        assert_eq!(item.content_hash, content_hash("Staging database runs on port 5433"));
+        assert_eq!(item.embedding, Some(vec![0.0, 1.0]));
+
+        // Shorter or equal-length content leaves self as is
+        item.merge_into(MemoryItem::new("Port 5433", None));
+        item.merge_into(MemoryItem::new("staging database runs on PORT 5433", None));
+        assert_eq!(item.content, "Staging database runs on port 5433");
+    }
+
     #[test]
     fn test_relevance_score() {
         let item = MemoryItem::new("test", None);
@@ -240,4 +582,30 @@ mod tests {
         // Score should be positive
         assert!(score > 0.0);
     }
+
+    #[test]
+    fn test_emotional_memory_outranks_equal_neutral_one() {
+        let neutral = MemoryItem::new("the deploy went out", None);
+        let mut charged = neutral.clone();
+        charged.emotion = Emotion::Negative;
+
+        assert!(charged.relevance_score() > neutral.relevance_score());
+    }
+
+    #[test]
+    fn test_detect_emotion() {
+        assert_eq!(detect_emotion("I love this new keyboard, so happy!"), Emotion::Positive);
+        assert_eq!(detect_emotion("Really frustrated, the build broke again"), Emotion::Negative);
+        assert_eq!(detect_emotion("Wow, that was unexpected"), Emotion::Surprise);
+        assert_eq!(detect_emotion("오늘 발표 성공해서 너무 기뻐"), Emotion::Positive);
+        assert_eq!(detect_emotion("Meeting moved to 3pm"), Emotion::Neutral);
+        // Mixed signals stay neutral
+        assert_eq!(detect_emotion("happy but sad"), Emotion::Neutral);
+        // Unrelated words that merely start like a lexicon entry don't count
+        assert_eq!(detect_emotion("Bought a saddle, a wowser and a thankless shockwave"), Emotion::Neutral);
+        assert_eq!(detect_emotion("Greatly annoyed, the worrying noise came back"), Emotion::Negative);
+
+        assert_eq!(Emotion::parse("Negative"), Some(Emotion::Negative));
+        assert_eq!(Emotion::parse("furious"), None);
+    }
 }
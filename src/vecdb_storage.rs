@@ -183,6 +183,9 @@ impl VecDbStorage {
         // Numeric fields (stored as string for metadata, indexed as numeric)
         metadata.insert("access_count".to_string(), item.access_count.to_string());
         metadata.insert("strength".to_string(), item.strength.to_string());
+        if let Some(at) = item.expires_at {
+            metadata.insert("expires_at".to_string(), at.timestamp_millis().to_string());
+        }
 
         let url = format!("{}/collections/{}/upsert_batch", self.base_url, self.collection);
         let req = UpsertBatchReq {
@@ -232,6 +235,9 @@ impl VecDbStorage {
                 metadata.insert("last_accessed".to_string(), item.last_accessed.timestamp_millis().to_string());
                 metadata.insert("access_count".to_string(), item.access_count.to_string());
                 metadata.insert("strength".to_string(), item.strength.to_string());
+                if let Some(at) = item.expires_at {
+                    metadata.insert("expires_at".to_string(), at.timestamp_millis().to_string());
+                }
 
                 BatchVectorReq {
                     vector: embedding.clone(),
@@ -394,6 +400,12 @@ impl VecDbStorage {
             .and_then(|s| s.parse().ok())
             .unwrap_or(1.0);
 
+        let expires_at = meta.get("expires_at")
+            .and_then(|s| s.parse::<i64>().ok())
+            .and_then(chrono::DateTime::from_timestamp_millis);
+
+        let content_hash = crate::types::content_hash(&content);
+
         MemoryItem {
             id,
             content,
@@ -407,6 +419,8 @@ impl VecDbStorage {
             embedding: None,
             tags,
             associations: vec![],
+            expires_at,
+            content_hash,
         }
     }
 
@@ -8,17 +8,33 @@
 //! (VLM) via Ollama. When a VLM provider is configured, descriptions can be
 //! auto-generated when storing images.
 
+use crate::cache::CacheStats;
+use crate::storage::escape_cql_string;
 use crate::visual::{ClipProvider, VisualContext, VisualMemory, cosine_similarity};
 use crate::vlm::{VlmProvider, OllamaVlm};
 use chrono::Utc;
 use coredb::CoreDB;
+use lru::LruCache;
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+/// CLIP embeddings kept per storage (text queries and image files)
+pub const DEFAULT_CLIP_CACHE_SIZE: usize = 1000;
+
+/// Cached CLIP embedding; image entries remember the file's mtime
+type ClipCacheEntry = (Option<SystemTime>, Vec<f32>);
+
+fn new_clip_cache() -> Mutex<LruCache<String, ClipCacheEntry>> {
+    Mutex::new(LruCache::new(NonZeroUsize::new(DEFAULT_CLIP_CACHE_SIZE).unwrap()))
+}
+
 /// Visual memory storage backed by CoreDB
 pub struct VisualStorage {
     db: Arc<RwLock<CoreDB>>,
@@ -26,8 +42,14 @@ pub struct VisualStorage {
     keyspace: String,
     /// In-memory cache of visual memories for fast search
     cache: RwLock<HashMap<Uuid, VisualMemory>>,
+    /// Cached memories changed since they were last written to CoreDB
+    dirty: RwLock<HashSet<Uuid>>,
     /// Optional VLM provider for auto-generating descriptions
     vlm: Option<Arc<dyn VlmProvider>>,
+    /// LRU cache of CLIP embeddings (`text:<query>` / `image:<path>`)
+    clip_cache: Mutex<LruCache<String, ClipCacheEntry>>,
+    clip_hits: AtomicU64,
+    clip_misses: AtomicU64,
 }
 
 impl VisualStorage {
@@ -42,7 +64,11 @@ impl VisualStorage {
             clip,
             keyspace: keyspace.to_string(),
             cache: RwLock::new(HashMap::new()),
+            dirty: RwLock::new(HashSet::new()),
             vlm: None,
+            clip_cache: new_clip_cache(),
+            clip_hits: AtomicU64::new(0),
+            clip_misses: AtomicU64::new(0),
         };
         
         storage.init_schema().await?;
@@ -65,7 +91,11 @@ impl VisualStorage {
             clip,
             keyspace: keyspace.to_string(),
             cache: RwLock::new(HashMap::new()),
+            dirty: RwLock::new(HashSet::new()),
             vlm: Some(vlm),
+            clip_cache: new_clip_cache(),
+            clip_hits: AtomicU64::new(0),
+            clip_misses: AtomicU64::new(0),
         };
         
         storage.init_schema().await?;
@@ -82,6 +112,78 @@ impl VisualStorage {
     pub fn has_vlm(&self) -> bool {
         self.vlm.is_some()
     }
+
+    /// Drop all cached CLIP embeddings and reset the hit/miss counters
+    pub fn clear_cache(&self) {
+        self.clip_cache.lock().unwrap().clear();
+        self.clip_hits.store(0, Ordering::Relaxed);
+        self.clip_misses.store(0, Ordering::Relaxed);
+    }
+
+    /// CLIP embedding cache statistics
+    pub fn cache_stats(&self) -> CacheStats {
+        let cache = self.clip_cache.lock().unwrap();
+        let hits = self.clip_hits.load(Ordering::Relaxed);
+        let misses = self.clip_misses.load(Ordering::Relaxed);
+        let memory_bytes: usize = cache
+            .iter()
+            .map(|(_, (_, embedding))| embedding.len() * std::mem::size_of::<f32>())
+            .sum();
+
+        CacheStats {
+            hits,
+            misses,
+            size: cache.len(),
+            capacity: cache.cap().get(),
+            hit_rate: if hits + misses > 0 { hits as f64 / (hits + misses) as f64 } else { 0.0 },
+            memory_bytes,
+            avg_embedding_size: if cache.is_empty() { 0 } else { memory_bytes / cache.len() },
+        }
+    }
+
+    /// CLIP text embedding, served from cache when possible
+    pub fn embed_text_cached(&self, text: &str) -> Result<Vec<f32>, VisualStorageError> {
+        let key = format!("text:{}", text);
+        if let Some((_, embedding)) = self.clip_cache.lock().unwrap().get(&key) {
+            self.clip_hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(embedding.clone());
+        }
+
+        self.clip_misses.fetch_add(1, Ordering::Relaxed);
+        let embedding = self.clip.embed_text(text)
+            .map_err(|e| VisualStorageError::EmbeddingError(e.to_string()))?;
+        self.clip_cache.lock().unwrap().put(key, (None, embedding.clone()));
+        Ok(embedding)
+    }
+
+    /// CLIP image embedding, cached until the file's mtime changes
+    fn embed_image_cached(&self, image_path: &Path) -> Result<Vec<f32>, VisualStorageError> {
+        let key = format!("image:{}", image_path.display());
+        let mtime = std::fs::metadata(image_path).and_then(|m| m.modified()).ok();
+
+        {
+            let mut cache = self.clip_cache.lock().unwrap();
+            match cache.get(&key).cloned() {
+                Some((cached_mtime, embedding)) if mtime.is_some() && cached_mtime == mtime => {
+                    self.clip_hits.fetch_add(1, Ordering::Relaxed);
+                    return Ok(embedding);
+                }
+                Some(_) => {
+                    // File changed (or its mtime is unreadable)
+                    cache.pop(&key);
+                }
+                None => {}
+            }
+        }
+
+        self.clip_misses.fetch_add(1, Ordering::Relaxed);
+        let embedding = self.clip.embed_image(image_path)
+            .map_err(|e| VisualStorageError::EmbeddingError(e.to_string()))?;
+        if mtime.is_some() {
+            self.clip_cache.lock().unwrap().put(key, (mtime, embedding.clone()));
+        }
+        Ok(embedding)
+    }
     
     /// Store image with auto-generated description using VLM
     pub async fn store_image_auto(
@@ -154,8 +256,7 @@ impl VisualStorage {
         emotional_valence: f32,
     ) -> Result<VisualMemory, VisualStorageError> {
         // Generate CLIP embedding
-        let embedding = self.clip.embed_image(image_path)
-            .map_err(|e| VisualStorageError::EmbeddingError(e.to_string()))?;
+        let embedding = self.embed_image_cached(image_path)?;
         
         // Create visual memory
         let mut memory = VisualMemory::new(
@@ -187,8 +288,54 @@ impl VisualStorage {
     
     /// Store a visual memory in CoreDB
     async fn store_memory(&self, memory: &VisualMemory) -> Result<(), VisualStorageError> {
+        let insert = self.insert_statement(memory)?;
+        let db = self.db.read().await;
+        db.execute_cql(&insert).await
+            .map_err(|e| VisualStorageError::DatabaseError(e.to_string()))?;
+        
+        Ok(())
+    }
+    
+    /// Write every dirty cached memory back to CoreDB
+    ///
+    /// Returns how many rows were written. Rows that fail to write stay
+    /// dirty for the next flush.
+    pub async fn flush_cache(&self) -> Result<usize, VisualStorageError> {
+        let ids: Vec<Uuid> = self.dirty.write().await.drain().collect();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        
+        let statements = {
+            let cache = self.cache.read().await;
+            let mut statements = Vec::with_capacity(ids.len());
+            for id in &ids {
+                if let Some(memory) = cache.get(id) {
+                    statements.push((*id, self.insert_statement(memory)?));
+                }
+            }
+            statements
+        };
+        
+        // One lock acquisition for the whole batch
         let db = self.db.read().await;
+        for (i, (_, insert)) in statements.iter().enumerate() {
+            if let Err(e) = db.execute_cql(insert).await {
+                self.dirty.write().await.extend(statements[i..].iter().map(|(id, _)| *id));
+                return Err(VisualStorageError::DatabaseError(e.to_string()));
+            }
+        }
         
+        Ok(statements.len())
+    }
+    
+    /// Number of cached memories waiting to be flushed
+    pub async fn dirty_count(&self) -> usize {
+        self.dirty.read().await.len()
+    }
+    
+    /// CQL upsert for one visual memory
+    fn insert_statement(&self, memory: &VisualMemory) -> Result<String, VisualStorageError> {
         let embedding_json = serde_json::to_string(&memory.embedding)
             .map_err(|e| VisualStorageError::SerializationError(e.to_string()))?;
         let context_json = serde_json::to_string(&memory.context)
@@ -212,24 +359,21 @@ impl VisualStorage {
             )",
             self.keyspace,
             memory.id,
-            memory.image_path.display().to_string().replace("'", "''"),
-            embedding_json.replace("'", "''"),
-            memory.description.replace("'", "''"),
-            context_json.replace("'", "''"),
-            tags_json.replace("'", "''"),
+            escape_cql_string(&memory.image_path.display().to_string()),
+            escape_cql_string(&embedding_json),
+            escape_cql_string(&memory.description),
+            escape_cql_string(&context_json),
+            escape_cql_string(&tags_json),
             memory.emotional_valence,
             memory.strength,
             memory.recall_count,
             memory.created_at.to_rfc3339(),
             memory.last_accessed.to_rfc3339(),
-            linked_memories_json.replace("'", "''"),
-            linked_visuals_json.replace("'", "''"),
+            escape_cql_string(&linked_memories_json),
+            escape_cql_string(&linked_visuals_json),
         );
         
-        db.execute_cql(&insert).await
-            .map_err(|e| VisualStorageError::DatabaseError(e.to_string()))?;
-        
-        Ok(())
+        Ok(insert)
     }
     
     /// Search for similar images by text query
@@ -239,8 +383,7 @@ impl VisualStorage {
         limit: usize,
     ) -> Result<Vec<(VisualMemory, f32)>, VisualStorageError> {
         // Generate text embedding
-        let query_embedding = self.clip.embed_text(query)
-            .map_err(|e| VisualStorageError::EmbeddingError(e.to_string()))?;
+        let query_embedding = self.embed_text_cached(query)?;
         
         self.search_by_embedding(&query_embedding, limit).await
     }
@@ -252,14 +395,13 @@ impl VisualStorage {
         limit: usize,
     ) -> Result<Vec<(VisualMemory, f32)>, VisualStorageError> {
         // Generate image embedding
-        let query_embedding = self.clip.embed_image(image_path)
-            .map_err(|e| VisualStorageError::EmbeddingError(e.to_string()))?;
+        let query_embedding = self.embed_image_cached(image_path)?;
         
         self.search_by_embedding(&query_embedding, limit).await
     }
     
-    /// Search by embedding vector
-    async fn search_by_embedding(
+    /// Search by embedding vector (cached memories, most similar first)
+    pub async fn search_by_embedding(
         &self,
         query_embedding: &[f32],
         limit: usize,
@@ -281,6 +423,11 @@ impl VisualStorage {
         Ok(scored)
     }
     
+    /// A cached visual memory stores the image at `path`
+    pub async fn has_image(&self, path: &Path) -> bool {
+        self.cache.read().await.values().any(|memory| memory.image_path == path)
+    }
+    
     /// Get a specific visual memory by ID
     pub async fn get(&self, id: Uuid) -> Result<Option<VisualMemory>, VisualStorageError> {
         let cache = self.cache.read().await;
@@ -301,6 +448,67 @@ impl VisualStorage {
         Ok(())
     }
     
+    /// Delete a visual memory and unlink it from the others
+    ///
+    /// Removes the row from CoreDB and the cache, then rewrites every memory
+    /// whose `linked_visuals` referenced it. Returns false if `id` is unknown.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, VisualStorageError> {
+        let (removed, unlinked) = {
+            let mut cache = self.cache.write().await;
+            let removed = cache.remove(&id).is_some();
+            let unlinked: Vec<Uuid> = cache
+                .values_mut()
+                .filter(|memory| memory.linked_visuals.contains(&id))
+                .map(|memory| {
+                    memory.linked_visuals.retain(|v| *v != id);
+                    memory.id
+                })
+                .collect();
+            (removed, unlinked)
+        };
+        if !removed && unlinked.is_empty() {
+            return Ok(false);
+        }
+
+        {
+            let mut dirty = self.dirty.write().await;
+            dirty.remove(&id);
+            dirty.extend(unlinked);
+        }
+
+        let delete = format!(
+            "DELETE FROM {}.visual_memories WHERE id = '{}'",
+            self.keyspace, id
+        );
+        {
+            let db = self.db.read().await;
+            db.execute_cql(&delete).await
+                .map_err(|e| VisualStorageError::DatabaseError(e.to_string()))?;
+        }
+        self.flush_cache().await?;
+
+        Ok(removed)
+    }
+    
+    /// Link a visual memory to a text memory and persist the link
+    pub async fn link_text_memory(
+        &self,
+        visual_id: Uuid,
+        memory_id: Uuid,
+    ) -> Result<VisualMemory, VisualStorageError> {
+        let memory = {
+            let mut cache = self.cache.write().await;
+            let memory = cache.get_mut(&visual_id)
+                .ok_or_else(|| VisualStorageError::NotFound(visual_id.to_string()))?;
+            memory.link_memory(memory_id);
+            memory.clone()
+        };
+        
+        self.store_memory(&memory).await?;
+        
+        Ok(memory)
+    }
+    
     /// Automatically link to similar visual memories
     async fn auto_link_memories(&self, memory: &mut VisualMemory) -> Result<(), VisualStorageError> {
         // Find similar visual memories
@@ -351,19 +559,22 @@ impl VisualStorage {
         Ok(count)
     }
     
-    /// Apply forgetting curve to all visual memories
+    /// Apply forgetting curve to all visual memories and persist the changes
     pub async fn apply_forgetting(&self, decay_rate: f32) -> Result<usize, VisualStorageError> {
-        let mut cache = self.cache.write().await;
-        let mut updated = 0;
+        let changed: Vec<Uuid> = {
+            let mut cache = self.cache.write().await;
+            cache.values_mut()
+                .filter_map(|memory| {
+                    let old_strength = memory.strength;
+                    memory.apply_decay(decay_rate);
+                    ((old_strength - memory.strength).abs() > 0.001).then_some(memory.id)
+                })
+                .collect()
+        };
         
-        for memory in cache.values_mut() {
-            let old_strength = memory.strength;
-            memory.apply_decay(decay_rate);
-            
-            if (old_strength - memory.strength).abs() > 0.001 {
-                updated += 1;
-            }
-        }
+        let updated = changed.len();
+        self.dirty.write().await.extend(changed);
+        self.flush_cache().await?;
         
         Ok(updated)
     }
@@ -456,3 +667,108 @@ fn parse_visual_memory_row(row: &coredb::query::Row) -> Option<VisualMemory> {
         linked_visuals,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clip_onnx::MockClipProvider;
+
+    async fn open_db(dir: &Path) -> Arc<RwLock<CoreDB>> {
+        let config = coredb::DatabaseConfig {
+            data_directory: dir.join("data"),
+            commitlog_directory: dir.join("commitlog"),
+            memtable_flush_threshold_mb: 16,
+            compaction_throughput_mb_per_sec: 16,
+            concurrent_reads: 32,
+            concurrent_writes: 32,
+            block_cache_size_mb: 64,
+            block_cache_max_entries: 5_000,
+        };
+        Arc::new(RwLock::new(CoreDB::new(config).await.unwrap()))
+    }
+
+    #[tokio::test]
+    async fn test_clip_embeddings_are_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let clip = Arc::new(MockClipProvider::new(16));
+        let storage = VisualStorage::new(open_db(dir.path()).await, clip.clone(), "visual_cache_test")
+            .await
+            .unwrap();
+
+        storage.search_by_text("sunset at the beach", 5).await.unwrap();
+        storage.search_by_text("sunset at the beach", 5).await.unwrap();
+        assert_eq!(clip.text_calls(), 1);
+        let stats = storage.cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.size), (1, 1, 1));
+
+        // Image entries are keyed by path and invalidated by a new mtime
+        let image = dir.path().join("photo.jpg");
+        std::fs::write(&image, b"not really a jpeg").unwrap();
+        storage.search_by_image(&image, 5).await.unwrap();
+        storage.search_by_image(&image, 5).await.unwrap();
+        assert_eq!(clip.image_calls(), 1);
+
+        let later = SystemTime::now() + std::time::Duration::from_secs(60);
+        std::fs::File::options().write(true).open(&image).unwrap().set_modified(later).unwrap();
+        storage.search_by_image(&image, 5).await.unwrap();
+        assert_eq!(clip.image_calls(), 2);
+
+        storage.clear_cache();
+        storage.search_by_text("sunset at the beach", 5).await.unwrap();
+        assert_eq!(clip.text_calls(), 2);
+        assert_eq!(storage.cache_stats().hits, 0);
+    }
+
+    #[tokio::test]
+    async fn test_apply_forgetting_persists_strength() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = open_db(dir.path()).await;
+        let image = dir.path().join("harbour.png");
+        std::fs::write(&image, b"pixels").unwrap();
+
+        let storage = VisualStorage::new(db.clone(), Arc::new(MockClipProvider::new(16)), "visual_decay_test")
+            .await
+            .unwrap();
+        let memory = storage.store_image(&image, "Boats in the harbour", None, vec![], 0.0).await.unwrap();
+
+        assert_eq!(storage.apply_forgetting(0.25).await.unwrap(), 1);
+        assert_eq!(storage.dirty_count().await, 0);
+        // Nothing changed since, so nothing to rewrite
+        assert_eq!(storage.flush_cache().await.unwrap(), 0);
+
+        // A fresh storage only sees what reached CoreDB
+        let reloaded = VisualStorage::new(db, Arc::new(MockClipProvider::new(16)), "visual_decay_test")
+            .await
+            .unwrap();
+        reloaded.load_cache().await.unwrap();
+        let strength = reloaded.get(memory.id).await.unwrap().unwrap().strength;
+        assert!((strength - (memory.strength - 0.25)).abs() < 1e-6, "{}", strength);
+    }
+
+    #[tokio::test]
+    async fn test_delete_unlinks_survivors() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = open_db(dir.path()).await;
+        let image = dir.path().join("harbour.png");
+        std::fs::write(&image, b"pixels").unwrap();
+
+        let storage = VisualStorage::new(db.clone(), Arc::new(MockClipProvider::new(16)), "visual_delete_test")
+            .await
+            .unwrap();
+        // Same image twice: identical embeddings, so the second links to the first
+        let first = storage.store_image(&image, "Boats in the harbour", None, vec![], 0.0).await.unwrap();
+        let second = storage.store_image(&image, "Boats again", None, vec![], 0.0).await.unwrap();
+        assert!(second.linked_visuals.contains(&first.id));
+
+        assert!(storage.delete(first.id).await.unwrap());
+        assert!(storage.get(first.id).await.unwrap().is_none());
+        assert!(!storage.delete(first.id).await.unwrap());
+
+        let reloaded = VisualStorage::new(db, Arc::new(MockClipProvider::new(16)), "visual_delete_test")
+            .await
+            .unwrap();
+        assert_eq!(reloaded.load_cache().await.unwrap(), 1);
+        let survivor = reloaded.get(second.id).await.unwrap().unwrap();
+        assert!(!survivor.linked_visuals.contains(&first.id));
+    }
+}
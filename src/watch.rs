@@ -6,9 +6,12 @@
 //! - Strength changes
 //! - Index statistics
 
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use std::io::{Write, stdout};
+use uuid::Uuid;
 use crate::Brain;
+use crate::predict::{AlertUrgency, Predictor};
 
 /// Watch configuration
 #[derive(Debug, Clone)]
@@ -21,6 +24,8 @@ pub struct WatchConfig {
     pub clear_screen: bool,
     /// Max iterations (0 = infinite)
     pub max_iterations: usize,
+    /// Report memories whose forgetting urgency rose since the last refresh
+    pub alert_on_forgetting: bool,
 }
 
 impl Default for WatchConfig {
@@ -30,10 +35,83 @@ impl Default for WatchConfig {
             detailed: false,
             clear_screen: true,
             max_iterations: 0,
+            alert_on_forgetting: false,
         }
     }
 }
 
+/// Memory contents and forgetting urgency at one refresh
+#[derive(Debug, Clone, Default)]
+pub struct ActivitySnapshot {
+    /// Content of every long-term memory by id
+    pub memories: HashMap<Uuid, String>,
+    /// Forgetting urgency of memories that currently have an alert
+    pub urgency: HashMap<Uuid, AlertUrgency>,
+}
+
+impl ActivitySnapshot {
+    fn from_brain(brain: &Brain, with_alerts: bool) -> Self {
+        let predictor = Predictor::new(brain);
+        let memories = predictor
+            .get_all_memories(crate::predict::DEFAULT_SCAN_LIMIT)
+            .into_iter()
+            .map(|m| (m.id, m.content))
+            .collect();
+        let urgency = if with_alerts {
+            predictor
+                .forgetting_alerts(usize::MAX)
+                .into_iter()
+                .map(|a| (a.memory.id, a.urgency))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        Self { memories, urgency }
+    }
+}
+
+/// What happened between two refreshes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActivityDiff {
+    /// Memories that appeared, as `(id, content)` sorted by content
+    pub new_memories: Vec<(Uuid, String)>,
+    /// Memories whose urgency rose (or that started alerting), most urgent first
+    pub escalated: Vec<(Uuid, String, AlertUrgency)>,
+}
+
+impl ActivityDiff {
+    pub fn is_empty(&self) -> bool {
+        self.new_memories.is_empty() && self.escalated.is_empty()
+    }
+}
+
+/// Compare two refreshes
+pub fn diff_activity(previous: &ActivitySnapshot, current: &ActivitySnapshot) -> ActivityDiff {
+    let mut new_memories: Vec<(Uuid, String)> = current
+        .memories
+        .iter()
+        .filter(|(id, _)| !previous.memories.contains_key(id))
+        .map(|(id, content)| (*id, content.clone()))
+        .collect();
+    new_memories.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let mut escalated: Vec<(Uuid, String, AlertUrgency)> = current
+        .urgency
+        .iter()
+        .filter(|(id, urgency)| {
+            let before = previous.urgency.get(id).map_or(0, |u| u.level());
+            urgency.level() > before
+        })
+        .map(|(id, urgency)| {
+            let content = current.memories.get(id).cloned().unwrap_or_default();
+            (*id, content, urgency.clone())
+        })
+        .collect();
+    escalated.sort_by(|a, b| b.2.level().cmp(&a.2.level()).then_with(|| a.1.cmp(&b.1)));
+
+    ActivityDiff { new_memories, escalated }
+}
+
 /// Memory snapshot for comparison
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -49,17 +127,16 @@ struct MemorySnapshot {
 
 impl MemorySnapshot {
     fn from_brain(brain: &Brain) -> Self {
-        let index_stats = brain.keyword_index.stats();
-        let bloom_stats = brain.keyword_bloom.stats();
+        let stats = brain.stats();
         
         Self {
             timestamp: Instant::now(),
-            semantic_count: brain.semantic.search("", 100000).map(|v| v.len()).unwrap_or(0),
-            episodic_count: brain.episodic.search("", 100000).map(|v| v.len()).unwrap_or(0),
-            procedural_count: brain.procedural.search("", 100000).map(|v| v.len()).unwrap_or(0),
-            index_keywords: index_stats.unique_keywords,
-            index_docs: index_stats.documents,
-            bloom_items: bloom_stats.items_added,
+            semantic_count: stats.semantic,
+            episodic_count: stats.episodic,
+            procedural_count: stats.procedural,
+            index_keywords: stats.index_stats.unique_keywords,
+            index_docs: stats.index_stats.documents,
+            bloom_items: stats.bloom_stats.items_added,
         }
     }
 
@@ -99,6 +176,7 @@ pub struct MemoryWatcher<'a> {
     brain: &'a Brain,
     config: WatchConfig,
     last_snapshot: Option<MemorySnapshot>,
+    last_activity: Option<ActivitySnapshot>,
     iteration: usize,
     start_time: Instant,
 }
@@ -109,6 +187,7 @@ impl<'a> MemoryWatcher<'a> {
             brain,
             config: WatchConfig::default(),
             last_snapshot: None,
+            last_activity: None,
             iteration: 0,
             start_time: Instant::now(),
         }
@@ -119,6 +198,7 @@ impl<'a> MemoryWatcher<'a> {
             brain,
             config,
             last_snapshot: None,
+            last_activity: None,
             iteration: 0,
             start_time: Instant::now(),
         }
@@ -207,6 +287,27 @@ impl<'a> MemoryWatcher<'a> {
             }
         }
 
+        // New memories and rising forgetting risk since the last refresh
+        let activity = ActivitySnapshot::from_brain(self.brain, self.config.alert_on_forgetting);
+        if let Some(ref last) = self.last_activity {
+            let diff = diff_activity(last, &activity);
+            if !diff.new_memories.is_empty() {
+                println!("╠══════════════════════════════════════════════════════════════╣");
+                println!("║  🆕 New Memories                                             ║");
+                for (id, content) in &diff.new_memories {
+                    println!("║  + {} {}  ║", &id.to_string()[..8], truncate(content, 47));
+                }
+            }
+            if !diff.escalated.is_empty() {
+                println!("╠══════════════════════════════════════════════════════════════╣");
+                println!("║  ⚠️  Forgetting Risk Rising                                   ║");
+                for (_, content, urgency) in &diff.escalated {
+                    println!("║  {} {}  ║", urgency, truncate(content, 55));
+                }
+            }
+        }
+        self.last_activity = Some(activity);
+
         if self.config.detailed {
             println!("╠══════════════════════════════════════════════════════════════╣");
             println!("║  📋 Recent Memories                                          ║");
@@ -264,5 +365,36 @@ mod tests {
         assert_eq!(config.interval_ms, 1000);
         assert!(!config.detailed);
         assert!(config.clear_screen);
+        assert!(!config.alert_on_forgetting);
+    }
+
+    #[test]
+    fn test_diff_activity_reports_new_and_escalated() {
+        let (kept, fading, calm, added) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        let mut previous = ActivitySnapshot::default();
+        previous.memories.insert(kept, "kept".to_string());
+        previous.memories.insert(fading, "fading".to_string());
+        previous.memories.insert(calm, "calm".to_string());
+        previous.urgency.insert(fading, AlertUrgency::Medium);
+        previous.urgency.insert(calm, AlertUrgency::High);
+
+        let mut current = previous.clone();
+        current.memories.insert(added, "added".to_string());
+        current.urgency.insert(fading, AlertUrgency::High);  // escalated
+        current.urgency.insert(calm, AlertUrgency::Medium);  // de-escalated
+        current.urgency.insert(kept, AlertUrgency::Low);     // newly alerting
+
+        let diff = diff_activity(&previous, &current);
+        assert_eq!(diff.new_memories, vec![(added, "added".to_string())]);
+        assert_eq!(
+            diff.escalated,
+            vec![
+                (fading, "fading".to_string(), AlertUrgency::High),
+                (kept, "kept".to_string(), AlertUrgency::Low),
+            ]
+        );
+
+        assert!(diff_activity(&current, &current).is_empty());
     }
 }
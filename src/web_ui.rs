@@ -3,7 +3,7 @@
 //! Beautiful dashboard with HTMX + Lucide icons
 
 use axum::{
-    extract::State,
+    extract::{Path, State},
     response::Html,
     routing::get,
     Router,
@@ -14,6 +14,9 @@ use serde::Deserialize;
 
 use crate::server::AppState;
 use crate::audit;
+use crate::visual::cosine_similarity;
+use crate::{MemoryEdit, MemoryItem};
+use std::collections::HashMap;
 
 // Load templates at compile time
 const BASE_TEMPLATE: &str = include_str!("../templates/base.html");
@@ -33,8 +36,7 @@ pub async fn dashboard_page(State(state): State<Arc<AppState>>) -> Html<String>
     let (stores, recalls, searches) = audit::get_daily_stats();
     let total = stores + recalls + searches;
     
-    // Get memory count
-    let memory_count = brain.semantic.search("", 10000).map(|v| v.len()).unwrap_or(0);
+    let memory_count = brain.stats().total;
     
     let store_pct = if total > 0 { stores * 100 / total } else { 0 };
     let recall_pct = if total > 0 { recalls * 100 / total } else { 0 };
@@ -127,53 +129,189 @@ pub async fn dashboard_page(State(state): State<Arc<AppState>>) -> Html<String>
     Html(render_page("Dashboard", &content))
 }
 
+/// Memories shown per page on `/memories`
+const MEMORIES_PAGE_SIZE: usize = 50;
+
+/// `/memories?page=N` (1-based)
+#[derive(Deserialize)]
+pub struct MemoriesQuery {
+    #[serde(default)]
+    page: Option<usize>,
+}
+
 /// Memories list page
-pub async fn memories_page(State(state): State<Arc<AppState>>) -> Html<String> {
+pub async fn memories_page(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(query): axum::extract::Query<MemoriesQuery>,
+) -> Html<String> {
     let brain = state.brain.read().await;
-    let memories = brain.semantic.search("", 50).unwrap_or_default();
+    let page = query.page.unwrap_or(1).max(1);
+    let memories = brain
+        .list_page(MEMORIES_PAGE_SIZE, (page - 1) * MEMORIES_PAGE_SIZE)
+        .unwrap_or_default();
+    let total = brain.long_term_count().unwrap_or(0);
+    let pages = total.div_ceil(MEMORIES_PAGE_SIZE).max(1);
     
-    let mut memory_cards = String::new();
-    for mem in memories {
-        let tags_html: String = mem.tags.iter()
-            .map(|t| format!(r#"<span class="badge bg-indigo-500/10 text-indigo-400">#{}</span>"#, t))
-            .collect::<Vec<_>>()
-            .join(" ");
-        
-        memory_cards.push_str(&format!(
-            r##"<div class="card p-5">
-                <p class="text-zinc-300 text-sm leading-relaxed mb-3">{}</p>
-                <div class="flex justify-between items-center">
-                    <div class="flex gap-1.5">{}</div>
-                    <span class="text-zinc-600 text-xs mono">{}</span>
-                </div>
-            </div>"##, 
-            html_escape(&mem.content),
-            tags_html,
-            &mem.id.to_string()[..8]
-        ));
-    }
+    let memory_cards: String = memories.iter().map(memory_card).collect();
     
     let content = format!(
         r##"<div class="flex flex-col sm:flex-row justify-between items-start sm:items-center gap-4 mb-6 sm:mb-8">
             <div>
                 <h1 class="text-2xl font-semibold tracking-tight">Memories</h1>
-                <p class="text-zinc-500 text-sm mt-1">All stored memories</p>
+                <p class="text-zinc-500 text-sm mt-1">All stored memories · {} total</p>
             </div>
             <a href="/store" class="flex items-center gap-2 bg-indigo-500/15 text-indigo-400 hover:bg-indigo-500/25 px-4 py-2.5 rounded-lg transition text-sm font-medium">
                 <i data-lucide="plus" class="w-3.5 h-3.5"></i> Store New
             </a>
         </div>
-        <div class="space-y-3">{}</div>"##,
+        <div class="space-y-3">{}</div>
+        {}"##,
+        total,
         if memory_cards.is_empty() { 
             r#"<div class="text-center text-zinc-600 py-16">No memories yet. <a href="/store" class="text-indigo-400 hover:text-indigo-300 transition">Store your first memory</a></div>"#.to_string()
         } else { 
             memory_cards 
-        }
+        },
+        pagination_nav(page, pages)
     );
     
     Html(render_page("Memories", &content))
 }
 
+/// Editable card on `/memories`; delete/edit swap the whole card via HTMX
+fn memory_card(mem: &MemoryItem) -> String {
+    let tags_html: String = mem.tags.iter()
+        .map(|t| format!(r#"<span class="badge bg-indigo-500/10 text-indigo-400">#{}</span>"#, html_escape(t)))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        r##"<div id="memory-{id}" class="card p-5">
+                <p class="text-zinc-300 text-sm leading-relaxed mb-3">{content}</p>
+                <div class="flex justify-between items-center">
+                    <div class="flex gap-1.5">{tags}</div>
+                    <div class="flex items-center gap-3">
+                        <span class="text-zinc-600 text-xs mono">{short}</span>
+                        <button hx-post="/memories/{id}/delete" hx-target="#memory-{id}" hx-swap="outerHTML" hx-confirm="Delete this memory?"
+                            class="text-zinc-500 hover:text-red-400 transition" title="Delete">
+                            <i data-lucide="trash-2" class="w-3.5 h-3.5"></i>
+                        </button>
+                    </div>
+                </div>
+                <details class="mt-3">
+                    <summary class="text-zinc-500 hover:text-zinc-300 text-xs cursor-pointer flex items-center gap-1">
+                        <i data-lucide="pencil" class="w-3 h-3"></i> Edit
+                    </summary>
+                    <form hx-post="/memories/{id}/edit" hx-target="#memory-{id}" hx-swap="outerHTML" class="mt-3 space-y-2">
+                        <textarea name="content" rows="3" class="w-full bg-zinc-900 border border-zinc-800 rounded-lg p-2 text-sm text-zinc-200">{content}</textarea>
+                        <input name="tags" value="{tag_list}" placeholder="tag1, tag2" class="w-full bg-zinc-900 border border-zinc-800 rounded-lg p-2 text-sm text-zinc-200" />
+                        <button type="submit" class="bg-indigo-500/15 text-indigo-400 hover:bg-indigo-500/25 px-3 py-1.5 rounded-lg transition text-xs font-medium">Save</button>
+                    </form>
+                </details>
+            </div>"##,
+        id = mem.id,
+        content = html_escape(&mem.content),
+        tags = tags_html,
+        short = &mem.id.to_string()[..8],
+        tag_list = html_escape(&mem.tags.join(", ")),
+    )
+}
+
+/// Inline error shown in place of a card
+fn card_error(message: &str) -> String {
+    format!(
+        r#"<div class="text-red-400 p-4 rounded-lg bg-red-500/10 border border-red-500/20 text-sm"><i data-lucide="x-circle" class="w-4 h-4 inline-block align-middle"></i> {}</div>"#,
+        html_escape(message)
+    )
+}
+
+/// Delete a memory (HTMX partial: empty div replaces the card)
+pub async fn memory_delete(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Html<String> {
+    let mut brain = state.brain.write().await;
+    match brain.delete(&id) {
+        Ok(item) => {
+            state.hnsw.remove(&item.id);
+            Html(format!(r#"<div id="memory-{}"></div>"#, item.id))
+        }
+        Err(e) => Html(card_error(&e.to_string())),
+    }
+}
+
+/// Edit form on a memory card
+#[derive(Deserialize)]
+pub struct EditForm {
+    /// Empty: keep the current content
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    tags: String,
+}
+
+/// Edit a memory (HTMX partial: the updated card)
+pub async fn memory_edit(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Form(form): Form<EditForm>,
+) -> Html<String> {
+    let mut brain = state.brain.write().await;
+    let before = match brain.get_by_id_prefix(&id) {
+        Ok(Some(item)) => item,
+        Ok(None) => return Html(card_error(&format!("Memory not found: {}", id))),
+        Err(e) => return Html(card_error(&e.to_string())),
+    };
+
+    let content = form.content.trim();
+    let changes = MemoryEdit {
+        content: (!content.is_empty()).then(|| content.to_string()),
+        tags: Some(
+            form.tags
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+        ),
+        strength: None,
+        memory_type: None,
+    };
+    match brain.edit(&before.id.to_string(), &changes) {
+        Ok(updated) => {
+            // Keep the server's HNSW index in step with re-embedded content
+            if updated.content != before.content {
+                state.hnsw.remove(&updated.id);
+                if let Some(ref emb) = updated.embedding {
+                    let _ = state.hnsw.add(updated.id, emb.clone());
+                }
+            }
+            Html(memory_card(&updated))
+        }
+        Err(e) => Html(card_error(&e.to_string())),
+    }
+}
+
+/// Previous/next links for `/memories` (empty when everything fits on one page)
+fn pagination_nav(page: usize, pages: usize) -> String {
+    if pages <= 1 {
+        return String::new();
+    }
+    let link = |target: usize, label: &str, enabled: bool| {
+        if enabled {
+            format!(r#"<a href="/memories?page={}" class="text-indigo-400 hover:text-indigo-300 transition">{}</a>"#, target, label)
+        } else {
+            format!(r#"<span class="text-zinc-700">{}</span>"#, label)
+        }
+    };
+    format!(
+        r#"<div class="flex justify-between items-center mt-6 text-sm">{}<span class="text-zinc-500">Page {} of {}</span>{}</div>"#,
+        link(page.saturating_sub(1), "← Previous", page > 1),
+        page,
+        pages,
+        link(page + 1, "Next →", page < pages)
+    )
+}
+
 /// Search page
 pub async fn search_page() -> Html<String> {
     Html(render_page("Search", SEARCH_TEMPLATE))
@@ -203,11 +341,11 @@ pub async fn search_results(
     }
     
     let limit = form.limit.unwrap_or(20);
-    let mut brain = state.brain.write().await;
+    let brain = state.brain.read().await;
     
     // 쿼리가 있으면 recall, 없으면 전체에서 필터
     let memories = if !form.query.is_empty() {
-        brain.recall(&form.query, limit)
+        brain.recall_with_access(&form.query, limit, false)  // browsing shouldn't strengthen memories
     } else {
         brain.semantic.search("", limit).unwrap_or_default()
     };
@@ -286,6 +424,9 @@ pub async fn store_page() -> Html<String> {
 pub struct StoreForm {
     content: String,
     tags: String,
+    /// Empty: let the consolidator classify
+    #[serde(default)]
+    memory_type: String,
 }
 
 pub async fn store_submit(
@@ -303,15 +444,19 @@ pub async fn store_submit(
         .filter(|s| !s.is_empty())
         .collect();
     
-    let embedding = state.embedder.embed(&form.content);
+    let memory_type = crate::server::parse_memory_type(&form.memory_type);
+    
+    let embedding = state.embedder.try_embed_document(&form.content).ok();
     
     let mut item = crate::MemoryItem::new(&form.content, None);
     item.tags = tags;
-    item.embedding = Some(embedding.clone());
+    item.embedding = embedding.clone();
     
-    match brain.semantic.store(item.clone()) {
-        Ok(_) => {
-            let _ = state.hnsw.add(item.id, embedding);
+    match brain.store_typed(item, memory_type) {
+        Ok(item) => {
+            if let Some(embedding) = embedding {
+                let _ = state.hnsw.add(item.id, embedding);
+            }
             crate::audit::log_store(&form.content, &item.tags);
             Html(format!(
                 r##"<div class="text-emerald-400 p-4 rounded-lg bg-emerald-500/10 border border-emerald-500/20 text-sm">
@@ -348,7 +493,7 @@ pub async fn visual_page() -> Html<String> {
     };
     
     // Load photos index to get count and recent photos
-    let index_path = std::path::Path::new("visual_index/photos_index.json");
+    let index_path = std::path::Path::new(PHOTOS_INDEX_PATH);
     let (total_images, gallery_html) = if index_path.exists() {
         let content = std::fs::read_to_string(index_path).unwrap_or_default();
         let photos: Vec<serde_json::Value> = serde_json::from_str(&content).unwrap_or_default();
@@ -403,77 +548,130 @@ pub struct VisualSearchForm {
     query: String,
 }
 
-pub async fn visual_search(Form(form): Form<VisualSearchForm>) -> Html<String> {
+/// Photos indexed by the external `photos_sync.py` script
+const PHOTOS_INDEX_PATH: &str = "visual_index/photos_index.json";
+
+/// Visual search results shown at once
+const VISUAL_RESULTS: usize = 12;
+
+/// One visual search hit, from CoreDB or the photos index
+struct VisualHit {
+    filename: String,
+    description: String,
+    place: String,
+    persons: Vec<String>,
+    similarity: f32,
+}
+
+/// CLIP text embedding from `clip_server.py` (no visual storage configured)
+fn clip_server_text_embedding(query: &str) -> Option<Vec<f32>> {
+    let body = serde_json::json!({ "text": query });
+    let result: serde_json::Value = ureq::post("http://localhost:5050/embed/text")
+        .send_json(body)
+        .ok()?
+        .into_json()
+        .ok()?;
+    let embedding: Vec<f32> = result
+        .get("embedding")?
+        .as_array()?
+        .iter()
+        .filter_map(|v| v.as_f64().map(|x| x as f32))
+        .collect();
+    (!embedding.is_empty()).then_some(embedding)
+}
+
+pub async fn visual_search(
+    State(state): State<Arc<AppState>>,
+    Form(form): Form<VisualSearchForm>,
+) -> Html<String> {
     if form.query.is_empty() {
         return Html(r#"<div class="text-gray-500 text-center py-8">Enter a text query to search images</div>"#.to_string());
     }
     
     // Load photos index
-    let index_path = std::path::Path::new("visual_index/photos_index.json");
-    let photos: Vec<serde_json::Value> = if index_path.exists() {
-        let content = std::fs::read_to_string(index_path).unwrap_or_default();
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
+    let photos: Vec<serde_json::Value> = std::fs::read_to_string(PHOTOS_INDEX_PATH)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    if photos.is_empty() && state.visual.is_none() {
         return Html(r#"<div class="text-amber-400/80 text-center py-10 text-sm">No photos indexed yet. Run: <code class="bg-gray-700 px-2 py-1 rounded">python photos_sync.py sync --limit 100</code></div>"#.to_string());
-    };
+    }
     
-    // Get query embedding from CLIP server
-    let body = serde_json::json!({ "text": form.query });
+    // Query embedding from the storage's (cached) CLIP provider, else the CLIP server
+    let query_embedding = match state.visual {
+        Some(ref visual) => visual.embed_text_cached(&form.query).ok(),
+        None => clip_server_text_embedding(&form.query),
+    };
+    let Some(query_embedding) = query_embedding else {
+        return Html(
+            r##"<div class="bg-amber-500/10 border border-amber-500/20 rounded-lg p-4">
+                <div class="text-amber-400 text-sm"><i data-lucide="alert-triangle" class="w-4 h-4 inline-block align-middle"></i> CLIP server not available</div>
+                <div class="text-zinc-500 text-xs mt-2">Start it with: <code class="bg-gray-700 px-2 py-1 rounded">python clip_server.py 5050</code></div>
+            </div>"##.to_string()
+        );
+    };
     
-    let query_embedding: Vec<f64> = match ureq::post("http://localhost:5050/embed/text").send_json(body) {
-        Ok(resp) => {
-            let result: serde_json::Value = resp.into_json().unwrap_or_default();
-            result.get("embedding")
-                .and_then(|e| e.as_array())
-                .map(|arr| arr.iter().filter_map(|v| v.as_f64()).collect())
-                .unwrap_or_default()
+    // Stored visual memories first, then the photos index; one hit per path
+    let mut hits: HashMap<String, VisualHit> = HashMap::new();
+    if let Some(ref visual) = state.visual {
+        for (memory, similarity) in visual.search_by_embedding(&query_embedding, VISUAL_RESULTS).await.unwrap_or_default() {
+            let path = memory.image_path.to_string_lossy().to_string();
+            let filename = memory.image_path.file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone());
+            hits.insert(path, VisualHit {
+                filename,
+                description: memory.description,
+                place: memory.context.location.unwrap_or_default(),
+                persons: memory.context.people,
+                similarity,
+            });
         }
-        Err(_e) => {
-            return Html(format!(
-                r##"<div class="bg-amber-500/10 border border-amber-500/20 rounded-lg p-4">
-                    <div class="text-amber-400 text-sm"><i data-lucide="alert-triangle" class="w-4 h-4 inline-block align-middle"></i> CLIP server not available</div>
-                    <div class="text-zinc-500 text-xs mt-2">Start it with: <code class="bg-gray-700 px-2 py-1 rounded">python clip_server.py 5050</code></div>
-                </div>"##
-            ));
+    }
+    for photo in &photos {
+        let Some(embedding) = photo.get("embedding").and_then(|e| e.as_array()) else {
+            continue;
+        };
+        let embedding: Vec<f32> = embedding.iter().filter_map(|v| v.as_f64().map(|x| x as f32)).collect();
+        let similarity = cosine_similarity(&query_embedding, &embedding);
+        let text = |key: &str| photo.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+        let path = text("path");
+        let place = text("place");
+        let persons: Vec<String> = photo.get("persons")
+            .and_then(|p| p.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+
+        match hits.get_mut(&path) {
+            Some(hit) => {
+                hit.similarity = hit.similarity.max(similarity);
+                if hit.place.is_empty() {
+                    hit.place = place;
+                }
+                if hit.persons.is_empty() {
+                    hit.persons = persons;
+                }
+            }
+            None => {
+                let filename = photo.get("filename").and_then(|f| f.as_str()).unwrap_or("?").to_string();
+                hits.insert(path, VisualHit { filename, description: String::new(), place, persons, similarity });
+            }
         }
-    };
-    
-    if query_embedding.is_empty() {
-        return Html(r#"<div class="text-red-400">Error generating embedding</div>"#.to_string());
     }
-    
-    // Calculate similarities
-    let mut scored: Vec<(f64, &serde_json::Value)> = photos.iter()
-        .filter_map(|photo| {
-            let emb: Vec<f64> = photo.get("embedding")
-                .and_then(|e| e.as_array())
-                .map(|arr| arr.iter().filter_map(|v| v.as_f64()).collect())?;
-            
-            let sim = cosine_similarity(&query_embedding, &emb);
-            Some((sim, photo))
-        })
-        .collect();
-    
-    // Sort by similarity (descending)
-    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut scored: Vec<(String, VisualHit)> = hits.into_iter().collect();
+    scored.sort_by(|a, b| b.1.similarity.partial_cmp(&a.1.similarity).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(VISUAL_RESULTS);
     
     // Build results HTML
     let mut html = format!(
         r#"<div class="text-gray-400 text-sm mb-4"><i data-lucide="search" class="w-4 h-4 inline-block align-middle"></i> Found {} photos for "{}"</div>
         <div class="grid grid-cols-2 md:grid-cols-4 gap-4">"#,
-        scored.len().min(12),
+        scored.len(),
         html_escape(&form.query)
     );
     
-    for (sim, photo) in scored.iter().take(12) {
-        let filename = photo.get("filename").and_then(|f| f.as_str()).unwrap_or("?");
-        let place = photo.get("place").and_then(|p| p.as_str()).unwrap_or("");
-        let persons: Vec<&str> = photo.get("persons")
-            .and_then(|p| p.as_array())
-            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
-            .unwrap_or_default();
-        let path = photo.get("path").and_then(|p| p.as_str()).unwrap_or("");
-        
+    for (path, hit) in &scored {
         html.push_str(&format!(
             r##"<div class="card p-3">
                 <div class="aspect-square bg-gray-700 rounded-lg mb-2 flex items-center justify-center overflow-hidden">
@@ -483,13 +681,15 @@ pub async fn visual_search(Form(form): Form<VisualSearchForm>) -> Html<String> {
                 <div class="text-xs text-indigo-400 mono">{:.1}%</div>
                 {}
                 {}
+                {}
             </div>"##,
             urlencoding::encode(path),
-            html_escape(filename),
-            html_escape(filename),
-            sim * 100.0,
-            if !place.is_empty() { format!(r#"<div class="text-xs text-zinc-600 truncate flex items-center gap-1"><i data-lucide="map-pin" class="w-3 h-3"></i>{}</div>"#, html_escape(place)) } else { String::new() },
-            if !persons.is_empty() { format!(r#"<div class="text-xs text-zinc-600 truncate flex items-center gap-1"><i data-lucide="user" class="w-3 h-3"></i>{}</div>"#, persons.join(", ")) } else { String::new() }
+            html_escape(&hit.filename),
+            html_escape(&hit.filename),
+            hit.similarity * 100.0,
+            if !hit.description.is_empty() { format!(r#"<div class="text-xs text-zinc-500 truncate">{}</div>"#, html_escape(&hit.description)) } else { String::new() },
+            if !hit.place.is_empty() { format!(r#"<div class="text-xs text-zinc-600 truncate flex items-center gap-1"><i data-lucide="map-pin" class="w-3 h-3"></i>{}</div>"#, html_escape(&hit.place)) } else { String::new() },
+            if !hit.persons.is_empty() { format!(r#"<div class="text-xs text-zinc-600 truncate flex items-center gap-1"><i data-lucide="user" class="w-3 h-3"></i>{}</div>"#, html_escape(&hit.persons.join(", "))) } else { String::new() }
         ));
     }
     
@@ -497,17 +697,6 @@ pub async fn visual_search(Form(form): Form<VisualSearchForm>) -> Html<String> {
     Html(html)
 }
 
-/// Cosine similarity between two vectors
-fn cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
-    if a.len() != b.len() || a.is_empty() {
-        return 0.0;
-    }
-    let dot: f64 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let norm_a: f64 = a.iter().map(|x| x * x).sum::<f64>().sqrt();
-    let norm_b: f64 = b.iter().map(|x| x * x).sum::<f64>().sqrt();
-    if norm_a == 0.0 || norm_b == 0.0 { 0.0 } else { dot / (norm_a * norm_b) }
-}
-
 /// Visual store API (HTMX)
 #[derive(Deserialize)]
 pub struct VisualStoreForm {
@@ -574,12 +763,34 @@ pub struct ThumbQuery {
     path: String,
 }
 
+/// `path` belongs to a stored visual memory or the photos index
+async fn is_known_image(state: &AppState, path: &str) -> bool {
+    if let Some(ref visual) = state.visual {
+        if visual.has_image(std::path::Path::new(path)).await {
+            return true;
+        }
+    }
+    std::fs::read_to_string(PHOTOS_INDEX_PATH)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Vec<serde_json::Value>>(&content).ok())
+        .is_some_and(|photos| photos.iter().any(|photo| photo.get("path").and_then(|p| p.as_str()) == Some(path)))
+}
+
+/// Serve a known image; any other path is a 403
 pub async fn visual_thumb(
+    State(state): State<Arc<AppState>>,
     axum::extract::Query(query): axum::extract::Query<ThumbQuery>
 ) -> axum::response::Response<axum::body::Body> {
     use axum::http::{header, Response, StatusCode};
     use axum::body::Body;
     
+    if !is_known_image(&state, &query.path).await {
+        return Response::builder()
+            .status(StatusCode::FORBIDDEN)
+            .body(Body::empty())
+            .unwrap();
+    }
+    
     let path = std::path::Path::new(&query.path);
     
     if !path.exists() {
@@ -620,7 +831,7 @@ pub async fn visual_thumb(
 /// Mind Map page - interactive D3.js force graph
 pub async fn mindmap_page(State(state): State<Arc<AppState>>) -> Html<String> {
     let brain = state.brain.read().await;
-    let memory_count = brain.semantic.search("", 10000).map(|v| v.len()).unwrap_or(0);
+    let memory_count = brain.semantic.count().unwrap_or(0);
     
     let content = format!(r##"
 <div class="mb-6"><h1 class="text-2xl font-semibold tracking-tight">Mind Map</h1>
@@ -645,6 +856,13 @@ pub async fn mindmap_page(State(state): State<Arc<AppState>>) -> Html<String> {
             <option value="500">500</option>
         </select>
     </div>
+    <div class="card px-3 py-2">
+        <span class="text-zinc-500 text-xs font-medium uppercase tracking-wider">Color</span>
+        <select id="cluster" onchange="loadMindMap()" class="bg-[#19191c] text-zinc-300 rounded-lg px-2 py-1 ml-2 text-sm border border-[#2a2a2e]">
+            <option value="tag" selected>태그</option>
+            <option value="embedding">의미 클러스터</option>
+        </select>
+    </div>
     <div class="card px-3 py-2 text-indigo-400 text-sm">
         <i data-lucide="brain" class="w-4 h-4 inline-block align-middle"></i> {memory_count} memories
     </div>
@@ -666,8 +884,10 @@ const colors = d3.schemeCategory10;
 function loadMindMap() {{
     const threshold = document.getElementById('threshold').value;
     const limit = document.getElementById('limit').value;
+    const cluster = document.getElementById('cluster').value;
     
-    fetch(`/mindmap/data?threshold=${{threshold}}&limit=${{limit}}`)
+    // Filters in the page URL (?tag=work&since=30d&type=episodic) are passed through
+    fetch(`/mindmap/data?threshold=${{threshold}}&limit=${{limit}}&cluster=${{cluster}}&${{location.search.slice(1)}}`)
         .then(r => r.json())
         .then(data => renderMindMap(data))
         .catch(e => {{
@@ -774,39 +994,47 @@ loadMindMap();
 }
 
 /// Mind Map JSON data endpoint
+///
+/// Unparseable `since`, `until` or `type` values are rejected with 400.
 pub async fn mindmap_data(
     State(state): State<Arc<AppState>>,
     axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
-) -> axum::Json<serde_json::Value> {
+) -> Result<axum::Json<serde_json::Value>, axum::http::StatusCode> {
+    use axum::http::StatusCode;
+
     let brain = state.brain.read().await;
     let threshold: f32 = params.get("threshold").and_then(|s| s.parse().ok()).unwrap_or(0.3);
     let limit: usize = params.get("limit").and_then(|s| s.parse().ok()).unwrap_or(100);
+    let k: usize = params.get("k").and_then(|s| s.parse().ok()).unwrap_or(crate::mindmap::DEFAULT_CLUSTERS);
+    let cluster = params
+        .get("cluster")
+        .and_then(|mode| crate::mindmap::ClusterMode::parse(mode, k))
+        .unwrap_or_default();
+    // Comma-separated `tag` and `type`; `since`/`until` as in `parse_time_spec`
+    let list = |key: &str| -> Vec<String> {
+        params
+            .get(key)
+            .map(|s| s.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect())
+            .unwrap_or_default()
+    };
+    let time = |key: &str| {
+        params
+            .get(key)
+            .map(|s| crate::parse_time_spec(s).map_err(|_| StatusCode::BAD_REQUEST))
+            .transpose()
+    };
+    let filter = crate::mindmap::MapFilter {
+        tags: list("tag"),
+        since: time("since")?,
+        until: time("until")?,
+        types: list("type")
+            .iter()
+            .map(|t| crate::server::parse_memory_type(t).ok_or(StatusCode::BAD_REQUEST))
+            .collect::<Result<_, _>>()?,
+    };
     
-    let map = crate::mindmap::MindMap::from_brain(&brain, limit, threshold);
-    
-    let nodes: Vec<serde_json::Value> = map.nodes.iter().map(|n| {
-        serde_json::json!({
-            "id": n.id,
-            "label": n.label,
-            "content": n.content,
-            "group": n.group,
-            "size": n.size,
-            "tags": n.tags,
-        })
-    }).collect();
-    
-    let links: Vec<serde_json::Value> = map.edges.iter().map(|e| {
-        serde_json::json!({
-            "source": e.source,
-            "target": e.target,
-            "weight": e.weight,
-        })
-    }).collect();
-    
-    axum::Json(serde_json::json!({
-        "nodes": nodes,
-        "links": links,
-    }))
+    let map = crate::mindmap::MindMap::from_brain_clustered(&brain, None, &filter, limit, threshold, cluster);
+    Ok(axum::Json(map.to_json()))
 }
 
 /// Timeline page - memories over time
@@ -826,19 +1054,15 @@ pub async fn timeline_page(State(_state): State<Arc<AppState>>) -> Html<String>
 /// Timeline data (HTMX partial)
 pub async fn timeline_data(State(state): State<Arc<AppState>>) -> Html<String> {
     let brain = state.brain.read().await;
-    let memories = brain.semantic.search("", 10000).unwrap_or_default();
+    let by_date = brain
+        .episodic
+        .timeline(chrono::DateTime::<chrono::Utc>::MIN_UTC, chrono::Utc::now())
+        .unwrap_or_default();
     
-    if memories.is_empty() {
+    if by_date.is_empty() {
         return Html(r#"<div class="text-zinc-600 text-center py-14 text-sm">기억이 없습니다.</div>"#.to_string());
     }
     
-    // Group by date
-    let mut by_date: std::collections::BTreeMap<String, Vec<&crate::MemoryItem>> = std::collections::BTreeMap::new();
-    for mem in &memories {
-        let date = mem.created_at.format("%Y-%m-%d").to_string();
-        by_date.entry(date).or_default().push(mem);
-    }
-    
     let type_colors = |t: &crate::MemoryType| match t {
         crate::MemoryType::Episodic => ("blue", "Episodic"),
         crate::MemoryType::Semantic => ("cyan", "Semantic"),
@@ -850,11 +1074,7 @@ pub async fn timeline_data(State(state): State<Arc<AppState>>) -> Html<String> {
     
     // Reverse chronological
     for (date, mems) in by_date.iter().rev() {
-        let weekday = if let Ok(d) = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d") {
-            d.format("%A").to_string()
-        } else {
-            String::new()
-        };
+        let weekday = date.format("%A").to_string();
         
         html.push_str(&format!(
             r##"<div class="mb-8">
@@ -865,7 +1085,7 @@ pub async fn timeline_data(State(state): State<Arc<AppState>>) -> Html<String> {
                     <span class="text-zinc-600 text-xs">({} memories)</span>
                 </div>
                 <div class="ml-6 border-l border-[#2a2a2e] pl-6 space-y-3">"##,
-            date, weekday, mems.len()
+            date.format("%Y-%m-%d"), weekday, mems.len()
         ));
         
         for mem in mems {
@@ -894,7 +1114,7 @@ pub async fn timeline_data(State(state): State<Arc<AppState>>) -> Html<String> {
                 color = color,
                 content = html_escape(&content_preview),
                 type_name = type_name,
-                time = mem.created_at.format("%H:%M"),
+                time = mem.created_at.with_timezone(&chrono::Local).format("%H:%M"),
                 strength = strength_pct,
                 tags = if mem.tags.is_empty() { String::new() } else {
                     format!("<span class=\"text-gray-600\">{}</span>", 
@@ -1045,27 +1265,93 @@ pub async fn coredb_query(
     }
 }
 
-/// Create web UI router
+/// Login form (`error` shown above it)
+fn login_form(error: Option<&str>) -> String {
+    format!(
+        r##"<div class="max-w-sm mx-auto card p-6">
+    <h1 class="text-xl font-semibold tracking-tight mb-4 flex items-center gap-2"><i data-lucide="key-round" class="w-5 h-5"></i> API Key</h1>
+    {}
+    <form method="post" action="/login" class="flex flex-col gap-3">
+        <input type="password" name="key" placeholder="MEMORY_BRAIN_API_KEYS 중 하나" class="bg-[#19191c] text-zinc-300 rounded-lg px-3 py-2 text-sm border border-[#2a2a2e]" autofocus />
+        <button type="submit" class="bg-indigo-500/15 text-indigo-400 hover:bg-indigo-500/25 py-2 rounded-lg transition text-sm font-medium">Login</button>
+    </form>
+</div>"##,
+        error.map(|e| format!(r#"<div class="text-red-400 text-sm mb-3">{}</div>"#, html_escape(e))).unwrap_or_default()
+    )
+}
+
+/// Login page for servers with API keys
+pub async fn login_page() -> Html<String> {
+    Html(render_page("Login", &login_form(None)))
+}
+
+#[derive(Deserialize)]
+pub struct LoginForm {
+    key: String,
+}
+
+/// Check the key and keep it in the [`crate::server::API_KEY_COOKIE`] cookie
+///
+/// The cookie is HttpOnly and SameSite=Strict, so the UI's HTMX requests
+/// carry it and other sites' forms don't.
+pub async fn login_submit(
+    State(state): State<Arc<AppState>>,
+    Form(form): Form<LoginForm>,
+) -> axum::response::Response {
+    use axum::http::{header, StatusCode};
+    use axum::response::IntoResponse;
+
+    let key = form.key.trim();
+    if !state.api_keys.contains(key) {
+        let page = render_page("Login", &login_form(Some("Invalid API key")));
+        return (StatusCode::UNAUTHORIZED, Html(page)).into_response();
+    }
+    let cookie = format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Strict",
+        crate::server::API_KEY_COOKIE,
+        urlencoding::encode(key)
+    );
+    (StatusCode::SEE_OTHER, [(header::SET_COOKIE, cookie), (header::LOCATION, "/".to_string())]).into_response()
+}
+
+/// Create web UI router (page shells and login, open to everyone)
 pub fn create_web_router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(dashboard_page))
-        .route("/memories", get(memories_page))
+        .route("/login", get(login_page).post(login_submit))
         .route("/visual", get(visual_page))
         .route("/mindmap", get(mindmap_page))
-        .route("/mindmap/data", get(mindmap_data))
         .route("/timeline", get(timeline_page))
-        .route("/timeline/data", get(timeline_data))
         .route("/coredb", get(coredb_page))
-        .route("/coredb/query", axum::routing::post(coredb_query))
         .route("/search", get(search_page))
-        .route("/search/results", axum::routing::post(search_results))
         .route("/store", get(store_page))
-        .route("/store/submit", axum::routing::post(store_submit))
+}
+
+/// Web routes that return memory contents or image files
+///
+/// The server puts these behind the API key check.
+pub fn create_web_read_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/memories", get(memories_page))
+        .route("/mindmap/data", get(mindmap_data))
+        .route("/timeline/data", get(timeline_data))
+        .route("/search/results", axum::routing::post(search_results))
         .route("/api/visual/search", axum::routing::post(visual_search))
-        .route("/api/visual/store", axum::routing::post(visual_store))
         .route("/api/visual/thumb", get(visual_thumb))
 }
 
+/// Web routes that change data or run raw CQL
+///
+/// The server puts these behind the API key check and write rate limit.
+pub fn create_web_write_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/memories/:id/delete", axum::routing::post(memory_delete))
+        .route("/memories/:id/edit", axum::routing::post(memory_edit))
+        .route("/coredb/query", axum::routing::post(coredb_query))
+        .route("/store/submit", axum::routing::post(store_submit))
+        .route("/api/visual/store", axum::routing::post(visual_store))
+}
+
 /// Escape HTML characters
 fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
@@ -1074,3 +1360,188 @@ fn html_escape(s: &str) -> String {
         .replace('"', "&quot;")
         .replace('\'', "&#39;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Brain, Embedder, GloVeEmbedder, HnswIndex};
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn test_app_state() -> (AppState, tempfile::TempDir) {
+        let embedder: Arc<dyn Embedder> = Arc::new(GloVeEmbedder::test_embedder());
+        let dim = embedder.dimension();
+        let dir = tempfile::tempdir().unwrap();
+        let brain = Brain::with_embedder(dir.path().join("test.db").to_str().unwrap(), embedder.clone()).unwrap();
+        (AppState::new(brain, HnswIndex::new(dim), embedder), dir)
+    }
+
+    fn test_state() -> (Arc<AppState>, tempfile::TempDir) {
+        let (state, dir) = test_app_state();
+        (Arc::new(state), dir)
+    }
+
+    async fn post_form(state: &Arc<AppState>, uri: &str, body: &str) -> String {
+        let request = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = crate::server::create_router(state.clone()).oneshot(request).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    async fn store(state: &Arc<AppState>, content: &str) -> uuid::Uuid {
+        let mut brain = state.brain.write().await;
+        let item = MemoryItem::new(content, None).with_tags(vec!["lang".to_string()]);
+        brain.store_typed(item, None).unwrap().id
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_visual_search_includes_coredb_memories() {
+        use crate::clip_onnx::MockClipProvider;
+        use crate::visual_storage::VisualStorage;
+
+        let (state, dir) = test_app_state();
+        let db = coredb::CoreDB::new(crate::default_coredb_config(dir.path().join("visual").to_str().unwrap()))
+            .await
+            .unwrap();
+        let image = dir.path().join("harbour.png");
+        std::fs::write(&image, b"pixels").unwrap();
+        let visual = VisualStorage::new(
+            Arc::new(tokio::sync::RwLock::new(db)),
+            Arc::new(MockClipProvider::new(16)),
+            "visual_web_test",
+        )
+        .await
+        .unwrap();
+        visual.store_image(&image, "Boats in the harbour", None, vec![], 0.0).await.unwrap();
+
+        let state = Arc::new(state.with_visual(Arc::new(visual)));
+        // The mock embeds a text exactly like an image path with the same bytes
+        let query = image.to_string_lossy().to_string();
+        let body = format!("query={}", urlencoding::encode(&query));
+        let html = post_form(&state, "/api/visual/search", &body).await;
+        assert!(html.contains("Found 1 photos"), "{}", html);
+        assert!(html.contains("harbour.png"));
+        assert!(html.contains("Boats in the harbour"));
+        assert!(html.contains("100.0%"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_memory_card_edit_returns_updated_fragment() {
+        let (state, _dir) = test_state();
+        let id = store(&state, "Python is a language").await;
+
+        let html = post_form(&state, &format!("/memories/{}/edit", id), "content=Rust+is+about+ownership&tags=rust%2C+memory").await;
+        assert!(html.contains(&format!(r#"id="memory-{}""#, id)));
+        assert!(html.contains("Rust is about ownership"));
+        assert!(html.contains("#memory"));
+        assert!(!html.contains("Python"));
+
+        let brain = state.brain.read().await;
+        assert!(brain.keyword_index.search_or("ownership").contains(&id));
+        assert!(!brain.keyword_index.search_or("python").contains(&id));
+        assert_eq!(brain.get_by_id(&id).unwrap().unwrap().tags, vec!["rust", "memory"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_memory_card_delete_returns_empty_div() {
+        let (state, _dir) = test_state();
+        let id = store(&state, "Rust is about ownership").await;
+
+        let html = post_form(&state, &format!("/memories/{}/delete", id), "").await;
+        assert_eq!(html, format!(r#"<div id="memory-{}"></div>"#, id));
+        {
+            let brain = state.brain.read().await;
+            assert!(brain.get_by_id(&id).unwrap().is_none());
+            assert!(!brain.keyword_index.search_or("ownership").contains(&id));
+        }
+
+        // Deleting again reports the missing memory instead of a card
+        let html = post_form(&state, &format!("/memories/{}/delete", id), "").await;
+        assert!(html.contains("Memory not found"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_keyed_server_rejects_unauthenticated_web_delete() {
+        let (state, _dir) = test_app_state();
+        let state = Arc::new(state.with_api_keys(std::collections::HashSet::from(["secret".to_string()])));
+        let id = store(&state, "Rust is about ownership").await;
+
+        let delete = |key: Option<&str>| {
+            let mut request = Request::builder().method("POST").uri(format!("/memories/{}/delete", id));
+            if let Some(key) = key {
+                request = request.header("authorization", format!("Bearer {}", key));
+            }
+            request.body(Body::empty()).unwrap()
+        };
+
+        let response = crate::server::create_router(state.clone()).oneshot(delete(None)).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+        assert!(state.brain.read().await.get_by_id(&id).unwrap().is_some());
+
+        // Memory data is keyed too; the page shells aren't
+        let get = |uri: &str, cookie: Option<&str>| {
+            let mut request = Request::builder().uri(uri);
+            if let Some(cookie) = cookie {
+                request = request.header("cookie", cookie);
+            }
+            request.body(Body::empty()).unwrap()
+        };
+        for uri in ["/memories", "/timeline/data", "/mindmap/data", "/api/visual/thumb?path=%2Fetc%2Fpasswd"] {
+            let response = crate::server::create_router(state.clone()).oneshot(get(uri, None)).await.unwrap();
+            assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED, "{}", uri);
+        }
+        let response = crate::server::create_router(state.clone()).oneshot(get("/search", None)).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+        // Logging in sets the cookie the UI's requests carry
+        let login = Request::builder()
+            .method("POST")
+            .uri("/login")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(Body::from("key=secret"))
+            .unwrap();
+        let response = crate::server::create_router(state.clone()).oneshot(login).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::SEE_OTHER);
+        let cookie = response.headers()["set-cookie"].to_str().unwrap().split(';').next().unwrap().to_string();
+        let response = crate::server::create_router(state.clone()).oneshot(get("/memories", Some(&cookie))).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+        let response = crate::server::create_router(state.clone()).oneshot(delete(Some("secret"))).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        assert!(state.brain.read().await.get_by_id(&id).unwrap().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_visual_thumb_serves_only_known_images() {
+        let (state, dir) = test_state();
+        let secret = dir.path().join("secret.png");
+        std::fs::write(&secret, b"not an image").unwrap();
+
+        let uri = format!("/api/visual/thumb?path={}", urlencoding::encode(&secret.to_string_lossy()));
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = crate::server::create_router(state).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mindmap_data_rejects_invalid_filters() {
+        let (state, _dir) = test_state();
+        store(&state, "Rust is about ownership").await;
+
+        let status = |uri: &str| {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            let router = crate::server::create_router(state.clone());
+            async move { router.oneshot(request).await.unwrap().status() }
+        };
+        assert_eq!(status("/mindmap/data?since=7d&type=semantic").await, axum::http::StatusCode::OK);
+        for uri in ["/mindmap/data?since=someday", "/mindmap/data?until=later", "/mindmap/data?type=semantic,dreams"] {
+            assert_eq!(status(uri).await, axum::http::StatusCode::BAD_REQUEST, "{}", uri);
+        }
+    }
+}
@@ -9,29 +9,55 @@
 use crate::types::{MemoryItem, MemoryType};
 use std::collections::VecDeque;
 
+/// Default capacity (Miller's magic number)
+pub const DEFAULT_WORKING_CAPACITY: usize = 7;
+
+/// Which item leaves working memory when it is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// Least recently accessed
+    #[default]
+    Lru,
+    /// Fewest accesses (least recently accessed on ties)
+    Lfu,
+    /// Weakest memory (oldest on ties)
+    LowestStrength,
+}
+
 /// Working memory with limited capacity
 pub struct WorkingMemory {
     items: VecDeque<MemoryItem>,
     capacity: usize,
+    policy: EvictionPolicy,
     importance_threshold: f32,
 }
 
 impl WorkingMemory {
     pub fn new(capacity: usize) -> Self {
+        Self::with_policy(capacity, EvictionPolicy::default())
+    }
+
+    pub fn with_policy(capacity: usize, policy: EvictionPolicy) -> Self {
         Self {
             items: VecDeque::with_capacity(capacity),
             capacity,
+            policy,
             importance_threshold: 0.7,
         }
     }
 
+    /// Eviction policy in use
+    pub fn policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
     /// Push a new item to working memory
-    /// Returns evicted item if capacity exceeded
+    /// Returns the item evicted by the policy if capacity was exceeded
     pub fn push(&mut self, mut item: MemoryItem) -> Option<MemoryItem> {
         item.memory_type = MemoryType::Working;
         
         let evicted = if self.items.len() >= self.capacity {
-            self.items.pop_front()
+            self.pop_victim()
         } else {
             None
         };
@@ -40,6 +66,40 @@ impl WorkingMemory {
         evicted
     }
 
+    /// Remove the item the eviction policy picks next
+    pub fn pop_victim(&mut self) -> Option<MemoryItem> {
+        let candidates = self.items.iter().enumerate();
+        let pos = match self.policy {
+            EvictionPolicy::Lru => candidates.min_by_key(|(_, item)| item.last_accessed),
+            EvictionPolicy::Lfu => candidates.min_by_key(|(_, item)| (item.access_count, item.last_accessed)),
+            EvictionPolicy::LowestStrength => candidates.min_by(|(_, a), (_, b)| {
+                a.strength.partial_cmp(&b.strength).unwrap_or(std::cmp::Ordering::Equal)
+            }),
+        }
+        .map(|(pos, _)| pos)?;
+        self.items.remove(pos)
+    }
+
+    /// Add an item without evicting
+    ///
+    /// The caller flushes any overflow to long-term memory
+    /// (see `Brain::maybe_consolidate`).
+    pub fn admit(&mut self, mut item: MemoryItem) {
+        item.memory_type = MemoryType::Working;
+        self.items.push_back(item);
+    }
+
+    /// Remove the least recently accessed item (oldest first on ties)
+    pub fn pop_least_recent(&mut self) -> Option<MemoryItem> {
+        let pos = self
+            .items
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, item)| item.last_accessed)
+            .map(|(pos, _)| pos)?;
+        self.items.remove(pos)
+    }
+
     /// Get all items in working memory
     pub fn get_all(&self) -> Vec<MemoryItem> {
         self.items.iter().cloned().collect()
@@ -67,6 +127,12 @@ impl WorkingMemory {
             .collect()
     }
 
+    /// Remove an item by id
+    pub fn remove(&mut self, id: &uuid::Uuid) -> Option<MemoryItem> {
+        let pos = self.items.iter().position(|item| item.id == *id)?;
+        self.items.remove(pos)
+    }
+
     /// Clear working memory (like after sleep)
     pub fn clear(&mut self) {
         self.items.clear();
@@ -77,6 +143,11 @@ impl WorkingMemory {
         self.items.len()
     }
 
+    /// Maximum number of items before eviction
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     /// Check if empty
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
@@ -117,6 +188,50 @@ mod tests {
         assert_eq!(wm.len(), 3);
     }
 
+    /// Fill a 3-slot memory, run `prepare`, push a fourth item; returns what was evicted
+    fn evict_with(policy: EvictionPolicy, prepare: impl Fn(&mut WorkingMemory)) -> String {
+        let mut wm = WorkingMemory::with_policy(3, policy);
+        for name in ["first", "second", "third"] {
+            wm.push(MemoryItem::new(name, None));
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        prepare(&mut wm);
+        let evicted = wm.push(MemoryItem::new("fourth", None)).unwrap();
+        assert_eq!(wm.len(), 3);
+        evicted.content
+    }
+
+    #[test]
+    fn test_lru_evicts_least_recently_accessed() {
+        assert_eq!(evict_with(EvictionPolicy::Lru, |_| {}), "first");
+        // Touching "first" makes "second" the stalest
+        assert_eq!(evict_with(EvictionPolicy::Lru, |wm| wm.rehearse("first")), "second");
+    }
+
+    #[test]
+    fn test_lfu_evicts_least_frequently_accessed() {
+        let evicted = evict_with(EvictionPolicy::Lfu, |wm| {
+            wm.rehearse("first");
+            wm.rehearse("first");
+            wm.rehearse("third");
+        });
+        assert_eq!(evicted, "second");
+    }
+
+    #[test]
+    fn test_lowest_strength_evicts_weakest() {
+        let evicted = evict_with(EvictionPolicy::LowestStrength, |wm| {
+            for item in wm.items.iter_mut() {
+                item.strength = match item.content.as_str() {
+                    "first" => 0.9,
+                    "second" => 0.8,
+                    _ => 0.2,
+                };
+            }
+        });
+        assert_eq!(evicted, "third");
+    }
+
     #[test]
     fn test_search() {
         let mut wm = WorkingMemory::new(5);
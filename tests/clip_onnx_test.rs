@@ -0,0 +1,52 @@
+//! Native ONNX CLIP tests (`cargo test --features clip-onnx`)
+//!
+//! Needs a CLIP ViT-B/32 export in `CLIP_MODEL_DIR`
+//! (default `~/.memory-brain/models/clip-vit-b32`); skipped otherwise.
+
+#![cfg(feature = "clip-onnx")]
+
+use memory_brain::clip_onnx::OnnxClipProvider;
+use memory_brain::config::Config;
+use memory_brain::visual::ClipProvider;
+
+fn load_provider() -> Option<OnnxClipProvider> {
+    let model_dir = Config::load().unwrap_or_default().visual.clip_model_dir;
+    if !OnnxClipProvider::is_available(&model_dir) {
+        eprintln!("ONNX CLIP model not found in {:?}, skipping", model_dir);
+        return None;
+    }
+    Some(OnnxClipProvider::new(&model_dir).expect("load ONNX CLIP"))
+}
+
+fn assert_unit_512(embedding: &[f32]) {
+    assert_eq!(embedding.len(), 512);
+    let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+    assert!((norm - 1.0).abs() < 1e-4, "norm {}", norm);
+}
+
+#[test]
+fn test_onnx_clip_embeds_image_and_text() {
+    let Some(clip) = load_provider() else { return };
+
+    // Fixture: a red square on white, non-square so the center crop kicks in
+    let dir = tempfile::tempdir().unwrap();
+    let image_path = dir.path().join("red_square.png");
+    let img = image::RgbImage::from_fn(320, 240, |x, y| {
+        if (100..220).contains(&x) && (60..180).contains(&y) {
+            image::Rgb([220, 20, 20])
+        } else {
+            image::Rgb([255, 255, 255])
+        }
+    });
+    img.save(&image_path).unwrap();
+
+    let image_emb = clip.embed_image(&image_path).unwrap();
+    assert_unit_512(&image_emb);
+
+    let red = clip.embed_text("a red square").unwrap();
+    let dog = clip.embed_text("a photo of a dog on the grass").unwrap();
+    assert_unit_512(&red);
+
+    let sim = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+    assert!(sim(&image_emb, &red) > sim(&image_emb, &dog));
+}
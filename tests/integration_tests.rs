@@ -1,6 +1,6 @@
 //! Integration tests for Memory Brain
 
-use memory_brain::{Brain, GloVeEmbedder};
+use memory_brain::{cosine_similarity, Brain, Embedder, GloVeEmbedder, MatchSource, MemoryItem, RecallWeights, SearchOptions};
 use std::sync::Arc;
 use tempfile::TempDir;
 
@@ -92,3 +92,1175 @@ fn test_sleep_consolidation() {
     ctx.brain.sleep().unwrap();
     assert_eq!(ctx.brain.working.len(), 0);
 }
+
+#[test]
+fn test_storage_count_matches_get_all() {
+    use memory_brain::storage::Storage;
+    use memory_brain::MemoryItem;
+
+    let dir = TempDir::new().unwrap();
+    let storage = Storage::new(dir.path().join("count.db").to_str().unwrap(), "count_test").unwrap();
+    assert_eq!(storage.count().unwrap(), 0);
+
+    for i in 0..25 {
+        let mut item = MemoryItem::new(&format!("Counted memory {}", i), None);
+        item.embedding = Some(vec![0.1; 64]);
+        storage.save(&item).unwrap();
+    }
+
+    assert_eq!(storage.count().unwrap(), storage.get_all().unwrap().len());
+    assert_eq!(storage.count().unwrap(), 25);
+}
+
+#[test]
+fn test_recall_diverse_prefers_distinct_memories() {
+    let mut ctx = TestContext::new();
+
+    // Three near-duplicates about ownership, two distinct memories
+    let duplicates = [
+        "Rust memory safety ownership",
+        "Rust ownership memory safety",
+        "Rust memory safety ownership management",
+    ];
+    let distinct = [
+        "Rust machine learning data",
+        "Rust apple silicon gpu hardware",
+    ];
+    for content in duplicates.iter().chain(distinct.iter()) {
+        ctx.brain.process(content, None).unwrap();
+    }
+
+    let results = ctx.brain.recall_diverse("Rust", 3, 0.5);
+    assert_eq!(results.len(), 3);
+
+    let contents: Vec<&str> = results.iter().map(|m| m.content.as_str()).collect();
+    for d in &distinct {
+        assert!(contents.contains(d), "expected {:?} in diverse results {:?}", d, contents);
+    }
+    let dup_count = contents.iter().filter(|c| duplicates.contains(c)).count();
+    assert_eq!(dup_count, 1);
+}
+
+#[test]
+fn test_recall_full_scans_do_not_grow_with_results() {
+    let mut ctx = TestContext::new();
+    for i in 0..30 {
+        ctx.brain.process(&format!("rust note number {}", i), None).unwrap();
+    }
+    ctx.brain.set_record_access(false);
+
+    let before = ctx.brain.semantic.full_scans();
+    let results = ctx.brain.recall("rust", 10);
+    let scans = ctx.brain.semantic.full_scans() - before;
+
+    assert!(!results.is_empty());
+    // 20 keyword hits used to cost one table scan each
+    assert!(scans <= 2, "recall issued {} full scans", scans);
+}
+
+#[test]
+fn test_recall_weights_similarity_reorders_results() {
+    let mut ctx = TestContext::new();
+    ctx.brain.set_record_access(false);
+
+    // A weak but on-topic memory and a strong, loosely related one
+    let mut similar = MemoryItem::new("ownership memory safety borrow checker", None);
+    similar.strength = 0.3;
+    let mut strong = MemoryItem::new("ownership of the garden tomatoes", None);
+    strong.strength = 1.0;
+    ctx.brain.store_or_update(similar).unwrap();
+    ctx.brain.store_or_update(strong).unwrap();
+
+    let query = "ownership memory safety";
+    let embedder = ctx.brain.embedder().clone();
+    let q = embedder.embed(query);
+    assert!(
+        cosine_similarity(&q, &embedder.embed("ownership memory safety borrow checker"))
+            > cosine_similarity(&q, &embedder.embed("ownership of the garden tomatoes"))
+    );
+
+    // Default weights: strength dominates
+    let results = ctx.brain.recall(query, 2);
+    assert_eq!(results[0].content, "ownership of the garden tomatoes");
+
+    ctx.brain.set_recall_weights(RecallWeights::parse("sim=1.0,strength=0,recency=0").unwrap());
+    let results = ctx.brain.recall(query, 2);
+    assert_eq!(results[0].content, "ownership memory safety borrow checker");
+
+    // Filtered recall ranks with the same weights
+    let opts = SearchOptions { memory_type: Some(memory_brain::MemoryType::Semantic), ..Default::default() };
+    let results = ctx.brain.recall_with_options(query, 2, &opts).unwrap();
+    assert_eq!(results[0].content, "ownership memory safety borrow checker");
+}
+
+#[test]
+fn test_embeddings_export_import_roundtrip() {
+    use memory_brain::embedding_io::{export_embeddings, ids_path, import_embeddings, read_npy};
+    use memory_brain::MemoryItem;
+
+    let mut ctx = TestContext::new();
+    let dir = TempDir::new().unwrap();
+    let dim = ctx.brain.embedder().dimension();
+
+    let mut ids = Vec::new();
+    for i in 0..4 {
+        let mut item = MemoryItem::new(&format!("Exported fact number {}", i), None);
+        item.embedding = Some(vec![i as f32; dim]);
+        ids.push(item.id);
+        ctx.brain.semantic.store(item).unwrap();
+    }
+
+    // Export: n × dim float32 matrix plus id list
+    let path = dir.path().join("emb.npy");
+    export_embeddings(&ctx.brain, &path).unwrap();
+    let (rows, cols, data) = read_npy(&path).unwrap();
+    assert_eq!((rows, cols), (4, dim));
+    assert_eq!(data.len(), 4 * dim);
+
+    let exported_ids: Vec<uuid::Uuid> = std::fs::read_to_string(ids_path(&path))
+        .unwrap()
+        .lines()
+        .map(|l| uuid::Uuid::parse_str(l).unwrap())
+        .collect();
+    for (row, id) in exported_ids.iter().enumerate() {
+        let n = ids.iter().position(|x| x == id).unwrap() as f32;
+        assert!(data[row * dim..(row + 1) * dim].iter().all(|v| *v == n));
+    }
+
+    // Import externally modified embeddings (row values + 10) back by id
+    let shifted: Vec<f32> = data.iter().map(|v| v + 10.0).collect();
+    memory_brain::embedding_io::write_npy(&path, rows, cols, &shifted).unwrap();
+    let stats = import_embeddings(&mut ctx.brain, &path).unwrap();
+    assert_eq!(stats.updated, 4);
+    assert!(stats.missing.is_empty());
+
+    for (i, id) in ids.iter().enumerate() {
+        let item = ctx.brain.semantic.get(id).unwrap().unwrap();
+        assert_eq!(item.embedding.unwrap(), vec![i as f32 + 10.0; dim]);
+    }
+
+    // Wrong dimension is rejected
+    memory_brain::embedding_io::write_npy(&path, rows, cols + 1, &vec![0.0; rows * (cols + 1)]).unwrap();
+    assert!(import_embeddings(&mut ctx.brain, &path).is_err());
+}
+
+#[test]
+fn test_delete_by_prefix_persists_after_reopen() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("delete.db");
+    let embedder = Arc::new(GloVeEmbedder::test_embedder());
+
+    let id = {
+        let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder.clone()).unwrap();
+        brain.process("Rust is a systems programming language", None).unwrap();
+        brain.process("Python is great for data science", None).unwrap();
+
+        let item = brain.semantic.search("Rust", 1).unwrap().remove(0);
+        let deleted = brain.delete(&item.id.to_string()[..8]).unwrap();
+        assert_eq!(deleted.id, item.id);
+        assert!(brain.keyword_index.search_ranked("rust", 5).iter().all(|(i, _)| *i != item.id));
+
+        // Nothing left to match
+        assert!(brain.delete(&item.id.to_string()[..8]).is_err());
+        item.id
+    };
+
+    let brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder).unwrap();
+    let remaining = brain.semantic.search("", 100).unwrap();
+    assert!(remaining.iter().all(|m| m.id != id));
+    assert!(remaining.iter().any(|m| m.content.contains("Python")));
+}
+
+#[test]
+fn test_working_memory_recovered_from_wal_after_crash() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("wal.db");
+    let embedder = Arc::new(GloVeEmbedder::test_embedder());
+
+    let forgotten = {
+        let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder.clone()).unwrap();
+        brain.process("Parked the car on level B2", None).unwrap();
+        brain.process("Meeting moved to Thursday", None).unwrap();
+        let forgotten = brain.process_returning("Scratch note to discard", None).unwrap();
+        brain.forget(forgotten.id).unwrap();
+        assert!(brain.working_wal_path().unwrap().exists());
+        forgotten.id
+        // Dropped without sleeping
+    };
+
+    let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder.clone()).unwrap();
+    let contents: Vec<String> = brain.working.get_all().into_iter().map(|m| m.content).collect();
+    assert_eq!(contents, vec!["Parked the car on level B2", "Meeting moved to Thursday"]);
+    assert!(brain.working.get_all().iter().all(|m| m.id != forgotten));
+
+    // Sleep consolidates and truncates the log
+    brain.sleep().unwrap();
+    assert!(!brain.working_wal_path().unwrap().exists());
+    drop(brain);
+    let brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder).unwrap();
+    assert_eq!(brain.working.len(), 0);
+}
+
+#[test]
+fn test_deleted_keyword_leaves_bloom_filter() {
+    let mut ctx = TestContext::new();
+    ctx.brain.process("Quokkas live on Rottnest island", None).unwrap();
+    ctx.brain.process("Rust is a systems programming language", None).unwrap();
+    assert!(ctx.brain.keyword_might_exist("quokkas"));
+
+    let (id, _) = ctx.brain.keyword_index.search_ranked("quokkas", 1).remove(0);
+    assert!(ctx.brain.forget(id).unwrap());
+
+    // Counters for the deleted words drop to zero; other memories keep theirs
+    assert!(!ctx.brain.keyword_might_exist("quokkas"));
+    assert!(!ctx.brain.keyword_might_exist("Rottnest"));
+    assert!(ctx.brain.keyword_might_exist("rust"));
+}
+
+#[test]
+fn test_associations_survive_reopen() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("assoc.db");
+    let embedder = Arc::new(GloVeEmbedder::test_embedder());
+
+    {
+        let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder.clone()).unwrap();
+        brain.process("Rust ownership gives memory safety", None).unwrap();
+        brain.process("Rust memory safety through ownership rules", None).unwrap();
+    }
+
+    let brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder).unwrap();
+    let items = brain.episodic.search("", 10).unwrap();
+    let first = items.iter().find(|m| m.content.contains("gives")).unwrap();
+    let second = items.iter().find(|m| m.content.contains("rules")).unwrap();
+
+    // New memory links to the related one, and the back-link is persisted too
+    assert!(second.associations.contains(&first.id));
+    assert!(first.associations.contains(&second.id));
+}
+
+#[test]
+fn test_process_returning_exposes_id_and_links() {
+    let mut ctx = TestContext::new();
+    let first = ctx.brain.process_returning("Rust ownership gives memory safety", None).unwrap();
+    let second = ctx.brain.process_returning("Rust memory safety through ownership rules", None).unwrap();
+
+    let stored = ctx.brain.get_by_id(&second.id).unwrap().unwrap();
+    assert_eq!(stored.id, second.id);
+    assert_eq!(stored.content, second.content);
+    assert_eq!(stored.memory_type, second.memory_type);
+
+    // Auto-links are reported without re-searching
+    assert!(second.associations.contains(&first.id));
+    assert_eq!(stored.associations, second.associations);
+}
+
+#[test]
+fn test_semantic_search_hnsw_matches_exact_top3() {
+    use memory_brain::MemoryItem;
+
+    let mut ctx = TestContext::new();
+    let dim = ctx.brain.embedder().dimension();
+
+    // Deterministic pseudo-random embeddings (LCG)
+    let mut seed: u64 = 7;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((seed >> 33) as f32 / (1u64 << 31) as f32) * 2.0 - 1.0
+    };
+    for i in 0..500 {
+        let mut item = MemoryItem::new(&format!("Synthetic memory {:03}", i), None);
+        item.embedding = Some((0..dim).map(|_| next()).collect());
+        ctx.brain.semantic.store(item).unwrap();
+    }
+    let stats = ctx.brain.rebuild_indexes().unwrap();
+    assert_eq!(stats.hnsw_count, 500);
+
+    for query in ["rust programming", "machine learning data", "apple silicon gpu"] {
+        let exact: Vec<_> = ctx.brain.semantic_search(query, 3).into_iter().map(|(m, _)| m.id).collect();
+        let approx: Vec<_> = ctx.brain.semantic_search_hnsw(query, 3).into_iter().map(|(m, _)| m.id).collect();
+        let overlap = approx.iter().filter(|id| exact.contains(id)).count();
+        assert!(overlap >= 2, "query {:?}: only {} of top-3 overlap", query, overlap);
+    }
+}
+
+#[test]
+fn test_recall_records_access_across_reopen() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("access.db");
+    let embedder = Arc::new(GloVeEmbedder::test_embedder());
+
+    let (id, initial) = {
+        let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder.clone()).unwrap();
+        brain.process("Rust is a systems programming language", None).unwrap();
+        let item = brain.semantic.search("Rust", 1).unwrap().remove(0);
+
+        brain.recall("Rust", 5);
+        brain.recall("Rust", 5);
+
+        // Read-only recall leaves the stored count alone
+        brain.recall_with_access("Rust", 5, false);
+        (item.id, item.access_count)
+    };
+
+    let brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder).unwrap();
+    let item = brain.semantic.get(&id).unwrap().unwrap();
+    assert_eq!(item.access_count, initial + 2);
+}
+
+#[test]
+fn test_keyword_index_persists_without_rebuild() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("indexes.db");
+    let embedder = Arc::new(GloVeEmbedder::test_embedder());
+
+    let keywords = {
+        let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder.clone()).unwrap();
+        brain.process("Rust is a systems programming language", None).unwrap();
+        brain.process("Python is great for data science", None).unwrap();
+        brain.keyword_index.stats().unique_keywords
+    };
+    assert!(keywords > 0);
+
+    // Dropping the brain saved the incrementally updated indexes
+    let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder).unwrap();
+    assert!(brain.index_path().exists());
+    assert!(brain.load_indexes().unwrap().is_some());
+    assert_eq!(brain.keyword_index.stats().unique_keywords, keywords);
+    assert!(!brain.recall("Python", 5).is_empty());
+}
+
+#[test]
+fn test_close_reports_failed_index_writes() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("close.db");
+    let embedder = Arc::new(GloVeEmbedder::test_embedder());
+
+    let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder.clone()).unwrap();
+    brain.process("Rust is a systems programming language", None).unwrap();
+    brain.close().unwrap();
+
+    let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder).unwrap();
+    assert!(brain.load_indexes().unwrap().is_some());
+
+    // A directory where the sidecar goes makes the write fail
+    brain.process("Python is great for data science", None).unwrap();
+    let index_path = brain.index_path().to_path_buf();
+    std::fs::remove_file(&index_path).unwrap();
+    std::fs::create_dir(&index_path).unwrap();
+    assert!(brain.close().is_err());
+}
+
+#[test]
+fn test_get_by_id_prefix() {
+    let mut ctx = TestContext::new();
+
+    let mut ids = Vec::new();
+    for i in 0..20 {
+        let item = MemoryItem::new(&format!("memory number {} about topic {}", i, i * 7), None);
+        ids.push(item.id);
+        ctx.brain.semantic.store(item).unwrap();
+    }
+    let procedural = MemoryItem::new("Pattern: always run clippy before commit", None);
+    let procedural_id = procedural.id;
+    ctx.brain.procedural.store(procedural).unwrap();
+
+    // Exact id, including one that only lives in procedural memory
+    let hit = ctx.brain.get_by_id_prefix(&ids[3].to_string()).unwrap().unwrap();
+    assert_eq!(hit.id, ids[3]);
+    assert!(ctx.brain.get_by_id(&procedural_id).unwrap().is_some());
+
+    // Unique prefix
+    let prefix = &procedural_id.to_string()[..13];
+    let hit = ctx.brain.get_by_id_prefix(prefix).unwrap().unwrap();
+    assert_eq!(hit.id, procedural_id);
+
+    // 21 ids over 16 hex digits: some first character is shared
+    let shared = (0..16u32)
+        .map(|d| std::char::from_digit(d, 16).unwrap())
+        .find(|c| ids.iter().chain([&procedural_id]).filter(|id| id.to_string().starts_with(*c)).count() > 1)
+        .unwrap();
+    assert!(ctx.brain.get_by_id_prefix(&shared.to_string()).is_err());
+
+    // Not found
+    assert!(ctx.brain.get_by_id_prefix("zzzz").unwrap().is_none());
+    assert!(ctx.brain.get_by_id(&uuid::Uuid::new_v4()).unwrap().is_none());
+}
+
+/// 200 memories where only the last 3 contain a ticket number / "rare" tag
+fn store_haystack(brain: &mut Brain) {
+    for i in 0..200 {
+        let mut item = if i >= 197 {
+            MemoryItem::new(&format!("ticket ABC-{} resolved for chores {}", i, i), None)
+        } else {
+            MemoryItem::new(&format!("note {} about daily chores {}", i, i * 3), None)
+        };
+        if i >= 197 {
+            item.tags = vec!["rare".to_string()];
+        }
+        brain.semantic.store(item).unwrap();
+    }
+}
+
+#[test]
+fn test_recall_with_options_regex_applies_limit_after_filtering() {
+    let mut ctx = TestContext::new();
+    store_haystack(&mut ctx.brain);
+
+    let opts = SearchOptions { regex: Some(r"ABC-\d+".to_string()), ..Default::default() };
+    let results = ctx.brain.recall_with_options(r"ABC-\d+", 5, &opts).unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|m| m.content.contains("ABC-")));
+
+    let bad = SearchOptions { regex: Some("(".to_string()), ..Default::default() };
+    assert!(ctx.brain.recall_with_options("(", 5, &bad).is_err());
+}
+
+#[test]
+fn test_recall_with_options_tag_filter() {
+    let mut ctx = TestContext::new();
+    store_haystack(&mut ctx.brain);
+
+    let opts = SearchOptions { tags: vec!["RARE".to_string()], ..Default::default() };
+    let results = ctx.brain.recall_with_options("chores", 5, &opts).unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|m| m.tags.contains(&"rare".to_string())));
+}
+
+#[test]
+fn test_working_overflow_is_persisted_not_dropped() {
+    let mut ctx = TestContext::new();
+    let capacity = ctx.brain.working.capacity();
+
+    // Items placed straight into working memory are flushed on overflow
+    let ids: Vec<_> = (0..capacity + 3)
+        .map(|i| {
+            let item = MemoryItem::new(&format!("yesterday I finished chore {} at stop {}", i, i * 11), None);
+            let id = item.id;
+            ctx.brain.working.admit(item);
+            id
+        })
+        .collect();
+    assert_eq!(ctx.brain.maybe_consolidate().unwrap(), 3);
+    assert_eq!(ctx.brain.working.len(), capacity);
+    for id in &ids[..3] {
+        let stored = ctx.brain.episodic.get(id).unwrap().unwrap();
+        assert_eq!(stored.memory_type, memory_brain::MemoryType::Episodic);
+    }
+
+    // New memories push the rest out; every one of the 10 is persisted
+    for i in 0..capacity {
+        ctx.brain.process(&format!("yesterday I walked route {} past gate {}", i, i * 13), None).unwrap();
+    }
+    assert_eq!(ctx.brain.working.len(), capacity);
+    for id in &ids {
+        assert!(ctx.brain.episodic.get(id).unwrap().is_some());
+    }
+}
+
+#[test]
+fn test_recall_in_range() {
+    use chrono::{Duration, Utc};
+
+    let mut ctx = TestContext::new();
+    let now = Utc::now();
+    for (days_ago, text) in [(30, "old deploy of the billing service"), (10, "mid deploy of the search service"), (1, "recent deploy of the auth service")] {
+        let mut item = MemoryItem::new(text, None);
+        item.created_at = now - Duration::days(days_ago);
+        ctx.brain.episodic.store(item).unwrap();
+    }
+
+    // Bounded range
+    let results = ctx.brain
+        .recall_in_range("deploy", 10, Some(now - Duration::days(15)), Some(now - Duration::days(5)))
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].content.contains("search"));
+
+    // Open-ended --since
+    let since = memory_brain::parse_time_spec_at("14d", now).unwrap();
+    let results = ctx.brain.recall_in_range("deploy", 10, Some(since), None).unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|m| !m.content.contains("billing")));
+
+    // Range that excludes everything
+    let results = ctx.brain
+        .recall_in_range("deploy", 10, Some(now - Duration::days(100)), Some(now - Duration::days(60)))
+        .unwrap();
+    assert!(results.is_empty());
+
+    assert!(memory_brain::parse_time_spec("2024-03-01").is_ok());
+    assert!(memory_brain::parse_time_spec("7x").is_err());
+}
+
+#[test]
+fn test_list_page_walks_every_memory_once() {
+    use chrono::{Duration, Utc};
+    use std::collections::HashSet;
+
+    let mut ctx = TestContext::new();
+    let now = Utc::now();
+    let mut stored = HashSet::new();
+    for i in 0..25 {
+        // Pairs share a timestamp so ordering must fall back to the id
+        let mut item = MemoryItem::new(&format!("paged memory {} of the set", i), None);
+        item.created_at = now - Duration::minutes(i / 2);
+        stored.insert(item.id);
+        match i % 3 {
+            0 => ctx.brain.episodic.store(item).unwrap(),
+            1 => ctx.brain.semantic.store(item).unwrap(),
+            _ => ctx.brain.procedural.store(item).unwrap(),
+        }
+    }
+    assert_eq!(ctx.brain.long_term_count().unwrap(), 25);
+
+    let mut seen = Vec::new();
+    let mut sizes = Vec::new();
+    for page in 0..3 {
+        let items = ctx.brain.list_page(10, page * 10).unwrap();
+        sizes.push(items.len());
+        seen.extend(items);
+    }
+    assert_eq!(sizes, vec![10, 10, 5]);
+    assert!(ctx.brain.list_page(10, 30).unwrap().is_empty());
+
+    let ids: HashSet<_> = seen.iter().map(|m| m.id).collect();
+    assert_eq!(ids.len(), 25, "no duplicates across pages");
+    assert_eq!(ids, stored, "no gaps across pages");
+    assert!(seen.windows(2).all(|w| (w[0].created_at, w[0].id) > (w[1].created_at, w[1].id)));
+}
+
+#[test]
+fn test_embedding_dim_mismatch_detected_on_reopen() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("test.db");
+    let db_path = db_path.to_str().unwrap();
+
+    {
+        // Brain::new embeds with a 256-dim hash embedder
+        let mut brain = Brain::new(db_path).unwrap();
+        let mut item = MemoryItem::new("Rust is a systems language", None);
+        item.embedding = Some(brain.embedder().embed(&item.content));
+        brain.semantic.store(item).unwrap();
+        assert_eq!(brain.semantic.embedding_dim().unwrap(), Some(256));
+        assert!(brain.check_embedding_dim().is_ok());
+    }
+
+    let embedder = Arc::new(GloVeEmbedder::test_embedder());
+    let mut brain = Brain::with_embedder(db_path, embedder.clone()).unwrap();
+    assert_eq!(brain.stored_embedding_dim(), Some(256));
+    let err = brain.check_embedding_dim().unwrap_err().to_string();
+    assert!(err.contains("256") && err.contains("50"), "{}", err);
+
+    assert_eq!(brain.reembed().unwrap(), 1);
+    assert!(brain.check_embedding_dim().is_ok());
+    drop(brain);
+
+    let brain = Brain::with_embedder(db_path, embedder).unwrap();
+    assert_eq!(brain.stored_embedding_dim(), Some(50));
+    assert!(brain.check_embedding_dim().is_ok());
+}
+
+#[test]
+fn test_recall_survives_zeroed_embedding() {
+    let mut ctx = TestContext::new();
+
+    // e.g. an all out-of-vocabulary memory embedded as zeros
+    let mut zeroed = MemoryItem::new("Rust zzqx wobble", None);
+    zeroed.embedding = Some(vec![0.0; ctx.brain.embedder().dimension()]);
+    ctx.brain.semantic.store(zeroed).unwrap();
+    let mut truncated = MemoryItem::new("Rust short vector", None);
+    truncated.embedding = Some(vec![1.0; 3]);
+    ctx.brain.semantic.store(truncated).unwrap();
+    let mut normal = MemoryItem::new("Rust is about memory safety", None);
+    normal.embedding = Some(ctx.brain.embedder().embed(&normal.content));
+    ctx.brain.semantic.store(normal).unwrap();
+    ctx.brain.rebuild_indexes().unwrap();
+
+    let results = ctx.brain.recall("rust", 10);
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|m| !m.strength.is_nan() && !m.relevance_score().is_nan()));
+    assert!(ctx.brain.semantic_search("rust", 10).iter().all(|(_, sim)| sim.is_finite()));
+}
+
+#[test]
+fn test_import_jsonl_streams_10k_lines() {
+    use memory_brain::import::{import_jsonl, ImportOptions};
+    use memory_brain::MemoryType;
+    use std::io::{BufReader, BufWriter, Write};
+
+    let mut ctx = TestContext::new();
+    let path = ctx._dir.path().join("memories.jsonl");
+    {
+        let mut out = BufWriter::new(std::fs::File::create(&path).unwrap());
+        for i in 0..10_000 {
+            writeln!(out, r#"{{"content": "streamed memory {}", "tags": ["bulk"]}}"#, i).unwrap();
+        }
+        writeln!(out, "not json").unwrap();
+    }
+
+    let options = ImportOptions { tags: vec!["imported".to_string()], memory_type: MemoryType::Episodic };
+    let mut progress = Vec::new();
+    let reader = BufReader::new(std::fs::File::open(&path).unwrap());
+    let stats = import_jsonl(&mut ctx.brain, reader, &options, |n| progress.push(n)).unwrap();
+
+    assert_eq!(stats.imported, 10_000);
+    assert_eq!(stats.skipped, 1);
+    assert_eq!(stats.failed, 0);
+    assert_eq!(progress.len(), 100);
+    assert_eq!(ctx.brain.episodic.count().unwrap(), 10_000);
+    // Indexed as they were stored
+    assert_eq!(ctx.brain.keyword_index.stats().documents, 10_000);
+
+    let sample = ctx.brain.episodic.get_recent(1, 0).unwrap().remove(0);
+    assert_eq!(sample.tags, vec!["bulk".to_string(), "imported".to_string()]);
+    assert!(sample.embedding.is_some());
+}
+
+#[test]
+fn test_csv_export_import_roundtrip() {
+    use memory_brain::import::{import_csv, ImportOptions};
+
+    let mut source = TestContext::new();
+    let tricky = MemoryItem::new("Deploy notes: \"v2\", rolled back\nafter errors", None)
+        .with_tags(vec!["deploy".to_string(), "ops".to_string()]);
+    let plain = MemoryItem::new("Rust is a language", None).with_tags(vec!["rust".to_string()]);
+    source.brain.episodic.store(tricky.clone()).unwrap();
+    source.brain.semantic.store(plain.clone()).unwrap();
+
+    let mut items = source.brain.episodic.search("", 10).unwrap();
+    items.extend(source.brain.semantic.search("", 10).unwrap());
+    let csv = memory_brain::export::to_csv(&items);
+
+    let mut target = TestContext::new();
+    let csv = format!("{}\"\",,,,\n", csv);
+    let stats = import_csv(&mut target.brain, &csv, &ImportOptions::default(), |_| {}).unwrap();
+    assert_eq!(stats.imported, 2);
+    assert_eq!(stats.skipped, 1);
+    assert_eq!(stats.errors, vec!["row 3: empty content".to_string()]);
+
+    let episodic = target.brain.episodic.search("", 10).unwrap();
+    assert_eq!(episodic.len(), 1);
+    assert_eq!(episodic[0].content, tricky.content);
+    assert_eq!(episodic[0].tags, tricky.tags);
+
+    let semantic = target.brain.semantic.search("", 10).unwrap();
+    assert_eq!(semantic.len(), 1);
+    assert_eq!(semantic[0].content, plain.content);
+    assert_eq!(semantic[0].tags, plain.tags);
+}
+
+#[test]
+fn test_snapshot_restore_roundtrip() {
+    use memory_brain::MemoryType;
+
+    let mut ctx = TestContext::new();
+    let mut previous = None;
+    for i in 0..50 {
+        let content = format!("Snapshot item {:03} about harbour logistics", i);
+        let memory_type = match i % 3 {
+            0 => MemoryType::Episodic,
+            1 => MemoryType::Semantic,
+            _ => MemoryType::Procedural,
+        };
+        let mut item = MemoryItem::new(&content, Some("snapshot test"))
+            .with_type(memory_type.clone())
+            .with_tags(vec![format!("batch{}", i % 5)]);
+        item.strength = 0.3 + (i as f32) / 100.0;
+        item.access_count = i;
+        item.embedding = Some(ctx.brain.embedder().embed(&content));
+        if let Some(prev) = previous {
+            item.associate(prev);
+        }
+        previous = Some(item.id);
+        match memory_type {
+            MemoryType::Episodic => ctx.brain.episodic.store(item).unwrap(),
+            MemoryType::Semantic => ctx.brain.semantic.store(item).unwrap(),
+            _ => ctx.brain.procedural.store(item).unwrap(),
+        }
+    }
+    ctx.brain.rebuild_indexes().unwrap();
+
+    let dump = |brain: &Brain| {
+        let mut items = brain.episodic.search("", usize::MAX).unwrap();
+        items.extend(brain.semantic.search("", usize::MAX).unwrap());
+        items.extend(brain.procedural.search("", usize::MAX).unwrap());
+        items.sort_by_key(|m| m.id);
+        serde_json::to_vec(&items).unwrap()
+    };
+    let before = dump(&ctx.brain);
+    let path = ctx._dir.path().join("brain.snapshot");
+    ctx.brain.snapshot(&path).unwrap();
+
+    // Mutate: delete, add and re-weight
+    let victim = ctx.brain.semantic.search("", 1).unwrap().remove(0);
+    ctx.brain.delete(&victim.id.to_string()).unwrap();
+    ctx.brain.episodic.store(MemoryItem::new("Unrelated zebra crossing", None)).unwrap();
+    let reweighted = ctx.brain.episodic.search("", 1).unwrap().remove(0);
+    ctx.brain.update_strength(&reweighted.id.to_string(), 0.01).unwrap();
+    assert_ne!(dump(&ctx.brain), before);
+
+    ctx.brain.restore(&path).unwrap();
+    assert_eq!(dump(&ctx.brain), before);
+    assert!(ctx.brain.keyword_index.search_or("zebra").is_empty());
+    assert!(ctx.brain.keyword_index.search_or("harbour").contains(&victim.id));
+
+    // Restoring again changes nothing
+    ctx.brain.restore(&path).unwrap();
+    assert_eq!(dump(&ctx.brain), before);
+    assert_eq!(ctx.brain.hnsw.stats().count, 50);
+}
+
+#[test]
+fn test_store_or_update_dedups_identical_content() {
+    let mut ctx = TestContext::new();
+
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        let item = MemoryItem::new("The office wifi password rotates monthly", None);
+        ids.push(ctx.brain.store_or_update(item).unwrap());
+    }
+
+    assert!(ids[0].1);
+    assert_eq!(ids[1], (ids[0].0, false));
+    assert_eq!(ids[2], (ids[0].0, false));
+
+    let rows = ctx.brain.semantic.search("", 10).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].access_count, 3);
+
+    // Different content is stored separately
+    let (_, inserted) = ctx.brain.store_or_update(MemoryItem::new("The office closes at six", None)).unwrap();
+    assert!(inserted);
+    assert_eq!(ctx.brain.semantic.count().unwrap(), 2);
+}
+
+#[test]
+fn test_store_batch_skips_failed_item_and_keeps_indexes_consistent() {
+    let mut ctx = TestContext::new();
+
+    let good = [
+        "Kayaks can be rented at the north pier",
+        "The ferry timetable changes in winter",
+        "Lighthouse tours start at nine",
+    ];
+    let mut items: Vec<MemoryItem> = good.iter().map(|c| MemoryItem::new(c, None)).collect();
+    // Forced failure: an embedding from some other model
+    let mut broken = MemoryItem::new("Harbour parking is free on Sundays", None);
+    broken.embedding = Some(vec![0.5; 3]);
+    let broken_id = broken.id;
+    items.insert(1, broken);
+
+    let report = ctx.brain.store_batch(items);
+    assert_eq!(report.stored, 3);
+    assert_eq!(report.failed_ids(), vec![broken_id]);
+    assert!(report.failed[0].1.contains("dimension"));
+
+    // Only the stored items reached the stores and the indexes
+    assert_eq!(ctx.brain.semantic.count().unwrap(), 3);
+    assert_eq!(ctx.brain.keyword_index.stats().documents, 3);
+    assert_eq!(ctx.brain.hnsw.stats().count, 3);
+    assert!(ctx.brain.keyword_index.search_or("sundays").is_empty());
+
+    // Retrying the failure with a fixed item stores it
+    let (mut retry, _) = report.failed.into_iter().next().unwrap();
+    retry.embedding = None;
+    assert_eq!(ctx.brain.store_batch(vec![retry]).stored, 1);
+    assert_eq!(ctx.brain.keyword_index.stats().documents, 4);
+}
+
+#[test]
+fn test_recall_dedups_by_content_hash() {
+    let mut ctx = TestContext::new();
+    ctx.brain.set_record_access(false);
+
+    // Same content up to whitespace, stored without dedup
+    let first = ctx.brain.process_returning("The harbour ferry leaves at noon", None).unwrap();
+    let second = ctx.brain.process_returning("The  harbour ferry\tleaves at noon", None).unwrap();
+    assert_ne!(first.id, second.id);
+    assert_eq!(first.content_hash, second.content_hash);
+
+    let results = ctx.brain.recall("harbour ferry", 10);
+    assert_eq!(results.iter().filter(|m| m.content_hash == first.content_hash).count(), 1);
+
+    let found = ctx.brain.find_exact("The harbour ferry leaves at noon").unwrap();
+    assert!(found.map_or(false, |m| m.id == first.id || m.id == second.id));
+    assert!(ctx.brain.find_exact("The harbour ferry leaves at dawn").unwrap().is_none());
+}
+
+#[test]
+fn test_recall_explained_reports_keywords_and_semantic_matches() {
+    let mut ctx = TestContext::new();
+    ctx.brain.set_record_access(false);
+
+    ctx.brain.process("Rust ownership keeps memory safe", None).unwrap();
+    let explained = ctx.brain.recall_explained("rust ownership", 5);
+    let (item, info) = explained.iter().find(|(m, _)| m.content.contains("ownership")).unwrap();
+    assert!(info.matched_keywords.contains(&"rust".to_string()));
+    assert_eq!(info.highlight(&item.content), "[Rust] [ownership] keeps memory safe");
+
+    // Shares no word with the query, only an embedding neighbourhood
+    let mut python = MemoryItem::new("Python programming", None);
+    python.embedding = Some(ctx.brain.embedder().embed("Python programming"));
+    let python_id = python.id;
+    ctx.brain.semantic.store(python).unwrap();
+
+    let explained = ctx.brain.recall_explained("rust code", 10);
+    let (_, info) = explained.iter().find(|(m, _)| m.id == python_id).unwrap();
+    assert_eq!(info.source, MatchSource::Semantic);
+    assert!(info.matched_keywords.is_empty());
+    assert!(info.similarity > 0.5);
+}
+
+#[test]
+fn test_builder_keyspaces_isolate_brains_in_one_directory() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("shared.db");
+    let db_path = db_path.to_str().unwrap();
+    let open = |keyspace: &str| {
+        Brain::builder()
+            .db_path(db_path)
+            .keyspace(keyspace)
+            .embedder(Arc::new(GloVeEmbedder::test_embedder()))
+            .working_capacity(3)
+            .build()
+            .unwrap()
+    };
+
+    {
+        let mut work = open("work_brain");
+        assert_eq!(work.working.capacity(), 3);
+        work.process("Quarterly roadmap review with the platform team", None).unwrap();
+    }
+    {
+        let mut home = open("home_brain");
+        assert_eq!(home.long_term_count().unwrap(), 0);
+        assert!(home.recall("roadmap", 5).is_empty());
+        home.process("Water the balcony tomatoes", None).unwrap();
+    }
+
+    let work = open("work_brain");
+    assert_eq!(work.long_term_count().unwrap(), 1);
+    assert!(work.recall("tomatoes", 5).is_empty());
+    assert!(!work.recall("roadmap", 5).is_empty());
+
+    assert!(Brain::builder().db_path(db_path).keyspace("bad-name; DROP").build().is_err());
+    assert!(Brain::builder().build().is_err());
+}
+
+#[test]
+fn test_spreading_activation_follows_association_chain() {
+    let mut ctx = TestContext::new();
+
+    // A → B → C linked only by associations (no embeddings)
+    let mut a = MemoryItem::new("Chain start", None);
+    let mut b = MemoryItem::new("Chain middle", None);
+    let c = MemoryItem::new("Chain end", None);
+    b.associate(c.id);
+    a.associate(b.id);
+    let (a_id, b_id, c_id) = (a.id, b.id, c.id);
+    for item in [a, b, c] {
+        ctx.brain.semantic.store(item).unwrap();
+    }
+
+    let one_hop = ctx.brain.spreading_activation(a_id, 1, 0.5);
+    assert_eq!(one_hop, vec![(b_id, 0.5)]);
+
+    let two_hops = ctx.brain.spreading_activation(a_id, 2, 0.5);
+    assert_eq!(two_hops, vec![(b_id, 0.5), (c_id, 0.25)]);
+}
+
+#[test]
+fn test_emotion_persists_through_storage() {
+    let mut ctx = TestContext::new();
+
+    let item = MemoryItem::new("The demo crashed in front of everyone", None)
+        .with_emotion(memory_brain::Emotion::Negative);
+    let id = item.id;
+    ctx.brain.semantic.store(item).unwrap();
+
+    let stored = ctx.brain.semantic.get(&id).unwrap().unwrap();
+    assert_eq!(stored.emotion, memory_brain::Emotion::Negative);
+}
+
+#[test]
+fn test_expired_memory_hidden_from_recall() {
+    let mut ctx = TestContext::new();
+    let expired = MemoryItem::new("Temporary parking code is 4812", None).with_ttl(chrono::Duration::hours(-1));
+    let live = MemoryItem::new("Permanent parking spot is level two", None).with_ttl(chrono::Duration::days(7));
+    ctx.brain.store_or_update(expired).unwrap();
+    ctx.brain.store_or_update(live).unwrap();
+
+    let results = ctx.brain.recall("parking", 10);
+    assert!(results.iter().all(|m| !m.content.contains("4812")));
+    assert!(results.iter().any(|m| m.content.contains("level two")));
+}
+
+#[test]
+fn test_sleep_purges_expired_memories() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("ttl.db");
+    let embedder = Arc::new(GloVeEmbedder::test_embedder());
+
+    let expired_id = {
+        let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder.clone()).unwrap();
+        let item = MemoryItem::new("Chat about the weather", None).with_ttl(chrono::Duration::minutes(-5));
+        brain.store_or_update(MemoryItem::new("Rust uses ownership", None)).unwrap();
+        brain.store_or_update(item).unwrap().0
+    };
+
+    // Expiry survives the round trip through storage
+    let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder).unwrap();
+    let stored = brain.get_by_id(&expired_id).unwrap().unwrap();
+    assert!(stored.is_expired());
+
+    brain.sleep().unwrap();
+    assert!(brain.get_by_id(&expired_id).unwrap().is_none());
+    assert!(brain.keyword_index.search_and("weather").is_empty());
+    assert!(brain.semantic.search("Rust", 10).unwrap().iter().any(|m| m.content.contains("ownership")));
+}
+
+#[test]
+fn test_brain_stats_totals_match_per_type_counts() {
+    use memory_brain::MemoryType;
+
+    let mut ctx = TestContext::new();
+    for (content, memory_type) in [
+        ("Rust uses ownership for memory safety", MemoryType::Semantic),
+        ("Python is great for data science", MemoryType::Semantic),
+        ("Deployed the release this morning", MemoryType::Episodic),
+        ("Pattern: use Result for errors", MemoryType::Procedural),
+    ] {
+        ctx.brain.store_typed(MemoryItem::new(content, None), Some(memory_type)).unwrap();
+    }
+
+    let stats = ctx.brain.stats();
+    assert_eq!((stats.semantic, stats.episodic, stats.procedural), (2, 1, 1));
+    assert_eq!(stats.total, stats.episodic + stats.semantic + stats.procedural);
+    assert_eq!(stats.total, ctx.brain.long_term_count().unwrap());
+    assert_eq!(stats.working, ctx.brain.working.len());
+    assert_eq!(stats.embedding_dim, 50);
+    assert!(stats.index_stats.documents >= 4);
+    assert!(stats.db_size_bytes.unwrap() > 0);
+}
+
+#[test]
+fn test_concurrent_recalls_share_read_lock() {
+    use std::sync::RwLock;
+
+    let mut ctx = TestContext::new();
+    ctx.brain.process("Rust uses ownership for memory safety", None).unwrap();
+    ctx.brain.process("Python is great for data science", None).unwrap();
+    let shared = Arc::new(RwLock::new(ctx.brain));
+
+    // Recalls don't need the write lock, even while another reader holds it
+    let held = shared.read().unwrap();
+    let handles: Vec<_> = (0..16)
+        .map(|i| {
+            let shared = shared.clone();
+            std::thread::spawn(move || {
+                let brain = shared.read().unwrap();
+                let (query, expected) = if i % 2 == 0 { ("Rust", "ownership") } else { ("Python", "data science") };
+                let results = brain.recall(query, 5);
+                results.iter().any(|m| m.content.contains(expected))
+            })
+        })
+        .collect();
+    for handle in handles {
+        assert!(handle.join().unwrap());
+    }
+    drop(held);
+
+    // Writers still get in afterwards
+    shared.write().unwrap().process("Go has goroutines", None).unwrap();
+    assert!(!shared.read().unwrap().recall("goroutines", 5).is_empty());
+}
+
+#[test]
+fn test_compress_embeddings_dry_run_then_execute() {
+    use memory_brain::MemoryType;
+
+    let mut ctx = TestContext::new();
+    for (content, memory_type) in [
+        ("Rust uses ownership for memory safety", MemoryType::Semantic),
+        ("Python is great for data science", MemoryType::Semantic),
+        ("Deployed the release this morning", MemoryType::Episodic),
+    ] {
+        ctx.brain.store_typed(MemoryItem::new(content, None), Some(memory_type)).unwrap();
+    }
+
+    // Dry run: a positive saving, nothing rewritten
+    let dry = ctx.brain.compress_embeddings(false).unwrap();
+    assert_eq!(dry.stats.items_compressed, ctx.brain.long_term_count().unwrap());
+    assert!(dry.stats.savings_percent() > 0.0);
+    assert!(dry.stats.mean_error > 0.0);
+    assert_eq!(dry.rewritten, 0);
+    assert_eq!(ctx.brain.embedding_bytes().unwrap(), dry.disk_bytes_before);
+
+    let report = ctx.brain.compress_embeddings(true).unwrap();
+    assert_eq!(report.rewritten, dry.stats.items_compressed);
+    assert!(report.disk_bytes_after < report.disk_bytes_before);
+
+    // Quantized embeddings still find their memory
+    let embedder = ctx.brain.embedder().clone();
+    let query = embedder.embed("Rust uses ownership for memory safety");
+    let stored = ctx.brain.semantic.search("ownership", 1).unwrap().remove(0);
+    assert!(cosine_similarity(&query, stored.embedding.as_ref().unwrap()) > 0.99);
+}
+
+#[test]
+fn test_recall_excluded_and_required_terms() {
+    use memory_brain::MemoryType;
+
+    let mut ctx = TestContext::new();
+    for content in ["Rust async runtime with tokio", "Rust ownership rules", "Python async generators"] {
+        ctx.brain.store_typed(MemoryItem::new(content, None), Some(MemoryType::Semantic)).unwrap();
+    }
+
+    // -async drops the tokio memory but keeps the other Rust one
+    let results = ctx.brain.recall("rust -async", 10);
+    assert!(results.iter().any(|item| item.content.contains("ownership")));
+    assert!(!results.iter().any(|item| item.content.contains("async")));
+
+    // +python keeps only memories that mention it
+    let results = ctx.brain.recall("+python async", 10);
+    assert!(!results.is_empty());
+    assert!(results.iter().all(|item| item.content.contains("Python")));
+}
+
+#[test]
+fn test_sidecar_reopen_refills_hnsw() {
+    use memory_brain::MemoryType;
+
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("hnsw.db");
+    let embedder = Arc::new(GloVeEmbedder::test_embedder());
+
+    let (rust, ownership) = {
+        let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder.clone()).unwrap();
+        let rust = brain.store_typed(MemoryItem::new("Rust programming code", None), Some(MemoryType::Semantic)).unwrap();
+        let ownership = brain.store_typed(MemoryItem::new("Rust programming language", None), Some(MemoryType::Episodic)).unwrap();
+        brain.save_indexes().unwrap();
+        (rust, ownership)
+    };
+
+    // The sidecar path, not a rebuild, must leave HNSW searchable
+    let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder).unwrap();
+    assert!(brain.load_indexes().unwrap().is_some());
+    // Filled on first use rather than while loading
+    assert_eq!(brain.hnsw.stats().count, 0);
+    assert_eq!(brain.hnsw_index().stats().count, 2);
+    let nearest = brain.hnsw_index().search(rust.embedding.as_ref().unwrap(), 1);
+    assert_eq!(nearest[0].0, rust.id);
+
+    // Associative recall follows embedding neighbors again
+    let activated = brain.spreading_activation(rust.id, 1, memory_brain::DEFAULT_SPREAD_DECAY);
+    assert!(activated.iter().any(|(id, _)| *id == ownership.id));
+}
+
+#[test]
+fn test_confirmed_types_train_the_classifier_across_reopen() {
+    use memory_brain::{HashEmbedder, MemoryEdit, MemoryType};
+
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("classify.db");
+    let embedder = Arc::new(HashEmbedder::new(256));
+    let probe = "I deployed the app and it is live";
+
+    {
+        let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder.clone()).unwrap();
+        // Untrained: " is " fools the keyword rules
+        let item = brain.store_typed(MemoryItem::new(probe, None), None).unwrap();
+        assert_eq!(item.memory_type, MemoryType::Semantic);
+
+        // Correcting the type moves the memory and counts as a label
+        let moved = brain
+            .edit(&item.id.to_string(), &MemoryEdit { memory_type: Some(MemoryType::Episodic), ..Default::default() })
+            .unwrap();
+        assert_eq!(moved.memory_type, MemoryType::Episodic);
+        assert!(brain.semantic.get(&item.id).unwrap().is_none());
+        assert!(brain.episodic.get(&item.id).unwrap().is_some());
+        brain.forget(item.id).unwrap();
+
+        for (text, memory_type) in [
+            ("I deployed the server yesterday", MemoryType::Episodic),
+            ("We pushed the app to production this morning", MemoryType::Episodic),
+            ("Rust is a systems programming language", MemoryType::Semantic),
+            ("Water boils at 100 degrees", MemoryType::Semantic),
+            ("HTTP is a stateless protocol", MemoryType::Semantic),
+        ] {
+            brain.store_typed(MemoryItem::new(text, None), Some(memory_type)).unwrap();
+        }
+        brain.save_indexes().unwrap();
+    }
+
+    // The centroids come back with the sidecar
+    let mut brain = Brain::with_embedder(db_path.to_str().unwrap(), embedder).unwrap();
+    assert!(brain.load_indexes().unwrap().is_some());
+    let item = brain.store_typed(MemoryItem::new(probe, None), None).unwrap();
+    assert_eq!(item.memory_type, MemoryType::Episodic);
+}
+
+#[test]
+fn test_time_range_recall_applies_exclusions() {
+    use chrono::{Duration, Utc};
+
+    let mut ctx = TestContext::new();
+    ctx.brain.process("Rust async runtime", None).unwrap();
+    ctx.brain.process("Rust ownership rules", None).unwrap();
+
+    let since = Some(Utc::now() - Duration::hours(1));
+    let results = ctx.brain.recall_in_range("rust -async", 10, since, None).unwrap();
+    assert!(results.iter().any(|m| m.content == "Rust ownership rules"));
+    assert!(results.iter().all(|m| !m.content.contains("async")));
+
+    // Filtered recall too
+    let opts = SearchOptions { memory_type: Some(memory_brain::MemoryType::Semantic), ..Default::default() };
+    let results = ctx.brain.recall_with_options("rust -async", 10, &opts).unwrap();
+    assert!(results.iter().all(|m| !m.content.contains("async")));
+}
+
+#[test]
+fn test_time_bounds_compose_with_other_filters() {
+    use chrono::{Duration, Utc};
+
+    let mut ctx = TestContext::new();
+    let now = Utc::now();
+    for (days_ago, text, tag) in [
+        (30, "old deploy of the billing service", "work"),
+        (2, "recent deploy of the auth service", "work"),
+        (1, "recent deploy of the garden shed", "home"),
+    ] {
+        let mut item = MemoryItem::new(text, None).with_tags(vec![tag.to_string()]);
+        item.created_at = now - Duration::days(days_ago);
+        ctx.brain.episodic.store(item).unwrap();
+    }
+
+    let opts = SearchOptions {
+        tags: vec!["work".to_string()],
+        since: Some(now - Duration::days(7)),
+        ..Default::default()
+    };
+    let results = ctx.brain.recall_with_options("deploy", 10, &opts).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].content.contains("auth"));
+
+    let opts = SearchOptions { until: Some(now - Duration::days(7)), regex: Some("billing|auth".to_string()), ..Default::default() };
+    let results = ctx.brain.recall_with_options("deploy", 10, &opts).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].content.contains("billing"));
+}
+
+#[test]
+fn test_process_returning_merged_fact_carries_existing_id() {
+    let mut ctx = TestContext::new();
+    let first = ctx.brain.process_returning("Rust is a systems programming language", None).unwrap();
+    let second = ctx.brain.process_returning("Rust is a systems programming language", None).unwrap();
+
+    // The repeat strengthened the stored fact; updates must reach it
+    assert_eq!(second.id, first.id);
+    assert!(!second.associations.contains(&first.id));
+    ctx.brain.update_strength(&second.id.to_string(), 0.95).unwrap();
+    let stored = ctx.brain.semantic.get(&first.id).unwrap().unwrap();
+    assert!((stored.strength - 0.95).abs() < 1e-6);
+}
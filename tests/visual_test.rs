@@ -0,0 +1,81 @@
+//! Visual memory ↔ text brain integration tests
+
+use coredb::{CoreDB, DatabaseConfig};
+use memory_brain::clip_onnx::MockClipProvider;
+use memory_brain::visual_storage::VisualStorage;
+use memory_brain::vlm::{VlmError, VlmProvider};
+use memory_brain::{Brain, GloVeEmbedder};
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+/// VLM stub that always returns the same description
+struct FixedVlm(&'static str);
+
+impl VlmProvider for FixedVlm {
+    fn describe_image(&self, _image_path: &Path, _prompt: Option<&str>) -> Result<String, VlmError> {
+        Ok(self.0.to_string())
+    }
+
+    fn model_name(&self) -> &str {
+        "fixed"
+    }
+}
+
+fn visual_db_config(path: &Path) -> DatabaseConfig {
+    DatabaseConfig {
+        data_directory: path.join("data"),
+        commitlog_directory: path.join("commitlog"),
+        memtable_flush_threshold_mb: 16,
+        compaction_throughput_mb_per_sec: 16,
+        concurrent_reads: 32,
+        concurrent_writes: 32,
+        block_cache_size_mb: 64,
+        block_cache_max_entries: 5_000,
+    }
+}
+
+#[test]
+fn test_auto_described_image_is_recallable_as_text() {
+    let dir = TempDir::new().unwrap();
+    let brain_path = dir.path().join("brain.db");
+    let visual_path = dir.path().join("visual.db");
+    let image_path = dir.path().join("beach.jpg");
+
+    // Brain storage drives its own runtime, so keep it outside the visual runtime
+    let embedder = Arc::new(GloVeEmbedder::test_embedder());
+    let mut brain = Brain::with_embedder(brain_path.to_str().unwrap(), embedder).unwrap();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let (storage, visual) = rt.block_on(async {
+        let db = Arc::new(RwLock::new(CoreDB::new(visual_db_config(&visual_path)).await.unwrap()));
+        let mut storage = VisualStorage::new(db, Arc::new(MockClipProvider::new(512)), "visual_test")
+            .await
+            .unwrap();
+        storage.set_vlm(Arc::new(FixedVlm("A sunny beach photo with palm trees")));
+
+        let visual = storage
+            .store_image_auto(&image_path, None, vec!["vacation".to_string()], 0.5, None)
+            .await
+            .unwrap();
+        (storage, visual)
+    });
+
+    let item = brain.remember_visual(&visual).unwrap();
+    let linked = rt.block_on(storage.link_text_memory(visual.id, item.id)).unwrap();
+
+    // Visual memory points at the text memory
+    assert!(linked.linked_memories.contains(&item.id));
+    let cached = rt.block_on(storage.get(visual.id)).unwrap().unwrap();
+    assert!(cached.linked_memories.contains(&item.id));
+
+    // Text brain surfaces the description and links back to the image id
+    let results = brain.recall("beach photo", 5);
+    let found = results
+        .iter()
+        .find(|m| m.content.contains("beach photo"))
+        .expect("description should be recallable");
+    assert!(found.tags.contains(&format!("visual:{}", visual.id)));
+    assert!(found.associations.contains(&visual.id));
+}